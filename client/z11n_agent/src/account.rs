@@ -0,0 +1,203 @@
+use crate::proto::{AccountInfo, LoginRecord, UserAccount, UserGroup};
+use std::{collections::HashMap, fs};
+
+const PASSWD: &str = "/etc/passwd";
+const GROUP: &str = "/etc/group";
+const UTMP: &str = "/var/run/utmp";
+const WTMP: &str = "/var/log/wtmp";
+// 拥有管理员权限的组
+const SUDO_GROUPS: [&str; 3] = ["sudo", "wheel", "admin"];
+// 只上报最近的登录记录
+const MAX_LOGINS: usize = 100;
+
+// glibc x86_64、aarch64 下 struct utmp 的布局
+const UTMP_SIZE: usize = 384;
+const USER_PROCESS: i16 = 7;
+const DEAD_PROCESS: i16 = 8;
+
+/// 采集本地账号、用户组、当前会话和最近登录记录
+pub fn account() -> anyhow::Result<AccountInfo> {
+    let passwd = fs::read_to_string(PASSWD)?;
+    let group = fs::read_to_string(GROUP).unwrap_or_default();
+    let groups = parse_group(&group);
+    let users = parse_passwd(&passwd, &groups);
+    let sessions = match fs::read(UTMP) {
+        Ok(v) => parse_utmp(&v)
+            .into_iter()
+            .filter(|v| v.logout_at.is_none())
+            .collect(),
+        Err(e) => {
+            log::warn!("read {UTMP} err: {}", e);
+            Vec::new()
+        }
+    };
+    let logins = match fs::read(WTMP) {
+        Ok(v) => {
+            let mut logins = parse_utmp(&v);
+            logins.reverse();
+            logins.truncate(MAX_LOGINS);
+            logins
+        }
+        Err(e) => {
+            log::warn!("read {WTMP} err: {}", e);
+            Vec::new()
+        }
+    };
+    Ok(AccountInfo {
+        users,
+        groups,
+        sessions,
+        logins,
+    })
+}
+
+fn parse_group(content: &str) -> Vec<UserGroup> {
+    let mut r = Vec::new();
+    for line in content.lines() {
+        let fields: Vec<&str> = line.split(':').collect();
+        if fields.len() < 4 || line.starts_with('#') {
+            continue;
+        }
+        let Ok(gid) = fields[2].parse() else {
+            continue;
+        };
+        r.push(UserGroup {
+            name: fields[0].to_string(),
+            gid,
+            members: fields[3]
+                .split(',')
+                .filter(|v| !v.is_empty())
+                .map(|v| v.to_string())
+                .collect(),
+        });
+    }
+    r
+}
+
+fn parse_passwd(content: &str, groups: &[UserGroup]) -> Vec<UserAccount> {
+    let mut r = Vec::new();
+    for line in content.lines() {
+        let fields: Vec<&str> = line.split(':').collect();
+        if fields.len() < 7 || line.starts_with('#') {
+            continue;
+        }
+        let (Ok(uid), Ok(gid)) = (fields[2].parse(), fields[3].parse::<u32>()) else {
+            continue;
+        };
+        let name = fields[0].to_string();
+        let user_groups: Vec<String> = groups
+            .iter()
+            .filter(|v| v.gid == gid || v.members.contains(&name))
+            .map(|v| v.name.clone())
+            .collect();
+        let shell = fields[6].to_string();
+        let sudo = uid == 0
+            || user_groups
+                .iter()
+                .any(|v| SUDO_GROUPS.contains(&v.as_str()));
+        let login = !shell.is_empty() && !shell.ends_with("nologin") && !shell.ends_with("false");
+        r.push(UserAccount {
+            uid,
+            gid,
+            gecos: Some(fields[4].to_string()).filter(|v| !v.is_empty()),
+            home: fields[5].to_string(),
+            sudo,
+            login,
+            groups: user_groups,
+            shell,
+            name,
+        });
+    }
+    r
+}
+
+/// 解析 utmp/wtmp 记录，按终端配对登录与注销，返回按时间排列的登录记录
+fn parse_utmp(content: &[u8]) -> Vec<LoginRecord> {
+    let mut r: Vec<LoginRecord> = Vec::new();
+    // 终端到最近一次未注销登录的下标
+    let mut open: HashMap<String, usize> = HashMap::new();
+    for record in content.chunks_exact(UTMP_SIZE) {
+        let ut_type = i16::from_ne_bytes([record[0], record[1]]);
+        let pid = i32::from_ne_bytes([record[4], record[5], record[6], record[7]]);
+        let tty = c_string(&record[8..40]);
+        let user = c_string(&record[44..76]);
+        let host = c_string(&record[76..332]);
+        let tv_sec = i32::from_ne_bytes([record[340], record[341], record[342], record[343]]);
+        match ut_type {
+            USER_PROCESS => {
+                open.insert(tty.clone(), r.len());
+                r.push(LoginRecord {
+                    user,
+                    tty,
+                    host: Some(host).filter(|v| !v.is_empty()),
+                    pid: u32::try_from(pid).ok(),
+                    login_at: tv_sec as i64,
+                    logout_at: None,
+                });
+            }
+            DEAD_PROCESS => {
+                if let Some(i) = open.remove(&tty) {
+                    r[i].logout_at = Some(tv_sec as i64);
+                }
+            }
+            _ => {}
+        }
+    }
+    r
+}
+
+fn c_string(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|v| *v == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utmp_record(ut_type: i16, tty: &str, user: &str, tv_sec: i32) -> Vec<u8> {
+        let mut record = vec![0; UTMP_SIZE];
+        record[0..2].copy_from_slice(&ut_type.to_ne_bytes());
+        record[4..8].copy_from_slice(&1234i32.to_ne_bytes());
+        record[8..8 + tty.len()].copy_from_slice(tty.as_bytes());
+        record[44..44 + user.len()].copy_from_slice(user.as_bytes());
+        record[340..344].copy_from_slice(&tv_sec.to_ne_bytes());
+        record
+    }
+
+    #[test]
+    fn parse_passwd_test() {
+        let groups = parse_group("root:x:0:\nsudo:x:27:alice\nalice:x:1000:\n");
+        let users = parse_passwd(
+            "root:x:0:0:root:/root:/bin/bash\nalice:x:1000:1000:Alice,,,:/home/alice:/bin/zsh\nwww-data:x:33:33:www-data:/var/www:/usr/sbin/nologin\n",
+            &groups,
+        );
+        assert_eq!(users.len(), 3);
+        assert!(users[0].sudo);
+        assert!(users[1].sudo);
+        assert_eq!(users[1].groups, vec!["sudo", "alice"]);
+        assert!(!users[2].sudo);
+        assert!(!users[2].login);
+    }
+
+    #[test]
+    fn parse_utmp_test() {
+        let mut content = utmp_record(USER_PROCESS, "pts/0", "alice", 100);
+        content.extend(utmp_record(USER_PROCESS, "pts/1", "bob", 200));
+        content.extend(utmp_record(DEAD_PROCESS, "pts/0", "", 300));
+        let logins = parse_utmp(&content);
+        assert_eq!(logins.len(), 2);
+        assert_eq!(logins[0].user, "alice");
+        assert_eq!(logins[0].logout_at, Some(300));
+        assert_eq!(logins[1].user, "bob");
+        assert_eq!(logins[1].logout_at, None);
+    }
+
+    #[test]
+    fn account_test() -> anyhow::Result<()> {
+        let _ = tracing_subscriber::fmt().with_ansi(true).try_init();
+        let account = account()?;
+        log::info!("{account:?}");
+        Ok(())
+    }
+}
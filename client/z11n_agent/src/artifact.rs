@@ -0,0 +1,83 @@
+use crate::{Z11nClient, config::Z11N_AGENT_TOML, proto::DownloadReq};
+use ed25519_dalek::{Signature, VerifyingKey, pkcs8::DecodePublicKey};
+use sha2::{Digest, Sha256};
+use std::{
+    fs::{self, File},
+    io::Write,
+    path::Path,
+};
+
+/// 从制品库下载制品到 path，校验 sha256 和发布密钥签名，失败时删除文件
+pub async fn download(client: &mut Z11nClient, id: &str, path: &Path) -> anyhow::Result<()> {
+    let r = download_to(client, id, path).await;
+    if r.is_err() {
+        let _ = fs::remove_file(path);
+    }
+    r
+}
+
+async fn download_to(client: &mut Z11nClient, id: &str, path: &Path) -> anyhow::Result<()> {
+    let public_key = public_key()?;
+    let mut stream = client
+        .download(DownloadReq {
+            download_ref: id.to_string(),
+        })
+        .await?
+        .into_inner();
+    let mut file = File::create(path)?;
+    let mut hasher = Sha256::new();
+    let mut signature = Vec::new();
+    while let Some(chunk) = stream.message().await? {
+        if signature.is_empty() {
+            signature = chunk.signature;
+        }
+        hasher.update(&chunk.data);
+        file.write_all(&chunk.data)?;
+    }
+    file.sync_all()?;
+    let digest = hasher.finalize();
+    let sha256 = format!("{digest:x}");
+    if sha256 != id {
+        anyhow::bail!("sha256 mismatch, expected {id}, got {sha256}");
+    }
+    verify(&public_key, &digest.into(), &signature)
+}
+
+fn public_key() -> anyhow::Result<VerifyingKey> {
+    let path = &Z11N_AGENT_TOML.artifact.public_key;
+    let pem = fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("read release public key {path} err: {e}"))?;
+    VerifyingKey::from_public_key_pem(&pem)
+        .map_err(|e| anyhow::anyhow!("parse release public key {path} err: {e}"))
+}
+
+/// 校验发布密钥对 sha256 摘要的签名
+fn verify(public_key: &VerifyingKey, digest: &[u8; 32], signature: &[u8]) -> anyhow::Result<()> {
+    let signature = Signature::from_slice(signature)
+        .map_err(|_| anyhow::anyhow!("artifact signature missing or malformed"))?;
+    public_key
+        .verify_strict(digest, &signature)
+        .map_err(|_| anyhow::anyhow!("artifact signature verify failed"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    #[test]
+    fn verify_test() {
+        let signing_key = SigningKey::from_bytes(&[7; 32]);
+        let digest: [u8; 32] = Sha256::digest(b"z11n_agent").into();
+        let signature = signing_key.sign(&digest).to_bytes();
+        let public_key = signing_key.verifying_key();
+        assert!(verify(&public_key, &digest, &signature).is_ok());
+
+        let mut tampered = digest;
+        tampered[0] ^= 1;
+        assert!(verify(&public_key, &tampered, &signature).is_err());
+        assert!(verify(&public_key, &digest, &[]).is_err());
+        let other = SigningKey::from_bytes(&[8; 32]).verifying_key();
+        assert!(verify(&other, &digest, &signature).is_err());
+    }
+}
@@ -1,25 +1,31 @@
-use std::{
-    fs::{self, File},
-    io::Write,
-    path::Path,
-    thread,
-};
+use std::thread;
 
 use once_cell::sync::OnceCell;
 use parking_lot::RwLock;
 use rustls::crypto::{CryptoProvider, ring};
-use tokio::sync::mpsc;
-use tokio_stream::StreamExt;
+use tokio::{
+    sync::mpsc,
+    time::{Duration, Instant},
+};
+use tonic::metadata::{Ascii, MetadataValue};
 use z11n_agent::{
-    AGENT_ID_TOKEN,
+    Z11nClient, account, cert,
     config::Z11N_AGENT_TOML,
-    host,
+    connection::{self, Backoff},
+    control::{self, Control},
+    hardware, host, identity,
+    metrics::Sampler,
+    outbox::{Entry, Outbox, Report},
+    profile,
     proto::{
-        Empty, HeartbeatRsp, HostReq, RegisterReq, UploadHost, heartbeat_rsp::Task,
-        upload_host::InfoType,
+        HeartbeatRsp, HostReq, MetricsReq, ServiceControlResult, Upgrade, UpgradeResult,
+        UploadHost, control_req::Msg, heartbeat_rsp::Task, upgrade_result, upload_host::InfoType,
     },
+    scheduled_task, service, socket, software, upgrade,
 };
 static HOST_INFO: OnceCell<RwLock<HostReq>> = OnceCell::new();
+// 升级后的新版本在该时间内连不上服务端时恢复原版本
+const UPGRADE_REGISTER_DEADLINE: Duration = Duration::from_secs(600);
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -28,44 +34,54 @@ async fn main() -> anyhow::Result<()> {
     if let Err(e) = HOST_INFO.set(HostReq::default().into()) {
         log::error!("HOST_INFO set err: {:?}", e);
     }
-    let agent_id_config = Path::new("./config/.agent_id");
-    let agent_id = if agent_id_config.exists() {
-        fs::read_to_string(agent_id_config)?
-    } else {
-        let agent_id = uuid::Uuid::new_v4().to_string();
-        let mut file = File::create(agent_id_config)?;
-        file.write_all(agent_id.as_bytes())?;
-        agent_id
-    };
-    let version = env!("CARGO_PKG_VERSION");
-    log::info!("agent_id: {agent_id}, version: {version}");
-
-    let agent_version_config = Path::new("./config/.agent_version");
-    let mut file = File::create(agent_version_config)?;
-    file.write_all(version.as_bytes())?;
-
     CryptoProvider::install_default(ring::default_provider())
         .expect("failed to install CryptoProvider");
 
-    let mut client = z11n_agent::build_client(&Z11N_AGENT_TOML.server.addr).await?;
+    // 升级后首次启动，注册失败时恢复原版本
+    let mut pending_upgrade = upgrade::check()?;
 
-    if let Err(e) = AGENT_ID_TOKEN.set(RwLock::new((agent_id.clone(), "".to_string()))) {
-        log::error!("AGENT_ID_TOKEN set err: {:?}", e);
-    }
-    let register_req = RegisterReq {
-        agent_id: agent_id.clone(),
-        agent_version: version.to_string(),
+    let register_req = identity::register_req()?;
+    let mut client = z11n_agent::register_client()?;
+    // 服务端不可达时退避重试，直到注册成功
+    let mut backoff = Backoff::reconnect();
+    let upgrade_deadline = Instant::now() + UPGRADE_REGISTER_DEADLINE;
+    let register_rsp = loop {
+        match client.register(register_req.clone()).await {
+            Ok(v) => break v,
+            Err(e) => {
+                // 服务端重启等连接错误继续退避，被服务端拒绝或超过期限时才回滚
+                if let Some(pending_upgrade) = pending_upgrade.as_mut()
+                    && pending_upgrade.rolled_back.is_none()
+                    && (!connection::unreachable(&e) || Instant::now() >= upgrade_deadline)
+                {
+                    return Err(upgrade::rollback(
+                        pending_upgrade,
+                        format!("register err: {e}"),
+                    ));
+                }
+                log::error!("register api err: {}", e);
+                if cert::rejected(&e) {
+                    cert::discard();
+                    client = z11n_agent::register_client()?;
+                }
+                connection::disconnected(&e);
+                backoff.wait().await;
+            }
+        }
     };
-    let register_rsp = client.register(register_req).await?;
-    let token = register_rsp.get_ref().token.clone();
-
-    if let Some(lock) = AGENT_ID_TOKEN.get() {
-        let mut write = lock.write();
-        *write = (agent_id, token);
-    }
+    connection::connected();
+    identity::registered(&register_req, register_rsp.get_ref())?;
+    tokio::spawn(cert::renew_task());
 
     let (tx_heartbeat_rsp, rx_heartbeat_rsp) = mpsc::channel(1_000);
     let (tx_req, rx_req) = mpsc::channel(1_000);
+    if let Some(result) = pending_upgrade.and_then(upgrade::finish) {
+        log::info!("upgrade result: {result:?}");
+        tx_req.send(Req::UpgradeResult(result)).await?;
+    }
+    let tx_metrics = tx_req.clone();
+    let control = Control::default();
+    let control_clone = control.clone();
 
     thread::spawn(|| {
         if let Err(e) = consume_heartbeat_rsp(rx_heartbeat_rsp, tx_req) {
@@ -73,80 +89,333 @@ async fn main() -> anyhow::Result<()> {
         }
     });
     tokio::spawn(async move {
-        if let Err(e) = consume_req(rx_req).await {
+        if let Err(e) = consume_req(rx_req, control_clone).await {
             log::error!("consume_heartbeat_rsp err: {}", e);
         }
     });
 
-    tx_heartbeat_rsp
-        .send(HeartbeatRsp {
-            task: Some(Task::UploadHost(UploadHost {
-                info_type: InfoType::System.into(),
-            })),
-        })
-        .await?;
-    tx_heartbeat_rsp
-        .send(HeartbeatRsp {
-            task: Some(Task::UploadHost(UploadHost {
-                info_type: InfoType::Disk.into(),
-            })),
-        })
-        .await?;
-    tx_heartbeat_rsp
-        .send(HeartbeatRsp {
-            task: Some(Task::UploadHost(UploadHost {
-                info_type: InfoType::Network.into(),
-            })),
-        })
-        .await?;
-    if let Err(e) = heartbeat(tx_heartbeat_rsp.clone()).await {
-        log::error!("heartbeat api err: {}", e);
+    tokio::spawn(async move {
+        if let Err(e) = push_metrics(tx_metrics).await {
+            log::error!("push_metrics err: {}", e);
+        }
+    });
+    let tx_collect = tx_heartbeat_rsp.clone();
+    tokio::spawn(async move {
+        if let Err(e) = collect_host(tx_collect).await {
+            log::error!("collect_host err: {}", e);
+        }
+    });
+
+    // 启动时上报全部主机信息
+    for info_type in [
+        InfoType::System,
+        InfoType::Disk,
+        InfoType::Network,
+        InfoType::Software,
+        InfoType::Socket,
+        InfoType::Hardware,
+        InfoType::Account,
+        InfoType::Service,
+        InfoType::ScheduledTask,
+    ] {
+        tx_heartbeat_rsp
+            .send(HeartbeatRsp {
+                task: Some(Task::UploadHost(UploadHost {
+                    info_type: info_type.into(),
+                })),
+            })
+            .await?;
+    }
+    if let Err(e) = control::run(control, Some(tx_heartbeat_rsp.clone())).await {
+        log::error!("control err: {}", e);
     }
     Ok(())
 }
 
 enum Req {
-    HostReq(HostReq),
+    Host(Box<HostReq>),
+    Metrics(MetricsReq),
+    ServiceControlResult(ServiceControlResult),
+    UpgradeResult(UpgradeResult),
+    Upgrade(Upgrade),
 }
-async fn heartbeat(tx_heartbeat_rsp: mpsc::Sender<HeartbeatRsp>) -> anyhow::Result<()> {
-    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(5));
+/// 按配置模板的间隔采样，模板变化后按新的间隔和批量重新开始
+async fn push_metrics(tx_req: mpsc::Sender<Req>) -> anyhow::Result<()> {
+    let mut rx_profile = profile::subscribe();
+    let mut sampler = Sampler::new();
     loop {
-        interval.tick().await;
-        // log::info!("heartbeat start");
-        let mut client = z11n_agent::build_client(&Z11N_AGENT_TOML.server.addr).await?;
-        let req = Empty {};
-        let rsp = client.heartbeat(req).await?;
-        let mut stream = rsp.into_inner();
-        while let Some(v) = stream.next().await {
-            match v {
-                Ok(heartbeat_rsp) => {
-                    log::info!("heartbeat_rsp: {heartbeat_rsp:?}");
-                    tx_heartbeat_rsp.send(heartbeat_rsp).await?;
+        let (period, batch) = {
+            let agent_profile = rx_profile.borrow_and_update();
+            (
+                Duration::from_secs(agent_profile.metrics_interval.max(1) as u64),
+                agent_profile.metrics_batch.max(1) as usize,
+            )
+        };
+        // 第一个采样在一个间隔之后，以保证有完整的统计区间
+        let mut interval = tokio::time::interval_at(Instant::now() + period, period);
+        let mut samples = Vec::new();
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {}
+                r = rx_profile.changed() => {
+                    r?;
+                    break;
                 }
-                Err(e) => {
-                    log::error!("stream {}", e);
+            }
+            samples.push(sampler.sample());
+            if samples.len() < batch {
+                continue;
+            }
+            let req = MetricsReq {
+                samples: std::mem::take(&mut samples),
+            };
+            tx_req.send(Req::Metrics(req)).await?;
+        }
+        // 模板变化时先上报已有的采样
+        if !samples.is_empty() {
+            tx_req.send(Req::Metrics(MetricsReq { samples })).await?;
+        }
+    }
+}
+
+/// 按配置模板定期重新采集指定类型的主机信息，collect_interval 为 0 时不采集
+async fn collect_host(tx_heartbeat_rsp: mpsc::Sender<HeartbeatRsp>) -> anyhow::Result<()> {
+    let mut rx_profile = profile::subscribe();
+    loop {
+        let (collect, collect_interval) = {
+            let agent_profile = rx_profile.borrow_and_update();
+            (
+                agent_profile.collect.clone(),
+                agent_profile.collect_interval,
+            )
+        };
+        if collect.is_empty() || collect_interval == 0 {
+            rx_profile.changed().await?;
+            continue;
+        }
+        let period = Duration::from_secs(collect_interval as u64);
+        let mut interval = tokio::time::interval_at(Instant::now() + period, period);
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {}
+                r = rx_profile.changed() => {
+                    r?;
                     break;
                 }
             }
+            for info_type in &collect {
+                tx_heartbeat_rsp
+                    .send(HeartbeatRsp {
+                        task: Some(Task::UploadHost(UploadHost {
+                            info_type: *info_type,
+                        })),
+                    })
+                    .await?;
+            }
         }
-        // log::info!("heartbeat end");
     }
 }
 
-async fn consume_req(mut rx_req: mpsc::Receiver<Req>) -> anyhow::Result<()> {
-    while let Some(req) = rx_req.recv().await {
-        match req {
-            Req::HostReq(host_req) => {
-                let mut client = z11n_agent::build_client(&Z11N_AGENT_TOML.server.addr).await?;
-                if let Err(e) = client.host(host_req).await {
-                    log::error!("host api err: {}", e);
+async fn consume_req(mut rx_req: mpsc::Receiver<Req>, control: Control) -> anyhow::Result<()> {
+    let outbox_config = &Z11N_AGENT_TOML.outbox;
+    let mut outbox = Outbox::open(&outbox_config.dir, outbox_config.max_size)?;
+    if !outbox.is_empty() {
+        log::info!("{} buffered report(s) to replay", outbox.len());
+    }
+    // 服务端已确认的版本和主机信息，只上报与之相比变化的部分
+    let mut acked: Option<(u64, HostReq)> = None;
+    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(
+        outbox_config.retry_interval.max(1),
+    ));
+    loop {
+        let req = tokio::select! {
+            req = rx_req.recv() => match req {
+                Some(v) => v,
+                None => return Ok(()),
+            },
+            _ = interval.tick() => {
+                flush(&mut outbox, &mut acked, &control).await;
+                continue;
+            }
+        };
+        // 上报先写入离线缓存，按顺序发出
+        let report = match req {
+            Req::Host(host_req) => Report::Host(host_req),
+            Req::Metrics(metrics_req) => Report::Metrics(metrics_req),
+            Req::ServiceControlResult(result) => Report::ServiceControlResult(result),
+            Req::UpgradeResult(result) => Report::UpgradeResult(result),
+            Req::Upgrade(upgrade) => {
+                let installed = async {
+                    let mut client = z11n_agent::client()?;
+                    upgrade::install(&mut client, &upgrade).await
                 }
+                .await;
+                let result = match installed {
+                    Ok(()) => UpgradeResult {
+                        id: upgrade.id,
+                        status: upgrade_result::Status::Installed.into(),
+                        message: format!("restarting as {}", upgrade.version),
+                    },
+                    Err(e) => {
+                        log::error!("upgrade to {} err: {}", upgrade.version, e);
+                        UpgradeResult {
+                            id: upgrade.id,
+                            status: upgrade_result::Status::Failed.into(),
+                            message: e.to_string(),
+                        }
+                    }
+                };
+                let installed = result.status() == upgrade_result::Status::Installed;
+                if let Err(e) = outbox.push(Report::UpgradeResult(result)) {
+                    log::error!("outbox push err: {}", e);
+                }
+                flush(&mut outbox, &mut acked, &control).await;
+                if installed {
+                    // 未发出的结果留在离线缓存中，由新版本重放
+                    log::info!("agent restarting");
+                    log::error!("restart err: {}", upgrade::restart());
+                }
+                continue;
+            }
+        };
+        if let Err(e) = outbox.push(report) {
+            log::error!("outbox push err: {}", e);
+        }
+        flush(&mut outbox, &mut acked, &control).await;
+    }
+}
+
+/// 按顺序发出离线缓存中的上报，服务端不可达时保留剩余上报等待重试
+async fn flush(outbox: &mut Outbox, acked: &mut Option<(u64, HostReq)>, control: &Control) {
+    if outbox.is_empty() {
+        return;
+    }
+    let mut client = match z11n_agent::client() {
+        Ok(v) => v,
+        Err(e) => {
+            log::error!("client err, {} report(s) buffered: {}", outbox.len(), e);
+            return;
+        }
+    };
+    while let Some(entry) = outbox.front() {
+        match send(&mut client, acked, control, entry).await {
+            Ok(()) => outbox.pop(),
+            Err(e) if retryable(&e) => {
+                log::error!("report err, {} report(s) buffered: {}", outbox.len(), e);
+                return;
+            }
+            Err(e) => {
+                log::error!("report rejected, drop it: {}", e);
+                outbox.pop();
             }
         }
     }
+}
+
+async fn send(
+    client: &mut Z11nClient,
+    acked: &mut Option<(u64, HostReq)>,
+    control: &Control,
+    entry: Entry,
+) -> anyhow::Result<()> {
+    let Some(report) = entry.report else {
+        return Ok(());
+    };
+    let report_id: MetadataValue<Ascii> = entry.report_id.parse()?;
+    match report {
+        Report::Host(host_req) => {
+            let host_diff_req = host::diff(acked.as_ref(), &host_req);
+            if host_diff_req.info_types.is_empty() {
+                log::info!("host info unchanged");
+                return Ok(());
+            }
+            let mut rsp = client
+                .host_diff(with_report_id(host_diff_req, &report_id))
+                .await;
+            // 服务端版本与本地不一致时重新全量上报
+            if let Err(e) = &rsp
+                && e.code() == tonic::Code::FailedPrecondition
+            {
+                log::warn!("host version mismatch, upload all: {}", e.message());
+                let host_diff_req = host::diff(None, &host_req);
+                rsp = client
+                    .host_diff(with_report_id(host_diff_req, &report_id))
+                    .await;
+            }
+            match rsp {
+                Ok(rsp) => *acked = Some((rsp.get_ref().version, *host_req)),
+                Err(e) => {
+                    *acked = None;
+                    return Err(e.into());
+                }
+            }
+        }
+        Report::Metrics(metrics_req) => {
+            client
+                .push_metrics(with_report_id(metrics_req, &report_id))
+                .await?;
+        }
+        // 任务结果优先通过长连接上报，未连接时使用单独的调用
+        Report::ServiceControlResult(result) if control.is_connected() => {
+            control
+                .report(entry.report_id, Msg::ServiceControlResult(result))
+                .await?;
+        }
+        Report::ServiceControlResult(result) => {
+            client
+                .report_service_control(with_report_id(result, &report_id))
+                .await?;
+        }
+        Report::UpgradeResult(result) if control.is_connected() => {
+            control
+                .report(entry.report_id, Msg::UpgradeResult(result))
+                .await?;
+        }
+        Report::UpgradeResult(result) => {
+            client
+                .report_upgrade(with_report_id(result, &report_id))
+                .await?;
+        }
+    }
     Ok(())
 }
 
+/// 带上去重 id，服务端忽略已入库的重放
+fn with_report_id<T>(message: T, report_id: &MetadataValue<Ascii>) -> tonic::Request<T> {
+    let mut req = tonic::Request::new(message);
+    req.metadata_mut().insert("report_id", report_id.clone());
+    req
+}
+
+/// 只有连接错误时保留上报重试，其余错误丢弃，避免一条确定失败的上报阻塞后面的上报
+///
+/// 等待审批时同样保留，审批通过后重放
+fn retryable(e: &anyhow::Error) -> bool {
+    e.downcast_ref::<tonic::Status>().is_some_and(|status| {
+        matches!(
+            status.code(),
+            tonic::Code::Unavailable | tonic::Code::DeadlineExceeded | tonic::Code::Cancelled
+        ) || (status.code() == tonic::Code::PermissionDenied
+            && status.message() == "pending approval")
+    })
+}
+
+/// 更新 HOST_INFO 中的一项后上报完整的主机信息
+fn refresh(tx_req: &mpsc::Sender<Req>, f: impl FnOnce(&mut HostReq)) {
+    let Some(lock) = HOST_INFO.get() else {
+        return;
+    };
+    let host_req = {
+        let mut write = lock.write();
+        f(&mut write);
+        Box::new(write.clone())
+    };
+    if let Err(e) = tx_req.blocking_send(Req::Host(host_req)) {
+        log::error!("tx_req send err: {}", e);
+    }
+}
+
+/// 处理服务端下发的任务，采集失败时记录日志并保留上次的结果，不退出循环
 fn consume_heartbeat_rsp(
     mut rx_heartbeat_rsp: mpsc::Receiver<HeartbeatRsp>,
     tx_req: mpsc::Sender<Req>,
@@ -157,62 +426,132 @@ fn consume_heartbeat_rsp(
                 Task::UploadHost(upload_host) => match upload_host.info_type() {
                     InfoType::System => {
                         log::info!("upload system info");
-                        let system = host::system()?;
-                        if let Some(lock) = HOST_INFO.get() {
-                            let mut write = lock.write();
-                            write.system = Some(system.clone());
-                        }
-                        if let Some(lock) = HOST_INFO.get() {
-                            let read = lock.read();
-                            let host_req = HostReq {
-                                system: read.system.clone(),
-                                disks: read.disks.clone(),
-                                networks: read.networks.clone(),
-                            };
-                            if let Err(e) = tx_req.blocking_send(Req::HostReq(host_req)) {
-                                log::error!("tx_req send err: {}", e);
+                        let system = match host::system() {
+                            Ok(v) => v,
+                            Err(e) => {
+                                log::error!("host::system err: {}", e);
+                                continue;
                             }
-                        }
+                        };
+                        refresh(&tx_req, |host| host.system = Some(system));
                     }
                     InfoType::Disk => {
                         log::info!("upload disk info");
-                        let disks = host::disk()?;
-                        if let Some(lock) = HOST_INFO.get() {
-                            let mut write = lock.write();
-                            write.disks = disks;
-                        }
-                        if let Some(lock) = HOST_INFO.get() {
-                            let read = lock.read();
-                            let host_req = HostReq {
-                                system: read.system.clone(),
-                                disks: read.disks.clone(),
-                                networks: read.networks.clone(),
-                            };
-                            if let Err(e) = tx_req.blocking_send(Req::HostReq(host_req)) {
-                                log::error!("tx_req send err: {}", e);
+                        let disks = match host::disk() {
+                            Ok(v) => v,
+                            Err(e) => {
+                                log::error!("host::disk err: {}", e);
+                                continue;
                             }
-                        }
+                        };
+                        refresh(&tx_req, |host| host.disks = disks);
                     }
                     InfoType::Network => {
                         log::info!("upload network info");
-                        let networks = host::network()?;
-                        if let Some(lock) = HOST_INFO.get() {
-                            let mut write = lock.write();
-                            write.networks = networks;
-                        }
-                        if let Some(lock) = HOST_INFO.get() {
-                            let read = lock.read();
-                            let host_req = HostReq {
-                                system: read.system.clone(),
-                                disks: read.disks.clone(),
-                                networks: read.networks.clone(),
-                            };
-                            if let Err(e) = tx_req.blocking_send(Req::HostReq(host_req)) {
-                                log::error!("tx_req send err: {}", e);
+                        let networks = match host::network() {
+                            Ok(v) => v,
+                            Err(e) => {
+                                log::error!("host::network err: {}", e);
+                                continue;
                             }
-                        }
+                        };
+                        refresh(&tx_req, |host| host.networks = networks);
+                    }
+                    InfoType::Software => {
+                        log::info!("upload software info");
+                        let softwares = match software::software() {
+                            Ok(v) => v,
+                            Err(e) => {
+                                log::error!("software::software err: {}", e);
+                                continue;
+                            }
+                        };
+                        refresh(&tx_req, |host| host.softwares = softwares);
+                    }
+                    InfoType::Socket => {
+                        log::info!("upload socket info");
+                        let sockets = match socket::socket() {
+                            Ok(v) => v,
+                            Err(e) => {
+                                log::error!("socket::socket err: {}", e);
+                                continue;
+                            }
+                        };
+                        refresh(&tx_req, |host| host.sockets = sockets);
+                    }
+                    InfoType::Hardware => {
+                        log::info!("upload hardware info");
+                        let hardware = match hardware::hardware() {
+                            Ok(v) => v,
+                            Err(e) => {
+                                log::error!("hardware::hardware err: {}", e);
+                                continue;
+                            }
+                        };
+                        refresh(&tx_req, |host| host.hardware = Some(hardware));
+                    }
+                    InfoType::Account => {
+                        log::info!("upload account info");
+                        let account = match account::account() {
+                            Ok(v) => v,
+                            Err(e) => {
+                                log::error!("account::account err: {}", e);
+                                continue;
+                            }
+                        };
+                        refresh(&tx_req, |host| host.account = Some(account));
+                    }
+                    InfoType::Service => {
+                        log::info!("upload service info");
+                        let services = match service::service() {
+                            Ok(v) => v,
+                            Err(e) => {
+                                log::error!("service::service err: {}", e);
+                                continue;
+                            }
+                        };
+                        refresh(&tx_req, |host| host.services = services);
+                    }
+                    InfoType::ScheduledTask => {
+                        log::info!("upload scheduled task info");
+                        let scheduled_tasks = match scheduled_task::scheduled_task() {
+                            Ok(v) => v,
+                            Err(e) => {
+                                log::error!("scheduled_task::scheduled_task err: {}", e);
+                                continue;
+                            }
+                        };
+                        refresh(&tx_req, |host| host.scheduled_tasks = scheduled_tasks);
                     }
                 },
+                // 由 control::run 应用，不会转到这里
+                Task::Profile(_) => {}
+                Task::Upgrade(upgrade) => {
+                    log::info!("upgrade to {}", upgrade.version);
+                    if let Err(e) = tx_req.blocking_send(Req::Upgrade(upgrade)) {
+                        log::error!("tx_req send err: {}", e);
+                    }
+                }
+                Task::ServiceControl(service_control) => {
+                    let result = service::control(
+                        service_control.id.clone(),
+                        &service_control.unit,
+                        service_control.action(),
+                    );
+                    log::info!("service control result: {result:?}");
+                    if let Err(e) = tx_req.blocking_send(Req::ServiceControlResult(result)) {
+                        log::error!("tx_req send err: {}", e);
+                    }
+                    // 控制后状态已变化，重新上报服务列表
+                    let services = match service::service() {
+                        Ok(v) => v,
+                        Err(e) => {
+                            log::error!("service::service err: {}", e);
+                            continue;
+                        }
+                    };
+                    refresh(&tx_req, |host| host.services = services);
+                }
             }
         }
     }
@@ -1,16 +1,29 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use base64::{Engine, prelude::BASE64_STANDARD};
 use clap::Parser;
 use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tokio::sync::{Semaphore, watch};
 use z11n_agent::{
-    agent_register, build_client,
+    LLM_CONSUMER_STATUS, Z11nClient, agent_register, client,
     config::Z11N_AGENT_TOML,
+    connection::Backoff,
     heartbeat,
-    proto::{Empty, LlmTaskAnswer, LlmTaskQuestion},
+    proto::{Embedding, Empty, LlmConsumerStatus, LlmTaskAnswer, LlmTaskKind, LlmTaskQuestion},
 };
 
 #[derive(Parser, Debug)]
 #[command(version)]
 struct Args {}
 
+const PUSH_ANSWER_ATTEMPTS: u32 = 5;
+// 生成速度的平滑系数，越大越偏向最近一次
+const TOKENS_PER_SECOND_ALPHA: f64 = 0.3;
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let _args = Args::parse();
@@ -18,29 +31,88 @@ async fn main() -> anyhow::Result<()> {
     log4rs::init_file("./config/log4rs.yml", Default::default())?;
     log::info!("llm task consumer starting");
     agent_register().await?;
-    let (tx, rx) = tokio::sync::mpsc::channel(100);
-    tokio::spawn(async move {
-        loop {
-            let tx_clone = tx.clone();
-            if let Err(e) = pull_llm_task_question(tx_clone).await {
-                log::error!("pull_llm_task_question err: {}", e);
-            }
-            tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+    let workers = Z11N_AGENT_TOML.llm.workers.max(1);
+    log::info!("llm task consumer workers: {workers}");
+    // 每个 permit 代表一个空闲 worker，拿到 permit 才去拉取任务
+    let semaphore = Arc::new(Semaphore::new(workers));
+    let (tx_shutdown, rx_shutdown) = watch::channel(false);
+    let http_client = reqwest::ClientBuilder::new().build()?;
+    let llm_consumer_status = LlmConsumerStatus {
+        total_slots: workers as u32,
+        free_slots: workers as u32,
+        ..Default::default()
+    };
+    if let Err(e) = LLM_CONSUMER_STATUS.set(llm_consumer_status.into()) {
+        log::error!("LLM_CONSUMER_STATUS set err: {:?}", e);
+    }
+    tokio::spawn(probe_backend(semaphore.clone(), http_client.clone()));
+    let grpc_client = client()?;
+    let semaphore_clone = semaphore.clone();
+    let pull_handle = tokio::spawn(async move {
+        if let Err(e) =
+            pull_llm_task_question(semaphore_clone, http_client, grpc_client, rx_shutdown).await
+        {
+            log::error!("pull_llm_task_question err: {}", e);
         }
     });
-    tokio::spawn(async move {
-        if let Err(e) = push_llm_task_answer(rx).await {
-            log::error!("push_llm_task_answer err: {}", e);
+    tokio::select! {
+        r = heartbeat() => {
+            if let Err(e) = r {
+                log::error!("heartbeat err: {}", e);
+            }
         }
-    });
-    heartbeat().await?;
+        _ = shutdown_signal() => {
+            log::info!("llm task consumer shutdown signal received");
+        }
+    }
+    // 停止拉取新任务，等待处理中的任务完成并提交答案
+    tx_shutdown.send(true)?;
+    pull_handle.await?;
+    let drain_timeout = Duration::from_secs(Z11N_AGENT_TOML.llm.drain_timeout);
+    match tokio::time::timeout(drain_timeout, semaphore.acquire_many(workers as u32)).await {
+        Ok(_) => log::info!("llm task consumer drained"),
+        Err(_) => log::warn!(
+            "llm task consumer drain timeout, {} task(s) abandoned",
+            workers - semaphore.available_permits()
+        ),
+    }
     Ok(())
 }
 
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            log::error!("ctrl_c err: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                log::error!("signal terminate err: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct Message {
     role: String,
     content: String,
+    // ollama 多模态输入，base64 编码的图片
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    images: Vec<String>,
 }
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct ReqBody {
@@ -48,58 +120,359 @@ struct ReqBody {
     messages: Vec<Message>,
     stream: bool,
 }
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct EmbedReqBody {
+    model: String,
+    input: Vec<String>,
+}
 
-async fn push_llm_task_answer(
-    mut rx: tokio::sync::mpsc::Receiver<LlmTaskQuestion>,
+async fn process(
+    http_client: reqwest::Client,
+    mut grpc_client: Z11nClient,
+    llm_task_question: LlmTaskQuestion,
 ) -> anyhow::Result<()> {
-    while let Some(llm_task_question) = rx.recv().await {
-        let ollama_url = "http://127.0.0.1:11434/api/chat";
-        let client = reqwest::ClientBuilder::new().build()?;
-        let system_msg = Message {
-            role: "system".to_string(),
-            content: llm_task_question.prompt,
+    let id = llm_task_question.id.clone();
+    let llm_task_answer = match llm_task_question.kind() {
+        LlmTaskKind::Chat => {
+            let content = chat(&http_client, llm_task_question).await?;
+            LlmTaskAnswer {
+                id: id.clone(),
+                content,
+                embeddings: Vec::new(),
+            }
+        }
+        LlmTaskKind::Embedding => {
+            let embeddings = embed(&http_client, llm_task_question).await?;
+            LlmTaskAnswer {
+                id: id.clone(),
+                content: "".to_string(),
+                embeddings,
+            }
+        }
+    };
+    // 推理结果来之不易，提交失败时重试
+    let mut delay = Duration::from_secs(1);
+    for attempt in 1..=PUSH_ANSWER_ATTEMPTS {
+        log::info!("push_llm_task_answer task: {id}, attempt: {attempt}");
+        match grpc_client
+            .push_llm_task_answer(llm_task_answer.clone())
+            .await
+        {
+            Ok(rsp) => {
+                log::info!("rsp: {rsp:?}");
+                return Ok(());
+            }
+            Err(e) => {
+                log::error!("push_llm_task_answer task: {id} err: {}", e);
+                if attempt < PUSH_ANSWER_ATTEMPTS {
+                    tokio::time::sleep(delay).await;
+                    delay *= 2;
+                }
+            }
+        }
+    }
+    anyhow::bail!("push_llm_task_answer task: {id} failed")
+}
+
+/// 定时探测推理后端，刷新随心跳上报的状态
+async fn probe_backend(semaphore: Arc<Semaphore>, http_client: reqwest::Client) {
+    let mut interval = tokio::time::interval(Duration::from_secs(5));
+    loop {
+        interval.tick().await;
+        let models = list_models(&http_client).await;
+        if let Err(e) = &models {
+            log::warn!("list_models err: {}", e);
+        }
+        let Some(lock) = LLM_CONSUMER_STATUS.get() else {
+            continue;
         };
+        let mut status = lock.write();
+        status.free_slots = semaphore.available_permits() as u32;
+        match models {
+            Ok((models, loaded_models)) => {
+                status.models = models;
+                status.loaded_models = loaded_models;
+                status.backend_reachable = true;
+            }
+            Err(_) => {
+                status.loaded_models.clear();
+                status.backend_reachable = false;
+            }
+        }
+    }
+}
 
-        let user_msg = Message {
-            role: "user".to_string(),
-            content: llm_task_question.content,
+/// 返回后端可用的模型和已加载的模型
+async fn list_models(client: &reqwest::Client) -> anyhow::Result<(Vec<String>, Vec<String>)> {
+    let names = |json: &serde_json::Value, key: &str, field: &str| -> Vec<String> {
+        json[key]
+            .as_array()
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|item| item[field].as_str().map(|v| v.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    if Z11N_AGENT_TOML.llm.backend.eq("openai") {
+        // openai 兼容接口没有加载状态，只能拿到模型列表
+        let json = get_json(client, "/v1/models").await?;
+        Ok((names(&json, "data", "id"), Vec::new()))
+    } else {
+        let json = get_json(client, "/api/tags").await?;
+        let models = names(&json, "models", "name");
+        let json = get_json(client, "/api/ps").await?;
+        let loaded_models = names(&json, "models", "name");
+        Ok((models, loaded_models))
+    }
+}
+
+fn record_tokens_per_second(tokens: u64, elapsed: Duration) {
+    let secs = elapsed.as_secs_f64();
+    if tokens == 0 || secs <= 0.0 {
+        return;
+    }
+    let current = tokens as f64 / secs;
+    if let Some(lock) = LLM_CONSUMER_STATUS.get() {
+        let mut status = lock.write();
+        status.tokens_per_second = if status.tokens_per_second > 0.0 {
+            TOKENS_PER_SECOND_ALPHA * current
+                + (1.0 - TOKENS_PER_SECOND_ALPHA) * status.tokens_per_second
+        } else {
+            current
         };
-        let req_body = ReqBody {
-            model: llm_task_question.model,
-            messages: [system_msg.clone(), user_msg].to_vec(),
-            stream: false,
+    }
+}
+
+async fn get_json(client: &reqwest::Client, path: &str) -> anyhow::Result<serde_json::Value> {
+    let llm = &Z11N_AGENT_TOML.llm;
+    let url = format!("{}{}", llm.base_url.trim_end_matches('/'), path);
+    let mut builder = client.get(url).timeout(Duration::from_secs(3));
+    if let Some(api_key) = &llm.api_key {
+        builder = builder.header("Authorization", format!("Bearer {api_key}"));
+    }
+    let rsp = builder.send().await?.error_for_status()?;
+    let text = rsp.text().await?;
+    let json: serde_json::Value = serde_json::from_str(&text)?;
+    Ok(json)
+}
+
+async fn post_json(
+    client: &reqwest::Client,
+    path: &str,
+    req_body: serde_json::Value,
+) -> anyhow::Result<serde_json::Value> {
+    let llm = &Z11N_AGENT_TOML.llm;
+    let url = format!("{}{}", llm.base_url.trim_end_matches('/'), path);
+    let mut builder = client
+        .post(url)
+        .header("Content-Type", "application/json")
+        .body(req_body.to_string());
+    if let Some(api_key) = &llm.api_key {
+        builder = builder.header("Authorization", format!("Bearer {api_key}"));
+    }
+    let rsp = builder.send().await?;
+    let text = rsp.text().await?;
+    let json: serde_json::Value = serde_json::from_str(&text)?;
+    Ok(json)
+}
+
+async fn chat(
+    client: &reqwest::Client,
+    llm_task_question: LlmTaskQuestion,
+) -> anyhow::Result<String> {
+    let system_msg = Message {
+        role: "system".to_string(),
+        content: llm_task_question.prompt,
+        images: Vec::new(),
+    };
+
+    let user_msg = Message {
+        role: "user".to_string(),
+        content: llm_task_question.content,
+        images: llm_task_question
+            .attachments
+            .iter()
+            .map(|attachment| BASE64_STANDARD.encode(&attachment.content))
+            .collect(),
+    };
+    let req_body = ReqBody {
+        model: llm_task_question.model,
+        messages: [system_msg.clone(), user_msg].to_vec(),
+        stream: false,
+    };
+    let mut req_body = serde_json::to_value(req_body)?;
+    let content = if Z11N_AGENT_TOML.llm.backend.eq("openai") {
+        // openai 的图片放在 content 数组中，以 data url 传递
+        if !llm_task_question.attachments.is_empty() {
+            let mut parts = vec![json!({
+                "type": "text",
+                "text": req_body["messages"][1]["content"],
+            })];
+            for attachment in &llm_task_question.attachments {
+                parts.push(json!({
+                    "type": "image_url",
+                    "image_url": {
+                        "url": format!(
+                            "data:{};base64,{}",
+                            attachment.mime_type,
+                            BASE64_STANDARD.encode(&attachment.content)
+                        ),
+                    },
+                }));
+            }
+            req_body["messages"][1] = json!({
+                "role": "user",
+                "content": parts,
+            });
+        }
+        let start = Instant::now();
+        let json = post_json(client, "/v1/chat/completions", req_body).await?;
+        let tokens = json["usage"]["completion_tokens"]
+            .as_u64()
+            .unwrap_or_default();
+        record_tokens_per_second(tokens, start.elapsed());
+        json["choices"][0]["message"]["content"]
+            .as_str()
+            .map(|v| v.to_string())
+    } else {
+        let json = post_json(client, "/api/chat", req_body).await?;
+        // eval_duration 单位为纳秒
+        let tokens = json["eval_count"].as_u64().unwrap_or_default();
+        let elapsed = Duration::from_nanos(json["eval_duration"].as_u64().unwrap_or_default());
+        record_tokens_per_second(tokens, elapsed);
+        json["message"]["content"].as_str().map(|v| v.to_string())
+    };
+    content.ok_or_else(|| anyhow::anyhow!("task {} chat content not found", llm_task_question.id))
+}
+
+async fn embed(
+    client: &reqwest::Client,
+    llm_task_question: LlmTaskQuestion,
+) -> anyhow::Result<Vec<Embedding>> {
+    let req_body = EmbedReqBody {
+        model: llm_task_question.model,
+        input: llm_task_question.inputs,
+    };
+    let req_body = serde_json::to_value(req_body)?;
+    let openai = Z11N_AGENT_TOML.llm.backend.eq("openai");
+    let path = if openai {
+        "/v1/embeddings"
+    } else {
+        "/api/embed"
+    };
+    let json = post_json(client, path, req_body).await?;
+    match embeddings(&json, openai)? {
+        Some(v) => Ok(v),
+        None => anyhow::bail!("task {} embeddings not found", llm_task_question.id),
+    }
+}
+
+/// 解析后端返回的向量，按输入的顺序排列
+fn embeddings(json: &serde_json::Value, openai: bool) -> anyhow::Result<Option<Vec<Embedding>>> {
+    let vectors = if openai {
+        // {"data":[{"index":0,"embedding":[...]}]}
+        let Some(items) = json["data"].as_array() else {
+            return Ok(None);
         };
-        let req_body = serde_json::to_value(req_body)?;
-
-        let rsp = client
-            .post(ollama_url)
-            .body(req_body.to_string())
-            .send()
-            .await?;
-        let text = rsp.text().await?;
-        let json: serde_json::Value = serde_json::from_str(&text)?;
-        if let Some(content) = json["message"]["content"].as_str() {
-            let mut client = build_client(&Z11N_AGENT_TOML.server.addr).await?;
-            let llm_task_answer = LlmTaskAnswer {
-                id: llm_task_question.id.clone(),
-                content: content.to_string(),
-            };
-            log::info!("push_llm_task_answer task: {}", llm_task_question.id);
-            let rsp = client.push_llm_task_answer(llm_task_answer).await?;
-            log::info!("rsp: {rsp:?}");
+        let mut items = items.clone();
+        items.sort_by_key(|item| item["index"].as_u64().unwrap_or_default());
+        items
+            .iter()
+            .map(|item| item["embedding"].clone())
+            .collect::<Vec<_>>()
+    } else {
+        // {"embeddings":[[...]]}
+        match json["embeddings"].as_array() {
+            Some(v) => v.clone(),
+            None => return Ok(None),
         }
+    };
+    let mut embeddings = Vec::new();
+    for vector in vectors {
+        let values: Vec<f32> = serde_json::from_value(vector)?;
+        embeddings.push(Embedding { values });
     }
-    Ok(())
+    Ok(Some(embeddings))
 }
 
 async fn pull_llm_task_question(
-    tx: tokio::sync::mpsc::Sender<LlmTaskQuestion>,
+    semaphore: Arc<Semaphore>,
+    http_client: reqwest::Client,
+    mut grpc_client: Z11nClient,
+    mut rx_shutdown: watch::Receiver<bool>,
 ) -> anyhow::Result<()> {
-    let mut client = build_client(&Z11N_AGENT_TOML.server.addr).await?;
-    let rsp = client.pull_llm_task_question(Empty {}).await?;
-    if let Some(llm_task_question) = &rsp.get_ref().llm_task_question {
-        log::info!("llm_task_question: {llm_task_question:?}");
-        tx.send(llm_task_question.clone()).await?;
+    let mut backoff = Backoff::reconnect();
+    loop {
+        let permit = tokio::select! {
+            permit = semaphore.clone().acquire_owned() => permit?,
+            _ = rx_shutdown.changed() => break,
+        };
+        if *rx_shutdown.borrow() {
+            break;
+        }
+        let llm_task_question = match grpc_client.pull_llm_task_question(Empty {}).await {
+            Ok(rsp) => {
+                backoff.reset();
+                rsp.into_inner().llm_task_question
+            }
+            Err(e) => {
+                log::error!("pull_llm_task_question err: {}", e);
+                // 服务端不可达时退避，避免频繁重连
+                drop(permit);
+                tokio::select! {
+                    _ = backoff.wait() => {}
+                    _ = rx_shutdown.changed() => break,
+                }
+                continue;
+            }
+        };
+        match llm_task_question {
+            Some(llm_task_question) => {
+                log::info!("llm_task_question: {}", llm_task_question.id);
+                let http_client = http_client.clone();
+                let grpc_client = grpc_client.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = process(http_client, grpc_client, llm_task_question).await {
+                        log::error!("process err: {}", e);
+                    }
+                    drop(permit);
+                });
+            }
+            None => {
+                drop(permit);
+                tokio::select! {
+                    _ = tokio::time::sleep(Duration::from_secs(1)) => {}
+                    _ = rx_shutdown.changed() => break,
+                }
+            }
+        }
     }
+    log::info!("pull_llm_task_question stopped");
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn embeddings_test() -> anyhow::Result<()> {
+        let json = json!({"embeddings": [[0.1, 0.2], [0.3, 0.4]]});
+        let r = embeddings(&json, false)?.unwrap_or_default();
+        assert_eq!(r.len(), 2);
+        assert_eq!(r[1].values, vec![0.3, 0.4]);
+        // openai 返回的顺序不保证与输入一致
+        let json = json!({"data": [
+            {"index": 1, "embedding": [0.3, 0.4]},
+            {"index": 0, "embedding": [0.1, 0.2]},
+        ]});
+        let r = embeddings(&json, true)?.unwrap_or_default();
+        assert_eq!(r[0].values, vec![0.1, 0.2]);
+        assert_eq!(r[1].values, vec![0.3, 0.4]);
+        assert!(embeddings(&json!({"error": "model not found"}), false)?.is_none());
+        assert!(embeddings(&json!({"embeddings": [["x"]]}), false).is_err());
+        Ok(())
+    }
+}
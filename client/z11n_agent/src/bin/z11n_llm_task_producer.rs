@@ -1,25 +1,52 @@
+use std::{fs, path::PathBuf};
+
 use clap::Parser;
 use z11n_agent::{
-    agent_register, build_client,
-    config::Z11N_AGENT_TOML,
-    heartbeat,
-    proto::{Empty, LlmTaskQuestionReq},
+    agent_register, client, heartbeat,
+    proto::{Attachment, Empty, LlmTaskKind, LlmTaskQuestionReq},
 };
 
 #[derive(Parser, Debug)]
 #[command(version)]
-struct Args {}
+struct Args {
+    /// 提交向量嵌入任务，默认提交对话任务
+    #[arg(long)]
+    embedding: bool,
+    /// 对话任务附带的图片路径，可多次指定
+    #[arg(long)]
+    image: Vec<PathBuf>,
+}
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    let _args = Args::parse();
+    let args = Args::parse();
 
     log4rs::init_file("./config/log4rs.yml", Default::default())?;
     log::info!("llm task producer starting");
     agent_register().await?;
+    let embedding = args.embedding;
+    let mut attachments = Vec::new();
+    for path in &args.image {
+        let mime_type = match path.extension().and_then(|v| v.to_str()) {
+            Some("png") => "image/png",
+            Some("jpg") | Some("jpeg") => "image/jpeg",
+            Some("gif") => "image/gif",
+            Some("webp") => "image/webp",
+            _ => anyhow::bail!("unsupported image: {}", path.to_string_lossy()),
+        };
+        attachments.push(Attachment {
+            mime_type: mime_type.to_string(),
+            content: fs::read(path)?,
+        });
+    }
     tokio::spawn(async move {
         loop {
-            if let Err(e) = push_llm_task_question().await {
+            let r = if embedding {
+                push_llm_task_embedding().await
+            } else {
+                push_llm_task_question(attachments.clone()).await
+            };
+            if let Err(e) = r {
                 log::error!("push_llm_task_question err: {}", e);
             }
             tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
@@ -38,16 +65,25 @@ async fn main() -> anyhow::Result<()> {
 }
 
 async fn pull_llm_task_answer() -> anyhow::Result<()> {
-    let mut client = build_client(&Z11N_AGENT_TOML.server.addr).await?;
+    let mut client = client()?;
     let empty = Empty {};
     match client.pull_llm_task_answer(empty).await {
         Ok(rsp) => {
             for llm_task_answer in &rsp.get_ref().items {
-                log::info!(
-                    "get task_id: {} answer: {}",
-                    llm_task_answer.id,
-                    llm_task_answer.content
-                );
+                if llm_task_answer.embeddings.is_empty() {
+                    log::info!(
+                        "get task_id: {} answer: {}",
+                        llm_task_answer.id,
+                        llm_task_answer.content
+                    );
+                } else {
+                    log::info!(
+                        "get task_id: {} embeddings: {}, dims: {}",
+                        llm_task_answer.id,
+                        llm_task_answer.embeddings.len(),
+                        llm_task_answer.embeddings[0].values.len()
+                    );
+                }
             }
         }
         Err(e) => {
@@ -56,15 +92,34 @@ async fn pull_llm_task_answer() -> anyhow::Result<()> {
     }
     Ok(())
 }
-async fn push_llm_task_question() -> anyhow::Result<()> {
+async fn push_llm_task_question(attachments: Vec<Attachment>) -> anyhow::Result<()> {
     let llm_task_question_req = LlmTaskQuestionReq {
         model: "gemma2:27b".to_string(),
         prompt: "你是一个资深的Rust程序员专家".to_string(),
         content: "如何遍历一个enum".to_string(),
+        attachments,
+        ..Default::default()
     };
-    let mut client = build_client(&Z11N_AGENT_TOML.server.addr).await?;
+    let mut client = client()?;
     let rsp = client.push_llm_task_question(llm_task_question_req).await?;
     let task_id = rsp.get_ref().id.clone();
     log::info!("push_llm_task_question task id: {task_id}");
     Ok(())
 }
+
+async fn push_llm_task_embedding() -> anyhow::Result<()> {
+    let llm_task_question_req = LlmTaskQuestionReq {
+        model: "nomic-embed-text".to_string(),
+        kind: LlmTaskKind::Embedding.into(),
+        inputs: vec![
+            "Rust 的所有权机制".to_string(),
+            "如何遍历一个enum".to_string(),
+        ],
+        ..Default::default()
+    };
+    let mut client = client()?;
+    let rsp = client.push_llm_task_question(llm_task_question_req).await?;
+    let task_id = rsp.get_ref().id.clone();
+    log::info!("push_llm_task_embedding task id: {task_id}");
+    Ok(())
+}
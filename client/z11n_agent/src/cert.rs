@@ -0,0 +1,102 @@
+use crate::{connection, proto::CertReq};
+use parking_lot::Mutex;
+use rcgen::{CertificateParams, KeyPair};
+use std::{fs, os::unix::fs::PermissionsExt, time::Duration};
+use tonic::{Code, Status, transport::Identity};
+
+// 服务端签发的客户端证书，SAN 为 agent 编号
+const AGENT_CRT: &str = "./config/agent.crt";
+const AGENT_KEY: &str = "./config/agent.key";
+// 检查续期的间隔，剩余有效期充足时服务端不签发
+const RENEW_INTERVAL: Duration = Duration::from_secs(12 * 3600);
+
+// 签名请求对应的私钥，收到证书前只保存在内存中
+static PENDING_KEY: Mutex<Option<String>> = Mutex::new(None);
+
+/// 生成新的私钥和签名请求
+pub fn csr() -> anyhow::Result<String> {
+    let key_pair = KeyPair::generate()?;
+    let csr = CertificateParams::default()
+        .serialize_request(&key_pair)?
+        .pem()?;
+    *PENDING_KEY.lock() = Some(key_pair.serialize_pem());
+    Ok(csr)
+}
+
+/// 保存服务端签发的证书，之后新建的连接使用该证书，为空时继续使用当前证书
+pub fn save(cert_pem: &str) -> anyhow::Result<()> {
+    if cert_pem.is_empty() {
+        return Ok(());
+    }
+    let Some(key_pem) = PENDING_KEY.lock().take() else {
+        anyhow::bail!("no pending key for the issued cert");
+    };
+    // 先写临时文件再替换，避免证书和私钥不匹配
+    let key_tmp = format!("{AGENT_KEY}.tmp");
+    let crt_tmp = format!("{AGENT_CRT}.tmp");
+    fs::write(&key_tmp, key_pem)?;
+    fs::set_permissions(&key_tmp, fs::Permissions::from_mode(0o600))?;
+    fs::write(&crt_tmp, cert_pem)?;
+    fs::rename(key_tmp, AGENT_KEY)?;
+    fs::rename(crt_tmp, AGENT_CRT)?;
+    connection::reset();
+    log::info!("client cert saved");
+    Ok(())
+}
+
+/// 本地保存的客户端证书，没有时只能注册
+pub fn identity() -> Option<Identity> {
+    let cert = fs::read(AGENT_CRT).ok()?;
+    let key = fs::read(AGENT_KEY).ok()?;
+    Some(Identity::from_pem(cert, key))
+}
+
+/// 证书已吊销或与编号不符，删除后重新注册申请
+pub fn discard() {
+    for path in [AGENT_CRT, AGENT_KEY] {
+        if let Err(e) = fs::remove_file(path) {
+            log::warn!("remove {path} err: {e}");
+        }
+    }
+    connection::reset();
+}
+
+/// 服务端拒绝了携带的证书
+pub fn rejected(status: &Status) -> bool {
+    status.code() == Code::Unauthenticated && status.message() == "client certificate invalid"
+}
+
+/// 定期续期，启动时的注册已按需签发
+pub async fn renew_task() {
+    let mut interval = tokio::time::interval(RENEW_INTERVAL);
+    interval.tick().await;
+    loop {
+        interval.tick().await;
+        if let Err(e) = renew().await {
+            log::error!("renew cert err: {}", e);
+        }
+    }
+}
+
+async fn renew() -> anyhow::Result<()> {
+    // 没有证书时由注册申请
+    if identity().is_none() {
+        return Ok(());
+    }
+    let csr = csr()?;
+    let cert_rsp = crate::client()?.renew_cert(CertReq { csr }).await?;
+    save(&cert_rsp.get_ref().cert)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csr_test() {
+        let csr = csr().unwrap();
+        assert!(csr.starts_with("-----BEGIN CERTIFICATE REQUEST-----"));
+        let key_pem = PENDING_KEY.lock().clone().unwrap();
+        assert!(KeyPair::from_pem(&key_pem).is_ok());
+    }
+}
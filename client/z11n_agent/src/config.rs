@@ -13,9 +13,165 @@ pub static Z11N_AGENT_TOML: Lazy<ServerToml> = Lazy::new(|| {
 #[derive(Debug, Deserialize)]
 pub struct ServerToml {
     pub server: Server,
+    #[serde(default)]
+    pub connection: Connection,
+    #[serde(default)]
+    pub llm: Llm,
+    #[serde(default)]
+    pub metrics: Metrics,
+    #[serde(default)]
+    pub artifact: Artifact,
+    #[serde(default)]
+    pub outbox: Outbox,
+    #[serde(default)]
+    pub enrollment: Enrollment,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct Server {
     pub addr: String,
+    // 服务端只开放注册、不强制客户端证书的端口，没有证书时在该端口注册
+    #[serde(default)]
+    pub bootstrap_addr: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct Connection {
+    // 校验服务端证书时使用的域名，需在证书的 SAN 中
+    pub domain_name: String,
+    // 建立连接的超时秒数
+    pub connect_timeout: u64,
+    // TCP 和 HTTP/2 keepalive 的间隔秒数
+    pub keepalive_interval: u64,
+    // HTTP/2 keepalive 等待响应的秒数，超时视为连接断开
+    pub keepalive_timeout: u64,
+    // 重连退避的初始毫秒数，每次失败翻倍
+    pub backoff_initial_ms: u64,
+    // 重连退避的上限秒数
+    pub backoff_max: u64,
+}
+
+impl Default for Connection {
+    fn default() -> Self {
+        Self {
+            domain_name: "z11n.com".to_string(),
+            connect_timeout: 10,
+            keepalive_interval: 30,
+            keepalive_timeout: 10,
+            backoff_initial_ms: 500,
+            backoff_max: 60,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct Llm {
+    // ollama 或 openai
+    pub backend: String,
+    pub base_url: String,
+    pub api_key: Option<String>,
+    // 同时处理的任务数
+    pub workers: usize,
+    // 退出时等待处理中任务的秒数
+    pub drain_timeout: u64,
+}
+
+impl Default for Llm {
+    fn default() -> Self {
+        Self {
+            backend: "ollama".to_string(),
+            base_url: "http://127.0.0.1:11434".to_string(),
+            api_key: None,
+            workers: 1,
+            drain_timeout: 300,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct Metrics {
+    // 采样间隔秒数
+    pub interval: u64,
+    // 每攒够多少个采样上报一次
+    pub batch: usize,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self {
+            interval: 10,
+            batch: 6,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct Artifact {
+    // ed25519 发布公钥，PEM 格式，用于校验下载的制品
+    pub public_key: String,
+}
+
+impl Default for Artifact {
+    fn default() -> Self {
+        Self {
+            public_key: "./config/release.pub".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct Outbox {
+    // 离线缓存上报的目录
+    pub dir: String,
+    // 缓存的字节数上限，超过时丢弃最旧的上报
+    pub max_size: u64,
+    // 服务端不可达时重试上报的间隔秒数
+    pub retry_interval: u64,
+}
+
+impl Default for Outbox {
+    fn default() -> Self {
+        Self {
+            dir: "./config/outbox".to_string(),
+            max_size: 64 * 1024 * 1024,
+            retry_interval: 10,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Enrollment {
+    // 首次注册时携带的 enrollment token，已注册的 agent 不再需要
+    pub token: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn llm_default_test() -> anyhow::Result<()> {
+        let toml = r#"
+            [server]
+            addr = "https://127.0.0.1:50051"
+
+            [llm]
+            workers = 4
+        "#;
+        let server_toml = config::Config::builder()
+            .add_source(config::File::from_str(toml, config::FileFormat::Toml))
+            .build()?
+            .try_deserialize::<ServerToml>()?;
+        assert_eq!(server_toml.llm.workers, 4);
+        // 未配置的项使用默认值
+        assert_eq!(server_toml.llm.drain_timeout, 300);
+        assert_eq!(server_toml.llm.backend, "ollama");
+        Ok(())
+    }
 }
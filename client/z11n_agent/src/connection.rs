@@ -0,0 +1,170 @@
+use crate::{cert, config::Z11N_AGENT_TOML};
+use parking_lot::RwLock;
+use rand::Rng;
+use std::{
+    fs,
+    sync::atomic::{AtomicU8, Ordering},
+    time::Duration,
+};
+use tonic::transport::{Certificate, Channel, ClientTlsConfig, Endpoint};
+
+// 进程内共享的连接，断开后由 tonic 在下次调用时重连
+static CHANNEL: RwLock<Option<Channel>> = RwLock::new(None);
+
+// 与服务端的连接状态，只在变化时打印日志
+const UNKNOWN: u8 = 0;
+const CONNECTED: u8 = 1;
+const DISCONNECTED: u8 = 2;
+static STATE: AtomicU8 = AtomicU8::new(UNKNOWN);
+
+/// 共享的 Channel，首次调用时读取 CA 和客户端证书并创建，不立即建立连接
+pub fn channel() -> anyhow::Result<Channel> {
+    if let Some(channel) = CHANNEL.read().as_ref() {
+        return Ok(channel.clone());
+    }
+    let mut guard = CHANNEL.write();
+    if let Some(channel) = guard.as_ref() {
+        return Ok(channel.clone());
+    }
+    let channel = build(&Z11N_AGENT_TOML.server.addr)?;
+    *guard = Some(channel.clone());
+    Ok(channel)
+}
+
+/// 注册使用的 Channel，还没有客户端证书时连接注册端口，只用于本次注册
+pub fn register_channel() -> anyhow::Result<Channel> {
+    match &Z11N_AGENT_TOML.server.bootstrap_addr {
+        Some(addr) if cert::identity().is_none() => build(addr),
+        _ => channel(),
+    }
+}
+
+/// 客户端证书变化后丢弃共享的 Channel，下次调用时重建，已建立的长连接不受影响
+pub fn reset() {
+    *CHANNEL.write() = None;
+}
+
+fn build(addr: &str) -> anyhow::Result<Channel> {
+    let connection_config = &Z11N_AGENT_TOML.connection;
+    // 信任签发服务端证书的中间 CA，旧部署中的服务端证书存在时一并信任
+    let mut pem = fs::read("./config/sub-ca.crt")?;
+    if let Ok(v) = fs::read("./config/z11n-ca.crt") {
+        pem.extend_from_slice(&v);
+    }
+    let ca = Certificate::from_pem(pem);
+    let mut tls = ClientTlsConfig::new()
+        .ca_certificate(ca)
+        .domain_name(connection_config.domain_name.clone());
+    // 首次注册前还没有客户端证书
+    if let Some(identity) = cert::identity() {
+        tls = tls.identity(identity);
+    }
+    let channel = Endpoint::from_shared(addr.to_string())?
+        .tls_config(tls)?
+        .connect_timeout(Duration::from_secs(connection_config.connect_timeout))
+        .tcp_keepalive(Some(Duration::from_secs(
+            connection_config.keepalive_interval,
+        )))
+        .http2_keep_alive_interval(Duration::from_secs(connection_config.keepalive_interval))
+        .keep_alive_timeout(Duration::from_secs(connection_config.keepalive_timeout))
+        .keep_alive_while_idle(true)
+        .connect_lazy();
+    log::info!("channel to {addr} created");
+    Ok(channel)
+}
+
+/// 调用成功后记录已连接
+pub fn connected() {
+    if STATE.swap(CONNECTED, Ordering::Relaxed) != CONNECTED {
+        log::info!("connected to {}", Z11N_AGENT_TOML.server.addr);
+    }
+}
+
+/// 调用失败后记录连接断开
+pub fn disconnected(reason: impl std::fmt::Display) {
+    if STATE.swap(DISCONNECTED, Ordering::Relaxed) != DISCONNECTED {
+        log::warn!(
+            "disconnected from {}: {reason}",
+            Z11N_AGENT_TOML.server.addr
+        );
+    }
+}
+
+/// 连接失败、超时或被取消，服务端没有处理请求
+pub fn unreachable(status: &tonic::Status) -> bool {
+    matches!(
+        status.code(),
+        tonic::Code::Unavailable | tonic::Code::DeadlineExceeded | tonic::Code::Cancelled
+    )
+}
+
+/// 带抖动的指数退避，每次失败后等待时间翻倍，不超过上限
+pub struct Backoff {
+    initial: Duration,
+    max: Duration,
+    current: Duration,
+}
+
+impl Backoff {
+    pub fn new(initial: Duration, max: Duration) -> Self {
+        Self {
+            initial,
+            max,
+            current: initial,
+        }
+    }
+
+    /// 按配置创建重连退避
+    pub fn reconnect() -> Self {
+        let connection_config = &Z11N_AGENT_TOML.connection;
+        Self::new(
+            Duration::from_millis(connection_config.backoff_initial_ms.max(1)),
+            Duration::from_secs(connection_config.backoff_max.max(1)),
+        )
+    }
+
+    /// 下一次等待时间，在 [current/2, current] 之间随机，避免大量 agent 同时重连
+    pub fn next_delay(&mut self) -> Duration {
+        let current = self.current;
+        self.current = (self.current * 2).min(self.max);
+        let half = current / 2;
+        half + rand::thread_rng().gen_range(Duration::ZERO..=current - half)
+    }
+
+    pub fn reset(&mut self) {
+        self.current = self.initial;
+    }
+
+    pub async fn wait(&mut self) {
+        tokio::time::sleep(self.next_delay()).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_test() {
+        let mut backoff = Backoff::new(Duration::from_millis(100), Duration::from_secs(1));
+        for max in [100, 200, 400, 800, 1000, 1000] {
+            let delay = backoff.next_delay();
+            assert!(delay >= Duration::from_millis(max / 2), "{delay:?}");
+            assert!(delay <= Duration::from_millis(max), "{delay:?}");
+        }
+        backoff.reset();
+        assert!(backoff.next_delay() <= Duration::from_millis(100));
+    }
+
+    #[test]
+    fn unreachable_test() {
+        assert!(unreachable(&tonic::Status::unavailable(
+            "connection refused"
+        )));
+        assert!(unreachable(&tonic::Status::deadline_exceeded("timeout")));
+        assert!(!unreachable(&tonic::Status::internal("save host err")));
+        assert!(!unreachable(&tonic::Status::unauthenticated(
+            "invalid enrollment token"
+        )));
+    }
+}
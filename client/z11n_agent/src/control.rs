@@ -0,0 +1,210 @@
+use crate::{
+    LLM_CONSUMER_STATUS,
+    connection::{self, Backoff},
+    profile,
+    proto::{
+        AgentProfile, ControlReq, HeartbeatReq, HeartbeatRsp, control_req, control_rsp,
+        heartbeat_rsp::Task,
+    },
+};
+use parking_lot::Mutex;
+use std::{collections::HashMap, sync::Arc, time::Duration};
+use tokio::sync::{mpsc, oneshot};
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::Status;
+
+// 等待服务端确认任务结果的时间，超时后由离线缓存重发
+const ACK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 与服务端的长连接，任务结果通过它上报并等待服务端确认
+#[derive(Clone, Default)]
+pub struct Control {
+    inner: Arc<Mutex<Inner>>,
+}
+
+#[derive(Default)]
+struct Inner {
+    tx: Option<mpsc::Sender<ControlReq>>,
+    // 等待确认的 report_id
+    pending: HashMap<String, oneshot::Sender<()>>,
+}
+
+impl Control {
+    pub fn is_connected(&self) -> bool {
+        self.inner.lock().tx.is_some()
+    }
+
+    fn attach(&self, tx: mpsc::Sender<ControlReq>) {
+        self.inner.lock().tx = Some(tx);
+    }
+
+    /// 连接断开，等待确认的上报按失败处理
+    fn detach(&self) {
+        let mut inner = self.inner.lock();
+        inner.tx = None;
+        inner.pending.clear();
+    }
+
+    fn ack(&self, report_id: &str) {
+        if let Some(tx_ack) = self.inner.lock().pending.remove(report_id) {
+            let _ = tx_ack.send(());
+        }
+    }
+
+    /// 通过长连接上报任务结果，服务端入库确认后返回
+    pub async fn report(&self, report_id: String, msg: control_req::Msg) -> Result<(), Status> {
+        let (tx_ack, rx_ack) = oneshot::channel();
+        let tx = {
+            let mut inner = self.inner.lock();
+            let Some(tx) = inner.tx.clone() else {
+                return Err(Status::unavailable("control stream not connected"));
+            };
+            inner.pending.insert(report_id.clone(), tx_ack);
+            tx
+        };
+        let control_req = ControlReq {
+            report_id: report_id.clone(),
+            msg: Some(msg),
+        };
+        let r = async {
+            tx.send(control_req)
+                .await
+                .map_err(|_| Status::unavailable("control stream closed"))?;
+            match tokio::time::timeout(ACK_TIMEOUT, rx_ack).await {
+                Ok(Ok(())) => Ok(()),
+                Ok(Err(_)) => Err(Status::unavailable("control stream closed")),
+                Err(_) => Err(Status::deadline_exceeded("report not acked")),
+            }
+        }
+        .await;
+        if r.is_err() {
+            self.inner.lock().pending.remove(&report_id);
+        }
+        r
+    }
+}
+
+fn heartbeat_req() -> ControlReq {
+    let profile = profile::current();
+    ControlReq {
+        report_id: String::new(),
+        msg: Some(control_req::Msg::Heartbeat(HeartbeatReq {
+            llm_consumer_status: LLM_CONSUMER_STATUS.get().map(|lock| lock.read().clone()),
+            profile_id: profile.id,
+            profile_version: profile.version,
+        })),
+    }
+}
+
+/// 保持与服务端的长连接，按配置模板的间隔发送心跳，收到的任务转给 tx_task，断开后退避重连
+pub async fn run(
+    control: Control,
+    tx_task: Option<mpsc::Sender<HeartbeatRsp>>,
+) -> anyhow::Result<()> {
+    let mut client = crate::client()?;
+    let mut backoff = Backoff::reconnect();
+    let mut rx_profile = profile::subscribe();
+    loop {
+        let (tx, rx) = mpsc::channel(100);
+        // 第一条消息为心跳，服务端据此刷新在线状态
+        tx.send(heartbeat_req()).await?;
+        let mut inbound = match client.control(ReceiverStream::new(rx)).await {
+            Ok(rsp) => rsp.into_inner(),
+            Err(e) => {
+                log::error!("control api err: {}", e);
+                connection::disconnected(&e);
+                backoff.wait().await;
+                continue;
+            }
+        };
+        connection::connected();
+        backoff.reset();
+        control.attach(tx.clone());
+        let mut interval = heartbeat_interval(&rx_profile.borrow_and_update());
+        let closed = loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    if tx.send(heartbeat_req()).await.is_err() {
+                        break "outbound closed".to_string();
+                    }
+                }
+                Ok(()) = rx_profile.changed() => {
+                    interval = heartbeat_interval(&rx_profile.borrow_and_update());
+                    // 立即上报新的模板版本，服务端据此停止重复下发
+                    if tx.send(heartbeat_req()).await.is_err() {
+                        break "outbound closed".to_string();
+                    }
+                }
+                msg = inbound.message() => match msg {
+                    Ok(Some(control_rsp)) => match control_rsp.msg {
+                        Some(control_rsp::Msg::Task(HeartbeatRsp {
+                            task: Some(Task::Profile(agent_profile)),
+                        })) => {
+                            if let Err(e) = profile::apply(agent_profile) {
+                                log::error!("apply profile err: {}", e);
+                            }
+                        }
+                        Some(control_rsp::Msg::Task(heartbeat_rsp)) => {
+                            log::info!("heartbeat_rsp: {heartbeat_rsp:?}");
+                            if let Some(tx_task) = &tx_task
+                                && let Err(e) = tx_task.send(heartbeat_rsp).await
+                            {
+                                control.detach();
+                                return Err(e.into());
+                            }
+                        }
+                        Some(control_rsp::Msg::Ack(report_id)) => control.ack(&report_id),
+                        None => {}
+                    },
+                    Ok(None) => break "closed by server".to_string(),
+                    Err(e) => break e.to_string(),
+                }
+            }
+        };
+        control.detach();
+        log::error!("control stream {closed}");
+        connection::disconnected(&closed);
+        backoff.wait().await;
+    }
+}
+
+fn heartbeat_interval(agent_profile: &AgentProfile) -> tokio::time::Interval {
+    let period = profile::heartbeat_interval(agent_profile);
+    // 首次 tick 在一个间隔之后，连接时已发送过心跳
+    tokio::time::interval_at(tokio::time::Instant::now() + period, period)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::ServiceControlResult;
+
+    fn result() -> control_req::Msg {
+        control_req::Msg::ServiceControlResult(ServiceControlResult::default())
+    }
+
+    #[tokio::test]
+    async fn report_test() {
+        let control = Control::default();
+        let r = control.report("1".to_string(), result()).await;
+        assert_eq!(r.unwrap_err().code(), tonic::Code::Unavailable);
+
+        let (tx, mut rx) = mpsc::channel(1);
+        control.attach(tx);
+        let server = control.clone();
+        tokio::spawn(async move {
+            while let Some(control_req) = rx.recv().await {
+                // 确认 2，收到其他上报时模拟连接断开
+                if control_req.report_id == "2" {
+                    server.ack(&control_req.report_id);
+                } else {
+                    server.detach();
+                }
+            }
+        });
+        assert!(control.report("2".to_string(), result()).await.is_ok());
+        let r = control.report("3".to_string(), result()).await;
+        assert_eq!(r.unwrap_err().code(), tonic::Code::Unavailable);
+        assert!(!control.is_connected());
+    }
+}
@@ -0,0 +1,409 @@
+use crate::proto::{BlockDevice, HardwareInfo, PciDevice, UsbDevice};
+use std::{
+    collections::{BTreeSet, HashMap},
+    fs,
+    path::Path,
+};
+
+const DMI_ID: &str = "/sys/class/dmi/id";
+// dmi type 17 为内存设备
+const DMI_MEMORY_DEVICE: &str = "/sys/firmware/dmi/entries";
+const PCI_DEVICES: &str = "/sys/bus/pci/devices";
+const USB_DEVICES: &str = "/sys/bus/usb/devices";
+const BLOCK_DEVICES: &str = "/sys/block";
+const PCI_IDS: [&str; 3] = [
+    "/usr/share/hwdata/pci.ids",
+    "/usr/share/misc/pci.ids",
+    "/usr/share/pci.ids",
+];
+
+/// 采集 dmi、cpu、内存条、pci、usb 和块设备信息，并推测虚拟化类型与云厂商
+pub fn hardware() -> anyhow::Result<HardwareInfo> {
+    let dmi = |name: &str| read_trimmed(Path::new(DMI_ID).join(name));
+    let mut hardware = HardwareInfo {
+        sys_vendor: dmi("sys_vendor"),
+        product_name: dmi("product_name"),
+        product_version: dmi("product_version"),
+        product_serial: dmi("product_serial"),
+        board_vendor: dmi("board_vendor"),
+        board_name: dmi("board_name"),
+        bios_vendor: dmi("bios_vendor"),
+        bios_version: dmi("bios_version"),
+        bios_date: dmi("bios_date"),
+        chassis_vendor: dmi("chassis_vendor"),
+        chassis_serial: dmi("chassis_serial"),
+        chassis_asset_tag: dmi("chassis_asset_tag"),
+        memory_modules: memory_modules(),
+        pci_devices: pci_devices(),
+        usb_devices: usb_devices(),
+        block_devices: block_devices(),
+        ..Default::default()
+    };
+    let cpuinfo = fs::read_to_string("/proc/cpuinfo").unwrap_or_default();
+    let cpu = parse_cpuinfo(&cpuinfo);
+    hardware.cpu_model = cpu.model;
+    hardware.cpu_sockets = cpu.sockets;
+    hardware.cpu_cores = cpu.cores;
+    hardware.cpu_threads = cpu.threads;
+    // cpuinfo_max_freq 单位为 kHz
+    hardware.cpu_frequency = read_trimmed("/sys/devices/system/cpu/cpu0/cpufreq/cpuinfo_max_freq")
+        .and_then(|v| v.parse::<u64>().ok())
+        .map(|v| v / 1000)
+        .unwrap_or(cpu.frequency);
+    let container = Path::new("/.dockerenv").exists() || Path::new("/run/.containerenv").exists();
+    hardware.virtualization = guess_virtualization(&hardware, cpu.hypervisor, container);
+    hardware.cloud_provider = guess_cloud_provider(&hardware);
+    Ok(hardware)
+}
+
+#[derive(Debug, Default)]
+struct Cpu {
+    model: Option<String>,
+    sockets: u32,
+    cores: u32,
+    threads: u32,
+    frequency: u64,
+    hypervisor: bool,
+}
+
+fn parse_cpuinfo(cpuinfo: &str) -> Cpu {
+    let mut cpu = Cpu::default();
+    let mut sockets = BTreeSet::new();
+    let mut cores = BTreeSet::new();
+    let mut physical_id = "";
+    for line in cpuinfo.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "processor" => cpu.threads += 1,
+            "model name" if cpu.model.is_none() => cpu.model = Some(value.to_string()),
+            "physical id" => {
+                physical_id = value;
+                sockets.insert(value);
+            }
+            "core id" => {
+                cores.insert((physical_id, value));
+            }
+            "cpu MHz" if cpu.frequency == 0 => {
+                cpu.frequency = value.parse::<f64>().map(|v| v as u64).unwrap_or_default()
+            }
+            "flags" => cpu.hypervisor |= value.split_whitespace().any(|v| v == "hypervisor"),
+            _ => {}
+        }
+    }
+    // 部分虚拟机和 arm 平台不提供 physical id、core id
+    cpu.sockets = (sockets.len() as u32).max(1);
+    cpu.cores = if cores.is_empty() {
+        cpu.threads
+    } else {
+        cores.len() as u32
+    };
+    cpu
+}
+
+/// dmi type 17 记录中 0x0C 处为容量，0 表示该插槽未安装内存
+fn memory_modules() -> Option<u32> {
+    let entries = fs::read_dir(DMI_MEMORY_DEVICE).ok()?;
+    let mut count = 0;
+    let mut readable = false;
+    for entry in entries.flatten() {
+        if !entry.file_name().to_string_lossy().starts_with("17-") {
+            continue;
+        }
+        let Ok(raw) = fs::read(entry.path().join("raw")) else {
+            continue;
+        };
+        readable = true;
+        if raw
+            .get(0x0C..0x0E)
+            .is_some_and(|v| u16::from_le_bytes([v[0], v[1]]) != 0)
+        {
+            count += 1;
+        }
+    }
+    readable.then_some(count)
+}
+
+fn pci_devices() -> Vec<PciDevice> {
+    let mut r = Vec::new();
+    let Ok(entries) = fs::read_dir(PCI_DEVICES) else {
+        return r;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let hex = |name: &str| {
+            read_trimmed(path.join(name)).map(|v| v.trim_start_matches("0x").to_string())
+        };
+        let (Some(vendor_id), Some(device_id)) = (hex("vendor"), hex("device")) else {
+            continue;
+        };
+        let driver = fs::read_link(path.join("driver"))
+            .ok()
+            .and_then(|v| v.file_name().map(|v| v.to_string_lossy().to_string()));
+        r.push(PciDevice {
+            slot: entry.file_name().to_string_lossy().to_string(),
+            class_id: hex("class").unwrap_or_default(),
+            vendor_id,
+            device_id,
+            vendor: None,
+            device: None,
+            driver,
+        });
+    }
+    r.sort_by(|a, b| a.slot.cmp(&b.slot));
+    if let Some(content) = PCI_IDS.iter().find_map(|v| fs::read_to_string(v).ok()) {
+        let names = parse_ids(&content);
+        for pci_device in &mut r {
+            pci_device.vendor = names.get(&(pci_device.vendor_id.clone(), None)).cloned();
+            pci_device.device = names
+                .get(&(
+                    pci_device.vendor_id.clone(),
+                    Some(pci_device.device_id.clone()),
+                ))
+                .cloned();
+        }
+    }
+    r
+}
+
+/// 解析 pci.ids 格式：顶格为厂商，一个 tab 缩进为设备，两个 tab 缩进的子系统忽略
+fn parse_ids(content: &str) -> HashMap<(String, Option<String>), String> {
+    let mut r = HashMap::new();
+    let mut vendor_id: Option<String> = None;
+    for line in content.lines() {
+        if line.starts_with('#') || line.trim().is_empty() || line.starts_with("\t\t") {
+            continue;
+        }
+        if let Some(line) = line.strip_prefix('\t') {
+            if let (Some(vendor_id), Some((device_id, name))) = (&vendor_id, line.split_once("  "))
+            {
+                r.insert(
+                    (vendor_id.clone(), Some(device_id.to_lowercase())),
+                    name.trim().to_string(),
+                );
+            }
+            continue;
+        }
+        // 文件末尾的设备类别列表以 "C " 开头
+        if line.starts_with("C ") {
+            break;
+        }
+        vendor_id = match line.split_once("  ") {
+            Some((id, name)) => {
+                let id = id.to_lowercase();
+                r.insert((id.clone(), None), name.trim().to_string());
+                Some(id)
+            }
+            None => None,
+        };
+    }
+    r
+}
+
+fn usb_devices() -> Vec<UsbDevice> {
+    let mut r = Vec::new();
+    let Ok(entries) = fs::read_dir(USB_DEVICES) else {
+        return r;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let (Some(vendor_id), Some(product_id)) = (
+            read_trimmed(path.join("idVendor")),
+            read_trimmed(path.join("idProduct")),
+        ) else {
+            continue;
+        };
+        r.push(UsbDevice {
+            bus_id: entry.file_name().to_string_lossy().to_string(),
+            vendor_id,
+            product_id,
+            manufacturer: read_trimmed(path.join("manufacturer")),
+            product: read_trimmed(path.join("product")),
+        });
+    }
+    r.sort_by(|a, b| a.bus_id.cmp(&b.bus_id));
+    r
+}
+
+fn block_devices() -> Vec<BlockDevice> {
+    let mut r = Vec::new();
+    let Ok(entries) = fs::read_dir(BLOCK_DEVICES) else {
+        return r;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        // 跳过 loop、内存盘等虚拟块设备
+        if ["loop", "ram", "zram", "dm-", "md", "nbd"]
+            .iter()
+            .any(|v| name.starts_with(v))
+        {
+            continue;
+        }
+        let path = entry.path();
+        // size 以 512 字节扇区为单位
+        let size = read_trimmed(path.join("size"))
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or_default()
+            * 512;
+        r.push(BlockDevice {
+            vendor: read_trimmed(path.join("device/vendor")),
+            model: read_trimmed(path.join("device/model")),
+            serial: read_trimmed(path.join("device/serial"))
+                .or_else(|| read_trimmed(path.join("serial"))),
+            size,
+            rotational: read_trimmed(path.join("queue/rotational")).as_deref() == Some("1"),
+            removable: read_trimmed(path.join("removable")).as_deref() == Some("1"),
+            name,
+        });
+    }
+    r.sort_by(|a, b| a.name.cmp(&b.name));
+    r
+}
+
+fn guess_virtualization(hardware: &HardwareInfo, hypervisor: bool, container: bool) -> String {
+    if container {
+        return "container".to_string();
+    }
+    let dmi = [
+        &hardware.sys_vendor,
+        &hardware.product_name,
+        &hardware.bios_vendor,
+        &hardware.board_vendor,
+    ]
+    .iter()
+    .filter_map(|v| v.as_deref())
+    .collect::<Vec<&str>>()
+    .join(" ")
+    .to_lowercase();
+    let r = [
+        ("vmware", "vmware"),
+        ("virtualbox", "virtualbox"),
+        ("innotek", "virtualbox"),
+        ("qemu", "kvm"),
+        ("kvm", "kvm"),
+        ("amazon ec2", "kvm"),
+        ("google", "kvm"),
+        ("openstack", "kvm"),
+        ("alibaba cloud", "kvm"),
+        ("xen", "xen"),
+        ("parallels", "parallels"),
+        ("bochs", "bochs"),
+    ]
+    .iter()
+    .find(|(keyword, _)| dmi.contains(keyword))
+    .map(|(_, virtualization)| *virtualization);
+    match r {
+        Some(v) => v.to_string(),
+        None if dmi.contains("microsoft corporation")
+            && hardware
+                .product_name
+                .as_deref()
+                .is_some_and(|v| v.contains("Virtual Machine")) =>
+        {
+            "hyper-v".to_string()
+        }
+        None if hypervisor => "unknown".to_string(),
+        None => "none".to_string(),
+    }
+}
+
+fn guess_cloud_provider(hardware: &HardwareInfo) -> Option<String> {
+    let contains = |v: &Option<String>, keyword: &str| {
+        v.as_deref()
+            .is_some_and(|v| v.to_lowercase().contains(keyword))
+    };
+    let r = if contains(&hardware.sys_vendor, "amazon")
+        || contains(&hardware.bios_version, "amazon")
+    {
+        "aws"
+    } else if contains(&hardware.product_name, "google compute engine") {
+        "gcp"
+    } else if hardware.chassis_asset_tag.as_deref() == Some("7783-7084-3265-9085-8269-3286-77") {
+        // azure 虚拟机固定的资产标签
+        "azure"
+    } else if contains(&hardware.sys_vendor, "alibaba cloud") {
+        "aliyun"
+    } else if contains(&hardware.sys_vendor, "tencent cloud") {
+        "tencent"
+    } else if contains(&hardware.sys_vendor, "huawei cloud") {
+        "huawei"
+    } else if contains(&hardware.sys_vendor, "digitalocean") {
+        "digitalocean"
+    } else if contains(&hardware.sys_vendor, "hetzner") {
+        "hetzner"
+    } else if contains(&hardware.product_name, "openstack")
+        || contains(&hardware.sys_vendor, "openstack")
+    {
+        "openstack"
+    } else {
+        return None;
+    };
+    Some(r.to_string())
+}
+
+/// 读取 sysfs 文件，无权限、不存在或内容为空时返回 None
+fn read_trimmed(path: impl AsRef<Path>) -> Option<String> {
+    let v = fs::read_to_string(path).ok()?;
+    let v = v.trim();
+    (!v.is_empty()).then(|| v.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_cpuinfo_test() {
+        let cpuinfo = "processor\t: 0\nmodel name\t: Intel(R) Xeon(R)\ncpu MHz\t\t: 2400.000\nphysical id\t: 0\ncore id\t\t: 0\nflags\t\t: fpu hypervisor\n\nprocessor\t: 1\nmodel name\t: Intel(R) Xeon(R)\nphysical id\t: 0\ncore id\t\t: 0\n\nprocessor\t: 2\nphysical id\t: 1\ncore id\t\t: 0\n";
+        let cpu = parse_cpuinfo(cpuinfo);
+        assert_eq!(cpu.model.as_deref(), Some("Intel(R) Xeon(R)"));
+        assert_eq!(cpu.sockets, 2);
+        assert_eq!(cpu.cores, 2);
+        assert_eq!(cpu.threads, 3);
+        assert_eq!(cpu.frequency, 2400);
+        assert!(cpu.hypervisor);
+    }
+
+    #[test]
+    fn guess_test() {
+        let hardware = HardwareInfo {
+            sys_vendor: Some("Amazon EC2".to_string()),
+            product_name: Some("m5.large".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(guess_virtualization(&hardware, true, false), "kvm");
+        assert_eq!(guess_cloud_provider(&hardware).as_deref(), Some("aws"));
+        let hardware = HardwareInfo {
+            sys_vendor: Some("Dell Inc.".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(guess_virtualization(&hardware, false, false), "none");
+        assert_eq!(guess_cloud_provider(&hardware), None);
+    }
+
+    #[test]
+    fn parse_ids_test() {
+        let content = "# comment\n8086  Intel Corporation\n\t1237  440FX - 82441FX PMC [Natoma]\n\t\t1af4 1100  Qemu virtual machine\n1af4  Red Hat, Inc.\n\t1000  Virtio network device\nC 00  Unclassified device\n";
+        let names = parse_ids(content);
+        assert_eq!(
+            names.get(&("8086".to_string(), None)).map(|v| v.as_str()),
+            Some("Intel Corporation")
+        );
+        assert_eq!(
+            names
+                .get(&("1af4".to_string(), Some("1000".to_string())))
+                .map(|v| v.as_str()),
+            Some("Virtio network device")
+        );
+    }
+
+    #[test]
+    fn hardware_test() -> anyhow::Result<()> {
+        let _ = tracing_subscriber::fmt().with_ansi(true).try_init();
+        let hardware = hardware()?;
+        log::info!("{hardware:?}");
+        Ok(())
+    }
+}
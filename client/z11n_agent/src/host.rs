@@ -1,9 +1,15 @@
-use crate::proto::{DiskInfo, NetworkInfo, ProcessInfo, SystemInfo};
-use sysinfo::{Disks, Networks, System};
+use crate::proto::{
+    DiskInfo, HostDiffReq, HostReq, NetworkInfo, ProcessInfo, SystemInfo, upload_host::InfoType,
+};
+use std::fs;
+use sysinfo::{Disks, Networks, System, Users};
 
 pub fn system() -> anyhow::Result<SystemInfo> {
     let mut system = System::new_all();
+    // cpu 使用率为两次刷新之间的差值
+    std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
     system.refresh_all();
+    let users = Users::new_with_refreshed_list();
     log::info!("{system:?}");
     let name = System::name();
     let kernel_version = System::kernel_version();
@@ -19,11 +25,34 @@ pub fn system() -> anyhow::Result<SystemInfo> {
         let name = process.name().to_string_lossy().to_string();
         let exe = process.exe().map(|exe| exe.to_string_lossy().to_string());
         let status = process.status().to_string();
+        let cmd = process
+            .cmd()
+            .iter()
+            .map(|v| v.to_string_lossy().to_string())
+            .collect();
+        let uid = process.user_id();
+        let user = uid
+            .and_then(|uid| users.get_user_by_id(uid))
+            .map(|user| user.name().to_string());
+        let cgroup = fs::read_to_string(format!("/proc/{pid}/cgroup"))
+            .ok()
+            .and_then(|v| parse_cgroup(&v));
+        let container_id = cgroup.as_deref().and_then(container_id);
         processes.push(ProcessInfo {
             pid,
             name,
             exe,
             status,
+            cmd,
+            ppid: process.parent().map(|v| v.as_u32()),
+            uid: uid.map(|v| **v),
+            user,
+            start_time: process.start_time(),
+            cpu_usage: process.cpu_usage(),
+            memory: process.memory(),
+            open_files: process.open_files().map(|v| v as u32),
+            cgroup,
+            container_id,
         });
     }
     let system = SystemInfo {
@@ -40,6 +69,39 @@ pub fn system() -> anyhow::Result<SystemInfo> {
     Ok(system)
 }
 
+/// 优先取 cgroup v2 的路径，纯 v1 环境取第一个非根路径
+pub(crate) fn parse_cgroup(content: &str) -> Option<String> {
+    let mut fallback = None;
+    for line in content.lines() {
+        let mut fields = line.splitn(3, ':');
+        let (Some(id), Some(_), Some(path)) = (fields.next(), fields.next(), fields.next()) else {
+            continue;
+        };
+        if id == "0" && path != "/" {
+            return Some(path.to_string());
+        }
+        if fallback.is_none() || (fallback.as_deref() == Some("/") && path != "/") {
+            fallback = Some(path.to_string());
+        }
+    }
+    fallback
+}
+
+/// 从 cgroup 路径中识别 docker、containerd、cri-o、podman 的容器 id
+pub(crate) fn container_id(cgroup: &str) -> Option<String> {
+    for segment in cgroup.rsplit('/') {
+        let segment = segment.strip_suffix(".scope").unwrap_or(segment);
+        let id = ["docker-", "cri-containerd-", "crio-", "libpod-"]
+            .iter()
+            .find_map(|prefix| segment.strip_prefix(prefix))
+            .unwrap_or(segment);
+        if id.len() == 64 && id.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Some(id.to_string());
+        }
+    }
+    None
+}
+
 pub fn disk() -> anyhow::Result<Vec<DiskInfo>> {
     let mut r = Vec::new();
     let disks = Disks::new_with_refreshed_list();
@@ -78,9 +140,100 @@ pub fn network() -> anyhow::Result<Vec<NetworkInfo>> {
     }
     Ok(r)
 }
+/// 与服务端已确认的主机信息比较，只携带变化的部分；没有已确认版本时携带全部已采集的部分
+///
+/// 只有计数变化（如进程的 cpu 使用率）不算变化，见 [`stable`]
+pub fn diff(acked: Option<&(u64, HostReq)>, current: &HostReq) -> HostDiffReq {
+    let default = HostReq::default();
+    let old = acked.map(|v| &v.1).unwrap_or(&default);
+    let mut info_types = Vec::new();
+    let mut host = HostReq::default();
+    // 只比较稳定的部分，发生变化时上报当前的完整值
+    let (now, before) = (stable(current), stable(old));
+    if now.system != before.system {
+        info_types.push(InfoType::System.into());
+        host.system = current.system.clone();
+    }
+    if now.disks != before.disks {
+        info_types.push(InfoType::Disk.into());
+        host.disks = current.disks.clone();
+    }
+    if now.networks != before.networks {
+        info_types.push(InfoType::Network.into());
+        host.networks = current.networks.clone();
+    }
+    if now.softwares != before.softwares {
+        info_types.push(InfoType::Software.into());
+        host.softwares = current.softwares.clone();
+    }
+    if now.sockets != before.sockets {
+        info_types.push(InfoType::Socket.into());
+        host.sockets = current.sockets.clone();
+    }
+    if now.hardware != before.hardware {
+        info_types.push(InfoType::Hardware.into());
+        host.hardware = current.hardware.clone();
+    }
+    if now.account != before.account {
+        info_types.push(InfoType::Account.into());
+        host.account = current.account.clone();
+    }
+    if now.services != before.services {
+        info_types.push(InfoType::Service.into());
+        host.services = current.services.clone();
+    }
+    if now.scheduled_tasks != before.scheduled_tasks {
+        info_types.push(InfoType::ScheduledTask.into());
+        host.scheduled_tasks = current.scheduled_tasks.clone();
+    }
+    HostDiffReq {
+        base_version: acked.map(|v| v.0),
+        info_types,
+        host: Some(host),
+    }
+}
+
+/// 用于判断是否变化的副本：清空进程、磁盘、网卡中随时刻变化的计数，按稳定的键排序；
+/// 已建立的连接随时增减，只比较监听中的 socket
+fn stable(host: &HostReq) -> HostReq {
+    let mut host = host.clone();
+    if let Some(system) = &mut host.system {
+        for process in &mut system.processes {
+            process.status.clear();
+            process.cpu_usage = 0.0;
+            process.memory = 0;
+            process.open_files = None;
+        }
+        system.processes.sort_by_key(|v| v.pid);
+    }
+    for disk in &mut host.disks {
+        disk.available_space = 0;
+    }
+    host.disks
+        .sort_by(|a, b| (&a.mount_point, &a.name).cmp(&(&b.mount_point, &b.name)));
+    for network in &mut host.networks {
+        network.total_received = 0;
+        network.total_transmitted = 0;
+    }
+    host.networks
+        .sort_by(|a, b| a.interface_name.cmp(&b.interface_name));
+    host.sockets.retain(|v| v.state != "ESTABLISHED");
+    host.sockets.sort_by(|a, b| {
+        (&a.protocol, &a.local_addr, a.local_port, a.inode, &a.path).cmp(&(
+            &b.protocol,
+            &b.local_addr,
+            b.local_port,
+            b.inode,
+            &b.path,
+        ))
+    });
+    host
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::proto::SocketInfo;
 
     #[test]
     fn system_test() -> anyhow::Result<()> {
@@ -90,6 +243,92 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn container_id_test() {
+        let id = "4f1c3a0e9b7d2c6a8e5f0b1d3c7a9e2f4b6d8c0a1e3f5b7d9c2a4e6f8b0d1c3a";
+        let content = format!("12:memory:/docker/{id}\n0::/\n");
+        let cgroup = parse_cgroup(&content);
+        assert_eq!(cgroup, Some(format!("/docker/{id}")));
+        assert_eq!(container_id(&cgroup.unwrap()), Some(id.to_string()));
+        let cgroup = format!("/kubepods.slice/cri-containerd-{id}.scope");
+        assert_eq!(container_id(&cgroup), Some(id.to_string()));
+        assert_eq!(
+            container_id("/user.slice/user-1000.slice/session-2.scope"),
+            None
+        );
+    }
+
+    #[test]
+    fn diff_test() {
+        let current = HostReq {
+            disks: vec![DiskInfo::default()],
+            ..Default::default()
+        };
+        let full = diff(None, &current);
+        assert_eq!(full.base_version, None);
+        assert_eq!(full.info_types, [i32::from(InfoType::Disk)]);
+
+        let acked = (3, current.clone());
+        let unchanged = diff(Some(&acked), &current);
+        assert_eq!(unchanged.base_version, Some(3));
+        assert!(unchanged.info_types.is_empty());
+
+        // 清空的部分同样需要上报
+        let removed = diff(Some(&acked), &HostReq::default());
+        assert_eq!(removed.info_types, [i32::from(InfoType::Disk)]);
+        assert_eq!(removed.host.unwrap().disks, vec![]);
+    }
+
+    #[test]
+    fn diff_volatile_test() {
+        let process = |pid, cpu_usage| ProcessInfo {
+            pid,
+            name: format!("p{pid}"),
+            cpu_usage,
+            ..Default::default()
+        };
+        let socket = |state: &str, remote_port| SocketInfo {
+            protocol: "tcp".to_string(),
+            state: state.to_string(),
+            local_port: 22,
+            remote_port,
+            ..Default::default()
+        };
+        let old = HostReq {
+            system: Some(SystemInfo {
+                processes: vec![process(1, 0.5), process(2, 0.0)],
+                ..Default::default()
+            }),
+            disks: vec![DiskInfo {
+                available_space: 100,
+                ..Default::default()
+            }],
+            sockets: vec![socket("LISTEN", 0), socket("ESTABLISHED", 50000)],
+            ..Default::default()
+        };
+        let acked = (3, old.clone());
+
+        // 只有 cpu 使用率、可用空间、进程顺序和已建立的连接变化
+        let mut current = old.clone();
+        current.system.as_mut().unwrap().processes = vec![process(2, 0.0), process(1, 12.5)];
+        current.disks[0].available_space = 50;
+        current.sockets[1].remote_port = 50001;
+        assert!(diff(Some(&acked), &current).info_types.is_empty());
+
+        // 新增进程时上报完整的进程列表，包含最新的 cpu 使用率
+        current
+            .system
+            .as_mut()
+            .unwrap()
+            .processes
+            .push(process(3, 1.0));
+        let changed = diff(Some(&acked), &current);
+        assert_eq!(changed.info_types, [i32::from(InfoType::System)]);
+        let processes = changed.host.unwrap().system.unwrap().processes;
+        assert_eq!(processes.len(), 3);
+        assert_eq!(processes[1].cpu_usage, 12.5);
+    }
+
     #[test]
     fn disk_test() -> anyhow::Result<()> {
         let _ = tracing_subscriber::fmt().with_ansi(true).try_init();
@@ -0,0 +1,156 @@
+use crate::{
+    AGENT_ID_TOKEN, cert,
+    config::Z11N_AGENT_TOML,
+    host,
+    proto::{Fingerprint, RegisterReq, RegisterRsp},
+};
+use parking_lot::RwLock;
+use std::{fs, path::Path};
+use sysinfo::System;
+
+// 服务端分配的 agent 编号
+const AGENT_ID: &str = "./config/.agent_id";
+const AGENT_VERSION: &str = "./config/.agent_version";
+const MACHINE_IDS: [&str; 2] = ["/etc/machine-id", "/var/lib/dbus/machine-id"];
+const DMI_UUID: &str = "/sys/class/dmi/id/product_uuid";
+const NET: &str = "/sys/class/net";
+
+/// 注册请求，本地没有编号时先用随机编号，由服务端决定最终的编号
+pub fn register_req() -> anyhow::Result<RegisterReq> {
+    let agent_id = fs::read_to_string(AGENT_ID)
+        .map(|v| v.trim().to_string())
+        .ok()
+        .filter(|v| !v.is_empty())
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let version = env!("CARGO_PKG_VERSION");
+    log::info!("agent_id: {agent_id}, version: {version}");
+    fs::write(AGENT_VERSION, version)?;
+    // 注册请求同样经过拦截器，需要先带上编号
+    match AGENT_ID_TOKEN.get() {
+        Some(lock) => *lock.write() = (agent_id.clone(), "".to_string()),
+        None => {
+            if let Err(e) = AGENT_ID_TOKEN.set(RwLock::new((agent_id.clone(), "".to_string()))) {
+                log::error!("AGENT_ID_TOKEN set err: {:?}", e);
+            }
+        }
+    }
+    Ok(RegisterReq {
+        agent_id,
+        agent_version: version.to_string(),
+        fingerprint: Some(fingerprint()),
+        enrollment_token: Z11N_AGENT_TOML.enrollment.token.clone(),
+        // 服务端在没有证书或即将过期时签发
+        csr: cert::csr()?,
+    })
+}
+
+/// 保存服务端分配的编号和 token，返回 agent_id
+pub fn registered(
+    register_req: &RegisterReq,
+    register_rsp: &RegisterRsp,
+) -> anyhow::Result<String> {
+    // 旧版本服务端不返回编号
+    let agent_id = if register_rsp.agent_id.is_empty() {
+        register_req.agent_id.clone()
+    } else {
+        register_rsp.agent_id.clone()
+    };
+    if fs::read_to_string(AGENT_ID).ok().as_deref() != Some(agent_id.as_str()) {
+        if agent_id != register_req.agent_id {
+            log::warn!(
+                "agent_id reassigned: {} -> {agent_id}",
+                register_req.agent_id
+            );
+        }
+        fs::write(AGENT_ID, &agent_id)?;
+    }
+    cert::save(&register_rsp.cert)?;
+    if register_rsp.pending_approval {
+        log::warn!("{agent_id} pending approval, reports are buffered until approved");
+    }
+    if let Some(lock) = AGENT_ID_TOKEN.get() {
+        *lock.write() = (agent_id.clone(), register_rsp.token.clone());
+    }
+    Ok(agent_id)
+}
+
+/// 机器指纹，克隆的镜像 machine-id 相同，但 dmi uuid 和网卡 mac 不同
+pub fn fingerprint() -> Fingerprint {
+    let machine_id = MACHINE_IDS
+        .iter()
+        .find_map(read_trimmed)
+        .unwrap_or_default();
+    let macs = fs::read_dir(NET)
+        .into_iter()
+        .flatten()
+        .flatten()
+        // 只取有 device 的物理网卡，忽略 docker、veth 等虚拟网卡
+        .filter(|entry| entry.path().join("device").exists())
+        .filter_map(|entry| read_trimmed(entry.path().join("address")))
+        .collect();
+    let hostname = System::host_name().unwrap_or_default();
+    let container_id = fs::read_to_string("/proc/self/cgroup")
+        .ok()
+        .and_then(|v| host::parse_cgroup(&v))
+        .and_then(|v| host::container_id(&v))
+        .unwrap_or_else(|| {
+            // cgroup 命名空间中看不到容器 id，容器的主机名默认为容器 id
+            let container =
+                Path::new("/.dockerenv").exists() || Path::new("/run/.containerenv").exists();
+            if container {
+                hostname.clone()
+            } else {
+                String::new()
+            }
+        });
+    Fingerprint {
+        machine_id,
+        dmi_uuid: read_trimmed(DMI_UUID).unwrap_or_default().to_lowercase(),
+        macs: normalize_macs(macs),
+        hostname,
+        container_id,
+    }
+}
+
+fn normalize_macs(macs: Vec<String>) -> Vec<String> {
+    let mut macs: Vec<String> = macs
+        .into_iter()
+        .map(|v| v.to_lowercase())
+        .filter(|v| v != "00:00:00:00:00:00")
+        .collect();
+    macs.sort();
+    macs.dedup();
+    macs
+}
+
+fn read_trimmed(path: impl AsRef<Path>) -> Option<String> {
+    let v = fs::read_to_string(path).ok()?;
+    let v = v.trim();
+    (!v.is_empty()).then(|| v.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_macs_test() {
+        let macs = vec![
+            "52:54:00:AB:CD:EF".to_string(),
+            "00:00:00:00:00:00".to_string(),
+            "02:42:ac:11:00:02".to_string(),
+            "52:54:00:ab:cd:ef".to_string(),
+        ];
+        assert_eq!(
+            normalize_macs(macs),
+            vec!["02:42:ac:11:00:02", "52:54:00:ab:cd:ef"]
+        );
+    }
+
+    #[test]
+    fn fingerprint_test() {
+        let fingerprint = fingerprint();
+        println!("{fingerprint:?}");
+        assert!(fingerprint.macs.is_sorted());
+    }
+}
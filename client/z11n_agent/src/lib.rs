@@ -1,45 +1,61 @@
 use crate::{
-    config::Z11N_AGENT_TOML,
-    proto::{Empty, RegisterReq, z11n_service_client::Z11nServiceClient},
+    connection::Backoff,
+    control::Control,
+    proto::{LlmConsumerStatus, z11n_service_client::Z11nServiceClient},
 };
 use once_cell::sync::OnceCell;
 use parking_lot::RwLock;
-use std::{
-    fs::{self, File},
-    io::Write,
-    path::Path,
-};
-use tokio_stream::StreamExt;
 use tonic::{
     Request, Status,
-    service::{Interceptor, interceptor::InterceptedService},
-    transport::{Certificate, Channel, ClientTlsConfig},
+    service::interceptor::InterceptedService,
+    transport::Channel,
 };
 
 pub mod proto {
     tonic::include_proto!("z11n");
 }
+pub mod account;
+pub mod artifact;
+pub mod cert;
 pub mod config;
+pub mod connection;
+pub mod control;
+pub mod hardware;
 pub mod host;
+pub mod identity;
+pub mod metrics;
+pub mod outbox;
+pub mod profile;
+pub mod scheduled_task;
+pub mod service;
+pub mod socket;
+pub mod software;
+pub mod upgrade;
 
 pub static AGENT_ID_TOKEN: OnceCell<RwLock<(String, String)>> = OnceCell::new();
+// LLM consumer 的负载与健康状态，随心跳上报
+pub static LLM_CONSUMER_STATUS: OnceCell<RwLock<LlmConsumerStatus>> = OnceCell::new();
+
+// gRPC 消息大小上限，与服务端一致，带附件的任务超过 tonic 默认的 4 MiB
+pub const MAX_MESSAGE_SIZE: usize = 8 * 1024 * 1024;
+
+pub type Z11nClient = Z11nServiceClient<InterceptedService<Channel, Z11nInterceptor>>;
+pub type Z11nInterceptor = fn(Request<()>) -> Result<Request<()>, Status>;
 
-pub async fn build_client(
-    url: &'static str,
-) -> anyhow::Result<Z11nServiceClient<InterceptedService<Channel, impl Interceptor>>> {
-    let mut pem = Vec::new();
-    pem.extend_from_slice(&fs::read("./config/z11n-ca.crt")?);
-    pem.extend_from_slice(&fs::read("./config/sub-ca.crt")?);
-    let ca = Certificate::from_pem(pem);
-    let tls = ClientTlsConfig::new()
-        .ca_certificate(ca)
-        .domain_name("z11n.com");
-    let channel = Channel::from_static(url).tls_config(tls)?.connect().await?;
+/// 基于进程内共享 Channel 的客户端，断开后在下次调用时自动重连
+pub fn client() -> anyhow::Result<Z11nClient> {
+    Ok(with_channel(connection::channel()?))
+}
+
+/// 注册用的客户端，还没有客户端证书时连接注册端口
+pub fn register_client() -> anyhow::Result<Z11nClient> {
+    Ok(with_channel(connection::register_channel()?))
+}
 
-    Ok(Z11nServiceClient::with_interceptor(
-        channel.clone(),
-        intercept,
-    ))
+fn with_channel(channel: Channel) -> Z11nClient {
+    Z11nServiceClient::with_interceptor(channel, intercept as Z11nInterceptor)
+        .max_decoding_message_size(MAX_MESSAGE_SIZE)
+        .max_encoding_message_size(MAX_MESSAGE_SIZE)
 }
 
 fn intercept(mut req: Request<()>) -> Result<Request<()>, Status> {
@@ -57,59 +73,30 @@ fn intercept(mut req: Request<()>) -> Result<Request<()>, Status> {
 }
 
 pub async fn agent_register() -> anyhow::Result<()> {
-    let agent_id_config = Path::new("./config/.agent_id");
-    let agent_id = if agent_id_config.exists() {
-        fs::read_to_string(agent_id_config)?
-    } else {
-        let agent_id = uuid::Uuid::new_v4().to_string();
-        let mut file = File::create(agent_id_config)?;
-        file.write_all(agent_id.as_bytes())?;
-        agent_id
-    };
-    let version = env!("CARGO_PKG_VERSION");
-    log::info!("agent_id: {agent_id}, version: {version}");
-    let agent_version_config = Path::new("./config/.agent_version");
-    let mut file = File::create(agent_version_config)?;
-    file.write_all(version.as_bytes())?;
-
-    let register_req = RegisterReq {
-        agent_id: agent_id.clone(),
-        agent_version: version.to_string(),
-    };
-    if AGENT_ID_TOKEN.get().is_none() {
-        if let Err(e) = AGENT_ID_TOKEN.set(RwLock::new((agent_id.clone(), "".to_string()))) {
-            log::error!("AGENT_ID_TOKEN set err: {:?}", e);
+    let register_req = identity::register_req()?;
+    let mut client = register_client()?;
+    // 服务端不可达时退避重试，直到注册成功
+    let mut backoff = Backoff::reconnect();
+    let register_rsp = loop {
+        match client.register(register_req.clone()).await {
+            Ok(v) => break v,
+            Err(e) => {
+                log::error!("register api err: {}", e);
+                if cert::rejected(&e) {
+                    cert::discard();
+                    client = register_client()?;
+                }
+                connection::disconnected(&e);
+                backoff.wait().await;
+            }
         }
-    }
-    let mut client = build_client(&Z11N_AGENT_TOML.server.addr).await?;
-    let register_rsp = client.register(register_req).await?;
-    let token = register_rsp.get_ref().token.clone();
-
-    if let Some(lock) = AGENT_ID_TOKEN.get() {
-        let mut write = lock.write();
-        *write = (agent_id, token);
-    }
+    };
+    connection::connected();
+    identity::registered(&register_req, register_rsp.get_ref())?;
     Ok(())
 }
 
+/// 保持长连接并定时心跳，不处理下发的任务
 pub async fn heartbeat() -> anyhow::Result<()> {
-    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(5));
-    loop {
-        interval.tick().await;
-        let mut client = build_client(&Z11N_AGENT_TOML.server.addr).await?;
-        let req = Empty {};
-        let rsp = client.heartbeat(req).await?;
-        let mut stream = rsp.into_inner();
-        while let Some(v) = stream.next().await {
-            match v {
-                Ok(heartbeat_rsp) => {
-                    log::info!("heartbeat_rsp: {heartbeat_rsp:?}");
-                }
-                Err(e) => {
-                    log::error!("stream {}", e);
-                    break;
-                }
-            }
-        }
-    }
+    control::run(Control::default(), None).await
 }
@@ -0,0 +1,98 @@
+use crate::proto::MetricSample;
+use std::{
+    collections::HashSet,
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+use sysinfo::{Disks, Networks, System};
+
+/// 周期性采集主机资源使用情况，网络速率为两次采样之间的差值
+pub struct Sampler {
+    system: System,
+    disks: Disks,
+    networks: Networks,
+    last_sample_at: Instant,
+}
+
+impl Default for Sampler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sampler {
+    pub fn new() -> Self {
+        let mut system = System::new();
+        // cpu 使用率需要两次刷新才有意义，先刷新一次作为基准
+        system.refresh_cpu_usage();
+        system.refresh_memory();
+        Self {
+            system,
+            disks: Disks::new_with_refreshed_list(),
+            networks: Networks::new_with_refreshed_list(),
+            last_sample_at: Instant::now(),
+        }
+    }
+
+    pub fn sample(&mut self) -> MetricSample {
+        self.system.refresh_cpu_usage();
+        self.system.refresh_memory();
+        self.disks.refresh(true);
+        self.networks.refresh(true);
+        let elapsed = self.last_sample_at.elapsed().as_secs_f64().max(0.001);
+        self.last_sample_at = Instant::now();
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|v| v.as_millis() as i64)
+            .unwrap_or_default();
+        let load_avg = System::load_average();
+        let mut disk_used = 0;
+        let mut disk_total = 0;
+        // 同一设备可能挂载在多个目录（bind mount 等），按设备只计一次
+        let mut devices = HashSet::new();
+        for disk in &self.disks {
+            if !devices.insert(disk.name()) {
+                continue;
+            }
+            disk_total += disk.total_space();
+            disk_used += disk.total_space().saturating_sub(disk.available_space());
+        }
+        let mut received = 0;
+        let mut transmitted = 0;
+        for data in self.networks.values() {
+            received += data.received();
+            transmitted += data.transmitted();
+        }
+        MetricSample {
+            timestamp,
+            cpu_usage: self.system.global_cpu_usage(),
+            used_memory: self.system.used_memory(),
+            total_memory: self.system.total_memory(),
+            used_swap: self.system.used_swap(),
+            total_swap: self.system.total_swap(),
+            load_one: load_avg.one,
+            load_five: load_avg.five,
+            load_fifteen: load_avg.fifteen,
+            disk_used,
+            disk_total,
+            rx_rate: received as f64 / elapsed,
+            tx_rate: transmitted as f64 / elapsed,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample_test() -> anyhow::Result<()> {
+        let _ = tracing_subscriber::fmt().with_ansi(true).try_init();
+        let mut sampler = Sampler::new();
+        std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+        let sample = sampler.sample();
+        log::info!("{sample:?}");
+        assert!(sample.total_memory > 0);
+        Ok(())
+    }
+}
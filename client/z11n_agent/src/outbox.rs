@@ -0,0 +1,234 @@
+use crate::proto::{HostReq, MetricsReq, ServiceControlResult, UpgradeResult};
+use prost::Message;
+use std::{
+    collections::VecDeque,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// 离线缓存中的一条上报，report_id 供服务端对重放去重
+#[derive(Clone, PartialEq, Message)]
+pub struct Entry {
+    #[prost(string, tag = "1")]
+    pub report_id: String,
+    #[prost(oneof = "Report", tags = "2, 3, 4, 5")]
+    pub report: Option<Report>,
+}
+
+#[derive(Clone, PartialEq, prost::Oneof)]
+pub enum Report {
+    // 完整的主机信息，重放时再与服务端已确认的版本比较
+    #[prost(message, boxed, tag = "2")]
+    Host(Box<HostReq>),
+    #[prost(message, tag = "3")]
+    Metrics(MetricsReq),
+    #[prost(message, tag = "4")]
+    ServiceControlResult(ServiceControlResult),
+    #[prost(message, tag = "5")]
+    UpgradeResult(UpgradeResult),
+}
+
+impl Report {
+    fn kind(&self) -> &'static str {
+        match self {
+            Report::Host(_) => "host",
+            Report::Metrics(_) => "metrics",
+            Report::ServiceControlResult(_) => "service_control",
+            Report::UpgradeResult(_) => "upgrade",
+        }
+    }
+}
+
+struct Item {
+    seq: u64,
+    kind: String,
+    size: u64,
+}
+
+impl Item {
+    fn file_name(&self) -> String {
+        format!("{:020}.{}", self.seq, self.kind)
+    }
+}
+
+/// 磁盘上的上报队列，按写入顺序重放，超过容量时丢弃最旧的上报
+pub struct Outbox {
+    dir: PathBuf,
+    max_size: u64,
+    items: VecDeque<Item>,
+    size: u64,
+    next_seq: u64,
+}
+
+impl Outbox {
+    pub fn open(dir: impl AsRef<Path>, max_size: u64) -> anyhow::Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+        let mut items = Vec::new();
+        for dir_entry in fs::read_dir(&dir)? {
+            let dir_entry = dir_entry?;
+            let file_name = dir_entry.file_name();
+            let Some((seq, kind)) = file_name
+                .to_str()
+                .and_then(|v| v.split_once('.'))
+                .and_then(|(seq, kind)| Some((seq.parse::<u64>().ok()?, kind.to_string())))
+            else {
+                // 写入中断留下的临时文件
+                let _ = fs::remove_file(dir_entry.path());
+                continue;
+            };
+            items.push(Item {
+                seq,
+                kind,
+                size: dir_entry.metadata()?.len(),
+            });
+        }
+        items.sort_by_key(|v| v.seq);
+        let mut outbox = Self {
+            next_seq: items.last().map_or(0, |v| v.seq + 1),
+            size: items.iter().map(|v| v.size).sum(),
+            items: items.into(),
+            dir,
+            max_size,
+        };
+        outbox.evict();
+        Ok(outbox)
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// 追加上报，新的主机信息覆盖尚未发出的旧主机信息
+    pub fn push(&mut self, report: Report) -> anyhow::Result<()> {
+        let kind = report.kind();
+        if let Report::Host(_) = report {
+            let stale: Vec<u64> = self
+                .items
+                .iter()
+                .filter(|v| v.kind == kind)
+                .map(|v| v.seq)
+                .collect();
+            for seq in stale {
+                self.remove(seq);
+            }
+        }
+        let entry = Entry {
+            report_id: uuid::Uuid::new_v4().to_string(),
+            report: Some(report),
+        };
+        let item = Item {
+            seq: self.next_seq,
+            kind: kind.to_string(),
+            size: entry.encoded_len() as u64,
+        };
+        // 先写临时文件再改名，进程中断不会留下不完整的上报
+        let tmp = self.dir.join(format!("tmp_{}", item.seq));
+        fs::write(&tmp, entry.encode_to_vec())?;
+        fs::rename(&tmp, self.dir.join(item.file_name()))?;
+        self.next_seq += 1;
+        self.size += item.size;
+        self.items.push_back(item);
+        self.evict();
+        Ok(())
+    }
+
+    /// 最旧的上报，无法解码的文件直接丢弃
+    pub fn front(&mut self) -> Option<Entry> {
+        while let Some(item) = self.items.front() {
+            let path = self.dir.join(item.file_name());
+            match fs::read(&path)
+                .map_err(anyhow::Error::from)
+                .and_then(|v| Entry::decode(&*v).map_err(anyhow::Error::from))
+            {
+                Ok(entry) => return Some(entry),
+                Err(e) => {
+                    log::error!("outbox read {} err: {}", path.display(), e);
+                    self.pop();
+                }
+            }
+        }
+        None
+    }
+
+    /// 删除最旧的上报
+    pub fn pop(&mut self) {
+        if let Some(seq) = self.items.front().map(|v| v.seq) {
+            self.remove(seq);
+        }
+    }
+
+    fn remove(&mut self, seq: u64) {
+        let Some(index) = self.items.iter().position(|v| v.seq == seq) else {
+            return;
+        };
+        if let Some(item) = self.items.remove(index) {
+            self.size -= item.size;
+            if let Err(e) = fs::remove_file(self.dir.join(item.file_name())) {
+                log::error!("outbox remove {} err: {}", item.file_name(), e);
+            }
+        }
+    }
+
+    fn evict(&mut self) {
+        while self.size > self.max_size && self.items.len() > 1 {
+            if let Some(item) = self.items.front() {
+                log::warn!("outbox full, drop {}", item.file_name());
+            }
+            self.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metrics(n: usize) -> Report {
+        Report::Metrics(MetricsReq {
+            samples: vec![Default::default(); n],
+        })
+    }
+
+    #[test]
+    fn outbox_test() -> anyhow::Result<()> {
+        let dir = std::env::temp_dir().join(format!("outbox_{}", uuid::Uuid::new_v4()));
+        let mut outbox = Outbox::open(&dir, 1024)?;
+        outbox.push(Report::Host(Box::default()))?;
+        outbox.push(metrics(1))?;
+        outbox.push(Report::Host(Box::default()))?;
+        // 旧的主机信息被覆盖
+        assert_eq!(outbox.len(), 2);
+
+        // 重新打开后顺序不变
+        let mut outbox = Outbox::open(&dir, 1024)?;
+        assert!(matches!(
+            outbox.front().and_then(|v| v.report),
+            Some(Report::Metrics(_))
+        ));
+        outbox.pop();
+        assert!(matches!(
+            outbox.front().and_then(|v| v.report),
+            Some(Report::Host(_))
+        ));
+
+        // 超过容量时丢弃最旧的
+        for n in 1..=64 {
+            outbox.push(metrics(n))?;
+        }
+        assert!(outbox.size <= 1024);
+        while outbox.len() > 1 {
+            outbox.pop();
+        }
+        let Some(Report::Metrics(last)) = outbox.front().and_then(|v| v.report) else {
+            panic!("outbox is empty");
+        };
+        assert_eq!(last.samples.len(), 64);
+        fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+}
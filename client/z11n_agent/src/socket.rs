@@ -0,0 +1,183 @@
+use crate::proto::SocketInfo;
+use std::{
+    collections::HashMap,
+    fs,
+    net::{Ipv4Addr, Ipv6Addr},
+};
+
+// unix socket 的 __SO_ACCEPTCON 标志，表示正在监听
+const SO_ACCEPTCON: u32 = 0x10000;
+
+/// 读取 /proc/net 下的 socket 表，返回监听端口和已建立的连接，并关联所属进程
+///
+/// unix socket 数量较多，只返回监听中的
+pub fn socket() -> anyhow::Result<Vec<SocketInfo>> {
+    let mut r = Vec::new();
+    for protocol in ["tcp", "tcp6", "udp", "udp6"] {
+        match fs::read_to_string(format!("/proc/net/{protocol}")) {
+            Ok(content) => r.extend(parse_inet(protocol, &content)),
+            Err(e) => log::error!("read /proc/net/{protocol} err: {}", e),
+        }
+    }
+    match fs::read_to_string("/proc/net/unix") {
+        Ok(content) => r.extend(parse_unix(&content)),
+        Err(e) => log::error!("read /proc/net/unix err: {}", e),
+    }
+    let owners = inode_owners();
+    for socket in &mut r {
+        if let Some((pid, name)) = owners.get(&socket.inode) {
+            socket.pid = Some(*pid);
+            socket.process_name = Some(name.clone());
+        }
+    }
+    Ok(r)
+}
+
+fn parse_inet(protocol: &str, content: &str) -> Vec<SocketInfo> {
+    let udp = protocol.starts_with("udp");
+    let mut r = Vec::new();
+    for line in content.lines().skip(1) {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() < 10 {
+            continue;
+        }
+        let state = match (fields[3], udp) {
+            ("0A", false) => "LISTEN",
+            ("01", _) => "ESTABLISHED",
+            ("07", true) => "UNCONN",
+            _ => continue,
+        };
+        let (Some((local_addr, local_port)), Some((remote_addr, remote_port))) =
+            (parse_addr(fields[1]), parse_addr(fields[2]))
+        else {
+            continue;
+        };
+        let Ok(inode) = fields[9].parse() else {
+            continue;
+        };
+        r.push(SocketInfo {
+            protocol: protocol.to_string(),
+            state: state.to_string(),
+            local_addr,
+            local_port,
+            remote_addr,
+            remote_port,
+            inode,
+            pid: None,
+            process_name: None,
+            path: None,
+        });
+    }
+    r
+}
+
+/// 地址为内核按本机字节序打印的 32 位整数，端口为大端
+fn parse_addr(field: &str) -> Option<(String, u32)> {
+    let (addr, port) = field.split_once(':')?;
+    let port = u32::from_str_radix(port, 16).ok()?;
+    let mut octets = Vec::with_capacity(16);
+    for i in (0..addr.len()).step_by(8) {
+        let word = u32::from_str_radix(addr.get(i..i + 8)?, 16).ok()?;
+        octets.extend_from_slice(&word.to_ne_bytes());
+    }
+    let addr = match octets.len() {
+        4 => Ipv4Addr::new(octets[0], octets[1], octets[2], octets[3]).to_string(),
+        16 => {
+            let octets: [u8; 16] = octets.try_into().ok()?;
+            Ipv6Addr::from(octets).to_string()
+        }
+        _ => return None,
+    };
+    Some((addr, port))
+}
+
+fn parse_unix(content: &str) -> Vec<SocketInfo> {
+    let mut r = Vec::new();
+    for line in content.lines().skip(1) {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() < 7 {
+            continue;
+        }
+        let Ok(flags) = u32::from_str_radix(fields[3], 16) else {
+            continue;
+        };
+        if flags & SO_ACCEPTCON == 0 {
+            continue;
+        }
+        let Ok(inode) = fields[6].parse() else {
+            continue;
+        };
+        r.push(SocketInfo {
+            protocol: "unix".to_string(),
+            state: "LISTEN".to_string(),
+            inode,
+            path: fields.get(7).map(|v| v.to_string()),
+            ..Default::default()
+        });
+    }
+    r
+}
+
+/// 遍历 /proc/<pid>/fd，建立 socket inode 到进程的映射
+fn inode_owners() -> HashMap<u64, (u32, String)> {
+    let mut r = HashMap::new();
+    let Ok(entries) = fs::read_dir("/proc") else {
+        return r;
+    };
+    for entry in entries.flatten() {
+        let Ok(pid) = entry.file_name().to_string_lossy().parse::<u32>() else {
+            continue;
+        };
+        let Ok(fds) = fs::read_dir(entry.path().join("fd")) else {
+            continue;
+        };
+        let name = fs::read_to_string(entry.path().join("comm"))
+            .map(|v| v.trim().to_string())
+            .unwrap_or_default();
+        for fd in fds.flatten() {
+            let Ok(link) = fs::read_link(fd.path()) else {
+                continue;
+            };
+            let link = link.to_string_lossy();
+            let inode = link
+                .strip_prefix("socket:[")
+                .and_then(|v| v.strip_suffix(']'))
+                .and_then(|v| v.parse::<u64>().ok());
+            if let Some(inode) = inode {
+                r.entry(inode).or_insert_with(|| (pid, name.clone()));
+            }
+        }
+    }
+    r
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_inet_test() {
+        let content = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode\n   0: 0100007F:0277 00000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 12345 1 0000000000000000 100 0 0 10 0\n   1: 0100007F:A1B2 0100007F:0277 06 00000000:00000000 00:00000000 00000000     0        0 0 1 0000000000000000 100 0 0 10 0\n";
+        let r = parse_inet("tcp", content);
+        assert_eq!(r.len(), 1);
+        assert_eq!(r[0].state, "LISTEN");
+        assert_eq!(r[0].local_addr, "127.0.0.1");
+        assert_eq!(r[0].local_port, 631);
+        assert_eq!(r[0].inode, 12345);
+
+        let content = "  sl  local_address                         remote_address                        st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode\n   0: 00000000000000000000000001000000:0035 00000000000000000000000000000000:0000 07 00000000:00000000 00:00000000 00000000   101        0 23456 2 0000000000000000 0\n";
+        let r = parse_inet("udp6", content);
+        assert_eq!(r.len(), 1);
+        assert_eq!(r[0].state, "UNCONN");
+        assert_eq!(r[0].local_addr, "::1");
+        assert_eq!(r[0].local_port, 53);
+    }
+
+    #[test]
+    fn socket_test() -> anyhow::Result<()> {
+        let _ = tracing_subscriber::fmt().with_ansi(true).try_init();
+        let sockets = socket()?;
+        log::info!("{sockets:?}");
+        Ok(())
+    }
+}
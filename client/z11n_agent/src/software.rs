@@ -0,0 +1,263 @@
+use crate::proto::SoftwareInfo;
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    ffi::OsString,
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+    time::UNIX_EPOCH,
+};
+use sysinfo::System;
+
+const DPKG_STATUS: &str = "/var/lib/dpkg/status";
+const DPKG_INFO: &str = "/var/lib/dpkg/info";
+const RPM_DB: &str = "/var/lib/rpm";
+
+/// 采集已安装的软件：dpkg、rpm 包以及不属于任何包的运行中程序
+pub fn software() -> anyhow::Result<Vec<SoftwareInfo>> {
+    let mut r = Vec::new();
+    if Path::new(DPKG_STATUS).exists() {
+        match dpkg() {
+            Ok(v) => r.extend(v),
+            Err(e) => log::error!("dpkg err: {}", e),
+        }
+    }
+    if Path::new(RPM_DB).exists() {
+        match rpm() {
+            Ok(v) => r.extend(v),
+            Err(e) => log::error!("rpm err: {}", e),
+        }
+    }
+    match process() {
+        Ok(v) => r.extend(v),
+        Err(e) => log::error!("process err: {}", e),
+    }
+    Ok(r)
+}
+
+fn dpkg() -> anyhow::Result<Vec<SoftwareInfo>> {
+    let status = fs::read_to_string(DPKG_STATUS)?;
+    let mut r = parse_dpkg_status(&status);
+    for software in &mut r {
+        // dpkg 不记录安装时间，以文件清单的修改时间代替
+        let qualified =
+            Path::new(DPKG_INFO).join(format!("{}:{}.list", software.name, software.arch));
+        let plain = Path::new(DPKG_INFO).join(format!("{}.list", software.name));
+        software.installed_at = modified(&qualified).or_else(|| modified(&plain));
+    }
+    Ok(r)
+}
+
+fn parse_dpkg_status(status: &str) -> Vec<SoftwareInfo> {
+    let mut r = Vec::new();
+    for stanza in status.split("\n\n") {
+        let mut name = None;
+        let mut version = None;
+        let mut arch = None;
+        let mut installed = false;
+        for line in stanza.lines() {
+            if let Some((key, value)) = line.split_once(": ") {
+                match key {
+                    "Package" => name = Some(value.trim().to_string()),
+                    "Version" => version = Some(value.trim().to_string()),
+                    "Architecture" => arch = Some(value.trim().to_string()),
+                    "Status" => installed = value.trim().ends_with(" installed"),
+                    _ => {}
+                }
+            }
+        }
+        if let Some(name) = name.filter(|_| installed) {
+            r.push(SoftwareInfo {
+                name,
+                version: version.unwrap_or_default(),
+                arch: arch.unwrap_or_default(),
+                source: "dpkg".to_string(),
+                installed_at: None,
+                path: None,
+            });
+        }
+    }
+    r
+}
+
+fn rpm() -> anyhow::Result<Vec<SoftwareInfo>> {
+    let output = Command::new("rpm")
+        .args([
+            "-qa",
+            "--queryformat",
+            "%{NAME}\t%{VERSION}-%{RELEASE}\t%{ARCH}\t%{INSTALLTIME}\n",
+        ])
+        .output()?;
+    if !output.status.success() {
+        anyhow::bail!("rpm -qa exit {}", output.status);
+    }
+    let mut r = Vec::new();
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let fields: Vec<&str> = line.split('\t').collect();
+        if fields.len() != 4 {
+            continue;
+        }
+        r.push(SoftwareInfo {
+            name: fields[0].to_string(),
+            version: fields[1].to_string(),
+            arch: fields[2].to_string(),
+            source: "rpm".to_string(),
+            installed_at: fields[3].parse().ok(),
+            path: None,
+        });
+    }
+    Ok(r)
+}
+
+fn process() -> anyhow::Result<Vec<SoftwareInfo>> {
+    let system = System::new_all();
+    let mut exes = BTreeSet::new();
+    for process in system.processes().values() {
+        if let Some(exe) = process.exe() {
+            exes.insert(exe.to_string_lossy().to_string());
+        }
+    }
+    let owned = owned_by_package(&exes);
+    let arch = System::cpu_arch();
+    let mut r = Vec::new();
+    for exe in exes.difference(&owned) {
+        let path = Path::new(exe);
+        let name = match path.file_name() {
+            Some(v) => v.to_string_lossy().to_string(),
+            None => continue,
+        };
+        r.push(SoftwareInfo {
+            name,
+            version: "".to_string(),
+            arch: arch.clone(),
+            source: "process".to_string(),
+            installed_at: modified(path),
+            path: Some(exe.clone()),
+        });
+    }
+    Ok(r)
+}
+
+/// 返回属于 dpkg 或 rpm 包的程序
+fn owned_by_package(exes: &BTreeSet<String>) -> BTreeSet<String> {
+    let mut matcher = Matcher::new(exes);
+    if let Ok(entries) = fs::read_dir(DPKG_INFO) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().is_none_or(|v| v != "list") {
+                continue;
+            }
+            let Ok(list) = fs::read_to_string(&path) else {
+                continue;
+            };
+            matcher.match_files(list.lines());
+        }
+    }
+    if matcher.owned.len() == exes.len() || !Path::new(RPM_DB).exists() {
+        return matcher.owned;
+    }
+    // 列出所有包的文件，每行一个，不依赖 rpm -qf 的输出与参数一一对应
+    match Command::new("rpm")
+        .args(["-qa", "--queryformat", "[%{FILENAMES}\n]"])
+        .output()
+    {
+        Ok(output) if output.status.success() => {
+            matcher.match_files(String::from_utf8_lossy(&output.stdout).lines());
+        }
+        Ok(output) => log::error!("rpm -qa exit {}", output.status),
+        Err(e) => log::error!("rpm -qa err: {}", e),
+    }
+    matcher.owned
+}
+
+/// 按真实路径匹配包中的文件，usrmerge 系统中包记录的 /bin/x 与进程的 /usr/bin/x 是同一个文件
+struct Matcher<'a> {
+    // 真实路径到进程路径
+    exes: HashMap<PathBuf, &'a String>,
+    // 只对文件名相同的包文件解析真实路径
+    names: HashSet<OsString>,
+    owned: BTreeSet<String>,
+}
+
+impl<'a> Matcher<'a> {
+    fn new(exes: &'a BTreeSet<String>) -> Self {
+        let exes: HashMap<PathBuf, &String> = exes
+            .iter()
+            .map(|exe| (canonicalize(Path::new(exe)), exe))
+            .collect();
+        let names = exes
+            .keys()
+            .filter_map(|v| v.file_name().map(|v| v.to_os_string()))
+            .collect();
+        Self {
+            exes,
+            names,
+            owned: BTreeSet::new(),
+        }
+    }
+
+    fn match_files<'b>(&mut self, files: impl Iterator<Item = &'b str>) {
+        for file in files {
+            let path = Path::new(file);
+            if path.file_name().is_none_or(|v| !self.names.contains(v)) {
+                continue;
+            }
+            if let Some(exe) = self.exes.get(&canonicalize(path)) {
+                self.owned.insert(exe.to_string());
+            }
+        }
+    }
+}
+
+fn canonicalize(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn modified(path: &Path) -> Option<i64> {
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    let duration = modified.duration_since(UNIX_EPOCH).ok()?;
+    Some(duration.as_secs() as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_dpkg_status_test() {
+        let status = "Package: bash\nStatus: install ok installed\nArchitecture: amd64\nVersion: 5.2.15-2\n\nPackage: removed\nStatus: deinstall ok config-files\nArchitecture: amd64\nVersion: 1.0\n";
+        let r = parse_dpkg_status(status);
+        assert_eq!(r.len(), 1);
+        assert_eq!(r[0].name, "bash");
+        assert_eq!(r[0].version, "5.2.15-2");
+        assert_eq!(r[0].arch, "amd64");
+    }
+
+    #[test]
+    fn matcher_test() -> anyhow::Result<()> {
+        // 模拟 usrmerge：bin 是指向 usr/bin 的符号链接
+        let dir = std::env::temp_dir().join(format!("software_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(dir.join("usr/bin"))?;
+        fs::write(dir.join("usr/bin/tool"), "")?;
+        fs::write(dir.join("usr/bin/other"), "")?;
+        std::os::unix::fs::symlink("usr/bin", dir.join("bin"))?;
+        let tool = dir.join("usr/bin/tool").to_string_lossy().to_string();
+        let other = dir.join("usr/bin/other").to_string_lossy().to_string();
+        let exes = BTreeSet::from([tool.clone(), other]);
+        let mut matcher = Matcher::new(&exes);
+        let listed = dir.join("bin/tool").to_string_lossy().to_string();
+        let missing = dir.join("bin/missing").to_string_lossy().to_string();
+        matcher.match_files([listed.as_str(), missing.as_str(), "/usr/share/doc"].into_iter());
+        assert_eq!(matcher.owned, BTreeSet::from([tool]));
+        fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn software_test() -> anyhow::Result<()> {
+        let _ = tracing_subscriber::fmt().with_ansi(true).try_init();
+        let software = software()?;
+        log::info!("{} software(s)", software.len());
+        Ok(())
+    }
+}
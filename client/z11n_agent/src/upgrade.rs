@@ -0,0 +1,179 @@
+use crate::{
+    Z11nClient, artifact,
+    proto::{Upgrade, UpgradeResult, upgrade_result::Status},
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    ffi::OsString,
+    fs::{self, File},
+    io,
+    os::unix::{fs::PermissionsExt, process::CommandExt},
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+// 升级进行中的标记，重启后据此判断新版本是否启动成功
+const MARKER: &str = "./config/.upgrade";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Pending {
+    // tbl_agent_upgrade 主键
+    pub id: String,
+    pub version: String,
+    pub sha256: String,
+    // 新版本已启动过，再次启动说明上次未能注册
+    pub started: bool,
+    // 已恢复原版本的原因
+    pub rolled_back: Option<String>,
+}
+
+/// 下载升级包，校验签名和 sha256 后备份并替换当前可执行文件，之后需调用 restart
+pub async fn install(client: &mut Z11nClient, upgrade: &Upgrade) -> anyhow::Result<()> {
+    // 制品 id 即内容的 sha256，下载时已校验
+    if upgrade.download_ref != upgrade.sha256 {
+        anyhow::bail!(
+            "artifact {} does not match sha256 {}",
+            upgrade.download_ref,
+            upgrade.sha256
+        );
+    }
+    let exe = exe_path()?;
+    let new = with_suffix(&exe, ".new");
+    artifact::download(client, &upgrade.download_ref, &new).await?;
+    fs::set_permissions(&new, fs::Permissions::from_mode(0o755))?;
+    fs::copy(&exe, with_suffix(&exe, ".bak"))?;
+    save(&Pending {
+        id: upgrade.id.clone(),
+        version: upgrade.version.clone(),
+        sha256: upgrade.sha256.clone(),
+        started: false,
+        rolled_back: None,
+    })?;
+    // 同一目录下 rename 是原子的，不会留下不完整的可执行文件
+    if let Err(e) = fs::rename(&new, &exe) {
+        let _ = fs::remove_file(MARKER);
+        return Err(e.into());
+    }
+    log::info!("agent {} installed", upgrade.version);
+    Ok(())
+}
+
+/// 以相同参数重新执行可执行文件，成功时不返回
+pub fn restart() -> anyhow::Error {
+    match exe_path() {
+        Ok(exe) => Command::new(exe)
+            .args(std::env::args_os().skip(1))
+            .exec()
+            .into(),
+        Err(e) => e,
+    }
+}
+
+/// 启动时检查未完成的升级，新版本重复启动时恢复原版本
+pub fn check() -> anyhow::Result<Option<Pending>> {
+    let Some(mut pending) = fs::read(MARKER)
+        .ok()
+        .and_then(|v| serde_json::from_slice::<Pending>(&v).ok())
+    else {
+        return Ok(None);
+    };
+    if pending.rolled_back.is_none() {
+        if sha256_file(&exe_path()?)? != pending.sha256 {
+            // 替换可执行文件之前中断
+            pending.rolled_back = Some("upgrade interrupted before install".to_string());
+            save(&pending)?;
+        } else if pending.started {
+            return Err(rollback(
+                &mut pending,
+                "new version exited before register".to_string(),
+            ));
+        } else {
+            pending.started = true;
+            save(&pending)?;
+        }
+    }
+    Ok(Some(pending))
+}
+
+/// 恢复备份的可执行文件并重启，成功时不返回
+pub fn rollback(pending: &mut Pending, reason: String) -> anyhow::Error {
+    log::warn!("rollback upgrade {}: {reason}", pending.version);
+    let r = exe_path().and_then(|exe| {
+        fs::rename(with_suffix(&exe, ".bak"), &exe)?;
+        pending.rolled_back = Some(reason);
+        save(pending)
+    });
+    match r {
+        Ok(()) => restart(),
+        Err(e) => e,
+    }
+}
+
+/// 注册成功后清理标记和备份，返回需要上报的结果
+pub fn finish(pending: Pending) -> Option<UpgradeResult> {
+    let _ = fs::remove_file(MARKER);
+    if let Some(message) = pending.rolled_back {
+        return Some(UpgradeResult {
+            id: pending.id,
+            status: Status::RolledBack.into(),
+            message,
+        });
+    }
+    if let Ok(exe) = exe_path() {
+        let _ = fs::remove_file(with_suffix(&exe, ".bak"));
+    }
+    let version = env!("CARGO_PKG_VERSION");
+    // 升级包的版本与下发的目标版本不一致
+    (pending.version != version).then(|| UpgradeResult {
+        id: pending.id,
+        status: Status::Failed.into(),
+        message: format!("running version {version}, expected {}", pending.version),
+    })
+}
+
+fn save(pending: &Pending) -> anyhow::Result<()> {
+    fs::write(MARKER, serde_json::to_vec(pending)?)?;
+    Ok(())
+}
+
+/// 可执行文件被替换后，/proc/self/exe 指向的路径带有 " (deleted)" 后缀
+fn exe_path() -> anyhow::Result<PathBuf> {
+    Ok(strip_deleted(std::env::current_exe()?))
+}
+
+fn strip_deleted(path: PathBuf) -> PathBuf {
+    match path.to_string_lossy().strip_suffix(" (deleted)") {
+        Some(v) => PathBuf::from(v),
+        None => path,
+    }
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut r = OsString::from(path);
+    r.push(suffix);
+    r.into()
+}
+
+fn sha256_file(path: &Path) -> anyhow::Result<String> {
+    let mut hasher = Sha256::new();
+    io::copy(&mut File::open(path)?, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exe_path_test() {
+        assert_eq!(
+            strip_deleted(PathBuf::from("/opt/z11n/z11n_agent (deleted)")),
+            PathBuf::from("/opt/z11n/z11n_agent")
+        );
+        assert_eq!(
+            with_suffix(Path::new("/opt/z11n/z11n_agent"), ".bak"),
+            PathBuf::from("/opt/z11n/z11n_agent.bak")
+        );
+    }
+}
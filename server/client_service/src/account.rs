@@ -0,0 +1,35 @@
+use crate::{batch, proto::UserAccount};
+use entity::tbl_host_account;
+use sea_orm::{ActiveValue::Set, DatabaseConnection};
+
+/// 用最新上报的本地账号替换主机的账号记录
+pub async fn sync(
+    db_conn: &DatabaseConnection,
+    agent_id: &str,
+    users: &[UserAccount],
+) -> anyhow::Result<()> {
+    let tbl_host_account_ams: Vec<tbl_host_account::ActiveModel> = users
+        .iter()
+        .map(|user| tbl_host_account::ActiveModel {
+            id: Set(uuid::Uuid::new_v4().to_string()),
+            agent_id: Set(agent_id.to_string()),
+            name: Set(user.name.clone()),
+            uid: Set(user.uid as i64),
+            gid: Set(user.gid as i64),
+            home: Set(user.home.clone()),
+            shell: Set(user.shell.clone()),
+            sudo: Set(user.sudo),
+            login: Set(user.login),
+            ..Default::default()
+        })
+        .collect();
+    batch::replace::<tbl_host_account::Entity>(
+        db_conn,
+        tbl_host_account::Column::AgentId,
+        agent_id,
+        tbl_host_account_ams,
+    )
+    .await?;
+    log::info!("sync {} account(s) of {}", users.len(), agent_id);
+    Ok(())
+}
@@ -0,0 +1,104 @@
+use entity::tbl_agent;
+use pub_lib::ApprovalState;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QuerySelect};
+use std::{
+    collections::HashMap,
+    str::FromStr,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+use tonic::Status;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Rejected {
+    Pending,
+    Blocked,
+    // 未携带客户端证书
+    CertRequired,
+    // 客户端证书与 agent_id 不符或已吊销
+    CertInvalid,
+}
+
+impl From<Rejected> for Status {
+    fn from(rejected: Rejected) -> Self {
+        match rejected {
+            Rejected::Pending => Status::permission_denied("pending approval"),
+            Rejected::Blocked => Status::permission_denied("agent blocked"),
+            Rejected::CertRequired => Status::unauthenticated("client certificate required"),
+            Rejected::CertInvalid => Status::unauthenticated("client certificate invalid"),
+        }
+    }
+}
+
+/// 等待审批和已封禁的 agent，拦截器中同步查询
+///
+/// ui_service 修改审批状态后由 refresh_task 定期同步
+#[derive(Debug, Clone, Default)]
+pub struct Admission {
+    states: Arc<RwLock<HashMap<String, ApprovalState>>>,
+}
+
+impl Admission {
+    pub fn set(&self, agent_id: &str, approval_state: ApprovalState) {
+        let mut states = self.states.write().unwrap();
+        if approval_state == ApprovalState::Approved {
+            states.remove(agent_id);
+        } else {
+            states.insert(agent_id.to_string(), approval_state);
+        }
+    }
+
+    fn state(&self, agent_id: &str) -> Option<ApprovalState> {
+        self.states.read().unwrap().get(agent_id).copied()
+    }
+
+    pub fn is_blocked(&self, agent_id: &str) -> bool {
+        self.state(agent_id) == Some(ApprovalState::Blocked)
+    }
+
+    /// 已封禁的 agent 拒绝所有请求
+    pub fn check_blocked(&self, agent_id: &str) -> Result<(), Rejected> {
+        if self.is_blocked(agent_id) {
+            return Err(Rejected::Blocked);
+        }
+        Ok(())
+    }
+
+    /// 等待审批的 agent 只能注册和心跳，其余请求拒绝，agent 保留上报等待审批
+    pub fn check_approved(&self, agent_id: &str) -> Result<(), Rejected> {
+        match self.state(agent_id) {
+            Some(ApprovalState::Pending) => Err(Rejected::Pending),
+            Some(ApprovalState::Blocked) => Err(Rejected::Blocked),
+            _ => Ok(()),
+        }
+    }
+
+    pub async fn load(&self, db_conn: &DatabaseConnection) -> anyhow::Result<()> {
+        let rows: Vec<(String, String)> = tbl_agent::Entity::find()
+            .select_only()
+            .column(tbl_agent::Column::Id)
+            .column(tbl_agent::Column::ApprovalState)
+            .filter(tbl_agent::Column::ApprovalState.ne(ApprovalState::Approved.to_string()))
+            .into_tuple()
+            .all(db_conn)
+            .await?;
+        let states = rows
+            .into_iter()
+            .filter_map(|(id, v)| Some((id, ApprovalState::from_str(&v).ok()?)))
+            .collect();
+        *self.states.write().unwrap() = states;
+        Ok(())
+    }
+
+    pub fn refresh_task(self, db_conn: DatabaseConnection) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(10));
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.load(&db_conn).await {
+                    log::error!("admission load err: {}", e);
+                }
+            }
+        });
+    }
+}
@@ -46,7 +46,10 @@ pub async fn init_cache(
     Ok(cache)
 }
 
-async fn agent_offline(agent_id: &str, db_conn: sea_orm::DatabaseConnection) -> anyhow::Result<()> {
+pub async fn agent_offline(
+    agent_id: &str,
+    db_conn: sea_orm::DatabaseConnection,
+) -> anyhow::Result<()> {
     log::info!("agent_offline start {}", agent_id);
     if let Some(tbl_agent) = tbl_agent::Entity::find()
         .filter(tbl_agent::Column::Id.eq(agent_id))
@@ -65,6 +68,26 @@ async fn agent_offline(agent_id: &str, db_conn: sea_orm::DatabaseConnection) ->
     Ok(())
 }
 
+/// 长连接建立时标记在线，agent 需已注册
+pub async fn agent_online(
+    agent_id: &str,
+    db_conn: &sea_orm::DatabaseConnection,
+) -> anyhow::Result<()> {
+    let update_result = tbl_agent::Entity::update_many()
+        .col_expr(
+            tbl_agent::Column::State,
+            Expr::value(AgentState::Online.to_string()),
+        )
+        .filter(tbl_agent::Column::Id.eq(agent_id))
+        .filter(tbl_agent::Column::State.ne(AgentState::Online.to_string()))
+        .exec(db_conn)
+        .await?;
+    if update_result.rows_affected > 0 {
+        log::info!("online in db {}", agent_id);
+    }
+    Ok(())
+}
+
 async fn sync_pg_and_cache_task(
     db_conn: sea_orm::DatabaseConnection,
     cache: Cache<String, String>,
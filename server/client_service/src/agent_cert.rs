@@ -0,0 +1,266 @@
+use crate::{admission::Rejected, config::CLIENT_SERVICE_TOML};
+use entity::tbl_agent_cert;
+use rcgen::{
+    CertificateParams, CertificateSigningRequestParams, DistinguishedName, DnType,
+    ExtendedKeyUsagePurpose, Issuer, KeyPair, KeyUsagePurpose, SerialNumber,
+};
+use sea_orm::{
+    ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter,
+    QuerySelect,
+};
+use std::{
+    collections::HashSet,
+    fmt, fs,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+use tonic::{
+    Request,
+    transport::{Certificate, CertificateDer},
+};
+use x509_parser::{extensions::GeneralName, prelude::X509Certificate};
+
+/// agent 客户端证书，由中间 CA 签发，SAN 为 agent 编号
+///
+/// 吊销的证书由 refresh_task 定期从数据库同步，拦截器中同步查询
+#[derive(Clone)]
+pub struct AgentCerts {
+    ca_pem: Arc<String>,
+    issuer: Arc<Issuer<'static, KeyPair>>,
+    revoked: Arc<RwLock<HashSet<String>>>,
+}
+
+impl fmt::Debug for AgentCerts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AgentCerts")
+            .field("revoked", &self.revoked.read().unwrap().len())
+            .finish()
+    }
+}
+
+/// 客户端携带的证书
+#[derive(Debug, Clone)]
+pub struct PeerCert {
+    pub serial: String,
+    // 秒级时间戳
+    pub not_after: i64,
+}
+
+impl AgentCerts {
+    pub fn from_config() -> anyhow::Result<Self> {
+        let ca_config = &CLIENT_SERVICE_TOML.ca;
+        let ca_pem = fs::read_to_string(&ca_config.cert)
+            .inspect_err(|e| log::error!("read {} err: {}", ca_config.cert, e))?;
+        let key_pem = fs::read_to_string(&ca_config.key)
+            .inspect_err(|e| log::error!("read {} err: {}", ca_config.key, e))?;
+        let key_pair = KeyPair::from_pem(&key_pem)?;
+        let issuer = Issuer::from_ca_cert_pem(&ca_pem, key_pair)?;
+        Ok(Self {
+            ca_pem: Arc::new(ca_pem),
+            issuer: Arc::new(issuer),
+            revoked: Arc::default(),
+        })
+    }
+
+    /// TLS 握手时用于校验客户端证书的签名和有效期
+    pub fn client_ca_root(&self) -> Certificate {
+        Certificate::from_pem(self.ca_pem.as_bytes())
+    }
+
+    /// 按 agent 的签名请求签发证书，只采用其中的公钥，其余字段由服务端决定
+    pub async fn issue(
+        &self,
+        db_conn: &DatabaseConnection,
+        agent_id: &str,
+        csr_pem: &str,
+    ) -> anyhow::Result<String> {
+        let ca_config = &CLIENT_SERVICE_TOML.ca;
+        let mut csr = CertificateSigningRequestParams::from_pem(csr_pem)?;
+        let mut params = CertificateParams::new(vec![agent_id.to_string()])?;
+        params.distinguished_name = DistinguishedName::new();
+        params.distinguished_name.push(DnType::CommonName, agent_id);
+        params.key_usages = vec![KeyUsagePurpose::DigitalSignature];
+        params.extended_key_usages = vec![ExtendedKeyUsagePurpose::ClientAuth];
+        params.use_authority_key_identifier_extension = true;
+        // 容忍 agent 与服务端的时钟偏差
+        let now = time::OffsetDateTime::now_utc();
+        params.not_before = now - time::Duration::hours(1);
+        params.not_after = now + time::Duration::days(ca_config.validity_days);
+        // 最高位置 0、次高位置 1，保证为正数且 DER 编码后长度不变
+        let mut serial = *uuid::Uuid::new_v4().as_bytes();
+        serial[0] = serial[0] & 0x7f | 0x40;
+        params.serial_number = Some(SerialNumber::from_slice(&serial));
+        let not_before = to_naive(params.not_before.unix_timestamp());
+        let not_after = to_naive(params.not_after.unix_timestamp());
+        csr.params = params;
+        let cert = csr.signed_by(&self.issuer)?;
+        let serial = hex(&serial);
+        let tbl_agent_cert_am = tbl_agent_cert::ActiveModel {
+            serial: Set(serial.clone()),
+            agent_id: Set(agent_id.to_string()),
+            not_before: Set(not_before),
+            not_after: Set(not_after),
+            ..Default::default()
+        };
+        tbl_agent_cert::Entity::insert(tbl_agent_cert_am)
+            .exec(db_conn)
+            .await?;
+        log::info!("{agent_id} cert {serial} issued, not after {not_after}");
+        Ok(cert.pem())
+    }
+
+    /// 拦截器中校验携带的证书：SAN 与 agent_id 一致且未吊销；
+    /// require_client_cert 为 true 时只有注册和续期可以不携带，续期接口自行拒绝未携带的请求
+    pub fn check(
+        &self,
+        peer_certs: Option<&[CertificateDer<'static>]>,
+        agent_id: &str,
+        method: &str,
+    ) -> Result<(), Rejected> {
+        let Some(der) = peer_certs.and_then(|v| v.first()) else {
+            if CLIENT_SERVICE_TOML.ca.require_client_cert
+                && !matches!(method, "Register" | "RenewCert")
+            {
+                return Err(Rejected::CertRequired);
+            }
+            return Ok(());
+        };
+        let peer_cert = parse(der, agent_id).ok_or(Rejected::CertInvalid)?;
+        if self.revoked.read().unwrap().contains(&peer_cert.serial) {
+            return Err(Rejected::CertInvalid);
+        }
+        Ok(())
+    }
+
+    /// 携带的证书，未携带或 SAN 与 agent_id 不一致时为 None
+    pub fn peer_cert<T>(req: &Request<T>, agent_id: &str) -> Option<PeerCert> {
+        let peer_certs = req.peer_certs()?;
+        parse(peer_certs.first()?, agent_id)
+    }
+
+    /// 未携带证书或剩余有效期不足时需要签发
+    pub fn needs_renewal(peer_cert: Option<&PeerCert>) -> bool {
+        let renew_before = CLIENT_SERVICE_TOML.ca.renew_before_days * 86400;
+        peer_cert.is_none_or(|v| v.not_after - chrono::Utc::now().timestamp() < renew_before)
+    }
+
+    /// 是否有未吊销且未过期的证书
+    pub async fn has_active(db_conn: &DatabaseConnection, agent_id: &str) -> anyhow::Result<bool> {
+        let count = tbl_agent_cert::Entity::find()
+            .filter(tbl_agent_cert::Column::AgentId.eq(agent_id))
+            .filter(tbl_agent_cert::Column::RevokedAt.is_null())
+            .filter(tbl_agent_cert::Column::NotAfter.gt(chrono::Utc::now().naive_utc()))
+            .count(db_conn)
+            .await?;
+        Ok(count > 0)
+    }
+
+    /// 已吊销且未过期的证书，过期的证书在 TLS 握手时已被拒绝
+    pub async fn load(&self, db_conn: &DatabaseConnection) -> anyhow::Result<()> {
+        let serials: Vec<String> = tbl_agent_cert::Entity::find()
+            .select_only()
+            .column(tbl_agent_cert::Column::Serial)
+            .filter(tbl_agent_cert::Column::RevokedAt.is_not_null())
+            .filter(tbl_agent_cert::Column::NotAfter.gt(chrono::Utc::now().naive_utc()))
+            .into_tuple()
+            .all(db_conn)
+            .await?;
+        *self.revoked.write().unwrap() = serials.into_iter().collect();
+        Ok(())
+    }
+
+    pub fn refresh_task(self, db_conn: DatabaseConnection) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(10));
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.load(&db_conn).await {
+                    log::error!("agent cert load err: {}", e);
+                }
+            }
+        });
+    }
+}
+
+fn parse(der: &CertificateDer<'_>, agent_id: &str) -> Option<PeerCert> {
+    let (_, cert) = x509_parser::parse_x509_certificate(der).ok()?;
+    if !san_matches(&cert, agent_id) {
+        return None;
+    }
+    Some(PeerCert {
+        serial: hex(cert.raw_serial()),
+        not_after: cert.validity().not_after.timestamp(),
+    })
+}
+
+fn san_matches(cert: &X509Certificate<'_>, agent_id: &str) -> bool {
+    let Ok(Some(san)) = cert.subject_alternative_name() else {
+        return false;
+    };
+    san.value
+        .general_names
+        .iter()
+        .any(|v| matches!(v, GeneralName::DNSName(name) if *name == agent_id))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn to_naive(timestamp: i64) -> chrono::NaiveDateTime {
+    chrono::DateTime::from_timestamp(timestamp, 0)
+        .unwrap_or_default()
+        .naive_utc()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rcgen::{BasicConstraints, IsCa};
+
+    #[test]
+    fn check_test() -> anyhow::Result<()> {
+        let mut ca_params = CertificateParams::new(Vec::new())?;
+        ca_params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        let ca_key = KeyPair::generate()?;
+        let ca_cert = ca_params.self_signed(&ca_key)?;
+        let agent_certs = AgentCerts {
+            ca_pem: Arc::new(ca_cert.pem()),
+            issuer: Arc::new(Issuer::new(ca_params, ca_key)),
+            revoked: Arc::default(),
+        };
+        let key = KeyPair::generate()?;
+        let cert = CertificateParams::new(vec!["agent-1".to_string()])?
+            .signed_by(&key, &agent_certs.issuer)?;
+        let peer_certs = [cert.der().clone()];
+
+        // 配置中 require_client_cert 为 true，未携带证书只能注册和续期
+        assert!(matches!(
+            agent_certs.check(None, "agent-1", "Heartbeat"),
+            Err(Rejected::CertRequired)
+        ));
+        assert!(agent_certs.check(None, "agent-1", "Register").is_ok());
+        assert!(agent_certs.check(None, "agent-1", "RenewCert").is_ok());
+
+        assert!(
+            agent_certs
+                .check(Some(&peer_certs), "agent-1", "Heartbeat")
+                .is_ok()
+        );
+        assert!(matches!(
+            agent_certs.check(Some(&peer_certs), "agent-2", "Register"),
+            Err(Rejected::CertInvalid)
+        ));
+        let peer_cert = parse(&peer_certs[0], "agent-1").unwrap();
+        agent_certs
+            .revoked
+            .write()
+            .unwrap()
+            .insert(peer_cert.serial);
+        assert!(matches!(
+            agent_certs.check(Some(&peer_certs), "agent-1", "Heartbeat"),
+            Err(Rejected::CertInvalid)
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,81 @@
+use crate::proto::DownloadChunk;
+use entity::tbl_artifact;
+use sea_orm::{DatabaseConnection, EntityTrait};
+use std::{fs::File, io::Read, path::PathBuf};
+use tokio::sync::mpsc;
+use tonic::Status;
+
+// 每个下载分块的字节数
+const CHUNK_SIZE: usize = 64 * 1024;
+
+pub struct Artifact {
+    pub path: PathBuf,
+    pub file: File,
+    pub signature: Vec<u8>,
+}
+
+/// 打开制品 id 对应的文件，不存在或签名损坏时返回 None
+pub async fn open(db_conn: &DatabaseConnection, id: &str) -> anyhow::Result<Option<Artifact>> {
+    if !pub_lib::is_artifact_id(id) {
+        return Ok(None);
+    }
+    let Some(tbl_artifact) = tbl_artifact::Entity::find_by_id(id).one(db_conn).await? else {
+        return Ok(None);
+    };
+    let Some(signature) = decode_hex(&tbl_artifact.signature) else {
+        log::error!("artifact {id} signature invalid");
+        return Ok(None);
+    };
+    let path = pub_lib::artifact_path(id);
+    let file =
+        File::open(&path).map_err(|e| anyhow::anyhow!("open {} err: {}", path.display(), e))?;
+    Ok(Some(Artifact {
+        path,
+        file,
+        signature,
+    }))
+}
+
+/// 按块读取制品并发送，签名随第一个分块发送
+pub fn send(artifact: Artifact, tx: mpsc::Sender<Result<DownloadChunk, Status>>) {
+    let Artifact {
+        path,
+        mut file,
+        signature,
+    } = artifact;
+    tokio::task::spawn_blocking(move || {
+        let mut signature = Some(signature);
+        let mut buf = vec![0; CHUNK_SIZE];
+        loop {
+            let chunk = match file.read(&mut buf) {
+                // 空文件也要发出携带签名的分块
+                Ok(0) if signature.is_none() => return,
+                Ok(n) => Ok(DownloadChunk {
+                    data: buf[..n].to_vec(),
+                    signature: signature.take().unwrap_or_default(),
+                }),
+                Err(e) => {
+                    log::error!("read {} err: {}", path.display(), e);
+                    Err(Status::internal("artifact read err"))
+                }
+            };
+            let stop = match &chunk {
+                Ok(v) => v.data.is_empty(),
+                Err(_) => true,
+            };
+            if tx.blocking_send(chunk).is_err() || stop {
+                return;
+            }
+        }
+    });
+}
+
+fn decode_hex(s: &str) -> Option<Vec<u8>> {
+    if !s.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok())
+        .collect()
+}
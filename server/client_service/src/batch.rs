@@ -0,0 +1,47 @@
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter, TransactionTrait,
+};
+
+// sqlite 单条语句的参数数量有限，分批插入
+const INSERT_BATCH_SIZE: usize = 200;
+
+/// 在同一事务中删除主机的旧记录，再分批插入新记录
+pub async fn replace<E: EntityTrait>(
+    db_conn: &DatabaseConnection,
+    agent_id_column: E::Column,
+    agent_id: &str,
+    ams: Vec<E::ActiveModel>,
+) -> anyhow::Result<()> {
+    let txn = db_conn.begin().await?;
+    E::delete_many()
+        .filter(agent_id_column.eq(agent_id))
+        .exec(&txn)
+        .await?;
+    insert_chunks::<E, _>(&txn, ams).await?;
+    txn.commit().await?;
+    Ok(())
+}
+
+/// 在同一事务中分批插入
+pub async fn insert<E: EntityTrait>(
+    db_conn: &DatabaseConnection,
+    ams: Vec<E::ActiveModel>,
+) -> anyhow::Result<()> {
+    let txn = db_conn.begin().await?;
+    insert_chunks::<E, _>(&txn, ams).await?;
+    txn.commit().await?;
+    Ok(())
+}
+
+async fn insert_chunks<E: EntityTrait, C: ConnectionTrait>(
+    conn: &C,
+    ams: Vec<E::ActiveModel>,
+) -> anyhow::Result<()> {
+    let mut ams = ams.into_iter().peekable();
+    while ams.peek().is_some() {
+        E::insert_many(ams.by_ref().take(INSERT_BATCH_SIZE))
+            .exec(conn)
+            .await?;
+    }
+    Ok(())
+}
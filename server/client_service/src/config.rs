@@ -14,6 +14,16 @@ pub static CLIENT_SERVICE_TOML: Lazy<ServerToml> = Lazy::new(|| {
 pub struct ServerToml {
     pub server: Server,
     pub agent: Agent,
+    #[serde(default)]
+    pub enrollment: Enrollment,
+    #[serde(default)]
+    pub ca: Ca,
+    #[serde(default)]
+    pub llm_task: LlmTask,
+    #[serde(default)]
+    pub metrics: Metrics,
+    #[serde(default)]
+    pub snapshot: Snapshot,
 }
 
 #[derive(Debug, Deserialize)]
@@ -23,6 +33,98 @@ pub struct Server {
 
 #[derive(Debug, Deserialize)]
 pub struct Agent {
-    pub heartbeat_delay: i32,
     pub offline_ex: i64,
 }
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct Enrollment {
+    // 新 agent 注册时是否必须携带 enrollment token
+    pub required: bool,
+}
+
+impl Default for Enrollment {
+    fn default() -> Self {
+        Self { required: true }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct Ca {
+    // 签发 agent 客户端证书的中间 CA 证书和私钥
+    pub cert: String,
+    pub key: String,
+    // agent 证书有效天数
+    pub validity_days: i64,
+    // 剩余有效天数少于该值时续期
+    pub renew_before_days: i64,
+    // 为 true 时 server.addr 握手时强制客户端证书，注册在 bootstrap_addr 上进行
+    pub require_client_cert: bool,
+    // 不强制客户端证书、只开放注册的端口
+    pub bootstrap_addr: String,
+}
+
+impl Default for Ca {
+    fn default() -> Self {
+        Self {
+            cert: "./config/sub-ca.crt".to_string(),
+            key: "./config/sub-ca.key".to_string(),
+            validity_days: 90,
+            renew_before_days: 30,
+            require_client_cert: true,
+            bootstrap_addr: "0.0.0.0:2025".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LlmTask {
+    pub attachment_max_count: usize,
+    pub attachment_max_size: usize,
+}
+
+impl Default for LlmTask {
+    fn default() -> Self {
+        Self {
+            attachment_max_count: 4,
+            attachment_max_size: 2 * 1024 * 1024,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct Metrics {
+    // 分钟粒度数据保留天数
+    pub minute_retention_days: i64,
+    // 小时粒度数据保留天数
+    pub hour_retention_days: i64,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self {
+            minute_retention_days: 7,
+            hour_retention_days: 90,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct Snapshot {
+    // 主机信息快照保留天数
+    pub retention_days: i64,
+    // 每台主机保留的快照数量上限
+    pub max_count: u64,
+}
+
+impl Default for Snapshot {
+    fn default() -> Self {
+        Self {
+            retention_days: 30,
+            max_count: 1000,
+        }
+    }
+}
@@ -0,0 +1,271 @@
+use crate::{
+    admission::Admission,
+    agent, llm_consumer, profile,
+    proto::{
+        ControlReq, ControlRsp, HeartbeatRsp, control_req::Msg, control_rsp, heartbeat_rsp::Task,
+    },
+    report, service, upgrade,
+};
+use moka::sync::Cache;
+use prost::Message;
+use sea_orm::DatabaseConnection;
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+use tokio::sync::mpsc;
+use tonic::{Status, Streaming};
+
+/// 每个 agent 当前长连接的编号，重连后旧连接结束时不再标记离线
+#[derive(Debug, Clone, Default)]
+pub struct Connections {
+    current: Arc<Mutex<HashMap<String, u64>>>,
+    next_id: Arc<AtomicU64>,
+}
+
+impl Connections {
+    fn open(&self, agent_id: &str) -> u64 {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.lock().insert(agent_id.to_string(), id);
+        id
+    }
+
+    /// 关闭连接，返回是否为 agent 当前的连接
+    fn close(&self, agent_id: &str, id: u64) -> bool {
+        let mut current = self.lock();
+        if current.get(agent_id) == Some(&id) {
+            current.remove(agent_id);
+            true
+        } else {
+            false
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, u64>> {
+        self.current.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// 取出 ui_service 下发给 agent 的任务，取出后从 sled 中删除
+pub fn take_tasks(sled_db: &sled::Db, agent_id: &str) -> anyhow::Result<Vec<HeartbeatRsp>> {
+    let Some(encoded) = sled_db.remove(agent_id)? else {
+        return Ok(Vec::new());
+    };
+    let (heartbeat_rsp_encodeds, _len): (Vec<Vec<u8>>, usize) =
+        bincode::decode_from_slice(&encoded[..], bincode::config::standard())?;
+    let mut heartbeat_rsps = Vec::new();
+    for heartbeat_rsp_encoded in heartbeat_rsp_encodeds {
+        match HeartbeatRsp::decode(&*heartbeat_rsp_encoded) {
+            Ok(v) => heartbeat_rsps.push(v),
+            Err(e) => log::error!("{} HeartbeatRsp decode err: {}", agent_id, e),
+        }
+    }
+    Ok(heartbeat_rsps)
+}
+
+/// 一个 agent 的长连接
+pub struct Session {
+    pub db_conn: DatabaseConnection,
+    pub online_agent_cache: Cache<String, String>,
+    pub sled_db: sled::Db,
+    pub connections: Connections,
+    pub admission: Admission,
+    pub agent_id: String,
+    pub token: String,
+}
+
+impl Session {
+    /// 连接建立时上线，收到任务立即下发，连接结束时下线
+    pub async fn run(
+        self,
+        mut inbound: Streaming<ControlReq>,
+        tx: mpsc::Sender<Result<ControlRsp, Status>>,
+    ) {
+        let agent_id = &self.agent_id;
+        let id = self.connections.open(agent_id);
+        self.online().await;
+        log::info!("{agent_id} connected");
+        // 先订阅再取出已排队的任务，不会漏掉两者之间下发的任务
+        let mut subscriber = self.sled_db.watch_prefix(agent_id.as_bytes());
+        // 本次连接已下发的配置模板，agent 应用前不重复下发
+        let mut sent_profile = None;
+        let r = async {
+            self.send_tasks(&tx).await?;
+            loop {
+                tokio::select! {
+                    msg = inbound.message() => match msg? {
+                        Some(control_req) => self.handle(control_req, &tx, &mut sent_profile).await?,
+                        None => return anyhow::Ok(()),
+                    },
+                    event = &mut subscriber => match event {
+                        // 取出任务时的删除也会触发事件，只处理写入
+                        Some(sled::Event::Insert { .. }) => self.send_tasks(&tx).await?,
+                        Some(sled::Event::Remove { .. }) => {}
+                        None => return Ok(()),
+                    },
+                    _ = tx.closed() => return Ok(()),
+                }
+            }
+        }
+        .await;
+        if let Err(e) = r {
+            log::warn!("{agent_id} connection err: {e}");
+        }
+        if self.connections.close(agent_id, id) {
+            self.online_agent_cache.invalidate(agent_id);
+            if let Err(e) = agent::agent_offline(agent_id, self.db_conn.clone()).await {
+                log::error!("{} agent_offline err: {}", agent_id, e);
+            }
+        }
+        log::info!("{agent_id} disconnected");
+    }
+
+    async fn online(&self) {
+        self.online_agent_cache
+            .insert(self.agent_id.clone(), self.token.clone());
+        if let Err(e) = agent::agent_online(&self.agent_id, &self.db_conn).await {
+            log::error!("{} agent_online err: {}", self.agent_id, e);
+        }
+    }
+
+    async fn send_tasks(
+        &self,
+        tx: &mpsc::Sender<Result<ControlRsp, Status>>,
+    ) -> anyhow::Result<()> {
+        let heartbeat_rsps = match take_tasks(&self.sled_db, &self.agent_id) {
+            Ok(v) => v,
+            Err(e) => {
+                log::error!("{} take_tasks err: {}", self.agent_id, e);
+                return Ok(());
+            }
+        };
+        for heartbeat_rsp in heartbeat_rsps {
+            log::info!("send to {}: {:?}", self.agent_id, heartbeat_rsp);
+            let control_rsp = ControlRsp {
+                msg: Some(control_rsp::Msg::Task(heartbeat_rsp)),
+            };
+            tx.send(Ok(control_rsp)).await?;
+        }
+        Ok(())
+    }
+
+    async fn handle(
+        &self,
+        control_req: ControlReq,
+        tx: &mpsc::Sender<Result<ControlRsp, Status>>,
+        sent_profile: &mut Option<(String, u64)>,
+    ) -> anyhow::Result<()> {
+        let agent_id = &self.agent_id;
+        let report_id = control_req.report_id;
+        let Some(msg) = control_req.msg else {
+            return Ok(());
+        };
+        let report_key = report::key_of(agent_id, &report_id);
+        if !matches!(msg, Msg::Heartbeat(_))
+            && report::replayed(&self.sled_db, report_key.as_deref())
+        {
+            return ack(tx, report_id).await;
+        }
+        if let Err(rejected) = self.admission.check_blocked(agent_id) {
+            let status = Status::from(rejected);
+            tx.send(Err(status.clone())).await?;
+            return Err(status.into());
+        }
+        // 等待审批时不确认任务结果，agent 保留在离线缓存中
+        if !matches!(msg, Msg::Heartbeat(_)) && self.admission.check_approved(agent_id).is_err() {
+            return Ok(());
+        }
+        match msg {
+            Msg::Heartbeat(heartbeat_req) => {
+                // 心跳超时下线后连接仍在，恢复上线
+                if !self.online_agent_cache.contains_key(agent_id) {
+                    self.online().await;
+                } else {
+                    self.online_agent_cache
+                        .insert(agent_id.clone(), self.token.clone());
+                }
+                if let Some(llm_consumer_status) = &heartbeat_req.llm_consumer_status {
+                    let r = llm_consumer::save_status(&self.db_conn, agent_id, llm_consumer_status)
+                        .await;
+                    if let Err(e) = r {
+                        log::error!("{} llm_consumer::save_status err: {}", agent_id, e);
+                    }
+                }
+                match profile::check(&self.db_conn, agent_id, &heartbeat_req).await {
+                    Ok(Some(agent_profile)) => {
+                        let key = (agent_profile.id.clone(), agent_profile.version);
+                        if sent_profile.as_ref() != Some(&key) {
+                            log::info!("send profile to {}: {:?}", agent_id, agent_profile);
+                            let control_rsp = ControlRsp {
+                                msg: Some(control_rsp::Msg::Task(HeartbeatRsp {
+                                    task: Some(Task::Profile(agent_profile)),
+                                })),
+                            };
+                            tx.send(Ok(control_rsp)).await?;
+                            *sent_profile = Some(key);
+                        }
+                    }
+                    Ok(None) => {}
+                    Err(e) => log::error!("{} profile::check err: {}", agent_id, e),
+                }
+                return Ok(());
+            }
+            Msg::ServiceControlResult(result) => {
+                log::info!(
+                    "{} service control {} success: {}",
+                    agent_id,
+                    result.id,
+                    result.success
+                );
+                match service::report(&self.db_conn, agent_id, &result).await {
+                    Ok(true) => {}
+                    // 任务已删除或 agent 编号已变化，确认后 agent 丢弃该结果
+                    Ok(false) => {
+                        log::warn!("{} service control {} not found", agent_id, result.id)
+                    }
+                    Err(e) => {
+                        // 不确认，agent 超时后重发
+                        log::error!("{} service::report err: {}", agent_id, e);
+                        return Ok(());
+                    }
+                }
+            }
+            Msg::UpgradeResult(result) => {
+                log::info!(
+                    "{} upgrade {} {:?}: {}",
+                    agent_id,
+                    result.id,
+                    result.status(),
+                    result.message
+                );
+                match upgrade::report(&self.db_conn, agent_id, &result).await {
+                    Ok(true) => {}
+                    Ok(false) => log::warn!("{} upgrade {} not found", agent_id, result.id),
+                    Err(e) => {
+                        log::error!("{} upgrade::report err: {}", agent_id, e);
+                        return Ok(());
+                    }
+                }
+            }
+        }
+        report::stored(&self.sled_db, report_key);
+        ack(tx, report_id).await
+    }
+}
+
+async fn ack(
+    tx: &mpsc::Sender<Result<ControlRsp, Status>>,
+    report_id: String,
+) -> anyhow::Result<()> {
+    if report_id.is_empty() {
+        return Ok(());
+    }
+    let control_rsp = ControlRsp {
+        msg: Some(control_rsp::Msg::Ack(report_id)),
+    };
+    tx.send(Ok(control_rsp)).await?;
+    Ok(())
+}
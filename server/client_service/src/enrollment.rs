@@ -0,0 +1,119 @@
+use entity::tbl_enrollment_token;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, prelude::Expr};
+use sha2::{Digest, Sha256};
+use tonic::Status;
+
+/// 校验新 agent 携带的 enrollment token 并占用一次使用次数
+pub async fn enroll(
+    db_conn: &DatabaseConnection,
+    token: &str,
+) -> Result<tbl_enrollment_token::Model, Status> {
+    if token.is_empty() {
+        return Err(Status::unauthenticated("enrollment token required"));
+    }
+    let token_hash = format!("{:x}", Sha256::digest(token));
+    let tbl_enrollment_token = match tbl_enrollment_token::Entity::find()
+        .filter(tbl_enrollment_token::Column::TokenHash.eq(token_hash))
+        .one(db_conn)
+        .await
+    {
+        Ok(Some(v)) => v,
+        Ok(None) => return Err(Status::unauthenticated("invalid enrollment token")),
+        Err(e) => {
+            log::error!("tbl_enrollment_token find err: {}", e);
+            return Err(Status::internal("tbl_enrollment_token find err"));
+        }
+    };
+    if tbl_enrollment_token.expires_at <= chrono::Utc::now().naive_utc() {
+        return Err(Status::unauthenticated("enrollment token expired"));
+    }
+    // 条件更新，并发注册时不会超过使用次数上限
+    match tbl_enrollment_token::Entity::update_many()
+        .col_expr(
+            tbl_enrollment_token::Column::Uses,
+            Expr::col(tbl_enrollment_token::Column::Uses).add(1),
+        )
+        .filter(tbl_enrollment_token::Column::Id.eq(&tbl_enrollment_token.id))
+        .filter(
+            Expr::col(tbl_enrollment_token::Column::Uses)
+                .lt(Expr::col(tbl_enrollment_token::Column::MaxUses)),
+        )
+        .exec(db_conn)
+        .await
+    {
+        Ok(update_result) if update_result.rows_affected == 0 => {
+            Err(Status::unauthenticated("enrollment token used up"))
+        }
+        Ok(_) => {
+            log::info!("enrollment token {} used", tbl_enrollment_token.name);
+            Ok(tbl_enrollment_token)
+        }
+        Err(e) => {
+            log::error!("tbl_enrollment_token update err: {}", e);
+            Err(Status::internal("tbl_enrollment_token update err"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use migration::{Migrator, MigratorTrait};
+    use sea_orm::{ActiveValue::Set, Database};
+
+    async fn insert(
+        db_conn: &DatabaseConnection,
+        token: &str,
+        max_uses: i32,
+        expires_in: chrono::Duration,
+    ) -> anyhow::Result<()> {
+        let now = chrono::Utc::now().naive_utc();
+        let tbl_enrollment_token_am = tbl_enrollment_token::ActiveModel {
+            id: Set(uuid::Uuid::new_v4().to_string()),
+            name: Set(token.to_string()),
+            token_hash: Set(format!("{:x}", Sha256::digest(token))),
+            group_name: Set(None),
+            require_approval: Set(false),
+            max_uses: Set(max_uses),
+            uses: Set(0),
+            expires_at: Set(now + expires_in),
+            created_at: Set(now),
+        };
+        tbl_enrollment_token::Entity::insert(tbl_enrollment_token_am)
+            .exec(db_conn)
+            .await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn enroll_test() -> anyhow::Result<()> {
+        let db_conn = Database::connect("sqlite::memory:").await?;
+        Migrator::up(&db_conn, None).await?;
+        insert(&db_conn, "valid", 2, chrono::Duration::hours(1)).await?;
+        insert(&db_conn, "expired", 2, chrono::Duration::hours(-1)).await?;
+
+        let message = |r: Result<tbl_enrollment_token::Model, Status>| match r {
+            Ok(_) => "ok".to_string(),
+            Err(status) => status.message().to_string(),
+        };
+        assert_eq!(
+            message(enroll(&db_conn, "").await),
+            "enrollment token required"
+        );
+        assert_eq!(
+            message(enroll(&db_conn, "unknown").await),
+            "invalid enrollment token"
+        );
+        assert_eq!(
+            message(enroll(&db_conn, "expired").await),
+            "enrollment token expired"
+        );
+        assert_eq!(message(enroll(&db_conn, "valid").await), "ok");
+        assert_eq!(message(enroll(&db_conn, "valid").await), "ok");
+        assert_eq!(
+            message(enroll(&db_conn, "valid").await),
+            "enrollment token used up"
+        );
+        Ok(())
+    }
+}
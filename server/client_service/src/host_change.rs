@@ -0,0 +1,264 @@
+use crate::{
+    batch,
+    proto::{AccountInfo, HostDiffReq, HostReq, SystemInfo, upload_host::InfoType},
+    socket,
+};
+use entity::tbl_host_change;
+use sea_orm::{ActiveValue::Set, DatabaseConnection};
+use std::collections::BTreeMap;
+
+#[derive(Debug, PartialEq)]
+pub struct Change {
+    pub info_type: &'static str,
+    pub item: String,
+    // added、removed、changed
+    pub change: &'static str,
+}
+
+/// 将差量中列出的部分覆盖到已保存的主机信息上
+pub fn merge(host_req: &mut HostReq, host_diff_req: &HostDiffReq) {
+    let diff = host_diff_req.host.clone().unwrap_or_default();
+    for info_type in host_diff_req.info_types() {
+        match info_type {
+            InfoType::System => host_req.system = diff.system.clone(),
+            InfoType::Disk => host_req.disks = diff.disks.clone(),
+            InfoType::Network => host_req.networks = diff.networks.clone(),
+            InfoType::Software => host_req.softwares = diff.softwares.clone(),
+            InfoType::Socket => host_req.sockets = diff.sockets.clone(),
+            InfoType::Hardware => host_req.hardware = diff.hardware.clone(),
+            InfoType::Account => host_req.account = diff.account.clone(),
+            InfoType::Service => host_req.services = diff.services.clone(),
+            InfoType::ScheduledTask => host_req.scheduled_tasks = diff.scheduled_tasks.clone(),
+        }
+    }
+}
+
+/// 逐条比较新旧主机信息，旧数据中为空的部分视为首次采集，不记录变化
+/// 进程、连接和计数类字段变化频繁，不计入
+pub fn changes(old: &HostReq, new: &HostReq) -> Vec<Change> {
+    let mut r = Vec::new();
+    if let (Some(old_system), Some(new_system)) = (&old.system, &new.system) {
+        let strip = |v: &SystemInfo| SystemInfo {
+            processes: Vec::new(),
+            ..v.clone()
+        };
+        let item = new_system.host_name.clone().unwrap_or("system".to_string());
+        diff_items(
+            &mut r,
+            "system",
+            BTreeMap::from([(item.clone(), strip(old_system))]),
+            BTreeMap::from([(item, strip(new_system))]),
+        );
+    }
+    if !old.disks.is_empty() {
+        let items = |v: &HostReq| {
+            v.disks
+                .iter()
+                .map(|v| {
+                    let value = (
+                        v.name.clone(),
+                        v.file_system.clone(),
+                        v.kind.clone(),
+                        v.total_space,
+                    );
+                    (v.mount_point.clone(), value)
+                })
+                .collect()
+        };
+        diff_items(&mut r, "disk", items(old), items(new));
+    }
+    if !old.networks.is_empty() {
+        let items = |v: &HostReq| {
+            v.networks
+                .iter()
+                .map(|v| (v.interface_name.clone(), v.addrs.clone()))
+                .collect()
+        };
+        diff_items(&mut r, "network", items(old), items(new));
+    }
+    if !old.softwares.is_empty() {
+        let items = |v: &HostReq| {
+            v.softwares
+                .iter()
+                .map(|v| {
+                    let path = v.path.as_deref().unwrap_or_default();
+                    let key = format!("{}:{}:{}:{}", v.source, v.name, v.arch, path);
+                    (key, v.version.clone())
+                })
+                .collect()
+        };
+        diff_items(&mut r, "software", items(old), items(new));
+    }
+    if !old.sockets.is_empty() {
+        let items = |v: &HostReq| {
+            socket::listening(&v.sockets)
+                .into_iter()
+                .map(|v| {
+                    let key = format!("{} {}:{}", v.protocol, v.local_addr, v.local_port);
+                    (key, v.process_name.clone())
+                })
+                .collect()
+        };
+        diff_items(&mut r, "socket", items(old), items(new));
+    }
+    if let (Some(old_hardware), Some(new_hardware)) = (&old.hardware, &new.hardware) {
+        diff_items(
+            &mut r,
+            "hardware",
+            BTreeMap::from([("hardware".to_string(), old_hardware)]),
+            BTreeMap::from([("hardware".to_string(), new_hardware)]),
+        );
+    }
+    if let (Some(old_account), Some(new_account)) = (&old.account, &new.account) {
+        let items = |v: &AccountInfo| {
+            v.users
+                .iter()
+                .map(|v| (v.name.clone(), v.clone()))
+                .collect()
+        };
+        diff_items(&mut r, "account", items(old_account), items(new_account));
+    }
+    if !old.services.is_empty() {
+        let items = |v: &HostReq| {
+            v.services
+                .iter()
+                .map(|v| {
+                    let value = (
+                        v.load_state.clone(),
+                        v.active_state.clone(),
+                        v.unit_file_state.clone(),
+                    );
+                    (v.name.clone(), value)
+                })
+                .collect()
+        };
+        diff_items(&mut r, "service", items(old), items(new));
+    }
+    if !old.scheduled_tasks.is_empty() {
+        let items = |v: &HostReq| {
+            v.scheduled_tasks
+                .iter()
+                .map(|v| {
+                    let target = v.unit.as_deref().unwrap_or(&v.command);
+                    let key = format!("{}: {} {}", v.path, v.schedule, target);
+                    (key, (v.user.clone(), v.modified_at))
+                })
+                .collect()
+        };
+        diff_items(&mut r, "scheduled_task", items(old), items(new));
+    }
+    r
+}
+
+fn diff_items<T: PartialEq>(
+    r: &mut Vec<Change>,
+    info_type: &'static str,
+    old: BTreeMap<String, T>,
+    mut new: BTreeMap<String, T>,
+) {
+    for (item, old_value) in old {
+        let change = match new.remove(&item) {
+            Some(new_value) if new_value == old_value => continue,
+            Some(_) => "changed",
+            None => "removed",
+        };
+        r.push(Change {
+            info_type,
+            item,
+            change,
+        });
+    }
+    for item in new.into_keys() {
+        r.push(Change {
+            info_type,
+            item,
+            change: "added",
+        });
+    }
+}
+
+/// 记录主机信息的变化
+pub async fn save(
+    db_conn: &DatabaseConnection,
+    agent_id: &str,
+    version: i64,
+    changes: &[Change],
+) -> anyhow::Result<()> {
+    if changes.is_empty() {
+        return Ok(());
+    }
+    let tbl_host_change_ams: Vec<tbl_host_change::ActiveModel> = changes
+        .iter()
+        .map(|change| tbl_host_change::ActiveModel {
+            id: Set(uuid::Uuid::new_v4().to_string()),
+            agent_id: Set(agent_id.to_string()),
+            version: Set(version),
+            info_type: Set(change.info_type.to_string()),
+            item: Set(change.item.clone()),
+            change: Set(change.change.to_string()),
+            ..Default::default()
+        })
+        .collect();
+    batch::insert::<tbl_host_change::Entity>(db_conn, tbl_host_change_ams).await?;
+    log::info!("save {} change(s) of {}", changes.len(), agent_id);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::DiskInfo;
+
+    fn disk(mount_point: &str, total_space: u64, available_space: u64) -> DiskInfo {
+        DiskInfo {
+            name: "sda1".to_string(),
+            mount_point: mount_point.to_string(),
+            total_space,
+            available_space,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn changes_test() {
+        let old = HostReq {
+            disks: vec![
+                disk("/", 100, 50),
+                disk("/boot", 10, 5),
+                disk("/data", 10, 5),
+            ],
+            ..Default::default()
+        };
+        let new = HostReq {
+            disks: vec![
+                disk("/", 100, 40),
+                disk("/boot", 20, 5),
+                disk("/home", 10, 5),
+            ],
+            // 旧数据中没有网卡，视为首次采集
+            networks: vec![Default::default()],
+            ..Default::default()
+        };
+        let changes = changes(&old, &new);
+        assert_eq!(
+            changes,
+            vec![
+                Change {
+                    info_type: "disk",
+                    item: "/boot".to_string(),
+                    change: "changed",
+                },
+                Change {
+                    info_type: "disk",
+                    item: "/data".to_string(),
+                    change: "removed",
+                },
+                Change {
+                    info_type: "disk",
+                    item: "/home".to_string(),
+                    change: "added",
+                },
+            ]
+        );
+    }
+}
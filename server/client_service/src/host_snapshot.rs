@@ -0,0 +1,191 @@
+use crate::{
+    config::CLIENT_SERVICE_TOML,
+    proto::{HostReq, SystemInfo},
+    socket,
+};
+use entity::tbl_host_snapshot;
+use prost::Message;
+use sea_orm::{
+    ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect,
+};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+// 每条 delete 语句删除的快照数量
+const DELETE_BATCH_SIZE: usize = 200;
+
+/// 保存主机信息快照，与最近一次快照只有进程、连接和计数类字段不同时不保存
+pub async fn save(
+    db_conn: &DatabaseConnection,
+    agent_id: &str,
+    version: i64,
+    host_req: &HostReq,
+) -> anyhow::Result<()> {
+    let hash = format!("{:x}", Sha256::digest(normalize(host_req).encode_to_vec()));
+    let latest_hash: Option<String> = tbl_host_snapshot::Entity::find()
+        .select_only()
+        .column(tbl_host_snapshot::Column::Hash)
+        .filter(tbl_host_snapshot::Column::AgentId.eq(agent_id))
+        .order_by_desc(tbl_host_snapshot::Column::CreatedAt)
+        .order_by_desc(tbl_host_snapshot::Column::Version)
+        .into_tuple()
+        .one(db_conn)
+        .await?;
+    if latest_hash.as_ref() == Some(&hash) {
+        log::info!("{agent_id} snapshot of version {version} unchanged");
+        return Ok(());
+    }
+    let tbl_host_snapshot_am = tbl_host_snapshot::ActiveModel {
+        id: Set(uuid::Uuid::new_v4().to_string()),
+        agent_id: Set(agent_id.to_string()),
+        version: Set(version),
+        hash: Set(hash),
+        content: Set(host_req.encode_to_vec()),
+        ..Default::default()
+    };
+    tbl_host_snapshot::Entity::insert(tbl_host_snapshot_am)
+        .exec(db_conn)
+        .await?;
+
+    // 超出数量上限的旧快照
+    let expired: Vec<String> = tbl_host_snapshot::Entity::find()
+        .select_only()
+        .column(tbl_host_snapshot::Column::Id)
+        .filter(tbl_host_snapshot::Column::AgentId.eq(agent_id))
+        .order_by_desc(tbl_host_snapshot::Column::CreatedAt)
+        .order_by_desc(tbl_host_snapshot::Column::Version)
+        .offset(CLIENT_SERVICE_TOML.snapshot.max_count)
+        .into_tuple()
+        .all(db_conn)
+        .await?;
+    delete(db_conn, &expired).await?;
+    Ok(())
+}
+
+/// 计算哈希用的副本，与 host_change::changes 一样去掉进程、已建立的连接和计数类字段，并按稳定的键排序
+fn normalize(host_req: &HostReq) -> HostReq {
+    let mut host_req = host_req.clone();
+    host_req.system = host_req.system.map(|v| SystemInfo {
+        processes: Vec::new(),
+        ..v
+    });
+    for disk in &mut host_req.disks {
+        disk.available_space = 0;
+    }
+    host_req
+        .disks
+        .sort_by(|a, b| a.mount_point.cmp(&b.mount_point));
+    for network in &mut host_req.networks {
+        network.total_received = 0;
+        network.total_transmitted = 0;
+    }
+    host_req
+        .networks
+        .sort_by(|a, b| a.interface_name.cmp(&b.interface_name));
+    host_req.sockets = socket::listening(&host_req.sockets)
+        .into_iter()
+        .cloned()
+        .collect();
+    host_req
+}
+
+async fn delete(db_conn: &DatabaseConnection, ids: &[String]) -> anyhow::Result<u64> {
+    let mut rows_affected = 0;
+    for chunk in ids.chunks(DELETE_BATCH_SIZE) {
+        rows_affected += tbl_host_snapshot::Entity::delete_many()
+            .filter(tbl_host_snapshot::Column::Id.is_in(chunk))
+            .exec(db_conn)
+            .await?
+            .rows_affected;
+    }
+    Ok(rows_affected)
+}
+
+/// 按配置的保留天数定时清理快照，每台主机最新的快照始终保留
+pub fn purge_task(db_conn: DatabaseConnection) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(3600));
+        loop {
+            interval.tick().await;
+            match purge(&db_conn).await {
+                Ok(0) => {}
+                Ok(v) => log::info!("purge {v} host snapshot(s)"),
+                Err(e) => log::error!("purge host snapshots err: {}", e),
+            }
+        }
+    });
+}
+
+async fn purge(db_conn: &DatabaseConnection) -> anyhow::Result<u64> {
+    let days = CLIENT_SERVICE_TOML.snapshot.retention_days;
+    let before = chrono::Utc::now().naive_utc() - chrono::Duration::days(days);
+    let latest: HashMap<String, chrono::NaiveDateTime> = tbl_host_snapshot::Entity::find()
+        .select_only()
+        .column(tbl_host_snapshot::Column::AgentId)
+        .column_as(tbl_host_snapshot::Column::CreatedAt.max(), "created_at")
+        .group_by(tbl_host_snapshot::Column::AgentId)
+        .into_tuple::<(String, chrono::NaiveDateTime)>()
+        .all(db_conn)
+        .await?
+        .into_iter()
+        .collect();
+    let expired: Vec<String> = tbl_host_snapshot::Entity::find()
+        .select_only()
+        .column(tbl_host_snapshot::Column::Id)
+        .column(tbl_host_snapshot::Column::AgentId)
+        .column(tbl_host_snapshot::Column::CreatedAt)
+        .filter(tbl_host_snapshot::Column::CreatedAt.lt(before))
+        .into_tuple::<(String, String, chrono::NaiveDateTime)>()
+        .all(db_conn)
+        .await?
+        .into_iter()
+        .filter(|(_, agent_id, created_at)| latest.get(agent_id).is_some_and(|v| created_at < v))
+        .map(|(id, _, _)| id)
+        .collect();
+    delete(db_conn, &expired).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::{DiskInfo, ProcessInfo, SocketInfo};
+
+    #[test]
+    fn normalize_test() {
+        let old = HostReq {
+            system: Some(SystemInfo {
+                host_name: Some("web-1".to_string()),
+                processes: vec![ProcessInfo {
+                    pid: 1,
+                    cpu_usage: 0.5,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }),
+            disks: vec![DiskInfo {
+                mount_point: "/".to_string(),
+                available_space: 100,
+                ..Default::default()
+            }],
+            sockets: vec![SocketInfo {
+                protocol: "tcp".to_string(),
+                state: "ESTABLISHED".to_string(),
+                remote_port: 50000,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let hash = |v: &HostReq| Sha256::digest(normalize(v).encode_to_vec());
+
+        // 只有进程、可用空间和已建立的连接变化
+        let mut new = old.clone();
+        new.system.as_mut().unwrap().processes[0].cpu_usage = 12.5;
+        new.disks[0].available_space = 50;
+        new.sockets[0].remote_port = 50001;
+        assert_eq!(hash(&old), hash(&new));
+
+        new.system.as_mut().unwrap().host_name = Some("web-2".to_string());
+        assert_ne!(hash(&old), hash(&new));
+    }
+}
@@ -0,0 +1,213 @@
+use crate::proto::{Fingerprint, RegisterReq};
+use entity::{tbl_agent, tbl_agent_conflict};
+use moka::sync::Cache;
+use sea_orm::{
+    ActiveValue::Set, ColumnTrait, Condition, DatabaseConnection, EntityTrait, QueryFilter,
+};
+
+/// 决定注册 agent 的编号
+///
+/// 指纹由 agent 自行上报，只用于发现冲突，不据此交出其他 agent 的编号：
+/// 声明的编号已被指纹不同的机器使用（克隆或迁移），或声明的编号不存在但指纹与已有 agent 相同（重装），
+/// 都分配新编号并记录冲突，由运维处理旧编号；旧版本 agent 不上报指纹，沿用声明的编号
+pub async fn assign(
+    db_conn: &DatabaseConnection,
+    online_agent_cache: &Cache<String, String>,
+    register_req: &RegisterReq,
+    peer_addr: Option<String>,
+) -> anyhow::Result<String> {
+    let agent_id = &register_req.agent_id;
+    let Some(fingerprint) = &register_req.fingerprint else {
+        return Ok(agent_id.clone());
+    };
+    match tbl_agent::Entity::find_by_id(agent_id).one(db_conn).await? {
+        // 升级前注册的 agent 没有指纹，首次上报时记录
+        Some(tbl_agent) if tbl_agent.machine_id.is_none() && tbl_agent.dmi_uuid.is_none() => {
+            Ok(agent_id.clone())
+        }
+        Some(tbl_agent) if same_machine(&tbl_agent, fingerprint) => Ok(agent_id.clone()),
+        Some(_) => {
+            let assigned_agent_id = uuid::Uuid::new_v4().to_string();
+            log::warn!(
+                "{agent_id} claimed by another machine {:?}, assigned {assigned_agent_id}",
+                fingerprint.hostname
+            );
+            conflict(
+                db_conn,
+                online_agent_cache,
+                agent_id,
+                &assigned_agent_id,
+                fingerprint,
+                peer_addr,
+            )
+            .await?;
+            Ok(assigned_agent_id)
+        }
+        // 本地没有编号或编号已被删除
+        None => {
+            let assigned_agent_id = uuid::Uuid::new_v4().to_string();
+            if let Some(tbl_agent) = find(db_conn, fingerprint).await? {
+                log::warn!(
+                    "{agent_id} matched {} by fingerprint, assigned {assigned_agent_id}",
+                    tbl_agent.id
+                );
+                conflict(
+                    db_conn,
+                    online_agent_cache,
+                    &tbl_agent.id,
+                    &assigned_agent_id,
+                    fingerprint,
+                    peer_addr,
+                )
+                .await?;
+            }
+            Ok(assigned_agent_id)
+        }
+    }
+}
+
+/// 记录编号冲突，agent_id 为已有的编号
+async fn conflict(
+    db_conn: &DatabaseConnection,
+    online_agent_cache: &Cache<String, String>,
+    agent_id: &str,
+    assigned_agent_id: &str,
+    fingerprint: &Fingerprint,
+    peer_addr: Option<String>,
+) -> anyhow::Result<()> {
+    let tbl_agent_conflict_am = tbl_agent_conflict::ActiveModel {
+        id: Set(uuid::Uuid::new_v4().to_string()),
+        agent_id: Set(agent_id.to_string()),
+        assigned_agent_id: Set(assigned_agent_id.to_string()),
+        machine_id: Set(non_empty(&fingerprint.machine_id)),
+        dmi_uuid: Set(non_empty(&fingerprint.dmi_uuid)),
+        macs: Set(non_empty(&fingerprint.macs.join(","))),
+        container_id: Set(non_empty(&fingerprint.container_id)),
+        hostname: Set(non_empty(&fingerprint.hostname)),
+        peer_addr: Set(peer_addr),
+        online: Set(online_agent_cache.contains_key(agent_id)),
+        status: Set("open".to_string()),
+        ..Default::default()
+    };
+    tbl_agent_conflict::Entity::insert(tbl_agent_conflict_am)
+        .exec(db_conn)
+        .await?;
+    Ok(())
+}
+
+/// 注册成功后记录指纹
+pub fn save(tbl_agent_am: &mut tbl_agent::ActiveModel, fingerprint: &Fingerprint) {
+    tbl_agent_am.machine_id = Set(non_empty(&fingerprint.machine_id));
+    tbl_agent_am.dmi_uuid = Set(non_empty(&fingerprint.dmi_uuid));
+    tbl_agent_am.macs = Set(non_empty(&fingerprint.macs.join(",")));
+    tbl_agent_am.container_id = Set(non_empty(&fingerprint.container_id));
+    tbl_agent_am.hostname = Set(non_empty(&fingerprint.hostname));
+}
+
+async fn find(
+    db_conn: &DatabaseConnection,
+    fingerprint: &Fingerprint,
+) -> anyhow::Result<Option<tbl_agent::Model>> {
+    let mut condition = Condition::any();
+    if !fingerprint.dmi_uuid.is_empty() {
+        condition = condition.add(tbl_agent::Column::DmiUuid.eq(&fingerprint.dmi_uuid));
+    }
+    if !fingerprint.machine_id.is_empty() {
+        condition = condition.add(tbl_agent::Column::MachineId.eq(&fingerprint.machine_id));
+    }
+    if condition.is_empty() {
+        return Ok(None);
+    }
+    let candidates = tbl_agent::Entity::find()
+        .filter(condition)
+        .all(db_conn)
+        .await?;
+    Ok(candidates
+        .into_iter()
+        .find(|tbl_agent| same_machine(tbl_agent, fingerprint)))
+}
+
+/// dmi uuid 都存在时以它为准，否则比较 machine-id 并要求网卡有重合；容器还需容器 id 一致
+fn same_machine(tbl_agent: &tbl_agent::Model, fingerprint: &Fingerprint) -> bool {
+    if tbl_agent.container_id.as_deref().unwrap_or_default() != fingerprint.container_id {
+        return false;
+    }
+    if let Some(dmi_uuid) = &tbl_agent.dmi_uuid
+        && !fingerprint.dmi_uuid.is_empty()
+    {
+        return *dmi_uuid == fingerprint.dmi_uuid;
+    }
+    if tbl_agent.machine_id.as_deref().unwrap_or_default() != fingerprint.machine_id
+        || fingerprint.machine_id.is_empty()
+    {
+        return false;
+    }
+    match tbl_agent.macs.as_deref() {
+        Some(macs) if !fingerprint.macs.is_empty() => macs
+            .split(',')
+            .any(|mac| fingerprint.macs.iter().any(|v| v == mac)),
+        _ => true,
+    }
+}
+
+fn non_empty(v: &str) -> Option<String> {
+    (!v.is_empty()).then(|| v.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use migration::{Migrator, MigratorTrait};
+    use sea_orm::{Database, PaginatorTrait};
+
+    #[tokio::test]
+    async fn assign_test() -> anyhow::Result<()> {
+        let db_conn = Database::connect("sqlite::memory:").await?;
+        Migrator::up(&db_conn, None).await?;
+        let online_agent_cache = Cache::new(10);
+        let fingerprint = Fingerprint {
+            machine_id: "m1".to_string(),
+            dmi_uuid: "d1".to_string(),
+            ..Default::default()
+        };
+        let mut tbl_agent_am = tbl_agent::ActiveModel {
+            id: Set("agent-1".to_string()),
+            version: Set("0.1.0".to_string()),
+            state: Set("Online".to_string()),
+            token: Set("token".to_string()),
+            approval_state: Set("Approved".to_string()),
+            ..Default::default()
+        };
+        save(&mut tbl_agent_am, &fingerprint);
+        tbl_agent::Entity::insert(tbl_agent_am)
+            .exec(&db_conn)
+            .await?;
+        let assign = |agent_id: &str, fingerprint: &Fingerprint| {
+            let register_req = RegisterReq {
+                agent_id: agent_id.to_string(),
+                fingerprint: Some(fingerprint.clone()),
+                ..Default::default()
+            };
+            let db_conn = db_conn.clone();
+            let online_agent_cache = online_agent_cache.clone();
+            async move { assign(&db_conn, &online_agent_cache, &register_req, None).await }
+        };
+
+        assert_eq!(assign("agent-1", &fingerprint).await?, "agent-1");
+        // 指纹相同但声明了其他编号，不交出已有的编号
+        let reinstalled = assign("agent-2", &fingerprint).await?;
+        assert_ne!(reinstalled, "agent-1");
+        let other = Fingerprint {
+            dmi_uuid: "d2".to_string(),
+            ..fingerprint.clone()
+        };
+        let cloned = assign("agent-1", &other).await?;
+        assert_ne!(cloned, "agent-1");
+        let conflicts = tbl_agent_conflict::Entity::find()
+            .filter(tbl_agent_conflict::Column::AgentId.eq("agent-1"))
+            .count(&db_conn)
+            .await?;
+        assert_eq!(conflicts, 2);
+        Ok(())
+    }
+}
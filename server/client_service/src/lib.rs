@@ -1,7 +1,25 @@
+pub mod account;
+pub mod admission;
 pub mod agent;
+pub mod agent_cert;
+pub mod artifact;
+pub mod batch;
 pub mod config;
+pub mod control;
+pub mod enrollment;
+pub mod host_change;
+pub mod host_snapshot;
+pub mod identity;
+pub mod llm_consumer;
+pub mod metrics;
+pub mod profile;
+pub mod report;
 pub mod server;
+pub mod service;
+pub mod socket;
+pub mod software;
 pub mod uds;
+pub mod upgrade;
 pub mod proto {
     tonic::include_proto!("z11n");
 }
@@ -0,0 +1,200 @@
+use crate::{config::CLIENT_SERVICE_TOML, proto::LlmConsumerStatus};
+use entity::{tbl_llm_consumer, tbl_llm_task};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait,
+    IntoActiveModel, QueryFilter, QueryOrder,
+};
+
+pub async fn save_status(
+    db_conn: &DatabaseConnection,
+    agent_id: &str,
+    status: &LlmConsumerStatus,
+) -> anyhow::Result<()> {
+    let models = bincode::encode_to_vec(&status.models, bincode::config::standard())?;
+    let loaded_models = bincode::encode_to_vec(&status.loaded_models, bincode::config::standard())?;
+    match tbl_llm_consumer::Entity::find_by_id(agent_id)
+        .one(db_conn)
+        .await?
+    {
+        Some(tbl_llm_consumer) => {
+            let mut tbl_llm_consumer_am = tbl_llm_consumer.into_active_model();
+            tbl_llm_consumer_am.models = Set(models);
+            tbl_llm_consumer_am.loaded_models = Set(loaded_models);
+            tbl_llm_consumer_am.total_slots = Set(status.total_slots as i32);
+            tbl_llm_consumer_am.free_slots = Set(status.free_slots as i32);
+            tbl_llm_consumer_am.tokens_per_second = Set(status.tokens_per_second);
+            tbl_llm_consumer_am.backend_reachable = Set(status.backend_reachable);
+            tbl_llm_consumer_am.updated_at = Set(chrono::Utc::now().naive_utc());
+            tbl_llm_consumer_am.save(db_conn).await?;
+        }
+        None => {
+            let tbl_llm_consumer_am = tbl_llm_consumer::ActiveModel {
+                agent_id: Set(agent_id.to_string()),
+                models: Set(models),
+                loaded_models: Set(loaded_models),
+                total_slots: Set(status.total_slots as i32),
+                free_slots: Set(status.free_slots as i32),
+                tokens_per_second: Set(status.tokens_per_second),
+                backend_reachable: Set(status.backend_reachable),
+                updated_at: Set(chrono::Utc::now().naive_utc()),
+            };
+            tbl_llm_consumer::Entity::insert(tbl_llm_consumer_am)
+                .exec(db_conn)
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+/// 为 consumer 挑选下一个任务
+///
+/// 没有上报过状态(或状态已过期)的 consumer 按提交顺序领取任意任务；
+/// 上报过状态的 consumer 只领取后端可用模型的任务，优先已加载的模型，
+/// 未加载的模型如果在其他有空闲 worker 的 consumer 上已加载，则留给对方；
+/// 后端不可达时不分配任务。
+pub async fn next_task(
+    db_conn: &DatabaseConnection,
+    agent_id: &str,
+) -> anyhow::Result<Option<tbl_llm_task::Model>> {
+    let pending = || {
+        tbl_llm_task::Entity::find()
+            .filter(tbl_llm_task::Column::ReqPullAt.is_null())
+            .order_by_asc(tbl_llm_task::Column::ReqPushAt)
+    };
+    let deadline = chrono::Utc::now().naive_utc()
+        - chrono::Duration::seconds(CLIENT_SERVICE_TOML.agent.offline_ex);
+    let tbl_llm_consumers = tbl_llm_consumer::Entity::find()
+        .filter(tbl_llm_consumer::Column::UpdatedAt.gt(deadline))
+        .all(db_conn)
+        .await?;
+    let Some(tbl_llm_consumer) = tbl_llm_consumers.iter().find(|v| v.agent_id.eq(agent_id)) else {
+        return Ok(pending().one(db_conn).await?);
+    };
+    if !tbl_llm_consumer.backend_reachable {
+        return Ok(None);
+    }
+    let candidates = candidates(tbl_llm_consumer, &tbl_llm_consumers)?;
+    for models in [candidates.loaded, candidates.unloaded] {
+        if models.is_empty() {
+            continue;
+        }
+        let tbl_llm_task = pending()
+            .filter(tbl_llm_task::Column::Model.is_in(models))
+            .one(db_conn)
+            .await?;
+        if tbl_llm_task.is_some() {
+            return Ok(tbl_llm_task);
+        }
+    }
+    Ok(None)
+}
+
+#[derive(Debug, PartialEq)]
+struct Candidates {
+    // 已加载的模型，优先领取
+    loaded: Vec<String>,
+    // 未加载、也没有在其他空闲 consumer 上加载的模型
+    unloaded: Vec<String>,
+}
+
+/// consumer 可以领取的模型，tbl_llm_consumers 为所有状态未过期的 consumer
+fn candidates(
+    tbl_llm_consumer: &tbl_llm_consumer::Model,
+    tbl_llm_consumers: &[tbl_llm_consumer::Model],
+) -> anyhow::Result<Candidates> {
+    let models = model_names(&decode_models(&tbl_llm_consumer.models)?);
+    let loaded = model_names(&decode_models(&tbl_llm_consumer.loaded_models)?);
+    let mut loaded_elsewhere = Vec::new();
+    for other in tbl_llm_consumers {
+        if other.agent_id.eq(&tbl_llm_consumer.agent_id)
+            || !other.backend_reachable
+            || other.free_slots <= 0
+        {
+            continue;
+        }
+        loaded_elsewhere.extend(model_names(&decode_models(&other.loaded_models)?));
+    }
+    let unloaded = models
+        .into_iter()
+        .filter(|model| !loaded.contains(model) && !loaded_elsewhere.contains(model))
+        .collect();
+    Ok(Candidates { loaded, unloaded })
+}
+
+fn decode_models(encoded: &[u8]) -> anyhow::Result<Vec<String>> {
+    let (models, _len): (Vec<String>, usize) =
+        bincode::decode_from_slice(encoded, bincode::config::standard())?;
+    Ok(models)
+}
+
+/// ollama 上报的模型名带 tag，任务里省略 tag 时等同于 latest
+fn model_names(models: &[String]) -> Vec<String> {
+    let mut names = Vec::new();
+    for model in models {
+        names.push(model.clone());
+        if let Some(name) = model.strip_suffix(":latest") {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn consumer(
+        agent_id: &str,
+        models: &[&str],
+        loaded_models: &[&str],
+        free_slots: i32,
+    ) -> tbl_llm_consumer::Model {
+        let encode = |v: &[&str]| {
+            let v: Vec<String> = v.iter().map(|v| v.to_string()).collect();
+            bincode::encode_to_vec(&v, bincode::config::standard()).unwrap_or_default()
+        };
+        tbl_llm_consumer::Model {
+            agent_id: agent_id.to_string(),
+            models: encode(models),
+            loaded_models: encode(loaded_models),
+            total_slots: 1,
+            free_slots,
+            tokens_per_second: 0.0,
+            backend_reachable: true,
+            updated_at: chrono::Utc::now().naive_utc(),
+        }
+    }
+
+    #[test]
+    fn model_names_test() {
+        let models = vec!["qwen3:latest".to_string(), "llama3:8b".to_string()];
+        assert_eq!(
+            model_names(&models),
+            vec!["qwen3:latest", "qwen3", "llama3:8b"]
+        );
+    }
+
+    #[test]
+    fn candidates_test() -> anyhow::Result<()> {
+        let a = consumer(
+            "a",
+            &["qwen3:latest", "llama3:8b", "gemma3:4b"],
+            &["qwen3:latest"],
+            1,
+        );
+        let b = consumer("b", &["llama3:8b"], &["llama3:8b"], 1);
+        // c 没有空闲 worker，已加载的模型不需要让给它
+        let c = consumer("c", &["gemma3:4b"], &["gemma3:4b"], 0);
+        let mut d = consumer("d", &["gemma3:4b"], &["gemma3:4b"], 1);
+        d.backend_reachable = false;
+        let tbl_llm_consumers = vec![a.clone(), b, c, d];
+        assert_eq!(
+            candidates(&a, &tbl_llm_consumers)?,
+            Candidates {
+                loaded: vec!["qwen3:latest".to_string(), "qwen3".to_string()],
+                unloaded: vec!["gemma3:4b".to_string()],
+            }
+        );
+        Ok(())
+    }
+}
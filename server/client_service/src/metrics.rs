@@ -0,0 +1,242 @@
+use std::collections::BTreeMap;
+
+use crate::{config::CLIENT_SERVICE_TOML, proto::MetricSample};
+use entity::tbl_host_metric;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait,
+    IntoActiveModel, QueryFilter, TransactionTrait,
+};
+
+// 降采样粒度：分钟、小时
+pub const MINUTE: i32 = 60;
+pub const HOUR: i32 = 3600;
+
+/// 区间内采样值的累加
+#[derive(Debug, Default)]
+struct Aggregate {
+    samples: i32,
+    cpu_sum: f64,
+    cpu_max: f64,
+    used_memory: f64,
+    total_memory: f64,
+    used_swap: f64,
+    total_swap: f64,
+    load_one: f64,
+    load_five: f64,
+    load_fifteen: f64,
+    disk_used: f64,
+    disk_total: f64,
+    rx_rate: f64,
+    tx_rate: f64,
+}
+
+impl Aggregate {
+    fn add(&mut self, sample: &MetricSample) {
+        let cpu_usage = sample.cpu_usage as f64;
+        self.samples += 1;
+        self.cpu_sum += cpu_usage;
+        self.cpu_max = self.cpu_max.max(cpu_usage);
+        self.used_memory += sample.used_memory as f64;
+        self.total_memory += sample.total_memory as f64;
+        self.used_swap += sample.used_swap as f64;
+        self.total_swap += sample.total_swap as f64;
+        self.load_one += sample.load_one;
+        self.load_five += sample.load_five;
+        self.load_fifteen += sample.load_fifteen;
+        self.disk_used += sample.disk_used as f64;
+        self.disk_total += sample.disk_total as f64;
+        self.rx_rate += sample.rx_rate;
+        self.tx_rate += sample.tx_rate;
+    }
+
+    /// 把数据库中已有的均值按采样数还原为累加值
+    fn add_model(&mut self, model: &tbl_host_metric::Model) {
+        let n = model.samples as f64;
+        self.samples += model.samples;
+        self.cpu_sum += model.cpu_avg * n;
+        self.cpu_max = self.cpu_max.max(model.cpu_max);
+        self.used_memory += model.used_memory as f64 * n;
+        self.total_memory += model.total_memory as f64 * n;
+        self.used_swap += model.used_swap as f64 * n;
+        self.total_swap += model.total_swap as f64 * n;
+        self.load_one += model.load_one * n;
+        self.load_five += model.load_five * n;
+        self.load_fifteen += model.load_fifteen * n;
+        self.disk_used += model.disk_used as f64 * n;
+        self.disk_total += model.disk_total as f64 * n;
+        self.rx_rate += model.rx_rate * n;
+        self.tx_rate += model.tx_rate * n;
+    }
+
+    fn fill(&self, am: &mut tbl_host_metric::ActiveModel) {
+        let n = self.samples.max(1) as f64;
+        am.samples = Set(self.samples);
+        am.cpu_avg = Set(self.cpu_sum / n);
+        am.cpu_max = Set(self.cpu_max);
+        am.used_memory = Set((self.used_memory / n) as i64);
+        am.total_memory = Set((self.total_memory / n) as i64);
+        am.used_swap = Set((self.used_swap / n) as i64);
+        am.total_swap = Set((self.total_swap / n) as i64);
+        am.load_one = Set(self.load_one / n);
+        am.load_five = Set(self.load_five / n);
+        am.load_fifteen = Set(self.load_fifteen / n);
+        am.disk_used = Set((self.disk_used / n) as i64);
+        am.disk_total = Set((self.disk_total / n) as i64);
+        am.rx_rate = Set(self.rx_rate / n);
+        am.tx_rate = Set(self.tx_rate / n);
+    }
+}
+
+/// 按粒度将采样分到各个区间，key 为区间起点的毫秒时间戳
+fn buckets(samples: &[MetricSample], resolution: i32) -> BTreeMap<i64, Aggregate> {
+    let mut buckets: BTreeMap<i64, Aggregate> = BTreeMap::new();
+    let step = resolution as i64 * 1000;
+    for sample in samples {
+        let bucket = sample.timestamp - sample.timestamp.rem_euclid(step);
+        buckets.entry(bucket).or_default().add(sample);
+    }
+    buckets
+}
+
+/// 将一批采样合并进分钟、小时两种粒度的聚合表
+pub async fn save(
+    db_conn: &DatabaseConnection,
+    agent_id: &str,
+    samples: &[MetricSample],
+) -> anyhow::Result<()> {
+    let txn = db_conn.begin().await?;
+    for resolution in [MINUTE, HOUR] {
+        for (bucket, mut aggregate) in buckets(samples, resolution) {
+            let bucket = match chrono::DateTime::from_timestamp_millis(bucket) {
+                Some(v) => v.naive_utc(),
+                None => continue,
+            };
+            match tbl_host_metric::Entity::find_by_id((agent_id.to_string(), resolution, bucket))
+                .one(&txn)
+                .await?
+            {
+                Some(tbl_host_metric) => {
+                    aggregate.add_model(&tbl_host_metric);
+                    let mut tbl_host_metric_am = tbl_host_metric.into_active_model();
+                    aggregate.fill(&mut tbl_host_metric_am);
+                    tbl_host_metric_am.save(&txn).await?;
+                }
+                None => {
+                    let mut tbl_host_metric_am = tbl_host_metric::ActiveModel {
+                        agent_id: Set(agent_id.to_string()),
+                        resolution: Set(resolution),
+                        bucket: Set(bucket),
+                        ..Default::default()
+                    };
+                    aggregate.fill(&mut tbl_host_metric_am);
+                    tbl_host_metric::Entity::insert(tbl_host_metric_am)
+                        .exec(&txn)
+                        .await?;
+                }
+            }
+        }
+    }
+    txn.commit().await?;
+    Ok(())
+}
+
+/// 按配置的保留天数定时清理聚合数据
+pub fn purge_task(db_conn: DatabaseConnection) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(3600));
+        loop {
+            interval.tick().await;
+            let metrics_config = &CLIENT_SERVICE_TOML.metrics;
+            for (resolution, days) in [
+                (MINUTE, metrics_config.minute_retention_days),
+                (HOUR, metrics_config.hour_retention_days),
+            ] {
+                let before = chrono::Utc::now().naive_utc() - chrono::Duration::days(days);
+                match tbl_host_metric::Entity::delete_many()
+                    .filter(tbl_host_metric::Column::Resolution.eq(resolution))
+                    .filter(tbl_host_metric::Column::Bucket.lt(before))
+                    .exec(&db_conn)
+                    .await
+                {
+                    Ok(delete_result) => {
+                        if delete_result.rows_affected > 0 {
+                            log::info!(
+                                "purge {} metric(s) of resolution {resolution}",
+                                delete_result.rows_affected
+                            );
+                        }
+                    }
+                    Err(e) => log::error!("purge metrics of resolution {resolution} err: {}", e),
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::TryIntoModel;
+
+    fn sample(timestamp: i64, cpu_usage: f32, disk_used: u64) -> MetricSample {
+        MetricSample {
+            timestamp,
+            cpu_usage,
+            disk_used,
+            disk_total: 1000,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn aggregate_test() {
+        let samples = vec![
+            sample(0, 10.0, 100),
+            sample(30_000, 30.0, 300),
+            sample(60_000, 50.0, 500),
+            sample(3_600_000, 70.0, 700),
+        ];
+        let minute = buckets(&samples, MINUTE);
+        assert_eq!(
+            minute.keys().copied().collect::<Vec<_>>(),
+            vec![0, 60_000, 3_600_000]
+        );
+        let hour = buckets(&samples, HOUR);
+        assert_eq!(hour.keys().copied().collect::<Vec<_>>(), vec![0, 3_600_000]);
+
+        let mut am = tbl_host_metric::ActiveModel {
+            ..Default::default()
+        };
+        hour[&0].fill(&mut am);
+        assert_eq!(am.samples, Set(3));
+        assert_eq!(am.cpu_avg, Set(30.0));
+        assert_eq!(am.cpu_max, Set(50.0));
+        assert_eq!(am.disk_used, Set(300));
+        assert_eq!(am.disk_total, Set(1000));
+    }
+
+    #[test]
+    fn add_model_test() -> anyhow::Result<()> {
+        // 同一区间分两批上报，合并后的均值按采样数加权
+        let mut am = tbl_host_metric::ActiveModel {
+            agent_id: Set("agent".to_string()),
+            resolution: Set(MINUTE),
+            bucket: Set(chrono::NaiveDateTime::default()),
+            ..Default::default()
+        };
+        buckets(&[sample(0, 10.0, 100), sample(10_000, 20.0, 200)], MINUTE)[&0].fill(&mut am);
+        let model = am.try_into_model()?;
+        let mut aggregate = Aggregate::default();
+        aggregate.add(&sample(20_000, 60.0, 600));
+        aggregate.add_model(&model);
+        let mut am = tbl_host_metric::ActiveModel {
+            ..Default::default()
+        };
+        aggregate.fill(&mut am);
+        assert_eq!(am.samples, Set(3));
+        assert_eq!(am.cpu_avg, Set(30.0));
+        assert_eq!(am.cpu_max, Set(60.0));
+        assert_eq!(am.disk_used, Set(300));
+        Ok(())
+    }
+}
@@ -0,0 +1,90 @@
+use crate::proto::{AgentProfile, HeartbeatReq, upload_host::InfoType};
+use entity::{tbl_agent, tbl_agent_profile, tbl_agent_profile_binding};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, prelude::Expr};
+
+/// 记录 agent 正在使用的配置模板，与分配的不一致时返回需要下发的模板
+///
+/// 取消分配后下发 id 为空的模板，agent 恢复本地配置
+pub async fn check(
+    db_conn: &DatabaseConnection,
+    agent_id: &str,
+    heartbeat_req: &HeartbeatReq,
+) -> anyhow::Result<Option<AgentProfile>> {
+    let Some(tbl_agent) = tbl_agent::Entity::find_by_id(agent_id).one(db_conn).await? else {
+        return Ok(None);
+    };
+    let running_id = Some(heartbeat_req.profile_id.clone()).filter(|v| !v.is_empty());
+    let running_version = running_id
+        .as_ref()
+        .map(|_| heartbeat_req.profile_version as i64);
+    if tbl_agent.profile_id != running_id || tbl_agent.profile_version != running_version {
+        tbl_agent::Entity::update_many()
+            .col_expr(
+                tbl_agent::Column::ProfileId,
+                Expr::value(running_id.clone()),
+            )
+            .col_expr(
+                tbl_agent::Column::ProfileVersion,
+                Expr::value(running_version),
+            )
+            .filter(tbl_agent::Column::Id.eq(agent_id))
+            .exec(db_conn)
+            .await?;
+        log::info!("{agent_id} running profile {running_id:?} {running_version:?}");
+    }
+    match assigned(db_conn, &tbl_agent).await? {
+        Some(tbl_agent_profile) => {
+            if running_id.as_deref() == Some(tbl_agent_profile.id.as_str())
+                && running_version == Some(tbl_agent_profile.version)
+            {
+                return Ok(None);
+            }
+            Ok(Some(to_proto(tbl_agent_profile)))
+        }
+        None if running_id.is_some() => Ok(Some(AgentProfile::default())),
+        None => Ok(None),
+    }
+}
+
+/// 分配给 agent 的配置模板，直接分配优先于按分组分配
+async fn assigned(
+    db_conn: &DatabaseConnection,
+    tbl_agent: &tbl_agent::Model,
+) -> anyhow::Result<Option<tbl_agent_profile::Model>> {
+    let mut targets = vec![("agent", tbl_agent.id.clone())];
+    if let Some(group_name) = &tbl_agent.group_name {
+        targets.push(("group", group_name.clone()));
+    }
+    for (target_type, target) in targets {
+        if let Some(tbl_agent_profile_binding) =
+            tbl_agent_profile_binding::Entity::find_by_id((target_type.to_string(), target))
+                .one(db_conn)
+                .await?
+        {
+            return Ok(
+                tbl_agent_profile::Entity::find_by_id(tbl_agent_profile_binding.profile_id)
+                    .one(db_conn)
+                    .await?,
+            );
+        }
+    }
+    Ok(None)
+}
+
+fn to_proto(tbl_agent_profile: tbl_agent_profile::Model) -> AgentProfile {
+    let collect = tbl_agent_profile
+        .collect
+        .split(',')
+        .filter_map(InfoType::from_str_name)
+        .map(Into::into)
+        .collect();
+    AgentProfile {
+        id: tbl_agent_profile.id,
+        version: tbl_agent_profile.version as u64,
+        heartbeat_interval: tbl_agent_profile.heartbeat_interval as u32,
+        metrics_interval: tbl_agent_profile.metrics_interval as u32,
+        metrics_batch: tbl_agent_profile.metrics_batch as u32,
+        collect,
+        collect_interval: tbl_agent_profile.collect_interval as u32,
+    }
+}
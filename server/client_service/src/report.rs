@@ -0,0 +1,81 @@
+use tonic::metadata::MetadataMap;
+
+// sled 中记录已入库上报 id 的树，值为入库时间毫秒
+const TREE: &str = "report_id";
+// 上报 id 的保留时间，agent 重放离线缓存通常在重连后立即完成
+const RETENTION_MILLIS: i64 = 7 * 24 * 3600 * 1000;
+
+/// agent 离线缓存的上报带有 report_id，没有时不去重
+pub fn key(metadata: &MetadataMap, agent_id: &str) -> Option<String> {
+    let report_id = metadata.get("report_id")?.to_str().ok()?;
+    key_of(agent_id, report_id)
+}
+
+/// 长连接上报的 report_id 在消息体中，为空时不去重
+pub fn key_of(agent_id: &str, report_id: &str) -> Option<String> {
+    if report_id.is_empty() {
+        return None;
+    }
+    Some(format!("{agent_id}/{report_id}"))
+}
+
+/// 上报是否已入库，重放的上报直接返回成功
+pub fn replayed(sled_db: &sled::Db, key: Option<&str>) -> bool {
+    let Some(key) = key else {
+        return false;
+    };
+    match sled_db
+        .open_tree(TREE)
+        .and_then(|tree| tree.contains_key(key))
+    {
+        Ok(v) => v,
+        Err(e) => {
+            log::error!("sled report_id get err: {}", e);
+            false
+        }
+    }
+}
+
+/// 上报入库后记录其 id
+pub fn stored(sled_db: &sled::Db, key: Option<String>) {
+    let Some(key) = key else {
+        return;
+    };
+    let now = chrono::Utc::now().timestamp_millis();
+    if let Err(e) = sled_db
+        .open_tree(TREE)
+        .and_then(|tree| tree.insert(key, &now.to_be_bytes()))
+    {
+        log::error!("sled report_id insert err: {}", e);
+    }
+}
+
+/// 定时清理过期的上报 id
+pub fn purge_task(sled_db: sled::Db) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(3600));
+        loop {
+            interval.tick().await;
+            match purge(&sled_db) {
+                Ok(0) => {}
+                Ok(v) => log::info!("purge {v} report id(s)"),
+                Err(e) => log::error!("purge report ids err: {}", e),
+            }
+        }
+    });
+}
+
+fn purge(sled_db: &sled::Db) -> anyhow::Result<u64> {
+    let tree = sled_db.open_tree(TREE)?;
+    let before = chrono::Utc::now().timestamp_millis() - RETENTION_MILLIS;
+    let mut count = 0;
+    for item in tree.iter() {
+        let (key, value) = item?;
+        let stored_at = <[u8; 8]>::try_from(value.as_ref()).map_or(0, i64::from_be_bytes);
+        if stored_at < before {
+            tree.remove(key)?;
+            count += 1;
+        }
+    }
+    Ok(count)
+}
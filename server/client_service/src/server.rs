@@ -1,39 +1,92 @@
-use std::fs;
+use std::{fs, str::FromStr};
 
 use crate::{
+    account,
+    admission::{Admission, Rejected},
     agent,
+    agent_cert::AgentCerts,
+    artifact,
     config::CLIENT_SERVICE_TOML,
+    control::{self, Connections, Session},
+    enrollment, host_change, host_snapshot, identity, llm_consumer, metrics, profile,
     proto::{
-        Empty, HeartbeatRsp, HostReq, LlmTaskAnswer, LlmTaskAnswers, LlmTaskId, LlmTaskQuestion,
-        LlmTaskQuestionReq, LlmTaskQuestionRsp, RegisterReq, RegisterRsp,
+        Attachment, CertReq, CertRsp, ControlReq, ControlRsp, DownloadChunk, DownloadReq,
+        Embedding, Empty, HeartbeatReq, HeartbeatRsp, HostDiffReq, HostDiffRsp, HostReq,
+        LlmTaskAnswer, LlmTaskAnswers, LlmTaskId, LlmTaskKind, LlmTaskQuestion, LlmTaskQuestionReq,
+        LlmTaskQuestionRsp, MetricsReq, RegisterReq, RegisterRsp, ServiceControlResult,
+        UpgradeResult,
+        heartbeat_rsp::Task,
         z11n_service_server::{Z11nService, Z11nServiceServer},
     },
+    report, service, socket, software, upgrade,
 };
-use entity::{tbl_agent, tbl_host, tbl_llm_task};
+use entity::{tbl_agent, tbl_host, tbl_llm_task, tbl_llm_task_attachment};
 use moka::sync::Cache;
 use prost::Message;
-use pub_lib::AgentState;
+use pub_lib::{AgentState, ApprovalState};
 use sea_orm::{
     ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait,
-    IntoActiveModel, QueryFilter,
+    IntoActiveModel, QueryFilter, QueryOrder,
 };
 use tokio::sync::mpsc;
 use tokio_stream::wrappers::ReceiverStream;
 use tonic::{
-    Code, Request, Response, Status,
+    Code, Request, Response, Status, Streaming,
     codec::CompressionEncoding,
+    codegen::http,
     metadata::MetadataMap,
     service::{Interceptor, interceptor::InterceptedService},
     transport::{Identity, Server, ServerTlsConfig},
 };
+use tower::util::MapRequestLayer;
 
+// gRPC 消息大小上限，agent 的客户端使用相同的值
+pub const MAX_MESSAGE_SIZE: usize = 8 * 1024 * 1024;
+// 问题的附件连同文本在拉取时放在一条消息中下发，为其余字段预留的字节数
+const MESSAGE_HEADROOM: usize = 64 * 1024;
+
+// 注册端口不强制客户端证书，只开放注册
+const BOOTSTRAP_METHOD: &str = "Register";
+
+/// gRPC 方法名，拦截器中看不到请求路径，由 record_method 写入 extensions
 #[derive(Debug, Clone)]
-pub struct Z11nInterceptor {}
+struct GrpcMethodName(String);
+
+fn record_method<B>(mut req: http::Request<B>) -> http::Request<B> {
+    let method = req.uri().path().rsplit('/').next().unwrap_or_default();
+    let method = GrpcMethodName(method.to_string());
+    req.extensions_mut().insert(method);
+    req
+}
+
+#[derive(Debug, Clone)]
+pub struct Z11nInterceptor {
+    admission: Admission,
+    agent_certs: AgentCerts,
+    // 是否为注册端口
+    bootstrap: bool,
+}
 
 impl Interceptor for Z11nInterceptor {
     fn call(&mut self, req: tonic::Request<()>) -> Result<tonic::Request<()>, Status> {
-        let _agent_id = extract_metadata_value(req.metadata(), "agent_id")?;
+        let agent_id = extract_metadata_value(req.metadata(), "agent_id")?;
         // log::info!("agent_id: {agent_id}");
+        let method = req
+            .extensions()
+            .get::<GrpcMethodName>()
+            .map_or("", |v| v.0.as_str());
+        if self.bootstrap && method != BOOTSTRAP_METHOD {
+            log::warn!("{agent_id} call {method} on bootstrap port");
+            return Err(Status::permission_denied(
+                "only register is allowed on bootstrap port",
+            ));
+        }
+        self.admission.check_blocked(agent_id)?;
+        self.agent_certs.check(
+            req.peer_certs().as_deref().map(Vec::as_slice),
+            agent_id,
+            method,
+        )?;
         Ok(req)
     }
 }
@@ -71,94 +124,187 @@ pub struct Z11nServer {
     pub db_conn: DatabaseConnection,
     pub online_agent_cache: Cache<String, String>,
     pub sled_db: sled::Db,
+    pub connections: Connections,
+    pub admission: Admission,
+    pub agent_certs: AgentCerts,
+}
+
+impl Z11nServer {
+    /// 校验注册时下发的 token，缓存中没有时(如已离线)查库
+    async fn authenticate(&self, agent_id: &str, token: &str) -> Result<(), Status> {
+        let registered = match self.online_agent_cache.get(agent_id) {
+            Some(v) => Some(v),
+            None => match tbl_agent::Entity::find_by_id(agent_id)
+                .one(&self.db_conn)
+                .await
+            {
+                Ok(v) => v.map(|tbl_agent| tbl_agent.token),
+                Err(e) => {
+                    log::error!("tbl_agent find by id err: {}", e);
+                    return Err(tonic::Status::new(
+                        tonic::Code::Internal,
+                        "tbl_agent find by id err".to_string(),
+                    ));
+                }
+            },
+        };
+        if registered.is_some_and(|v| v.eq(token)) {
+            Ok(())
+        } else {
+            Err(tonic::Status::new(
+                tonic::Code::Unauthenticated,
+                "Unauthenticated".to_string(),
+            ))
+        }
+    }
 }
 
 #[tonic::async_trait]
 impl Z11nService for Z11nServer {
     type HeartbeatStream = ReceiverStream<Result<HeartbeatRsp, Status>>;
+    type ControlStream = ReceiverStream<Result<ControlRsp, Status>>;
+    type DownloadStream = ReceiverStream<Result<DownloadChunk, Status>>;
     async fn heartbeat(
         &self,
-        req: Request<Empty>,
+        req: Request<HeartbeatReq>,
     ) -> Result<Response<Self::HeartbeatStream>, Status> {
         let agent_id = extract_metadata_value(req.metadata(), "agent_id")?;
         let token = extract_metadata_value(req.metadata(), "token")?;
+        // token 只在注册时下发，校验通过后才刷新在线状态
+        self.authenticate(agent_id, token).await?;
         self.online_agent_cache
             .insert(agent_id.to_string(), token.to_string());
         // log::info!("online in cache {}", agent_id);
-        match self.online_agent_cache.get(agent_id) {
-            Some(v) => {
-                if !v.eq(token) {
-                    return Err(tonic::Status::new(
-                        tonic::Code::Unauthenticated,
-                        "Unauthenticated".to_string(),
-                    ));
-                }
-            }
-            None => {
-                return Err(tonic::Status::new(
-                    tonic::Code::Unauthenticated,
-                    "Unauthenticated".to_string(),
-                ));
+        if let Some(llm_consumer_status) = &req.get_ref().llm_consumer_status {
+            let r = llm_consumer::save_status(&self.db_conn, agent_id, llm_consumer_status).await;
+            if let Err(e) = r {
+                log::error!("{} llm_consumer::save_status err: {}", agent_id, e);
             }
         }
+        let agent_profile = match profile::check(&self.db_conn, agent_id, req.get_ref()).await {
+            Ok(v) => v,
+            Err(e) => {
+                log::error!("{} profile::check err: {}", agent_id, e);
+                None
+            }
+        };
         let (tx, rx) = mpsc::channel(10);
         let sled_db_clone = self.sled_db.clone();
         let agent_id = agent_id.to_string();
         tokio::spawn(async move {
-            match sled_db_clone.remove(&agent_id) {
-                Ok(op) => match op {
-                    Some(encoded) => {
-                        let (heartbeat_rsp_encodeds, _len): (Vec<Vec<u8>>, usize) =
-                            match bincode::decode_from_slice(
-                                &encoded[..],
-                                bincode::config::standard(),
-                            ) {
-                                Ok(v) => v,
-                                Err(e) => {
-                                    log::error!("bincode::decode_from_slice err: {}", e);
-                                    return;
-                                }
-                            };
-                        for heartbeat_rsp_encoded in heartbeat_rsp_encodeds {
-                            if let Ok(heartbeat_rsp) = HeartbeatRsp::decode(&*heartbeat_rsp_encoded)
-                            {
-                                if let Err(e) = tx.send(Ok(heartbeat_rsp)).await {
-                                    log::error!("tx send err: {}", e);
-                                }
-                            }
-                        }
-                    }
-                    None => {
-                        let heartbeat_rsp = HeartbeatRsp { task: None };
-                        if let Err(e) = tx.send(Ok(heartbeat_rsp)).await {
-                            log::error!("tx send err: {}", e);
-                        }
-                    }
-                },
+            let mut heartbeat_rsps = match control::take_tasks(&sled_db_clone, &agent_id) {
+                Ok(v) => v,
                 Err(e) => {
-                    log::error!("sled_db.get err: {}", e);
+                    log::error!("take_tasks err: {}", e);
+                    return;
+                }
+            };
+            if let Some(agent_profile) = agent_profile {
+                heartbeat_rsps.push(HeartbeatRsp {
+                    task: Some(Task::Profile(agent_profile)),
+                });
+            }
+            if heartbeat_rsps.is_empty() {
+                heartbeat_rsps.push(HeartbeatRsp { task: None });
+            }
+            for heartbeat_rsp in heartbeat_rsps {
+                if let Err(e) = tx.send(Ok(heartbeat_rsp)).await {
+                    log::error!("tx send err: {}", e);
                 }
             }
         });
         Ok(Response::new(ReceiverStream::new(rx)))
     }
 
+    async fn control(
+        &self,
+        req: Request<Streaming<ControlReq>>,
+    ) -> Result<Response<Self::ControlStream>, Status> {
+        let agent_id = extract_metadata_value(req.metadata(), "agent_id")?.to_string();
+        let token = extract_metadata_value(req.metadata(), "token")?.to_string();
+        self.authenticate(&agent_id, &token).await?;
+        let session = Session {
+            db_conn: self.db_conn.clone(),
+            online_agent_cache: self.online_agent_cache.clone(),
+            sled_db: self.sled_db.clone(),
+            connections: self.connections.clone(),
+            admission: self.admission.clone(),
+            agent_id,
+            token,
+        };
+        let (tx, rx) = mpsc::channel(10);
+        tokio::spawn(session.run(req.into_inner(), tx));
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+
     async fn register(&self, req: Request<RegisterReq>) -> Result<Response<RegisterRsp>, Status> {
+        let peer_addr = req.remote_addr().map(|v| v.to_string());
         let register_req = req.get_ref();
+        let agent_id = match identity::assign(
+            &self.db_conn,
+            &self.online_agent_cache,
+            register_req,
+            peer_addr,
+        )
+        .await
+        {
+            Ok(v) => v,
+            Err(e) => {
+                log::error!("{} identity::assign err: {}", register_req.agent_id, e);
+                return Err(tonic::Status::new(
+                    tonic::Code::Internal,
+                    "assign agent_id err".to_string(),
+                ));
+            }
+        };
+        // 拦截器只检查了声明的编号
+        self.admission.check_blocked(&agent_id)?;
+        // 克隆的机器带着原编号的证书，分配新编号后视为未携带
+        let peer_cert = AgentCerts::peer_cert(&req, &agent_id);
         let token = uuid::Uuid::new_v4().to_string();
-        self.online_agent_cache
-            .insert(register_req.agent_id.clone(), token.clone());
-        log::info!("online in cache {}", register_req.agent_id);
-        match tbl_agent::Entity::find_by_id(&register_req.agent_id)
+        let approval_state = match tbl_agent::Entity::find_by_id(&agent_id)
             .one(&self.db_conn)
             .await
         {
             Ok(tbl_agent_op) => match tbl_agent_op {
                 Some(tbl_agent) => {
+                    // 重新注册需证明是同一个 agent：携带该编号的有效证书或当前的 token
+                    let token_matches = req
+                        .metadata()
+                        .get("token")
+                        .and_then(|v| v.to_str().ok())
+                        .is_some_and(|v| !v.is_empty() && v == tbl_agent.token);
+                    let proven = peer_cert.is_some() || token_matches;
+                    // 无法证明时（如重装后证书丢失）须重新使用 enrollment token，并重新等待审批
+                    let (approval_state, tbl_enrollment_token) = if proven {
+                        let approval_state = ApprovalState::from_str(&tbl_agent.approval_state)
+                            .unwrap_or(ApprovalState::Approved);
+                        (approval_state, None)
+                    } else {
+                        let tbl_enrollment_token =
+                            enrollment::enroll(&self.db_conn, &register_req.enrollment_token)
+                                .await
+                                .inspect_err(|e| {
+                                    log::warn!(
+                                        "{} without cert or token, enroll refused: {}",
+                                        agent_id,
+                                        e.message()
+                                    )
+                                })?;
+                        log::warn!("{agent_id} without cert or token, pending approval");
+                        (ApprovalState::Pending, Some(tbl_enrollment_token))
+                    };
                     let mut tbl_agent_am = tbl_agent.into_active_model();
                     tbl_agent_am.state = Set(AgentState::Online.to_string());
                     tbl_agent_am.version = Set(register_req.agent_version.to_string());
                     tbl_agent_am.token = Set(token.clone());
+                    if let Some(tbl_enrollment_token) = tbl_enrollment_token {
+                        tbl_agent_am.approval_state = Set(approval_state.to_string());
+                        tbl_agent_am.enrollment_token_id = Set(Some(tbl_enrollment_token.id));
+                    }
+                    if let Some(fingerprint) = &register_req.fingerprint {
+                        identity::save(&mut tbl_agent_am, fingerprint);
+                    }
                     if let Err(e) = tbl_agent_am.save(&self.db_conn).await {
                         log::error!("tbl_agent save err: {}", e);
                         return Err(tonic::Status::new(
@@ -166,16 +312,47 @@ impl Z11nService for Z11nServer {
                             "tbl_agent find by id err".to_string(),
                         ));
                     }
-                    log::info!("online in db {}", register_req.agent_id);
+                    log::info!("online in db {}", agent_id);
+                    approval_state
                 }
                 None => {
-                    let tbl_agent_am = tbl_agent::ActiveModel {
-                        id: Set(register_req.agent_id.to_string()),
+                    // 新 agent 需要 enrollment token，token 决定分组和是否需要审批
+                    let tbl_enrollment_token = if CLIENT_SERVICE_TOML.enrollment.required
+                        || !register_req.enrollment_token.is_empty()
+                    {
+                        Some(
+                            enrollment::enroll(&self.db_conn, &register_req.enrollment_token)
+                                .await
+                                .inspect_err(|e| {
+                                    log::warn!("{} enroll refused: {}", agent_id, e.message())
+                                })?,
+                        )
+                    } else {
+                        None
+                    };
+                    let approval_state = if tbl_enrollment_token
+                        .as_ref()
+                        .is_some_and(|v| v.require_approval)
+                    {
+                        ApprovalState::Pending
+                    } else {
+                        ApprovalState::Approved
+                    };
+                    let mut tbl_agent_am = tbl_agent::ActiveModel {
+                        id: Set(agent_id.clone()),
                         version: Set(register_req.agent_version.to_string()),
                         state: Set(AgentState::Online.to_string()),
                         token: Set(token.clone()),
+                        approval_state: Set(approval_state.to_string()),
                         ..Default::default()
                     };
+                    if let Some(tbl_enrollment_token) = tbl_enrollment_token {
+                        tbl_agent_am.group_name = Set(tbl_enrollment_token.group_name);
+                        tbl_agent_am.enrollment_token_id = Set(Some(tbl_enrollment_token.id));
+                    }
+                    if let Some(fingerprint) = &register_req.fingerprint {
+                        identity::save(&mut tbl_agent_am, fingerprint);
+                    }
                     if let Err(e) = tbl_agent::Entity::insert(tbl_agent_am)
                         .exec(&self.db_conn)
                         .await
@@ -186,7 +363,8 @@ impl Z11nService for Z11nServer {
                             "tbl_agent find by id err".to_string(),
                         ));
                     }
-                    log::info!("online in db {}", register_req.agent_id);
+                    log::info!("online in db {} {}", agent_id, approval_state);
+                    approval_state
                 }
             },
             Err(e) => {
@@ -197,98 +375,66 @@ impl Z11nService for Z11nServer {
                 ));
             }
         };
-        let register_rsp = RegisterRsp { token };
+        self.admission.set(&agent_id, approval_state);
+        self.admission.check_blocked(&agent_id)?;
+        let cert = if register_req.csr.is_empty() || !AgentCerts::needs_renewal(peer_cert.as_ref())
+        {
+            String::new()
+        } else {
+            match self
+                .agent_certs
+                .issue(&self.db_conn, &agent_id, &register_req.csr)
+                .await
+            {
+                Ok(v) => v,
+                Err(e) => {
+                    log::error!("{} issue cert err: {}", agent_id, e);
+                    return Err(Status::internal("issue cert err"));
+                }
+            }
+        };
+        self.online_agent_cache
+            .insert(agent_id.clone(), token.clone());
+        log::info!("online in cache {}", agent_id);
+        if let Err(e) =
+            upgrade::registered(&self.db_conn, &agent_id, &register_req.agent_version).await
+        {
+            log::error!("{} upgrade::registered err: {}", agent_id, e);
+        }
+        let register_rsp = RegisterRsp {
+            token,
+            agent_id,
+            pending_approval: approval_state == ApprovalState::Pending,
+            cert,
+        };
         Ok(Response::new(register_rsp))
     }
 
     async fn host(&self, req: Request<HostReq>) -> Result<Response<Empty>, Status> {
         let agent_id = extract_metadata_value(req.metadata(), "agent_id")?;
-        let host_req = req.get_ref();
+        self.admission.check_approved(agent_id)?;
+        self.save_host(agent_id, req.get_ref()).await?;
+        Ok(Response::new(Empty {}))
+    }
 
-        match tbl_host::Entity::find_by_id(agent_id)
+    async fn host_diff(&self, req: Request<HostDiffReq>) -> Result<Response<HostDiffRsp>, Status> {
+        let agent_id = extract_metadata_value(req.metadata(), "agent_id")?;
+        self.admission.check_approved(agent_id)?;
+        let host_diff_req = req.get_ref();
+        let report_key = report::key(req.metadata(), agent_id);
+        let (mut host_req, version) = match tbl_host::Entity::find_by_id(agent_id)
             .one(&self.db_conn)
             .await
         {
-            Ok(tbl_host_op) => match tbl_host_op {
-                // 数据里有
-                Some(tbl_host) => {
-                    if let Some(system) = &host_req.system {
-                        let mut tbl_host_am = tbl_host.clone().into_active_model();
-                        tbl_host_am.name = Set(system.name.clone());
-                        tbl_host_am.host_name = Set(system.host_name.clone());
-                        tbl_host_am.os_version = Set(system.os_version.clone());
-                        tbl_host_am.cpu_arch = Set(system.cpu_arch.clone());
-                        tbl_host_am.content = Set(host_req.encode_to_vec());
-                        tbl_host_am.updated_at = Set(chrono::Utc::now().naive_utc());
-                        if let Err(e) = tbl_host_am.save(&self.db_conn).await {
-                            log::error!("tbl_host save err: {}", e);
-                            return Err(tonic::Status::new(
-                                tonic::Code::Internal,
-                                "tbl_host save err".to_string(),
-                            ));
-                        }
-                    } else {
-                        let mut tbl_host_am = tbl_host.clone().into_active_model();
-                        tbl_host_am.name = Set(None);
-                        tbl_host_am.host_name = Set(None);
-                        tbl_host_am.os_version = Set(None);
-                        tbl_host_am.cpu_arch = Set("".to_string());
-                        tbl_host_am.updated_at = Set(chrono::Utc::now().naive_utc());
-                        tbl_host_am.content = Set(host_req.encode_to_vec());
-                        if let Err(e) = tbl_host_am.save(&self.db_conn).await {
-                            log::error!("tbl_host save err: {}", e);
-                            return Err(tonic::Status::new(
-                                tonic::Code::Internal,
-                                "tbl_host save err".to_string(),
-                            ));
-                        }
-                    }
-                }
-                // 数据库里没有
-                None => {
-                    if let Some(system) = &host_req.system {
-                        let tbl_host_am = tbl_host::ActiveModel {
-                            agent_id: Set(agent_id.to_string()),
-                            name: Set(system.name.clone()),
-                            host_name: Set(system.host_name.clone()),
-                            os_version: Set(system.os_version.clone()),
-                            cpu_arch: Set(system.cpu_arch.clone()),
-                            content: Set(host_req.encode_to_vec()),
-                            ..Default::default()
-                        };
-                        if let Err(e) = tbl_host::Entity::insert(tbl_host_am)
-                            .exec(&self.db_conn)
-                            .await
-                        {
-                            log::error!("tbl_host insert err: {}", e);
-                            return Err(tonic::Status::new(
-                                tonic::Code::Internal,
-                                "tbl_host insert err".to_string(),
-                            ));
-                        }
-                    } else {
-                        let tbl_host_am = tbl_host::ActiveModel {
-                            agent_id: Set(agent_id.to_string()),
-                            name: Set(None),
-                            host_name: Set(None),
-                            os_version: Set(None),
-                            cpu_arch: Set("".to_string()),
-                            content: Set(host_req.encode_to_vec()),
-                            ..Default::default()
-                        };
-                        if let Err(e) = tbl_host::Entity::insert(tbl_host_am)
-                            .exec(&self.db_conn)
-                            .await
-                        {
-                            log::error!("tbl_host insert err: {}", e);
-                            return Err(tonic::Status::new(
-                                tonic::Code::Internal,
-                                "tbl_host insert err".to_string(),
-                            ));
-                        }
-                    }
+            Ok(Some(tbl_host)) => match HostReq::decode(&*tbl_host.content) {
+                Ok(v) => (v, tbl_host.version),
+                // 无法解码时要求 agent 全量上报
+                Err(e) => {
+                    log::error!("{} HostReq decode err: {}", agent_id, e);
+                    (HostReq::default(), -1)
                 }
             },
+            Ok(None) => (HostReq::default(), 0),
             Err(e) => {
                 log::error!("tbl_host find by id err: {}", e);
                 return Err(tonic::Status::new(
@@ -296,8 +442,154 @@ impl Z11nService for Z11nServer {
                     "tbl_host find by id err".to_string(),
                 ));
             }
+        };
+        // 已入库的重放上报，返回当前版本
+        if report::replayed(&self.sled_db, report_key.as_deref()) {
+            log::info!("{agent_id} host diff replayed, version {version}");
+            return Ok(Response::new(HostDiffRsp {
+                version: version.max(0) as u64,
+            }));
+        }
+        if let Some(base_version) = host_diff_req.base_version
+            && base_version as i64 != version
+        {
+            log::warn!(
+                "{agent_id} host diff base version {base_version}, server version {version}"
+            );
+            return Err(tonic::Status::new(
+                tonic::Code::FailedPrecondition,
+                "host version mismatch".to_string(),
+            ));
+        }
+        host_change::merge(&mut host_req, host_diff_req);
+        let version = self.save_host(agent_id, &host_req).await?;
+        report::stored(&self.sled_db, report_key);
+        Ok(Response::new(HostDiffRsp {
+            version: version as u64,
+        }))
+    }
+
+    async fn push_metrics(&self, req: Request<MetricsReq>) -> Result<Response<Empty>, Status> {
+        let agent_id = extract_metadata_value(req.metadata(), "agent_id")?;
+        self.admission.check_approved(agent_id)?;
+        let metrics_req = req.get_ref();
+        let report_key = report::key(req.metadata(), agent_id);
+        if metrics_req.samples.is_empty() || report::replayed(&self.sled_db, report_key.as_deref())
+        {
+            return Ok(Response::new(Empty {}));
+        }
+        if let Err(e) = metrics::save(&self.db_conn, agent_id, &metrics_req.samples).await {
+            log::error!("{} metrics::save err: {}", agent_id, e);
+            return Err(tonic::Status::new(
+                tonic::Code::Internal,
+                "metrics save err".to_string(),
+            ));
+        }
+        report::stored(&self.sled_db, report_key);
+        Ok(Response::new(Empty {}))
+    }
+
+    async fn report_service_control(
+        &self,
+        req: Request<ServiceControlResult>,
+    ) -> Result<Response<Empty>, Status> {
+        let agent_id = extract_metadata_value(req.metadata(), "agent_id")?;
+        self.admission.check_approved(agent_id)?;
+        let result = req.get_ref();
+        log::info!(
+            "{} service control {} success: {}",
+            agent_id,
+            result.id,
+            result.success
+        );
+        let report_key = report::key(req.metadata(), agent_id);
+        if report::replayed(&self.sled_db, report_key.as_deref()) {
+            return Ok(Response::new(Empty {}));
+        }
+        match service::report(&self.db_conn, agent_id, result).await {
+            Ok(true) => {}
+            // 任务已删除或 agent 编号已变化，重发也不会成功
+            Ok(false) => {
+                log::warn!("{} service control {} not found", agent_id, result.id);
+                return Err(tonic::Status::new(
+                    tonic::Code::NotFound,
+                    "service control not found".to_string(),
+                ));
+            }
+            Err(e) => {
+                log::error!("{} service::report err: {}", agent_id, e);
+                return Err(tonic::Status::new(
+                    tonic::Code::Internal,
+                    "service control report err".to_string(),
+                ));
+            }
         }
-        log::info!("save host success");
+        report::stored(&self.sled_db, report_key);
+        Ok(Response::new(Empty {}))
+    }
+
+    async fn download(
+        &self,
+        req: Request<DownloadReq>,
+    ) -> Result<Response<Self::DownloadStream>, Status> {
+        let agent_id = extract_metadata_value(req.metadata(), "agent_id")?;
+        self.admission.check_approved(agent_id)?;
+        let download_req = req.get_ref();
+        log::info!("{} download {}", agent_id, download_req.download_ref);
+        let artifact = match artifact::open(&self.db_conn, &download_req.download_ref).await {
+            Ok(Some(v)) => v,
+            Ok(None) => {
+                return Err(tonic::Status::new(
+                    tonic::Code::NotFound,
+                    "artifact not found".to_string(),
+                ));
+            }
+            Err(e) => {
+                log::error!("artifact open err: {}", e);
+                return Err(tonic::Status::new(
+                    tonic::Code::Internal,
+                    "artifact open err".to_string(),
+                ));
+            }
+        };
+        let (tx, rx) = mpsc::channel(4);
+        artifact::send(artifact, tx);
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+
+    async fn report_upgrade(&self, req: Request<UpgradeResult>) -> Result<Response<Empty>, Status> {
+        let agent_id = extract_metadata_value(req.metadata(), "agent_id")?;
+        self.admission.check_approved(agent_id)?;
+        let result = req.get_ref();
+        log::info!(
+            "{} upgrade {} {:?}: {}",
+            agent_id,
+            result.id,
+            result.status(),
+            result.message
+        );
+        let report_key = report::key(req.metadata(), agent_id);
+        if report::replayed(&self.sled_db, report_key.as_deref()) {
+            return Ok(Response::new(Empty {}));
+        }
+        match upgrade::report(&self.db_conn, agent_id, result).await {
+            Ok(true) => {}
+            Ok(false) => {
+                log::warn!("{} upgrade {} not found", agent_id, result.id);
+                return Err(tonic::Status::new(
+                    tonic::Code::NotFound,
+                    "upgrade not found".to_string(),
+                ));
+            }
+            Err(e) => {
+                log::error!("{} upgrade::report err: {}", agent_id, e);
+                return Err(tonic::Status::new(
+                    tonic::Code::Internal,
+                    "upgrade report err".to_string(),
+                ));
+            }
+        }
+        report::stored(&self.sled_db, report_key);
         Ok(Response::new(Empty {}))
     }
 
@@ -306,7 +598,93 @@ impl Z11nService for Z11nServer {
         req: Request<LlmTaskQuestionReq>,
     ) -> Result<Response<LlmTaskId>, Status> {
         let agent_id = extract_metadata_value(req.metadata(), "agent_id")?;
+        self.admission.check_approved(agent_id)?;
         let llm_task_question = req.get_ref();
+        let (kind, req_inputs) = match llm_task_question.kind() {
+            LlmTaskKind::Chat => (pub_lib::LlmTaskKind::Chat, None),
+            LlmTaskKind::Embedding => {
+                if llm_task_question.inputs.is_empty() {
+                    log::warn!("embedding task inputs is empty");
+                    return Err(tonic::Status::new(
+                        tonic::Code::InvalidArgument,
+                        "embedding task inputs is empty".to_string(),
+                    ));
+                }
+                let encoded: Vec<u8> = match bincode::encode_to_vec(
+                    &llm_task_question.inputs,
+                    bincode::config::standard(),
+                ) {
+                    Ok(v) => v,
+                    Err(e) => {
+                        log::error!("bincode::encode_to_vec err: {}", e);
+                        return Err(tonic::Status::new(
+                            tonic::Code::Internal,
+                            "bincode::encode_to_vec err".to_string(),
+                        ));
+                    }
+                };
+                (pub_lib::LlmTaskKind::Embedding, Some(encoded))
+            }
+        };
+        let llm_task_config = &CLIENT_SERVICE_TOML.llm_task;
+        if llm_task_question.attachments.len() > llm_task_config.attachment_max_count {
+            log::warn!(
+                "attachment count {} exceeds {}",
+                llm_task_question.attachments.len(),
+                llm_task_config.attachment_max_count
+            );
+            return Err(tonic::Status::new(
+                tonic::Code::InvalidArgument,
+                "attachment count exceeds limit".to_string(),
+            ));
+        }
+        for attachment in &llm_task_question.attachments {
+            if !attachment.mime_type.starts_with("image/") {
+                log::warn!(
+                    "attachment mime type {} not supported",
+                    attachment.mime_type
+                );
+                return Err(tonic::Status::new(
+                    tonic::Code::InvalidArgument,
+                    format!(
+                        "attachment mime type {} not supported",
+                        attachment.mime_type
+                    ),
+                ));
+            }
+            if attachment.content.is_empty()
+                || attachment.content.len() > llm_task_config.attachment_max_size
+            {
+                log::warn!(
+                    "attachment size {} out of range, max {}",
+                    attachment.content.len(),
+                    llm_task_config.attachment_max_size
+                );
+                return Err(tonic::Status::new(
+                    tonic::Code::InvalidArgument,
+                    "attachment size out of range".to_string(),
+                ));
+            }
+        }
+        // 超过传输上限的问题 consumer 无法解码
+        let total_size = llm_task_question
+            .attachments
+            .iter()
+            .map(|v| v.content.len())
+            .sum::<usize>()
+            + llm_task_question.prompt.len()
+            + llm_task_question.content.len();
+        if total_size > MAX_MESSAGE_SIZE - MESSAGE_HEADROOM {
+            log::warn!(
+                "question size {} exceeds {}",
+                total_size,
+                MAX_MESSAGE_SIZE - MESSAGE_HEADROOM
+            );
+            return Err(tonic::Status::new(
+                tonic::Code::InvalidArgument,
+                "question size exceeds limit".to_string(),
+            ));
+        }
         let id = uuid::Uuid::new_v4().to_string();
         let tbl_llm_task_am = tbl_llm_task::ActiveModel {
             id: Set(id),
@@ -314,16 +692,15 @@ impl Z11nService for Z11nServer {
             model: Set(llm_task_question.model.clone()),
             prompt: Set(llm_task_question.prompt.clone()),
             req_content: Set(llm_task_question.content.clone()),
+            kind: Set(kind.to_string()),
+            req_inputs: Set(req_inputs),
             ..Default::default()
         };
-        match tbl_llm_task::Entity::insert(tbl_llm_task_am)
+        let id = match tbl_llm_task::Entity::insert(tbl_llm_task_am)
             .exec(&self.db_conn)
             .await
         {
-            Ok(insert_result) => {
-                let id = insert_result.last_insert_id;
-                return Ok(Response::new(LlmTaskId { id }));
-            }
+            Ok(insert_result) => insert_result.last_insert_id,
             Err(e) => {
                 log::error!("tbl_llm_task insert err: {}", e);
                 return Err(tonic::Status::new(
@@ -331,25 +708,101 @@ impl Z11nService for Z11nServer {
                     "tbl_llm_task insert err".to_string(),
                 ));
             }
+        };
+        if !llm_task_question.attachments.is_empty() {
+            let tbl_llm_task_attachment_ams =
+                llm_task_question.attachments.iter().map(|attachment| {
+                    tbl_llm_task_attachment::ActiveModel {
+                        id: Set(uuid::Uuid::new_v4().to_string()),
+                        llm_task_id: Set(id.clone()),
+                        mime_type: Set(attachment.mime_type.clone()),
+                        size: Set(attachment.content.len() as i64),
+                        content: Set(attachment.content.clone()),
+                        ..Default::default()
+                    }
+                });
+            if let Err(e) =
+                tbl_llm_task_attachment::Entity::insert_many(tbl_llm_task_attachment_ams)
+                    .exec(&self.db_conn)
+                    .await
+            {
+                log::error!("tbl_llm_task_attachment insert err: {}", e);
+                // 附件写入失败，任务不可用，一并删除
+                if let Err(e) = tbl_llm_task::Entity::delete_by_id(&id)
+                    .exec(&self.db_conn)
+                    .await
+                {
+                    log::error!("tbl_llm_task delete {id} err: {}", e);
+                }
+                return Err(tonic::Status::new(
+                    tonic::Code::Internal,
+                    "tbl_llm_task_attachment insert err".to_string(),
+                ));
+            }
         }
+        Ok(Response::new(LlmTaskId { id }))
     }
 
     async fn pull_llm_task_question(
         &self,
-        _req: Request<Empty>,
+        req: Request<Empty>,
     ) -> Result<Response<LlmTaskQuestionRsp>, Status> {
-        match tbl_llm_task::Entity::find()
-            .filter(tbl_llm_task::Column::ReqPullAt.is_null())
-            .one(&self.db_conn)
-            .await
-        {
+        let agent_id = extract_metadata_value(req.metadata(), "agent_id")?;
+        self.admission.check_approved(agent_id)?;
+        match llm_consumer::next_task(&self.db_conn, agent_id).await {
             Ok(op) => match op {
                 Some(tbl_llm_task) => {
+                    let kind = match pub_lib::LlmTaskKind::from_str(&tbl_llm_task.kind) {
+                        Ok(pub_lib::LlmTaskKind::Embedding) => LlmTaskKind::Embedding,
+                        _ => LlmTaskKind::Chat,
+                    };
+                    let inputs = match &tbl_llm_task.req_inputs {
+                        Some(encoded) => {
+                            match bincode::decode_from_slice::<Vec<String>, _>(
+                                &encoded[..],
+                                bincode::config::standard(),
+                            ) {
+                                Ok((v, _len)) => v,
+                                Err(e) => {
+                                    log::error!("bincode::decode_from_slice err: {}", e);
+                                    return Err(tonic::Status::new(
+                                        tonic::Code::Internal,
+                                        "bincode::decode_from_slice err".to_string(),
+                                    ));
+                                }
+                            }
+                        }
+                        None => Vec::new(),
+                    };
+                    let attachments = match tbl_llm_task_attachment::Entity::find()
+                        .filter(tbl_llm_task_attachment::Column::LlmTaskId.eq(&tbl_llm_task.id))
+                        .order_by_asc(tbl_llm_task_attachment::Column::CreatedAt)
+                        .all(&self.db_conn)
+                        .await
+                    {
+                        Ok(v) => v
+                            .into_iter()
+                            .map(|tbl_llm_task_attachment| Attachment {
+                                mime_type: tbl_llm_task_attachment.mime_type,
+                                content: tbl_llm_task_attachment.content,
+                            })
+                            .collect(),
+                        Err(e) => {
+                            log::error!("tbl_llm_task_attachment find err: {}", e);
+                            return Err(tonic::Status::new(
+                                tonic::Code::Internal,
+                                "tbl_llm_task_attachment find err".to_string(),
+                            ));
+                        }
+                    };
                     let llm_task_question = LlmTaskQuestion {
                         id: tbl_llm_task.id.clone(),
                         model: tbl_llm_task.model.clone(),
                         prompt: tbl_llm_task.prompt.clone(),
                         content: tbl_llm_task.req_content.clone(),
+                        kind: kind.into(),
+                        inputs,
+                        attachments,
                     };
                     let r = LlmTaskQuestionRsp {
                         llm_task_question: Some(llm_task_question),
@@ -375,10 +828,10 @@ impl Z11nService for Z11nServer {
                 }
             },
             Err(e) => {
-                log::error!("tbl_llm_task find err: {}", e);
+                log::error!("llm_consumer::next_task err: {}", e);
                 return Err(tonic::Status::new(
                     tonic::Code::Internal,
-                    "tbl_llm_task find err".to_string(),
+                    "llm_consumer::next_task err".to_string(),
                 ));
             }
         }
@@ -389,6 +842,7 @@ impl Z11nService for Z11nServer {
         req: Request<LlmTaskAnswer>,
     ) -> Result<Response<Empty>, Status> {
         let agent_id = extract_metadata_value(req.metadata(), "agent_id")?;
+        self.admission.check_approved(agent_id)?;
         let llm_task_answer = req.get_ref();
         match tbl_llm_task::Entity::find_by_id(&llm_task_answer.id)
             .one(&self.db_conn)
@@ -396,9 +850,29 @@ impl Z11nService for Z11nServer {
         {
             Ok(op) => match op {
                 Some(tbl_llm_task) => {
+                    let rsp_embeddings = if llm_task_answer.embeddings.is_empty() {
+                        None
+                    } else {
+                        let embeddings: Vec<Vec<f32>> = llm_task_answer
+                            .embeddings
+                            .iter()
+                            .map(|embedding| embedding.values.clone())
+                            .collect();
+                        match bincode::encode_to_vec(&embeddings, bincode::config::standard()) {
+                            Ok(v) => Some(v),
+                            Err(e) => {
+                                log::error!("bincode::encode_to_vec err: {}", e);
+                                return Err(tonic::Status::new(
+                                    tonic::Code::Internal,
+                                    "bincode::encode_to_vec err".to_string(),
+                                ));
+                            }
+                        }
+                    };
                     let mut tbl_llm_task_am = tbl_llm_task.into_active_model();
                     tbl_llm_task_am.rsp_agent_id = Set(Some(agent_id.to_string()));
                     tbl_llm_task_am.rsp_content = Set(Some(llm_task_answer.content.clone()));
+                    tbl_llm_task_am.rsp_embeddings = Set(rsp_embeddings);
                     tbl_llm_task_am.rsp_push_at = Set(Some(chrono::Utc::now().naive_utc()));
                     if let Err(e) = tbl_llm_task_am.save(&self.db_conn).await {
                         log::error!("tbl_llm_task_am.save err: {}", e);
@@ -427,11 +901,39 @@ impl Z11nService for Z11nServer {
         Ok(Response::new(Empty {}))
     }
 
+    async fn renew_cert(&self, req: Request<CertReq>) -> Result<Response<CertRsp>, Status> {
+        let agent_id = extract_metadata_value(req.metadata(), "agent_id")?;
+        // 续期必须携带当前证书，丢失证书的 agent 通过注册重新申请
+        let Some(peer_cert) = AgentCerts::peer_cert(&req, agent_id) else {
+            return Err(Rejected::CertRequired.into());
+        };
+        if !AgentCerts::needs_renewal(Some(&peer_cert)) {
+            return Ok(Response::new(CertRsp {
+                cert: String::new(),
+            }));
+        }
+        match self
+            .agent_certs
+            .issue(&self.db_conn, agent_id, &req.get_ref().csr)
+            .await
+        {
+            Ok(cert) => {
+                log::info!("{} cert {} renewed", agent_id, peer_cert.serial);
+                Ok(Response::new(CertRsp { cert }))
+            }
+            Err(e) => {
+                log::error!("{} renew cert err: {}", agent_id, e);
+                Err(Status::internal("issue cert err"))
+            }
+        }
+    }
+
     async fn pull_llm_task_answer(
         &self,
         req: Request<Empty>,
     ) -> Result<Response<LlmTaskAnswers>, Status> {
         let agent_id = extract_metadata_value(req.metadata(), "agent_id")?;
+        self.admission.check_approved(agent_id)?;
         let mut results = Vec::new();
         match tbl_llm_task::Entity::find()
             .filter(tbl_llm_task::Column::ReqAgentId.eq(agent_id))
@@ -443,9 +945,28 @@ impl Z11nService for Z11nServer {
                 for tbl_llm_task in vec {
                     match tbl_llm_task.rsp_content.clone() {
                         Some(rsp_content) => {
+                            let embeddings = match &tbl_llm_task.rsp_embeddings {
+                                Some(encoded) => {
+                                    match bincode::decode_from_slice::<Vec<Vec<f32>>, _>(
+                                        &encoded[..],
+                                        bincode::config::standard(),
+                                    ) {
+                                        Ok((v, _len)) => v
+                                            .into_iter()
+                                            .map(|values| Embedding { values })
+                                            .collect(),
+                                        Err(e) => {
+                                            log::error!("bincode::decode_from_slice err: {}", e);
+                                            continue;
+                                        }
+                                    }
+                                }
+                                None => Vec::new(),
+                            };
                             let r = LlmTaskAnswer {
                                 id: tbl_llm_task.id.clone(),
                                 content: rsp_content,
+                                embeddings,
                             };
                             let mut tbl_llm_task_am = tbl_llm_task.into_active_model();
                             tbl_llm_task_am.rsp_pull_at = Set(Some(chrono::Utc::now().naive_utc()));
@@ -476,29 +997,271 @@ impl Z11nService for Z11nServer {
     }
 }
 
+impl Z11nServer {
+    /// 保存完整的主机信息，记录与上一版本的差异，返回新版本
+    async fn save_host(&self, agent_id: &str, host_req: &HostReq) -> Result<i64, Status> {
+        // 软件清单较大，只在变化时重写 tbl_host_software
+        let mut softwares_changed = !host_req.softwares.is_empty();
+        // 连接列表变化频繁，只在监听端口变化时重写 tbl_host_listen_port
+        let mut listen_ports_changed = !host_req.sockets.is_empty();
+        let users = host_req.account.as_ref().map(|v| &v.users[..]);
+        let mut accounts_changed = users.is_some();
+        let mut services_changed = !host_req.services.is_empty();
+        let mut changes = Vec::new();
+        let mut version = 1;
+
+        match tbl_host::Entity::find_by_id(agent_id)
+            .one(&self.db_conn)
+            .await
+        {
+            Ok(tbl_host_op) => match tbl_host_op {
+                // 数据里有
+                Some(tbl_host) => {
+                    (
+                        softwares_changed,
+                        listen_ports_changed,
+                        accounts_changed,
+                        services_changed,
+                    ) = match HostReq::decode(&*tbl_host.content) {
+                        Ok(v) => {
+                            changes = host_change::changes(&v, host_req);
+                            (
+                                v.softwares != host_req.softwares,
+                                socket::listening(&v.sockets)
+                                    != socket::listening(&host_req.sockets),
+                                users.is_some()
+                                    && v.account.as_ref().map(|v| &v.users[..]) != users,
+                                v.services != host_req.services,
+                            )
+                        }
+                        Err(_) => (true, true, users.is_some(), true),
+                    };
+                    version = tbl_host.version + 1;
+                    if let Some(system) = &host_req.system {
+                        let mut tbl_host_am = tbl_host.clone().into_active_model();
+                        tbl_host_am.name = Set(system.name.clone());
+                        tbl_host_am.host_name = Set(system.host_name.clone());
+                        tbl_host_am.os_version = Set(system.os_version.clone());
+                        tbl_host_am.cpu_arch = Set(system.cpu_arch.clone());
+                        tbl_host_am.content = Set(host_req.encode_to_vec());
+                        tbl_host_am.updated_at = Set(chrono::Utc::now().naive_utc());
+                        tbl_host_am.version = Set(version);
+                        if let Err(e) = tbl_host_am.save(&self.db_conn).await {
+                            log::error!("tbl_host save err: {}", e);
+                            return Err(tonic::Status::new(
+                                tonic::Code::Internal,
+                                "tbl_host save err".to_string(),
+                            ));
+                        }
+                    } else {
+                        let mut tbl_host_am = tbl_host.clone().into_active_model();
+                        tbl_host_am.name = Set(None);
+                        tbl_host_am.host_name = Set(None);
+                        tbl_host_am.os_version = Set(None);
+                        tbl_host_am.cpu_arch = Set("".to_string());
+                        tbl_host_am.updated_at = Set(chrono::Utc::now().naive_utc());
+                        tbl_host_am.version = Set(version);
+                        tbl_host_am.content = Set(host_req.encode_to_vec());
+                        if let Err(e) = tbl_host_am.save(&self.db_conn).await {
+                            log::error!("tbl_host save err: {}", e);
+                            return Err(tonic::Status::new(
+                                tonic::Code::Internal,
+                                "tbl_host save err".to_string(),
+                            ));
+                        }
+                    }
+                }
+                // 数据库里没有
+                None => {
+                    if let Some(system) = &host_req.system {
+                        let tbl_host_am = tbl_host::ActiveModel {
+                            agent_id: Set(agent_id.to_string()),
+                            name: Set(system.name.clone()),
+                            host_name: Set(system.host_name.clone()),
+                            os_version: Set(system.os_version.clone()),
+                            cpu_arch: Set(system.cpu_arch.clone()),
+                            content: Set(host_req.encode_to_vec()),
+                            version: Set(version),
+                            ..Default::default()
+                        };
+                        if let Err(e) = tbl_host::Entity::insert(tbl_host_am)
+                            .exec(&self.db_conn)
+                            .await
+                        {
+                            log::error!("tbl_host insert err: {}", e);
+                            return Err(tonic::Status::new(
+                                tonic::Code::Internal,
+                                "tbl_host insert err".to_string(),
+                            ));
+                        }
+                    } else {
+                        let tbl_host_am = tbl_host::ActiveModel {
+                            agent_id: Set(agent_id.to_string()),
+                            name: Set(None),
+                            host_name: Set(None),
+                            os_version: Set(None),
+                            cpu_arch: Set("".to_string()),
+                            content: Set(host_req.encode_to_vec()),
+                            version: Set(version),
+                            ..Default::default()
+                        };
+                        if let Err(e) = tbl_host::Entity::insert(tbl_host_am)
+                            .exec(&self.db_conn)
+                            .await
+                        {
+                            log::error!("tbl_host insert err: {}", e);
+                            return Err(tonic::Status::new(
+                                tonic::Code::Internal,
+                                "tbl_host insert err".to_string(),
+                            ));
+                        }
+                    }
+                }
+            },
+            Err(e) => {
+                log::error!("tbl_host find by id err: {}", e);
+                return Err(tonic::Status::new(
+                    tonic::Code::Internal,
+                    "tbl_host find by id err".to_string(),
+                ));
+            }
+        }
+        if softwares_changed {
+            let r = software::sync(&self.db_conn, agent_id, &host_req.softwares).await;
+            if let Err(e) = r {
+                log::error!("software::sync err: {}", e);
+                return Err(tonic::Status::new(
+                    tonic::Code::Internal,
+                    "software sync err".to_string(),
+                ));
+            }
+        }
+        if listen_ports_changed {
+            let listen_ports = socket::listening(&host_req.sockets);
+            let r = socket::sync(&self.db_conn, agent_id, &listen_ports).await;
+            if let Err(e) = r {
+                log::error!("socket::sync err: {}", e);
+                return Err(tonic::Status::new(
+                    tonic::Code::Internal,
+                    "socket sync err".to_string(),
+                ));
+            }
+        }
+        if let Some(users) = users.filter(|_| accounts_changed) {
+            let r = account::sync(&self.db_conn, agent_id, users).await;
+            if let Err(e) = r {
+                log::error!("account::sync err: {}", e);
+                return Err(tonic::Status::new(
+                    tonic::Code::Internal,
+                    "account sync err".to_string(),
+                ));
+            }
+        }
+        if services_changed {
+            let r = service::sync(&self.db_conn, agent_id, &host_req.services).await;
+            if let Err(e) = r {
+                log::error!("service::sync err: {}", e);
+                return Err(tonic::Status::new(
+                    tonic::Code::Internal,
+                    "service sync err".to_string(),
+                ));
+            }
+        }
+        if let Err(e) = host_change::save(&self.db_conn, agent_id, version, &changes).await {
+            log::error!("host_change::save err: {}", e);
+        }
+        if let Err(e) = host_snapshot::save(&self.db_conn, agent_id, version, host_req).await {
+            log::error!("host_snapshot::save err: {}", e);
+        }
+        log::info!("save host success, version {version}");
+        Ok(version)
+    }
+}
+
 pub async fn serve(db_conn: sea_orm::DatabaseConnection, sled_db: sled::Db) -> anyhow::Result<()> {
     let online_agent_cache = agent::init_cache(&db_conn).await?;
+    metrics::purge_task(db_conn.clone());
+    host_snapshot::purge_task(db_conn.clone());
+    report::purge_task(sled_db.clone());
+
+    let admission = Admission::default();
+    admission.load(&db_conn).await?;
+    admission.clone().refresh_task(db_conn.clone());
+    let agent_certs = AgentCerts::from_config()?;
+    agent_certs.load(&db_conn).await?;
+    agent_certs.clone().refresh_task(db_conn.clone());
 
     let server = Z11nServer {
         db_conn,
         online_agent_cache,
         sled_db,
+        connections: Connections::default(),
+        admission: admission.clone(),
+        agent_certs: agent_certs.clone(),
     };
     let service = Z11nServiceServer::new(server)
         .send_compressed(CompressionEncoding::Gzip)
         .accept_compressed(CompressionEncoding::Gzip)
-        .max_decoding_message_size(8 * 1024 * 1024)
-        .max_encoding_message_size(8 * 1024 * 1024);
-    let z11n_interceptor = Z11nInterceptor {};
+        .max_decoding_message_size(MAX_MESSAGE_SIZE)
+        .max_encoding_message_size(MAX_MESSAGE_SIZE);
     let cert = fs::read("./config/z11n-ca.crt")?;
     let key = fs::read("./config/z11n-ca.key")?;
     let identity = Identity::from_pem(cert, key);
+    let tls_config = |client_auth_optional| {
+        ServerTlsConfig::new()
+            .identity(identity.clone())
+            .client_ca_root(agent_certs.client_ca_root())
+            .client_auth_optional(client_auth_optional)
+    };
+    let z11n_interceptor = Z11nInterceptor {
+        admission,
+        agent_certs: agent_certs.clone(),
+        bootstrap: false,
+    };
     let addr = CLIENT_SERVICE_TOML.server.addr.parse()?;
+    let require_client_cert = CLIENT_SERVICE_TOML.ca.require_client_cert;
     log::info!("client service listening on {}", addr);
+    let main = serve_on(
+        addr,
+        tls_config(!require_client_cert),
+        InterceptedService::new(service.clone(), z11n_interceptor.clone()),
+    );
+    if !require_client_cert {
+        // 允许不带证书的旧版本 agent，握手时不强制，由拦截器校验携带的证书
+        log::info!("client service is running");
+        return main.await;
+    }
+    // 主端口握手时强制客户端证书，还没有证书的 agent 在注册端口注册
+    let bootstrap_addr = CLIENT_SERVICE_TOML.ca.bootstrap_addr.parse()?;
+    log::info!("client service bootstrap listening on {}", bootstrap_addr);
+    let bootstrap = serve_on(
+        bootstrap_addr,
+        tls_config(true),
+        InterceptedService::new(
+            service,
+            Z11nInterceptor {
+                bootstrap: true,
+                ..z11n_interceptor
+            },
+        ),
+    );
     log::info!("client service is running");
+    tokio::try_join!(main, bootstrap)?;
+    Ok(())
+}
+
+async fn serve_on(
+    addr: std::net::SocketAddr,
+    tls_config: ServerTlsConfig,
+    service: InterceptedService<Z11nServiceServer<Z11nServer>, Z11nInterceptor>,
+) -> anyhow::Result<()> {
+    // 及时发现已断开的长连接，将 agent 标记离线
     Server::builder()
-        .tls_config(ServerTlsConfig::new().identity(identity))?
-        .add_service(InterceptedService::new(service, z11n_interceptor))
+        .http2_keepalive_interval(Some(std::time::Duration::from_secs(30)))
+        .http2_keepalive_timeout(Some(std::time::Duration::from_secs(10)))
+        .tls_config(tls_config)?
+        .layer(MapRequestLayer::new(record_method))
+        .add_service(service)
         .serve(addr)
         .await?;
     Ok(())
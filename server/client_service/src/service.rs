@@ -0,0 +1,65 @@
+use crate::{
+    batch,
+    proto::{ServiceControlResult, ServiceInfo},
+};
+use entity::{tbl_host_service, tbl_service_control};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait,
+    IntoActiveModel, QueryFilter,
+};
+
+/// 用最新上报的 systemd 服务替换主机的服务记录
+pub async fn sync(
+    db_conn: &DatabaseConnection,
+    agent_id: &str,
+    services: &[ServiceInfo],
+) -> anyhow::Result<()> {
+    let tbl_host_service_ams: Vec<tbl_host_service::ActiveModel> = services
+        .iter()
+        .map(|service| tbl_host_service::ActiveModel {
+            id: Set(uuid::Uuid::new_v4().to_string()),
+            agent_id: Set(agent_id.to_string()),
+            name: Set(service.name.clone()),
+            load_state: Set(service.load_state.clone()),
+            active_state: Set(service.active_state.clone()),
+            sub_state: Set(service.sub_state.clone()),
+            unit_file_state: Set(service.unit_file_state.clone()),
+            main_pid: Set(service.main_pid.map(|v| v as i32)),
+            ..Default::default()
+        })
+        .collect();
+    batch::replace::<tbl_host_service::Entity>(
+        db_conn,
+        tbl_host_service::Column::AgentId,
+        agent_id,
+        tbl_host_service_ams,
+    )
+    .await?;
+    log::info!("sync {} service(s) of {}", services.len(), agent_id);
+    Ok(())
+}
+
+/// 记录 agent 上报的服务控制结果，只接受本 agent 下发的任务，任务不存在时返回 false
+pub async fn report(
+    db_conn: &DatabaseConnection,
+    agent_id: &str,
+    result: &ServiceControlResult,
+) -> anyhow::Result<bool> {
+    let Some(tbl_service_control) = tbl_service_control::Entity::find_by_id(&result.id)
+        .filter(tbl_service_control::Column::AgentId.eq(agent_id))
+        .one(db_conn)
+        .await?
+    else {
+        return Ok(false);
+    };
+    let mut tbl_service_control_am = tbl_service_control.into_active_model();
+    tbl_service_control_am.status = Set(if result.success {
+        "success".to_string()
+    } else {
+        "failed".to_string()
+    });
+    tbl_service_control_am.output = Set(Some(result.output.clone()));
+    tbl_service_control_am.finished_at = Set(Some(chrono::Utc::now().naive_utc()));
+    tbl_service_control_am.save(db_conn).await?;
+    Ok(true)
+}
@@ -0,0 +1,50 @@
+use crate::{batch, proto::SocketInfo};
+use entity::tbl_host_listen_port;
+use sea_orm::{ActiveValue::Set, DatabaseConnection};
+
+/// 监听中的 tcp、udp 端口，不含 unix socket
+pub fn listening(sockets: &[SocketInfo]) -> Vec<&SocketInfo> {
+    let mut r: Vec<&SocketInfo> = sockets
+        .iter()
+        .filter(|v| v.protocol != "unix" && (v.state == "LISTEN" || v.state == "UNCONN"))
+        .collect();
+    r.sort_by(|a, b| {
+        (&a.protocol, &a.local_addr, a.local_port, a.pid).cmp(&(
+            &b.protocol,
+            &b.local_addr,
+            b.local_port,
+            b.pid,
+        ))
+    });
+    r
+}
+
+/// 用最新上报的监听端口替换主机的端口记录
+pub async fn sync(
+    db_conn: &DatabaseConnection,
+    agent_id: &str,
+    listen_ports: &[&SocketInfo],
+) -> anyhow::Result<()> {
+    let listen_port_ams: Vec<tbl_host_listen_port::ActiveModel> = listen_ports
+        .iter()
+        .map(|socket| tbl_host_listen_port::ActiveModel {
+            id: Set(uuid::Uuid::new_v4().to_string()),
+            agent_id: Set(agent_id.to_string()),
+            protocol: Set(socket.protocol.clone()),
+            addr: Set(socket.local_addr.clone()),
+            port: Set(socket.local_port as i32),
+            pid: Set(socket.pid.map(|v| v as i32)),
+            process_name: Set(socket.process_name.clone()),
+            ..Default::default()
+        })
+        .collect();
+    batch::replace::<tbl_host_listen_port::Entity>(
+        db_conn,
+        tbl_host_listen_port::Column::AgentId,
+        agent_id,
+        listen_port_ams,
+    )
+    .await?;
+    log::info!("sync {} listen port(s) of {}", listen_ports.len(), agent_id);
+    Ok(())
+}
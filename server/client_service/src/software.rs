@@ -0,0 +1,37 @@
+use crate::{batch, proto::SoftwareInfo};
+use entity::tbl_host_software;
+use sea_orm::{ActiveValue::Set, DatabaseConnection};
+
+/// 用最新上报的软件清单替换主机的软件记录
+pub async fn sync(
+    db_conn: &DatabaseConnection,
+    agent_id: &str,
+    softwares: &[SoftwareInfo],
+) -> anyhow::Result<()> {
+    let tbl_host_software_ams: Vec<tbl_host_software::ActiveModel> = softwares
+        .iter()
+        .map(|software| tbl_host_software::ActiveModel {
+            id: Set(uuid::Uuid::new_v4().to_string()),
+            agent_id: Set(agent_id.to_string()),
+            name: Set(software.name.clone()),
+            version: Set(software.version.clone()),
+            arch: Set(software.arch.clone()),
+            source: Set(software.source.clone()),
+            installed_at: Set(software
+                .installed_at
+                .and_then(|v| chrono::DateTime::from_timestamp(v, 0))
+                .map(|v| v.naive_utc())),
+            path: Set(software.path.clone()),
+            ..Default::default()
+        })
+        .collect();
+    batch::replace::<tbl_host_software::Entity>(
+        db_conn,
+        tbl_host_software::Column::AgentId,
+        agent_id,
+        tbl_host_software_ams,
+    )
+    .await?;
+    log::info!("sync {} software(s) of {}", softwares.len(), agent_id);
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use crate::proto::{UpgradeResult, upgrade_result};
+use entity::tbl_agent_upgrade;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait,
+    IntoActiveModel, QueryFilter,
+};
+/// 记录 agent 上报的升级结果，升级任务不存在时返回 false
+pub async fn report(
+    db_conn: &DatabaseConnection,
+    agent_id: &str,
+    result: &UpgradeResult,
+) -> anyhow::Result<bool> {
+    let Some(tbl_agent_upgrade) = tbl_agent_upgrade::Entity::find_by_id(&result.id)
+        .filter(tbl_agent_upgrade::Column::AgentId.eq(agent_id))
+        .one(db_conn)
+        .await?
+    else {
+        return Ok(false);
+    };
+    // 新版本已注册成功时，迟到的上报不覆盖结果
+    if tbl_agent_upgrade.status == "success" {
+        return Ok(true);
+    }
+    let status = match result.status() {
+        upgrade_result::Status::Installed => "installed",
+        upgrade_result::Status::Failed => "failed",
+        upgrade_result::Status::RolledBack => "rolled_back",
+    };
+    let mut tbl_agent_upgrade_am = tbl_agent_upgrade.into_active_model();
+    tbl_agent_upgrade_am.status = Set(status.to_string());
+    tbl_agent_upgrade_am.message = Set(Some(result.message.clone()).filter(|v| !v.is_empty()));
+    tbl_agent_upgrade_am.updated_at = Set(chrono::Utc::now().naive_utc());
+    tbl_agent_upgrade_am.save(db_conn).await?;
+    Ok(true)
+}
+
+/// agent 注册时，以目标版本注册的未完成升级记为成功
+pub async fn registered(
+    db_conn: &DatabaseConnection,
+    agent_id: &str,
+    agent_version: &str,
+) -> anyhow::Result<()> {
+    let rows = tbl_agent_upgrade::Entity::find()
+        .filter(tbl_agent_upgrade::Column::AgentId.eq(agent_id))
+        .filter(tbl_agent_upgrade::Column::Version.eq(agent_version))
+        .filter(tbl_agent_upgrade::Column::Status.is_in(["pending", "installed"]))
+        .all(db_conn)
+        .await?;
+    for tbl_agent_upgrade in rows {
+        log::info!(
+            "{agent_id} upgrade {} to {agent_version} success",
+            tbl_agent_upgrade.id
+        );
+        let mut tbl_agent_upgrade_am = tbl_agent_upgrade.into_active_model();
+        tbl_agent_upgrade_am.status = Set("success".to_string());
+        tbl_agent_upgrade_am.updated_at = Set(chrono::Utc::now().naive_utc());
+        tbl_agent_upgrade_am.save(db_conn).await?;
+    }
+    Ok(())
+}
@@ -3,9 +3,26 @@
 pub mod prelude;
 
 pub mod tbl_agent;
+pub mod tbl_agent_cert;
+pub mod tbl_agent_conflict;
+pub mod tbl_agent_profile;
+pub mod tbl_agent_profile_binding;
+pub mod tbl_agent_upgrade;
+pub mod tbl_artifact;
 pub mod tbl_auth_role;
 pub mod tbl_auth_user;
 pub mod tbl_auth_user_role;
+pub mod tbl_enrollment_token;
 pub mod tbl_host;
+pub mod tbl_host_account;
+pub mod tbl_host_change;
+pub mod tbl_host_listen_port;
+pub mod tbl_host_metric;
+pub mod tbl_host_service;
+pub mod tbl_host_snapshot;
+pub mod tbl_host_software;
+pub mod tbl_llm_consumer;
 pub mod tbl_llm_task;
+pub mod tbl_llm_task_attachment;
+pub mod tbl_service_control;
 pub mod tbl_system_config;
@@ -1,9 +1,26 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.12
 
 pub use super::tbl_agent::Entity as TblAgent;
+pub use super::tbl_agent_cert::Entity as TblAgentCert;
+pub use super::tbl_agent_conflict::Entity as TblAgentConflict;
+pub use super::tbl_agent_profile::Entity as TblAgentProfile;
+pub use super::tbl_agent_profile_binding::Entity as TblAgentProfileBinding;
+pub use super::tbl_agent_upgrade::Entity as TblAgentUpgrade;
+pub use super::tbl_artifact::Entity as TblArtifact;
 pub use super::tbl_auth_role::Entity as TblAuthRole;
 pub use super::tbl_auth_user::Entity as TblAuthUser;
 pub use super::tbl_auth_user_role::Entity as TblAuthUserRole;
+pub use super::tbl_enrollment_token::Entity as TblEnrollmentToken;
 pub use super::tbl_host::Entity as TblHost;
+pub use super::tbl_host_account::Entity as TblHostAccount;
+pub use super::tbl_host_change::Entity as TblHostChange;
+pub use super::tbl_host_listen_port::Entity as TblHostListenPort;
+pub use super::tbl_host_metric::Entity as TblHostMetric;
+pub use super::tbl_host_service::Entity as TblHostService;
+pub use super::tbl_host_snapshot::Entity as TblHostSnapshot;
+pub use super::tbl_host_software::Entity as TblHostSoftware;
+pub use super::tbl_llm_consumer::Entity as TblLlmConsumer;
 pub use super::tbl_llm_task::Entity as TblLlmTask;
+pub use super::tbl_llm_task_attachment::Entity as TblLlmTaskAttachment;
+pub use super::tbl_service_control::Entity as TblServiceControl;
 pub use super::tbl_system_config::Entity as TblSystemConfig;
@@ -12,12 +12,36 @@ pub struct Model {
     pub state: String,
     pub token: String,
     pub created_at: DateTime,
+    pub group_name: Option<String>,
+    pub profile_id: Option<String>,
+    pub profile_version: Option<i64>,
+    pub machine_id: Option<String>,
+    pub dmi_uuid: Option<String>,
+    pub macs: Option<String>,
+    pub container_id: Option<String>,
+    pub hostname: Option<String>,
+    pub approval_state: String,
+    pub enrollment_token_id: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::tbl_agent_upgrade::Entity")]
+    TblAgentUpgrade,
     #[sea_orm(has_one = "super::tbl_host::Entity")]
     TblHost,
+    #[sea_orm(has_many = "super::tbl_host_metric::Entity")]
+    TblHostMetric,
+    #[sea_orm(has_one = "super::tbl_llm_consumer::Entity")]
+    TblLlmConsumer,
+    #[sea_orm(has_many = "super::tbl_service_control::Entity")]
+    TblServiceControl,
+}
+
+impl Related<super::tbl_agent_upgrade::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TblAgentUpgrade.def()
+    }
 }
 
 impl Related<super::tbl_host::Entity> for Entity {
@@ -26,4 +50,22 @@ impl Related<super::tbl_host::Entity> for Entity {
     }
 }
 
+impl Related<super::tbl_host_metric::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TblHostMetric.def()
+    }
+}
+
+impl Related<super::tbl_llm_consumer::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TblLlmConsumer.def()
+    }
+}
+
+impl Related<super::tbl_service_control::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TblServiceControl.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -16,6 +16,7 @@ pub struct Model {
     pub content: Vec<u8>,
     pub created_at: DateTime,
     pub updated_at: DateTime,
+    pub version: i64,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -28,6 +29,18 @@ pub enum Relation {
         on_delete = "Cascade"
     )]
     TblAgent,
+    #[sea_orm(has_many = "super::tbl_host_account::Entity")]
+    TblHostAccount,
+    #[sea_orm(has_many = "super::tbl_host_change::Entity")]
+    TblHostChange,
+    #[sea_orm(has_many = "super::tbl_host_listen_port::Entity")]
+    TblHostListenPort,
+    #[sea_orm(has_many = "super::tbl_host_service::Entity")]
+    TblHostService,
+    #[sea_orm(has_many = "super::tbl_host_snapshot::Entity")]
+    TblHostSnapshot,
+    #[sea_orm(has_many = "super::tbl_host_software::Entity")]
+    TblHostSoftware,
 }
 
 impl Related<super::tbl_agent::Entity> for Entity {
@@ -36,4 +49,40 @@ impl Related<super::tbl_agent::Entity> for Entity {
     }
 }
 
+impl Related<super::tbl_host_account::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TblHostAccount.def()
+    }
+}
+
+impl Related<super::tbl_host_change::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TblHostChange.def()
+    }
+}
+
+impl Related<super::tbl_host_listen_port::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TblHostListenPort.def()
+    }
+}
+
+impl Related<super::tbl_host_service::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TblHostService.def()
+    }
+}
+
+impl Related<super::tbl_host_snapshot::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TblHostSnapshot.def()
+    }
+}
+
+impl Related<super::tbl_host_software::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TblHostSoftware.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
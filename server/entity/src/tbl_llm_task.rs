@@ -18,6 +18,11 @@ pub struct Model {
     pub rsp_content: Option<String>,
     pub rsp_push_at: Option<DateTime>,
     pub rsp_pull_at: Option<DateTime>,
+    pub kind: String,
+    #[sea_orm(column_type = "VarBinary(StringLen::None)", nullable)]
+    pub req_inputs: Option<Vec<u8>>,
+    #[sea_orm(column_type = "VarBinary(StringLen::None)", nullable)]
+    pub rsp_embeddings: Option<Vec<u8>>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -38,6 +43,14 @@ pub enum Relation {
         on_delete = "Cascade"
     )]
     TblAgent1,
+    #[sea_orm(has_many = "super::tbl_llm_task_attachment::Entity")]
+    TblLlmTaskAttachment,
+}
+
+impl Related<super::tbl_llm_task_attachment::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TblLlmTaskAttachment.def()
+    }
 }
 
 impl ActiveModelBehavior for ActiveModel {}
@@ -7,6 +7,27 @@ mod m20250727_145621_create_tbl_llm_task;
 mod m20250807_152429_create_tbl_auth_role;
 mod m20250807_152654_create_tbl_auth_user_role;
 mod m20250815_020235_create_tbl_system_config;
+mod m20261018_020000_alter_tbl_llm_task_add_kind;
+mod m20261018_030000_create_tbl_llm_task_attachment;
+mod m20261018_040000_create_tbl_llm_consumer;
+mod m20261019_010000_create_tbl_host_software;
+mod m20261019_020000_create_tbl_host_metric;
+mod m20261019_030000_create_tbl_host_listen_port;
+mod m20261019_040000_create_tbl_host_account;
+mod m20261019_050000_create_tbl_host_service;
+mod m20261019_060000_create_tbl_service_control;
+mod m20261019_070000_alter_tbl_host_add_version;
+mod m20261019_080000_create_tbl_host_change;
+mod m20261019_090000_create_tbl_host_snapshot;
+mod m20261019_100000_create_tbl_agent_upgrade;
+mod m20261019_110000_create_tbl_artifact;
+mod m20261019_120000_create_tbl_agent_profile;
+mod m20261019_130000_alter_tbl_agent_add_profile;
+mod m20261019_140000_alter_tbl_agent_add_fingerprint;
+mod m20261019_150000_create_tbl_agent_conflict;
+mod m20261019_160000_create_tbl_enrollment_token;
+mod m20261019_170000_alter_tbl_agent_add_approval;
+mod m20261019_180000_create_tbl_agent_cert;
 
 pub struct Migrator;
 
@@ -21,6 +42,27 @@ impl MigratorTrait for Migrator {
             Box::new(m20250807_152429_create_tbl_auth_role::Migration),
             Box::new(m20250807_152654_create_tbl_auth_user_role::Migration),
             Box::new(m20250815_020235_create_tbl_system_config::Migration),
+            Box::new(m20261018_020000_alter_tbl_llm_task_add_kind::Migration),
+            Box::new(m20261018_030000_create_tbl_llm_task_attachment::Migration),
+            Box::new(m20261018_040000_create_tbl_llm_consumer::Migration),
+            Box::new(m20261019_010000_create_tbl_host_software::Migration),
+            Box::new(m20261019_020000_create_tbl_host_metric::Migration),
+            Box::new(m20261019_030000_create_tbl_host_listen_port::Migration),
+            Box::new(m20261019_040000_create_tbl_host_account::Migration),
+            Box::new(m20261019_050000_create_tbl_host_service::Migration),
+            Box::new(m20261019_060000_create_tbl_service_control::Migration),
+            Box::new(m20261019_070000_alter_tbl_host_add_version::Migration),
+            Box::new(m20261019_080000_create_tbl_host_change::Migration),
+            Box::new(m20261019_090000_create_tbl_host_snapshot::Migration),
+            Box::new(m20261019_100000_create_tbl_agent_upgrade::Migration),
+            Box::new(m20261019_110000_create_tbl_artifact::Migration),
+            Box::new(m20261019_120000_create_tbl_agent_profile::Migration),
+            Box::new(m20261019_130000_alter_tbl_agent_add_profile::Migration),
+            Box::new(m20261019_140000_alter_tbl_agent_add_fingerprint::Migration),
+            Box::new(m20261019_150000_create_tbl_agent_conflict::Migration),
+            Box::new(m20261019_160000_create_tbl_enrollment_token::Migration),
+            Box::new(m20261019_170000_alter_tbl_agent_add_approval::Migration),
+            Box::new(m20261019_180000_create_tbl_agent_cert::Migration),
         ]
     }
 }
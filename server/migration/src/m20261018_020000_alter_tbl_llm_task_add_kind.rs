@@ -0,0 +1,70 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // sqlite 一次只能 alter 一列
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(TblLlmTask::Table)
+                    .add_column(string(TblLlmTask::Kind).default("Chat"))
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(TblLlmTask::Table)
+                    .add_column(binary_null(TblLlmTask::ReqInputs))
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(TblLlmTask::Table)
+                    .add_column(binary_null(TblLlmTask::RspEmbeddings))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(TblLlmTask::Table)
+                    .drop_column(TblLlmTask::RspEmbeddings)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(TblLlmTask::Table)
+                    .drop_column(TblLlmTask::ReqInputs)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(TblLlmTask::Table)
+                    .drop_column(TblLlmTask::Kind)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum TblLlmTask {
+    Table,
+    Kind,          // 任务类型，Chat 或 Embedding
+    ReqInputs,     // 向量嵌入输入文本，bincode 编码的 Vec<String>
+    RspEmbeddings, // 向量嵌入结果，bincode 编码的 Vec<Vec<f32>>
+}
@@ -0,0 +1,65 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TblLlmTaskAttachment::Table)
+                    .if_not_exists()
+                    .col(string(TblLlmTaskAttachment::Id).primary_key())
+                    .col(string(TblLlmTaskAttachment::LlmTaskId))
+                    .col(string(TblLlmTaskAttachment::MimeType))
+                    .col(big_integer(TblLlmTaskAttachment::Size))
+                    .col(binary(TblLlmTaskAttachment::Content))
+                    .col(
+                        date_time(TblLlmTaskAttachment::CreatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(TblLlmTaskAttachment::Table, TblLlmTaskAttachment::LlmTaskId)
+                            .to(TblLlmTask::Table, TblLlmTask::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_tbl_llm_task_attachment_llm_task_id")
+                    .table(TblLlmTaskAttachment::Table)
+                    .col(TblLlmTaskAttachment::LlmTaskId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TblLlmTaskAttachment::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum TblLlmTaskAttachment {
+    Table,
+    Id,
+    LlmTaskId,
+    MimeType, // 附件类型，例如 image/png
+    Size,     // 附件字节数
+    Content,  // 附件内容
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum TblLlmTask {
+    Table,
+    Id,
+}
@@ -0,0 +1,53 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::m20250722_172354_create_tbl_agent::TblAgent;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TblLlmConsumer::Table)
+                    .if_not_exists()
+                    .col(string(TblLlmConsumer::AgentId).primary_key())
+                    .col(binary(TblLlmConsumer::Models))
+                    .col(binary(TblLlmConsumer::LoadedModels))
+                    .col(integer(TblLlmConsumer::TotalSlots))
+                    .col(integer(TblLlmConsumer::FreeSlots))
+                    .col(double(TblLlmConsumer::TokensPerSecond))
+                    .col(boolean(TblLlmConsumer::BackendReachable))
+                    .col(date_time(TblLlmConsumer::UpdatedAt).default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(TblLlmConsumer::Table, TblLlmConsumer::AgentId)
+                            .to(TblAgent::Table, TblAgent::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TblLlmConsumer::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum TblLlmConsumer {
+    Table,
+    AgentId,
+    Models,           // 可用模型，bincode 编码的 Vec<String>
+    LoadedModels,     // 已加载模型，bincode 编码的 Vec<String>
+    TotalSlots,       // worker 总数
+    FreeSlots,        // 空闲 worker 数
+    TokensPerSecond,  // 最近的生成速度
+    BackendReachable, // 推理后端是否可达
+    UpdatedAt,        // 最近一次心跳上报时间
+}
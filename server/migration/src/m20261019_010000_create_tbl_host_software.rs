@@ -0,0 +1,77 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TblHostSoftware::Table)
+                    .if_not_exists()
+                    .col(string(TblHostSoftware::Id).primary_key())
+                    .col(string(TblHostSoftware::AgentId))
+                    .col(string(TblHostSoftware::Name))
+                    .col(string(TblHostSoftware::Version))
+                    .col(string(TblHostSoftware::Arch))
+                    .col(string(TblHostSoftware::Source))
+                    .col(date_time_null(TblHostSoftware::InstalledAt))
+                    .col(string_null(TblHostSoftware::Path))
+                    .col(date_time(TblHostSoftware::CreatedAt).default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(TblHostSoftware::Table, TblHostSoftware::AgentId)
+                            .to(TblHost::Table, TblHost::AgentId)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_tbl_host_software_agent_id")
+                    .table(TblHostSoftware::Table)
+                    .col(TblHostSoftware::AgentId)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_tbl_host_software_name")
+                    .table(TblHostSoftware::Table)
+                    .col(TblHostSoftware::Name)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TblHostSoftware::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum TblHostSoftware {
+    Table,
+    Id,
+    AgentId,
+    Name,        // 包名或程序名
+    Version,     // 版本
+    Arch,        // 架构
+    Source,      // 来源：dpkg、rpm、process
+    InstalledAt, // 安装时间
+    Path,        // process 来源的程序路径
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum TblHost {
+    Table,
+    AgentId,
+}
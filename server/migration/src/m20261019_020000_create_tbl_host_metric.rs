@@ -0,0 +1,77 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::m20250722_172354_create_tbl_agent::TblAgent;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TblHostMetric::Table)
+                    .if_not_exists()
+                    .col(string(TblHostMetric::AgentId))
+                    .col(integer(TblHostMetric::Resolution))
+                    .col(date_time(TblHostMetric::Bucket))
+                    .col(integer(TblHostMetric::Samples))
+                    .col(double(TblHostMetric::CpuAvg))
+                    .col(double(TblHostMetric::CpuMax))
+                    .col(big_integer(TblHostMetric::UsedMemory))
+                    .col(big_integer(TblHostMetric::TotalMemory))
+                    .col(big_integer(TblHostMetric::UsedSwap))
+                    .col(big_integer(TblHostMetric::TotalSwap))
+                    .col(double(TblHostMetric::LoadOne))
+                    .col(double(TblHostMetric::LoadFive))
+                    .col(double(TblHostMetric::LoadFifteen))
+                    .col(big_integer(TblHostMetric::DiskUsed))
+                    .col(big_integer(TblHostMetric::DiskTotal))
+                    .col(double(TblHostMetric::RxRate))
+                    .col(double(TblHostMetric::TxRate))
+                    .primary_key(
+                        Index::create()
+                            .col(TblHostMetric::AgentId)
+                            .col(TblHostMetric::Resolution)
+                            .col(TblHostMetric::Bucket),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(TblHostMetric::Table, TblHostMetric::AgentId)
+                            .to(TblAgent::Table, TblAgent::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TblHostMetric::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum TblHostMetric {
+    Table,
+    AgentId,
+    Resolution, // 聚合粒度，单位秒
+    Bucket,     // 聚合区间起点
+    Samples,    // 区间内的采样数
+    CpuAvg,     // CPU 使用率均值，百分比
+    CpuMax,     // CPU 使用率峰值，百分比
+    UsedMemory, // 以下均为区间内均值
+    TotalMemory,
+    UsedSwap,
+    TotalSwap,
+    LoadOne,
+    LoadFive,
+    LoadFifteen,
+    DiskUsed,
+    DiskTotal,
+    RxRate, // 字节每秒
+    TxRate, // 字节每秒
+}
@@ -0,0 +1,75 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TblHostListenPort::Table)
+                    .if_not_exists()
+                    .col(string(TblHostListenPort::Id).primary_key())
+                    .col(string(TblHostListenPort::AgentId))
+                    .col(string(TblHostListenPort::Protocol))
+                    .col(string(TblHostListenPort::Addr))
+                    .col(integer(TblHostListenPort::Port))
+                    .col(integer_null(TblHostListenPort::Pid))
+                    .col(string_null(TblHostListenPort::ProcessName))
+                    .col(date_time(TblHostListenPort::CreatedAt).default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(TblHostListenPort::Table, TblHostListenPort::AgentId)
+                            .to(TblHost::Table, TblHost::AgentId)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_tbl_host_listen_port_agent_id")
+                    .table(TblHostListenPort::Table)
+                    .col(TblHostListenPort::AgentId)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_tbl_host_listen_port_port")
+                    .table(TblHostListenPort::Table)
+                    .col(TblHostListenPort::Port)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TblHostListenPort::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum TblHostListenPort {
+    Table,
+    Id,
+    AgentId,
+    Protocol,    // tcp、tcp6、udp、udp6
+    Addr,        // 监听地址
+    Port,        // 监听端口
+    Pid,         // 所属进程
+    ProcessName, // 所属进程名
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum TblHost {
+    Table,
+    AgentId,
+}
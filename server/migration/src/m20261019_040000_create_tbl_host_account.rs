@@ -0,0 +1,79 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TblHostAccount::Table)
+                    .if_not_exists()
+                    .col(string(TblHostAccount::Id).primary_key())
+                    .col(string(TblHostAccount::AgentId))
+                    .col(string(TblHostAccount::Name))
+                    .col(big_integer(TblHostAccount::Uid))
+                    .col(big_integer(TblHostAccount::Gid))
+                    .col(string(TblHostAccount::Home))
+                    .col(string(TblHostAccount::Shell))
+                    .col(boolean(TblHostAccount::Sudo))
+                    .col(boolean(TblHostAccount::Login))
+                    .col(date_time(TblHostAccount::CreatedAt).default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(TblHostAccount::Table, TblHostAccount::AgentId)
+                            .to(TblHost::Table, TblHost::AgentId)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_tbl_host_account_agent_id")
+                    .table(TblHostAccount::Table)
+                    .col(TblHostAccount::AgentId)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_tbl_host_account_name")
+                    .table(TblHostAccount::Table)
+                    .col(TblHostAccount::Name)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TblHostAccount::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum TblHostAccount {
+    Table,
+    Id,
+    AgentId,
+    Name,  // 用户名
+    Uid,   // 用户 id
+    Gid,   // 主组 id
+    Home,  // 家目录
+    Shell, // 登录 shell
+    Sudo,  // 是否属于 sudo、wheel、admin 组
+    Login, // 是否可登录
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum TblHost {
+    Table,
+    AgentId,
+}
@@ -0,0 +1,77 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TblHostService::Table)
+                    .if_not_exists()
+                    .col(string(TblHostService::Id).primary_key())
+                    .col(string(TblHostService::AgentId))
+                    .col(string(TblHostService::Name))
+                    .col(string(TblHostService::LoadState))
+                    .col(string(TblHostService::ActiveState))
+                    .col(string(TblHostService::SubState))
+                    .col(string(TblHostService::UnitFileState))
+                    .col(integer_null(TblHostService::MainPid))
+                    .col(date_time(TblHostService::CreatedAt).default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(TblHostService::Table, TblHostService::AgentId)
+                            .to(TblHost::Table, TblHost::AgentId)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_tbl_host_service_agent_id")
+                    .table(TblHostService::Table)
+                    .col(TblHostService::AgentId)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_tbl_host_service_name")
+                    .table(TblHostService::Table)
+                    .col(TblHostService::Name)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TblHostService::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum TblHostService {
+    Table,
+    Id,
+    AgentId,
+    Name,          // unit 名称
+    LoadState,     // loaded、not-found、masked 等
+    ActiveState,   // active、inactive、failed 等
+    SubState,      // running、exited、dead 等
+    UnitFileState, // enabled、disabled、static 等
+    MainPid,       // 主进程 pid
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum TblHost {
+    Table,
+    AgentId,
+}
@@ -0,0 +1,62 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::m20250722_172354_create_tbl_agent::TblAgent;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TblServiceControl::Table)
+                    .if_not_exists()
+                    .col(string(TblServiceControl::Id).primary_key())
+                    .col(string(TblServiceControl::AgentId))
+                    .col(string(TblServiceControl::Unit))
+                    .col(string(TblServiceControl::Action))
+                    .col(string(TblServiceControl::Status))
+                    .col(string_null(TblServiceControl::Output))
+                    .col(date_time(TblServiceControl::CreatedAt).default(Expr::current_timestamp()))
+                    .col(date_time_null(TblServiceControl::FinishedAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(TblServiceControl::Table, TblServiceControl::AgentId)
+                            .to(TblAgent::Table, TblAgent::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_tbl_service_control_agent_id")
+                    .table(TblServiceControl::Table)
+                    .col(TblServiceControl::AgentId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TblServiceControl::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum TblServiceControl {
+    Table,
+    Id,
+    AgentId,
+    Unit,       // unit 名称
+    Action,     // start、stop、restart、enable、disable
+    Status,     // pending、success、failed
+    Output,     // systemctl 输出
+    CreatedAt,  // 下发时间
+    FinishedAt, // 结果上报时间
+}
@@ -0,0 +1,65 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TblHostChange::Table)
+                    .if_not_exists()
+                    .col(string(TblHostChange::Id).primary_key())
+                    .col(string(TblHostChange::AgentId))
+                    .col(big_integer(TblHostChange::Version))
+                    .col(string(TblHostChange::InfoType))
+                    .col(string(TblHostChange::Item))
+                    .col(string(TblHostChange::Change))
+                    .col(date_time(TblHostChange::CreatedAt).default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(TblHostChange::Table, TblHostChange::AgentId)
+                            .to(TblHost::Table, TblHost::AgentId)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_tbl_host_change_agent_id_created_at")
+                    .table(TblHostChange::Table)
+                    .col(TblHostChange::AgentId)
+                    .col(TblHostChange::CreatedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TblHostChange::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum TblHostChange {
+    Table,
+    Id,
+    AgentId,
+    Version,  // 产生变化的主机信息版本
+    InfoType, // system、disk、network 等
+    Item,     // 条目主键，如挂载点、软件名
+    Change,   // added、removed、changed
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum TblHost {
+    Table,
+    AgentId,
+}
@@ -0,0 +1,63 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TblHostSnapshot::Table)
+                    .if_not_exists()
+                    .col(string(TblHostSnapshot::Id).primary_key())
+                    .col(string(TblHostSnapshot::AgentId))
+                    .col(big_integer(TblHostSnapshot::Version))
+                    .col(string(TblHostSnapshot::Hash))
+                    .col(binary(TblHostSnapshot::Content))
+                    .col(date_time(TblHostSnapshot::CreatedAt).default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(TblHostSnapshot::Table, TblHostSnapshot::AgentId)
+                            .to(TblHost::Table, TblHost::AgentId)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_tbl_host_snapshot_agent_id_created_at")
+                    .table(TblHostSnapshot::Table)
+                    .col(TblHostSnapshot::AgentId)
+                    .col(TblHostSnapshot::CreatedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TblHostSnapshot::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum TblHostSnapshot {
+    Table,
+    Id,
+    AgentId,
+    Version, // 快照对应的主机信息版本
+    Hash,    // content 的 sha256，用于去重
+    Content, // HostReq 的 protobuf 编码
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum TblHost {
+    Table,
+    AgentId,
+}
@@ -0,0 +1,66 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::m20250722_172354_create_tbl_agent::TblAgent;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TblAgentUpgrade::Table)
+                    .if_not_exists()
+                    .col(string(TblAgentUpgrade::Id).primary_key())
+                    .col(string(TblAgentUpgrade::AgentId))
+                    .col(string(TblAgentUpgrade::FromVersion))
+                    .col(string(TblAgentUpgrade::Version))
+                    .col(string(TblAgentUpgrade::DownloadRef))
+                    .col(string(TblAgentUpgrade::Sha256))
+                    .col(string(TblAgentUpgrade::Status))
+                    .col(string_null(TblAgentUpgrade::Message))
+                    .col(date_time(TblAgentUpgrade::CreatedAt).default(Expr::current_timestamp()))
+                    .col(date_time(TblAgentUpgrade::UpdatedAt).default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(TblAgentUpgrade::Table, TblAgentUpgrade::AgentId)
+                            .to(TblAgent::Table, TblAgent::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_tbl_agent_upgrade_agent_id")
+                    .table(TblAgentUpgrade::Table)
+                    .col(TblAgentUpgrade::AgentId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TblAgentUpgrade::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum TblAgentUpgrade {
+    Table,
+    Id,
+    AgentId,
+    FromVersion, // 下发时 agent 的版本
+    Version,     // 目标版本
+    DownloadRef, // 升级包的下载引用
+    Sha256,      // 升级包的 sha256
+    Status,      // pending、installed、success、failed、rolled_back
+    Message,     // 失败原因
+    CreatedAt,   // 下发时间
+    UpdatedAt,   // 状态更新时间
+}
@@ -0,0 +1,41 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TblArtifact::Table)
+                    .if_not_exists()
+                    .col(string(TblArtifact::Id).primary_key())
+                    .col(string(TblArtifact::Name))
+                    .col(string(TblArtifact::Version))
+                    .col(big_integer(TblArtifact::Size))
+                    .col(string(TblArtifact::Signature))
+                    .col(date_time(TblArtifact::CreatedAt).default(Expr::current_timestamp()))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TblArtifact::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum TblArtifact {
+    Table,
+    Id,        // 内容的 sha256，十六进制小写
+    Name,      // 上传时的文件名
+    Version,   // 制品版本
+    Size,      // 字节数
+    Signature, // ed25519 发布密钥对 sha256 摘要的签名，十六进制
+    CreatedAt, // 上传时间
+}
@@ -0,0 +1,94 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TblAgentProfile::Table)
+                    .if_not_exists()
+                    .col(string(TblAgentProfile::Id).primary_key())
+                    .col(string_uniq(TblAgentProfile::Name))
+                    .col(big_integer(TblAgentProfile::Version).default(1))
+                    .col(integer(TblAgentProfile::HeartbeatInterval))
+                    .col(integer(TblAgentProfile::MetricsInterval))
+                    .col(integer(TblAgentProfile::MetricsBatch))
+                    .col(string(TblAgentProfile::Collect).default(""))
+                    .col(integer(TblAgentProfile::CollectInterval).default(0))
+                    .col(date_time(TblAgentProfile::CreatedAt).default(Expr::current_timestamp()))
+                    .col(date_time(TblAgentProfile::UpdatedAt).default(Expr::current_timestamp()))
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_table(
+                Table::create()
+                    .table(TblAgentProfileBinding::Table)
+                    .if_not_exists()
+                    .col(string(TblAgentProfileBinding::TargetType))
+                    .col(string(TblAgentProfileBinding::Target))
+                    .col(string(TblAgentProfileBinding::ProfileId))
+                    .col(
+                        date_time(TblAgentProfileBinding::CreatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(TblAgentProfileBinding::TargetType)
+                            .col(TblAgentProfileBinding::Target),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(
+                                TblAgentProfileBinding::Table,
+                                TblAgentProfileBinding::ProfileId,
+                            )
+                            .to(TblAgentProfile::Table, TblAgentProfile::Id)
+                            .on_delete(ForeignKeyAction::Restrict),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(TblAgentProfileBinding::Table)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(Table::drop().table(TblAgentProfile::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum TblAgentProfile {
+    Table,
+    Id,
+    Name,
+    Version,           // 每次修改加一，agent 心跳时带回正在使用的版本
+    HeartbeatInterval, // 心跳间隔秒数
+    MetricsInterval,   // 资源指标采样间隔秒数
+    MetricsBatch,      // 每批上报的采样数
+    Collect,           // 定时采集的主机信息类型，逗号分隔，如 SOFTWARE,SOCKET
+    CollectInterval,   // 定时采集的间隔秒数，0 表示不定时采集
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum TblAgentProfileBinding {
+    Table,
+    TargetType, // agent 或 group
+    Target,     // agent_id 或分组名
+    ProfileId,
+    CreatedAt,
+}
@@ -0,0 +1,52 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // sqlite 每次只能增加一列
+        for column in [
+            string_null(TblAgent::GroupName),
+            string_null(TblAgent::ProfileId),
+            big_integer_null(TblAgent::ProfileVersion),
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(TblAgent::Table)
+                        .add_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [
+            TblAgent::GroupName,
+            TblAgent::ProfileId,
+            TblAgent::ProfileVersion,
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(TblAgent::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum TblAgent {
+    Table,
+    GroupName,      // 分组名，配置模板可按分组分配
+    ProfileId,      // agent 上报的正在使用的配置模板
+    ProfileVersion, // agent 上报的正在使用的配置模板版本
+}
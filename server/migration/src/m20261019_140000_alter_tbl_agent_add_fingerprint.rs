@@ -0,0 +1,80 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // sqlite 每次只能增加一列
+        for column in [
+            string_null(TblAgent::MachineId),
+            string_null(TblAgent::DmiUuid),
+            string_null(TblAgent::Macs),
+            string_null(TblAgent::ContainerId),
+            string_null(TblAgent::Hostname),
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(TblAgent::Table)
+                        .add_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_tbl_agent_machine_id")
+                    .table(TblAgent::Table)
+                    .col(TblAgent::MachineId)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_tbl_agent_dmi_uuid")
+                    .table(TblAgent::Table)
+                    .col(TblAgent::DmiUuid)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for name in ["idx_tbl_agent_machine_id", "idx_tbl_agent_dmi_uuid"] {
+            manager
+                .drop_index(Index::drop().name(name).table(TblAgent::Table).to_owned())
+                .await?;
+        }
+        for column in [
+            TblAgent::MachineId,
+            TblAgent::DmiUuid,
+            TblAgent::Macs,
+            TblAgent::ContainerId,
+            TblAgent::Hostname,
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(TblAgent::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum TblAgent {
+    Table,
+    MachineId,   // 注册时上报的 /etc/machine-id
+    DmiUuid,     // 注册时上报的 dmi product_uuid
+    Macs,        // 物理网卡 mac，逗号分隔
+    ContainerId, // 运行在容器中时的容器 id
+    Hostname,
+}
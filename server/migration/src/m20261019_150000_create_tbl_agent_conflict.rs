@@ -0,0 +1,65 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TblAgentConflict::Table)
+                    .if_not_exists()
+                    .col(string(TblAgentConflict::Id).primary_key())
+                    .col(string(TblAgentConflict::AgentId))
+                    .col(string(TblAgentConflict::AssignedAgentId))
+                    .col(string_null(TblAgentConflict::MachineId))
+                    .col(string_null(TblAgentConflict::DmiUuid))
+                    .col(string_null(TblAgentConflict::Macs))
+                    .col(string_null(TblAgentConflict::ContainerId))
+                    .col(string_null(TblAgentConflict::Hostname))
+                    .col(string_null(TblAgentConflict::PeerAddr))
+                    .col(boolean(TblAgentConflict::Online))
+                    .col(string(TblAgentConflict::Status).default("open"))
+                    .col(date_time(TblAgentConflict::CreatedAt).default(Expr::current_timestamp()))
+                    .col(date_time_null(TblAgentConflict::ResolvedAt))
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_tbl_agent_conflict_status")
+                    .table(TblAgentConflict::Table)
+                    .col(TblAgentConflict::Status)
+                    .col(TblAgentConflict::CreatedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TblAgentConflict::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum TblAgentConflict {
+    Table,
+    Id,
+    AgentId,         // 注册时声明的编号
+    AssignedAgentId, // 服务端重新分配的编号
+    MachineId,       // 以下为重新分配的 agent 的指纹
+    DmiUuid,
+    Macs,
+    ContainerId,
+    Hostname,
+    PeerAddr,
+    Online, // 注册时原编号的 agent 是否在线，在线即为克隆
+    Status, // open、resolved
+    CreatedAt,
+    ResolvedAt,
+}
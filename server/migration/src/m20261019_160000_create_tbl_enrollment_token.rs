@@ -0,0 +1,49 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TblEnrollmentToken::Table)
+                    .if_not_exists()
+                    .col(string(TblEnrollmentToken::Id).primary_key())
+                    .col(string(TblEnrollmentToken::Name))
+                    .col(string_uniq(TblEnrollmentToken::TokenHash))
+                    .col(string_null(TblEnrollmentToken::GroupName))
+                    .col(boolean(TblEnrollmentToken::RequireApproval).default(false))
+                    .col(integer(TblEnrollmentToken::MaxUses))
+                    .col(integer(TblEnrollmentToken::Uses).default(0))
+                    .col(date_time(TblEnrollmentToken::ExpiresAt))
+                    .col(
+                        date_time(TblEnrollmentToken::CreatedAt).default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TblEnrollmentToken::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum TblEnrollmentToken {
+    Table,
+    Id,
+    Name,
+    TokenHash,       // token 的 sha256，明文只在创建时返回一次
+    GroupName,       // 作用范围，注册的 agent 加入该分组
+    RequireApproval, // 注册的 agent 是否需要审批
+    MaxUses,
+    Uses,
+    ExpiresAt,
+    CreatedAt,
+}
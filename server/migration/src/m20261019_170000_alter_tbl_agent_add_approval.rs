@@ -0,0 +1,48 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // sqlite 每次只能增加一列；已注册的 agent 视为已审批
+        for column in [
+            string(TblAgent::ApprovalState)
+                .default("approved")
+                .to_owned(),
+            string_null(TblAgent::EnrollmentTokenId),
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(TblAgent::Table)
+                        .add_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [TblAgent::ApprovalState, TblAgent::EnrollmentTokenId] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(TblAgent::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum TblAgent {
+    Table,
+    ApprovalState,     // pending、approved、blocked
+    EnrollmentTokenId, // 注册时使用的 enrollment token
+}
@@ -0,0 +1,50 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TblAgentCert::Table)
+                    .if_not_exists()
+                    .col(string(TblAgentCert::Serial).primary_key())
+                    .col(string(TblAgentCert::AgentId))
+                    .col(date_time(TblAgentCert::NotBefore))
+                    .col(date_time(TblAgentCert::NotAfter))
+                    .col(date_time_null(TblAgentCert::RevokedAt))
+                    .col(date_time(TblAgentCert::CreatedAt).default(Expr::current_timestamp()))
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_tbl_agent_cert_agent_id")
+                    .table(TblAgentCert::Table)
+                    .col(TblAgentCert::AgentId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TblAgentCert::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum TblAgentCert {
+    Table,
+    Serial, // 十六进制序列号
+    AgentId,
+    NotBefore,
+    NotAfter,
+    RevokedAt, // 吊销时间，删除 agent 时吊销其所有证书
+    CreatedAt,
+}
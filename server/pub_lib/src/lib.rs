@@ -6,10 +6,57 @@ pub enum AgentState {
     Offline, // 离线
 }
 
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    strum_macros::EnumString,
+    strum_macros::Display,
+    strum_macros::EnumIter,
+)]
+#[strum(serialize_all = "snake_case")]
+pub enum ApprovalState {
+    Pending,  // 等待审批，只能心跳
+    Approved, // 已审批
+    Blocked,  // 已封禁，请求在拦截器中被拒绝
+}
+
+#[derive(
+    Debug, PartialEq, strum_macros::EnumString, strum_macros::Display, strum_macros::EnumIter,
+)]
+pub enum LlmTaskKind {
+    Chat,      // 对话补全
+    Embedding, // 向量嵌入
+}
+
+#[derive(
+    Debug, Clone, PartialEq, strum_macros::EnumString, strum_macros::Display, strum_macros::EnumIter,
+)]
+pub enum LlmTaskStatus {
+    Pending,    // 等待 consumer 拉取
+    Processing, // consumer 处理中
+    Answered,   // 已有答案，producer 未取
+    Completed,  // producer 已取答案
+}
+
 pub const DATA_DIR: &str = "./data";
 pub const DB_DIR: &str = "../db";
 pub const DB_PATH: &str = "../db/z11n.sqlite";
 pub const UDS_PATH: &str = "../db/uds";
+pub const ARTIFACT_DIR: &str = "./data/artifacts";
+
+/// 制品按 sha256 存放，前两位作为子目录
+pub fn artifact_path(sha256: &str) -> std::path::PathBuf {
+    std::path::Path::new(ARTIFACT_DIR)
+        .join(&sha256[..2])
+        .join(sha256)
+}
+
+/// 制品 id 为小写十六进制的 sha256
+pub fn is_artifact_id(id: &str) -> bool {
+    id.len() == 64 && id.chars().all(|c| matches!(c, '0'..='9' | 'a'..='f'))
+}
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,120 @@
+use axum::{
+    Json, Router,
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::get,
+};
+use entity::{tbl_host, tbl_host_account};
+use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use validator::Validate;
+
+use crate::AppState;
+
+pub fn routers(state: AppState) -> Router {
+    Router::new()
+        .route("/accounts", get(query))
+        .with_state(state)
+}
+
+#[derive(Deserialize, Debug, Validate)]
+struct QueryInputDto {
+    name: Option<String>,
+    sudo: Option<bool>,
+    login: Option<bool>,
+    agent_id: Option<String>,
+    size: u64,
+    page: u64,
+}
+
+#[derive(Serialize, Debug)]
+struct QueryOutputDto {
+    agent_id: String,
+    host_name: Option<String>,
+    name: String,
+    uid: i64,
+    gid: i64,
+    home: String,
+    shell: String,
+    sudo: bool,
+    login: bool,
+}
+
+/// 按用户名查找存在该账号的主机
+async fn query(
+    app_state: State<AppState>,
+    Query(query_input_dto): Query<QueryInputDto>,
+) -> impl IntoResponse {
+    let mut select = tbl_host_account::Entity::find().find_also_related(tbl_host::Entity);
+    if let Some(v) = query_input_dto.name.filter(|v| !v.is_empty()) {
+        let like_pattern = format!("%{v}%");
+        select = select.filter(tbl_host_account::Column::Name.like(like_pattern));
+    }
+    if let Some(v) = query_input_dto.sudo {
+        select = select.filter(tbl_host_account::Column::Sudo.eq(v));
+    }
+    if let Some(v) = query_input_dto.login {
+        select = select.filter(tbl_host_account::Column::Login.eq(v));
+    }
+    if let Some(v) = query_input_dto.agent_id.filter(|v| !v.is_empty()) {
+        select = select.filter(tbl_host_account::Column::AgentId.eq(v));
+    }
+
+    let paginator = select
+        .order_by_asc(tbl_host_account::Column::Name)
+        .order_by_asc(tbl_host_account::Column::AgentId)
+        .paginate(&app_state.db_conn, query_input_dto.size);
+    let num_pages = match paginator.num_pages().await {
+        Ok(v) => v,
+        Err(e) => {
+            log::error!("num_pages err: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let num_items = match paginator.num_items().await {
+        Ok(v) => v,
+        Err(e) => {
+            log::error!("num_items err: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let rows = match paginator.fetch_page(query_input_dto.page).await {
+        Ok(v) => v,
+        Err(e) => {
+            log::error!("fetch_page err: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let mut accounts = Vec::new();
+    for (tbl_host_account, tbl_host) in rows {
+        accounts.push(QueryOutputDto {
+            agent_id: tbl_host_account.agent_id,
+            host_name: tbl_host.and_then(|v| v.host_name),
+            name: tbl_host_account.name,
+            uid: tbl_host_account.uid,
+            gid: tbl_host_account.gid,
+            home: tbl_host_account.home,
+            shell: tbl_host_account.shell,
+            sudo: tbl_host_account.sudo,
+            login: tbl_host_account.login,
+        });
+    }
+    (
+        StatusCode::OK,
+        Json(json!(
+            {
+            "page":{
+              "size":query_input_dto.size,
+              "total_elements":num_items,
+              "total_pages":num_pages
+            },
+            "_embedded":{
+                "account":accounts
+            }
+           }
+        )),
+    )
+        .into_response()
+}
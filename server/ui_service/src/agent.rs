@@ -3,12 +3,16 @@ use axum::{
     extract::{Path, Query, State},
     http::StatusCode,
     response::IntoResponse,
-    routing::get,
+    routing::{get, put},
+};
+use entity::{tbl_agent, tbl_agent_cert};
+use pub_lib::ApprovalState;
+use sea_orm::{
+    ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder, prelude::Expr,
 };
-use entity::tbl_agent;
-use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::str::FromStr;
 use validator::Validate;
 
 use crate::AppState;
@@ -17,12 +21,17 @@ pub fn routers(state: AppState) -> Router {
     Router::new()
         .route("/agents", get(query))
         .route("/agents/{id}", get(detail).delete(delete))
+        .route("/agents/{id}/group", put(group))
+        .route("/agent_approvals/{id}", put(approval))
         .with_state(state)
 }
 
 #[derive(Deserialize, Debug, Validate)]
 struct QueryInputDto {
     ip: Option<String>,
+    group_name: Option<String>,
+    // pending、approved、blocked
+    approval_state: Option<String>,
     size: u64,
     page: u64,
 }
@@ -32,6 +41,12 @@ struct QueryOutputDto {
     id: String,
     version: String,
     state: String,
+    hostname: Option<String>,
+    group_name: Option<String>,
+    // 正在使用的配置模板
+    profile_id: Option<String>,
+    profile_version: Option<i64>,
+    approval_state: String,
     created_at: i64,
     updated_at: i64,
 }
@@ -46,6 +61,12 @@ async fn query(
             select = select.filter(tbl_agent::Column::Id.like(like_pattern));
         }
     }
+    if let Some(v) = query_input_dto.group_name.filter(|v| !v.is_empty()) {
+        select = select.filter(tbl_agent::Column::GroupName.eq(v));
+    }
+    if let Some(v) = query_input_dto.approval_state.filter(|v| !v.is_empty()) {
+        select = select.filter(tbl_agent::Column::ApprovalState.eq(v));
+    }
 
     let paginator = select
         .order_by_desc(tbl_agent::Column::CreatedAt)
@@ -77,6 +98,11 @@ async fn query(
             id: tbl_agent.id,
             version: tbl_agent.version,
             state: tbl_agent.state,
+            hostname: tbl_agent.hostname,
+            group_name: tbl_agent.group_name,
+            profile_id: tbl_agent.profile_id,
+            profile_version: tbl_agent.profile_version,
+            approval_state: tbl_agent.approval_state,
             created_at: tbl_agent.created_at.and_utc().timestamp_millis(),
             updated_at: tbl_agent.created_at.and_utc().timestamp_millis(),
         });
@@ -111,6 +137,13 @@ async fn detail(Path(id): Path<String>, State(app_state): State<AppState>) -> im
                     Json(json!({
                         "agent_id":tbl_agent.id,
                         "agent_version":tbl_agent.version,
+                        "hostname":tbl_agent.hostname,
+                        "machine_id":tbl_agent.machine_id,
+                        "dmi_uuid":tbl_agent.dmi_uuid,
+                        "macs":tbl_agent.macs,
+                        "container_id":tbl_agent.container_id,
+                        "approval_state":tbl_agent.approval_state,
+                        "enrollment_token_id":tbl_agent.enrollment_token_id,
                         "created_at":tbl_agent.created_at.and_utc().timestamp_millis()
                     })),
                 )
@@ -127,7 +160,21 @@ async fn detail(Path(id): Path<String>, State(app_state): State<AppState>) -> im
     }
 }
 
+/// 删除 agent 并吊销其客户端证书，client_service 同步后拒绝该证书
 async fn delete(Path(id): Path<String>, State(app_state): State<AppState>) -> impl IntoResponse {
+    if let Err(e) = tbl_agent_cert::Entity::update_many()
+        .col_expr(
+            tbl_agent_cert::Column::RevokedAt,
+            Expr::value(chrono::Utc::now().naive_utc()),
+        )
+        .filter(tbl_agent_cert::Column::AgentId.eq(&id))
+        .filter(tbl_agent_cert::Column::RevokedAt.is_null())
+        .exec(&app_state.db_conn)
+        .await
+    {
+        log::error!("revoke agent {id} certs db err: {e}");
+        return StatusCode::INTERNAL_SERVER_ERROR;
+    }
     match tbl_agent::Entity::delete_by_id(&id)
         .exec(&app_state.db_conn)
         .await
@@ -149,3 +196,77 @@ async fn delete(Path(id): Path<String>, State(app_state): State<AppState>) -> im
         }
     }
 }
+
+#[derive(Deserialize, Debug, Validate)]
+struct GroupInputDto {
+    // 为空时移出分组
+    group_name: Option<String>,
+}
+
+/// 设置 agent 所在分组，按分组分配的配置模板在下次心跳时生效
+async fn group(
+    Path(id): Path<String>,
+    State(app_state): State<AppState>,
+    Json(group_input_dto): Json<GroupInputDto>,
+) -> impl IntoResponse {
+    let group_name = group_input_dto
+        .group_name
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty());
+    match tbl_agent::Entity::update_many()
+        .col_expr(tbl_agent::Column::GroupName, Expr::value(group_name.clone()))
+        .filter(tbl_agent::Column::Id.eq(&id))
+        .exec(&app_state.db_conn)
+        .await
+    {
+        Ok(update_result) if update_result.rows_affected == 0 => {
+            StatusCode::NOT_FOUND.into_response()
+        }
+        Ok(_) => {
+            log::info!("agent {id} group set to {group_name:?}");
+            StatusCode::OK.into_response()
+        }
+        Err(e) => {
+            log::error!("agent {id} group update err: {e}");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Validate)]
+struct ApprovalInputDto {
+    // pending、approved、blocked
+    approval_state: String,
+}
+
+/// 审批或封禁 agent，client_service 定期同步，封禁的 agent 所有请求被拒绝
+async fn approval(
+    Path(id): Path<String>,
+    State(app_state): State<AppState>,
+    Json(approval_input_dto): Json<ApprovalInputDto>,
+) -> impl IntoResponse {
+    let Ok(approval_state) = ApprovalState::from_str(&approval_input_dto.approval_state) else {
+        return (StatusCode::BAD_REQUEST, "unknown approval_state").into_response();
+    };
+    match tbl_agent::Entity::update_many()
+        .col_expr(
+            tbl_agent::Column::ApprovalState,
+            Expr::value(approval_state.to_string()),
+        )
+        .filter(tbl_agent::Column::Id.eq(&id))
+        .exec(&app_state.db_conn)
+        .await
+    {
+        Ok(update_result) if update_result.rows_affected == 0 => {
+            StatusCode::NOT_FOUND.into_response()
+        }
+        Ok(_) => {
+            log::info!("agent {id} approval state set to {approval_state}");
+            StatusCode::OK.into_response()
+        }
+        Err(e) => {
+            log::error!("agent {id} approval update err: {e}");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use crate::AppState;
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{get, patch},
+};
+use entity::tbl_agent_conflict;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, EntityTrait, IntoActiveModel, PaginatorTrait,
+    QueryFilter, QueryOrder,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use validator::Validate;
+
+pub fn routers(state: AppState) -> Router {
+    Router::new()
+        .route("/agent_conflicts", get(query))
+        .route("/agent_conflicts/{id}", patch(update))
+        .with_state(state)
+}
+
+#[derive(Deserialize, Debug, Validate)]
+struct QueryInputDto {
+    agent_id: Option<String>,
+    // open、resolved
+    status: Option<String>,
+    size: u64,
+    page: u64,
+}
+
+#[derive(Serialize, Debug)]
+struct QueryOutputDto {
+    id: String,
+    agent_id: String,
+    assigned_agent_id: String,
+    machine_id: Option<String>,
+    dmi_uuid: Option<String>,
+    macs: Option<String>,
+    container_id: Option<String>,
+    hostname: Option<String>,
+    peer_addr: Option<String>,
+    online: bool,
+    status: String,
+    created_at: i64,
+    resolved_at: Option<i64>,
+}
+
+impl From<tbl_agent_conflict::Model> for QueryOutputDto {
+    fn from(tbl_agent_conflict: tbl_agent_conflict::Model) -> Self {
+        Self {
+            id: tbl_agent_conflict.id,
+            agent_id: tbl_agent_conflict.agent_id,
+            assigned_agent_id: tbl_agent_conflict.assigned_agent_id,
+            machine_id: tbl_agent_conflict.machine_id,
+            dmi_uuid: tbl_agent_conflict.dmi_uuid,
+            macs: tbl_agent_conflict.macs,
+            container_id: tbl_agent_conflict.container_id,
+            hostname: tbl_agent_conflict.hostname,
+            peer_addr: tbl_agent_conflict.peer_addr,
+            online: tbl_agent_conflict.online,
+            status: tbl_agent_conflict.status,
+            created_at: tbl_agent_conflict.created_at.and_utc().timestamp_millis(),
+            resolved_at: tbl_agent_conflict
+                .resolved_at
+                .map(|v| v.and_utc().timestamp_millis()),
+        }
+    }
+}
+
+/// 编号冲突记录，agent_id 为声明的编号，assigned_agent_id 为重新分配给另一台机器的编号
+async fn query(
+    app_state: State<AppState>,
+    Query(query_input_dto): Query<QueryInputDto>,
+) -> impl IntoResponse {
+    let mut select = tbl_agent_conflict::Entity::find();
+    if let Some(v) = query_input_dto.agent_id.filter(|v| !v.is_empty()) {
+        select = select.filter(
+            tbl_agent_conflict::Column::AgentId
+                .eq(&v)
+                .or(tbl_agent_conflict::Column::AssignedAgentId.eq(&v)),
+        );
+    }
+    if let Some(v) = query_input_dto.status.filter(|v| !v.is_empty()) {
+        select = select.filter(tbl_agent_conflict::Column::Status.eq(v));
+    }
+    let paginator = select
+        .order_by_desc(tbl_agent_conflict::Column::CreatedAt)
+        .paginate(&app_state.db_conn, query_input_dto.size);
+    let num_pages = match paginator.num_pages().await {
+        Ok(v) => v,
+        Err(e) => {
+            log::error!("num_pages err: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let num_items = match paginator.num_items().await {
+        Ok(v) => v,
+        Err(e) => {
+            log::error!("num_items err: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let rows = match paginator.fetch_page(query_input_dto.page).await {
+        Ok(v) => v,
+        Err(e) => {
+            log::error!("fetch_page err: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let conflicts: Vec<QueryOutputDto> = rows.into_iter().map(Into::into).collect();
+    (
+        StatusCode::OK,
+        Json(json!(
+            {
+            "page":{
+              "size":query_input_dto.size,
+              "total_elements":num_items,
+              "total_pages":num_pages
+            },
+            "_embedded":{
+                "agent_conflict":conflicts
+            }
+           }
+        )),
+    )
+        .into_response()
+}
+
+#[derive(Deserialize, Debug, Validate)]
+struct UpdateInputDto {
+    status: String,
+}
+
+/// 标记冲突已处理或重新打开，重复的 agent 可在 Agent 列表中删除
+async fn update(
+    Path(id): Path<String>,
+    State(app_state): State<AppState>,
+    Json(update_input_dto): Json<UpdateInputDto>,
+) -> impl IntoResponse {
+    let resolved_at = match update_input_dto.status.as_str() {
+        "resolved" => Some(chrono::Utc::now().naive_utc()),
+        "open" => None,
+        _ => return (StatusCode::BAD_REQUEST, "unknown status").into_response(),
+    };
+    let tbl_agent_conflict = match tbl_agent_conflict::Entity::find_by_id(&id)
+        .one(&app_state.db_conn)
+        .await
+    {
+        Ok(Some(v)) => v,
+        Ok(None) => return StatusCode::NOT_FOUND.into_response(),
+        Err(e) => {
+            log::error!("tbl_agent_conflict find by id err: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let mut tbl_agent_conflict_am = tbl_agent_conflict.into_active_model();
+    tbl_agent_conflict_am.status = Set(update_input_dto.status.clone());
+    tbl_agent_conflict_am.resolved_at = Set(resolved_at);
+    match tbl_agent_conflict_am.update(&app_state.db_conn).await {
+        Ok(tbl_agent_conflict) => {
+            log::info!("agent conflict {id} {}", tbl_agent_conflict.status);
+            (
+                StatusCode::OK,
+                Json(QueryOutputDto::from(tbl_agent_conflict)),
+            )
+                .into_response()
+        }
+        Err(e) => {
+            log::error!("tbl_agent_conflict update err: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
@@ -0,0 +1,438 @@
+use crate::{AppState, z11n::upload_host::InfoType};
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{delete, get, patch},
+};
+use entity::{tbl_agent, tbl_agent_profile, tbl_agent_profile_binding};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, EntityTrait, IntoActiveModel, PaginatorTrait,
+    QueryFilter, QueryOrder, sea_query::OnConflict,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use validator::Validate;
+
+pub fn routers(state: AppState) -> Router {
+    Router::new()
+        .route("/agent_profiles", get(query).post(create))
+        .route("/agent_profiles/{id}", patch(update).delete(remove))
+        .route("/agent_profile_bindings", get(binding_query).put(bind))
+        .route(
+            "/agent_profile_bindings/{target_type}/{target}",
+            delete(unbind),
+        )
+        .with_state(state)
+}
+
+#[derive(Serialize, Debug)]
+struct ProfileOutputDto {
+    id: String,
+    name: String,
+    version: i64,
+    heartbeat_interval: i32,
+    metrics_interval: i32,
+    metrics_batch: i32,
+    collect: Vec<String>,
+    collect_interval: i32,
+    created_at: i64,
+    updated_at: i64,
+}
+
+impl From<tbl_agent_profile::Model> for ProfileOutputDto {
+    fn from(tbl_agent_profile: tbl_agent_profile::Model) -> Self {
+        Self {
+            id: tbl_agent_profile.id,
+            name: tbl_agent_profile.name,
+            version: tbl_agent_profile.version,
+            heartbeat_interval: tbl_agent_profile.heartbeat_interval,
+            metrics_interval: tbl_agent_profile.metrics_interval,
+            metrics_batch: tbl_agent_profile.metrics_batch,
+            collect: tbl_agent_profile
+                .collect
+                .split(',')
+                .filter(|v| !v.is_empty())
+                .map(str::to_string)
+                .collect(),
+            collect_interval: tbl_agent_profile.collect_interval,
+            created_at: tbl_agent_profile.created_at.and_utc().timestamp_millis(),
+            updated_at: tbl_agent_profile.updated_at.and_utc().timestamp_millis(),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Validate)]
+struct ProfileInputDto {
+    name: String,
+    heartbeat_interval: i32,
+    metrics_interval: i32,
+    metrics_batch: i32,
+    // 主机信息类型，如 SOFTWARE、SOCKET
+    collect: Vec<String>,
+    collect_interval: i32,
+}
+
+impl ProfileInputDto {
+    /// 校验取值范围，返回逗号分隔的主机信息类型
+    fn check(&self) -> Result<String, String> {
+        if self.name.trim().is_empty() {
+            return Err("name is required".to_string());
+        }
+        // 心跳间隔需小于 client_service.toml 中的 offline_ex
+        if !(1..=300).contains(&self.heartbeat_interval) {
+            return Err("heartbeat_interval must be in 1..=300".to_string());
+        }
+        if !(1..=3600).contains(&self.metrics_interval) || !(1..=1000).contains(&self.metrics_batch)
+        {
+            return Err("metrics_interval or metrics_batch out of range".to_string());
+        }
+        if self.collect_interval != 0 && self.collect_interval < 60 {
+            return Err("collect_interval must be 0 or at least 60".to_string());
+        }
+        for info_type in &self.collect {
+            if InfoType::from_str_name(info_type).is_none() {
+                return Err(format!("unknown info type {info_type}"));
+            }
+        }
+        Ok(self.collect.join(","))
+    }
+}
+
+#[derive(Deserialize, Debug, Validate)]
+struct QueryInputDto {
+    name: Option<String>,
+    size: u64,
+    page: u64,
+}
+
+async fn query(
+    app_state: State<AppState>,
+    Query(query_input_dto): Query<QueryInputDto>,
+) -> impl IntoResponse {
+    let mut select = tbl_agent_profile::Entity::find();
+    if let Some(v) = query_input_dto.name.filter(|v| !v.is_empty()) {
+        select = select.filter(tbl_agent_profile::Column::Name.contains(v));
+    }
+    let paginator = select
+        .order_by_asc(tbl_agent_profile::Column::Name)
+        .paginate(&app_state.db_conn, query_input_dto.size);
+    let num_pages = match paginator.num_pages().await {
+        Ok(v) => v,
+        Err(e) => {
+            log::error!("num_pages err: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let num_items = match paginator.num_items().await {
+        Ok(v) => v,
+        Err(e) => {
+            log::error!("num_items err: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let rows = match paginator.fetch_page(query_input_dto.page).await {
+        Ok(v) => v,
+        Err(e) => {
+            log::error!("fetch_page err: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let profiles: Vec<ProfileOutputDto> = rows.into_iter().map(Into::into).collect();
+    (
+        StatusCode::OK,
+        Json(json!(
+            {
+            "page":{
+              "size":query_input_dto.size,
+              "total_elements":num_items,
+              "total_pages":num_pages
+            },
+            "_embedded":{
+                "agent_profile":profiles
+            }
+           }
+        )),
+    )
+        .into_response()
+}
+
+async fn create(
+    app_state: State<AppState>,
+    Json(profile_input_dto): Json<ProfileInputDto>,
+) -> impl IntoResponse {
+    let collect = match profile_input_dto.check() {
+        Ok(v) => v,
+        Err(e) => return (StatusCode::BAD_REQUEST, e).into_response(),
+    };
+    let tbl_agent_profile_am = tbl_agent_profile::ActiveModel {
+        id: Set(uuid::Uuid::new_v4().to_string()),
+        name: Set(profile_input_dto.name.trim().to_string()),
+        version: Set(1),
+        heartbeat_interval: Set(profile_input_dto.heartbeat_interval),
+        metrics_interval: Set(profile_input_dto.metrics_interval),
+        metrics_batch: Set(profile_input_dto.metrics_batch),
+        collect: Set(collect),
+        collect_interval: Set(profile_input_dto.collect_interval),
+        ..Default::default()
+    };
+    match tbl_agent_profile::Entity::insert(tbl_agent_profile_am)
+        .exec_with_returning(&app_state.db_conn)
+        .await
+    {
+        Ok(tbl_agent_profile) => {
+            log::info!("agent profile {} created", tbl_agent_profile.name);
+            (
+                StatusCode::OK,
+                Json(ProfileOutputDto::from(tbl_agent_profile)),
+            )
+                .into_response()
+        }
+        Err(e) => {
+            log::error!("tbl_agent_profile insert err: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+/// 修改配置模板，版本加一，使用该模板的 agent 在下次心跳时收到新版本
+async fn update(
+    Path(id): Path<String>,
+    app_state: State<AppState>,
+    Json(profile_input_dto): Json<ProfileInputDto>,
+) -> impl IntoResponse {
+    let collect = match profile_input_dto.check() {
+        Ok(v) => v,
+        Err(e) => return (StatusCode::BAD_REQUEST, e).into_response(),
+    };
+    let tbl_agent_profile = match tbl_agent_profile::Entity::find_by_id(&id)
+        .one(&app_state.db_conn)
+        .await
+    {
+        Ok(Some(v)) => v,
+        Ok(None) => return StatusCode::NOT_FOUND.into_response(),
+        Err(e) => {
+            log::error!("tbl_agent_profile find by id err: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let version = tbl_agent_profile.version + 1;
+    let mut tbl_agent_profile_am = tbl_agent_profile.into_active_model();
+    tbl_agent_profile_am.name = Set(profile_input_dto.name.trim().to_string());
+    tbl_agent_profile_am.version = Set(version);
+    tbl_agent_profile_am.heartbeat_interval = Set(profile_input_dto.heartbeat_interval);
+    tbl_agent_profile_am.metrics_interval = Set(profile_input_dto.metrics_interval);
+    tbl_agent_profile_am.metrics_batch = Set(profile_input_dto.metrics_batch);
+    tbl_agent_profile_am.collect = Set(collect);
+    tbl_agent_profile_am.collect_interval = Set(profile_input_dto.collect_interval);
+    tbl_agent_profile_am.updated_at = Set(chrono::Utc::now().naive_utc());
+    match tbl_agent_profile_am.update(&app_state.db_conn).await {
+        Ok(tbl_agent_profile) => {
+            log::info!("agent profile {id} updated to version {version}");
+            (
+                StatusCode::OK,
+                Json(ProfileOutputDto::from(tbl_agent_profile)),
+            )
+                .into_response()
+        }
+        Err(e) => {
+            log::error!("tbl_agent_profile update err: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+/// 删除配置模板，仍分配给 agent 或分组时拒绝
+async fn remove(Path(id): Path<String>, State(app_state): State<AppState>) -> impl IntoResponse {
+    match tbl_agent_profile_binding::Entity::find()
+        .filter(tbl_agent_profile_binding::Column::ProfileId.eq(&id))
+        .count(&app_state.db_conn)
+        .await
+    {
+        Ok(0) => {}
+        Ok(_) => return (StatusCode::CONFLICT, "profile is bound").into_response(),
+        Err(e) => {
+            log::error!("tbl_agent_profile_binding count err: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    }
+    match tbl_agent_profile::Entity::delete_by_id(&id)
+        .exec(&app_state.db_conn)
+        .await
+    {
+        Ok(delete_result) if delete_result.rows_affected == 0 => {
+            StatusCode::NOT_FOUND.into_response()
+        }
+        Ok(_) => {
+            log::info!("delete agent profile {id} success");
+            StatusCode::OK.into_response()
+        }
+        Err(e) => {
+            log::error!("delete agent profile {id} db err: {e}");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Validate)]
+struct BindingQueryInputDto {
+    target_type: Option<String>,
+    profile_id: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+struct BindingOutputDto {
+    target_type: String,
+    target: String,
+    profile_id: String,
+    profile_name: Option<String>,
+    created_at: i64,
+}
+
+/// 配置模板的分配，agent 直接分配优先于所在分组的分配
+async fn binding_query(
+    app_state: State<AppState>,
+    Query(binding_query_input_dto): Query<BindingQueryInputDto>,
+) -> impl IntoResponse {
+    let mut select =
+        tbl_agent_profile_binding::Entity::find().find_also_related(tbl_agent_profile::Entity);
+    if let Some(v) = binding_query_input_dto
+        .target_type
+        .filter(|v| !v.is_empty())
+    {
+        select = select.filter(tbl_agent_profile_binding::Column::TargetType.eq(v));
+    }
+    if let Some(v) = binding_query_input_dto.profile_id.filter(|v| !v.is_empty()) {
+        select = select.filter(tbl_agent_profile_binding::Column::ProfileId.eq(v));
+    }
+    let rows = match select
+        .order_by_asc(tbl_agent_profile_binding::Column::TargetType)
+        .order_by_asc(tbl_agent_profile_binding::Column::Target)
+        .all(&app_state.db_conn)
+        .await
+    {
+        Ok(v) => v,
+        Err(e) => {
+            log::error!("tbl_agent_profile_binding find err: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let bindings: Vec<BindingOutputDto> = rows
+        .into_iter()
+        .map(
+            |(tbl_agent_profile_binding, tbl_agent_profile)| BindingOutputDto {
+                target_type: tbl_agent_profile_binding.target_type,
+                target: tbl_agent_profile_binding.target,
+                profile_id: tbl_agent_profile_binding.profile_id,
+                profile_name: tbl_agent_profile.map(|v| v.name),
+                created_at: tbl_agent_profile_binding
+                    .created_at
+                    .and_utc()
+                    .timestamp_millis(),
+            },
+        )
+        .collect();
+    (StatusCode::OK, Json(json!({ "bindings": bindings }))).into_response()
+}
+
+#[derive(Deserialize, Debug, Validate)]
+struct BindInputDto {
+    // agent 或 group
+    target_type: String,
+    // agent_id 或分组名
+    target: String,
+    profile_id: String,
+}
+
+/// 将配置模板分配给 agent 或分组，已有分配时替换
+async fn bind(
+    app_state: State<AppState>,
+    Json(bind_input_dto): Json<BindInputDto>,
+) -> impl IntoResponse {
+    let target = bind_input_dto.target.trim().to_string();
+    if target.is_empty() {
+        return (StatusCode::BAD_REQUEST, "target is required").into_response();
+    }
+    match bind_input_dto.target_type.as_str() {
+        "agent" => match tbl_agent::Entity::find_by_id(&target)
+            .one(&app_state.db_conn)
+            .await
+        {
+            Ok(Some(_)) => {}
+            Ok(None) => return (StatusCode::BAD_REQUEST, "agent not found").into_response(),
+            Err(e) => {
+                log::error!("tbl_agent find by id err: {}", e);
+                return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+            }
+        },
+        "group" => {}
+        _ => return (StatusCode::BAD_REQUEST, "unknown target type").into_response(),
+    }
+    match tbl_agent_profile::Entity::find_by_id(&bind_input_dto.profile_id)
+        .one(&app_state.db_conn)
+        .await
+    {
+        Ok(Some(_)) => {}
+        Ok(None) => return (StatusCode::BAD_REQUEST, "profile not found").into_response(),
+        Err(e) => {
+            log::error!("tbl_agent_profile find by id err: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    }
+    let tbl_agent_profile_binding_am = tbl_agent_profile_binding::ActiveModel {
+        target_type: Set(bind_input_dto.target_type.clone()),
+        target: Set(target.clone()),
+        profile_id: Set(bind_input_dto.profile_id.clone()),
+        ..Default::default()
+    };
+    match tbl_agent_profile_binding::Entity::insert(tbl_agent_profile_binding_am)
+        .on_conflict(
+            OnConflict::columns([
+                tbl_agent_profile_binding::Column::TargetType,
+                tbl_agent_profile_binding::Column::Target,
+            ])
+            .update_column(tbl_agent_profile_binding::Column::ProfileId)
+            .to_owned(),
+        )
+        .exec(&app_state.db_conn)
+        .await
+    {
+        Ok(_) => {
+            log::info!(
+                "bind profile {} to {} {}",
+                bind_input_dto.profile_id,
+                bind_input_dto.target_type,
+                target
+            );
+            StatusCode::OK.into_response()
+        }
+        Err(e) => {
+            log::error!("tbl_agent_profile_binding insert err: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+/// 取消分配，没有其他分配时 agent 恢复本地配置
+async fn unbind(
+    Path((target_type, target)): Path<(String, String)>,
+    State(app_state): State<AppState>,
+) -> impl IntoResponse {
+    match tbl_agent_profile_binding::Entity::delete_by_id((target_type.clone(), target.clone()))
+        .exec(&app_state.db_conn)
+        .await
+    {
+        Ok(delete_result) if delete_result.rows_affected == 0 => {
+            StatusCode::NOT_FOUND.into_response()
+        }
+        Ok(_) => {
+            log::info!("unbind profile from {target_type} {target}");
+            StatusCode::OK.into_response()
+        }
+        Err(e) => {
+            log::error!("tbl_agent_profile_binding delete err: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
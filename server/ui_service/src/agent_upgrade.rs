@@ -0,0 +1,199 @@
+use crate::{
+    AppState,
+    z11n::{HeartbeatRsp, Upgrade, heartbeat_rsp::Task},
+};
+use axum::{
+    Json, Router,
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::get,
+};
+use entity::{tbl_agent, tbl_agent_upgrade, tbl_artifact};
+use sea_orm::{
+    ActiveValue::Set, ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use validator::Validate;
+
+pub fn routers(state: AppState) -> Router {
+    Router::new()
+        .route("/agent_upgrades", get(query).post(create))
+        .with_state(state)
+}
+
+#[derive(Deserialize, Debug, Validate)]
+struct CreateInputDto {
+    agent_ids: Vec<String>,
+    // 升级包在制品库中的 id
+    artifact_id: String,
+}
+
+/// 向 agent 下发升级任务，每个 agent 记录一条升级状态
+async fn create(
+    app_state: State<AppState>,
+    Json(create_input_dto): Json<CreateInputDto>,
+) -> impl IntoResponse {
+    if create_input_dto.agent_ids.is_empty() {
+        return StatusCode::BAD_REQUEST.into_response();
+    }
+    let tbl_artifact = match tbl_artifact::Entity::find_by_id(&create_input_dto.artifact_id)
+        .one(&app_state.db_conn)
+        .await
+    {
+        Ok(Some(v)) => v,
+        Ok(None) => return (StatusCode::BAD_REQUEST, "artifact not found").into_response(),
+        Err(e) => {
+            log::error!("tbl_artifact find err: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let tbl_agents = match tbl_agent::Entity::find()
+        .filter(tbl_agent::Column::Id.is_in(&create_input_dto.agent_ids))
+        .all(&app_state.db_conn)
+        .await
+    {
+        Ok(v) => v,
+        Err(e) => {
+            log::error!("tbl_agent find err: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    if tbl_agents.len() != create_input_dto.agent_ids.len() {
+        return (StatusCode::BAD_REQUEST, "agent not found").into_response();
+    }
+
+    let mut ids = Vec::new();
+    for tbl_agent in tbl_agents {
+        let id = uuid::Uuid::new_v4().to_string();
+        let tbl_agent_upgrade_am = tbl_agent_upgrade::ActiveModel {
+            id: Set(id.clone()),
+            agent_id: Set(tbl_agent.id.clone()),
+            from_version: Set(tbl_agent.version),
+            version: Set(tbl_artifact.version.clone()),
+            download_ref: Set(tbl_artifact.id.clone()),
+            sha256: Set(tbl_artifact.id.clone()),
+            status: Set("pending".to_string()),
+            ..Default::default()
+        };
+        if let Err(e) = tbl_agent_upgrade::Entity::insert(tbl_agent_upgrade_am)
+            .exec(&app_state.db_conn)
+            .await
+        {
+            log::error!("tbl_agent_upgrade insert err: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+        let heartbeat_rsp = HeartbeatRsp {
+            task: Some(Task::Upgrade(Upgrade {
+                id: id.clone(),
+                version: tbl_artifact.version.clone(),
+                download_ref: tbl_artifact.id.clone(),
+                sha256: tbl_artifact.id.clone(),
+            })),
+        };
+        if let Err(e) = app_state
+            .tx_heartbeat_rsp
+            .send((tbl_agent.id, heartbeat_rsp))
+        {
+            log::error!("tx_heartbeat_rsp.send err: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+        ids.push(id);
+    }
+    (StatusCode::OK, Json(json!({ "ids": ids }))).into_response()
+}
+
+#[derive(Deserialize, Debug, Validate)]
+struct QueryInputDto {
+    agent_id: Option<String>,
+    version: Option<String>,
+    status: Option<String>,
+    size: u64,
+    page: u64,
+}
+
+#[derive(Serialize, Debug)]
+struct QueryOutputDto {
+    id: String,
+    agent_id: String,
+    from_version: String,
+    version: String,
+    download_ref: String,
+    status: String,
+    message: Option<String>,
+    created_at: i64,
+    updated_at: i64,
+}
+
+/// 升级记录，按下发时间倒序
+async fn query(
+    app_state: State<AppState>,
+    Query(query_input_dto): Query<QueryInputDto>,
+) -> impl IntoResponse {
+    let mut select = tbl_agent_upgrade::Entity::find();
+    if let Some(v) = query_input_dto.agent_id.filter(|v| !v.is_empty()) {
+        select = select.filter(tbl_agent_upgrade::Column::AgentId.eq(v));
+    }
+    if let Some(v) = query_input_dto.version.filter(|v| !v.is_empty()) {
+        select = select.filter(tbl_agent_upgrade::Column::Version.eq(v));
+    }
+    if let Some(v) = query_input_dto.status.filter(|v| !v.is_empty()) {
+        select = select.filter(tbl_agent_upgrade::Column::Status.eq(v));
+    }
+    let paginator = select
+        .order_by_desc(tbl_agent_upgrade::Column::CreatedAt)
+        .order_by_asc(tbl_agent_upgrade::Column::AgentId)
+        .paginate(&app_state.db_conn, query_input_dto.size);
+    let num_pages = match paginator.num_pages().await {
+        Ok(v) => v,
+        Err(e) => {
+            log::error!("num_pages err: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let num_items = match paginator.num_items().await {
+        Ok(v) => v,
+        Err(e) => {
+            log::error!("num_items err: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let rows = match paginator.fetch_page(query_input_dto.page).await {
+        Ok(v) => v,
+        Err(e) => {
+            log::error!("fetch_page err: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let mut agent_upgrades = Vec::new();
+    for tbl_agent_upgrade in rows {
+        agent_upgrades.push(QueryOutputDto {
+            id: tbl_agent_upgrade.id,
+            agent_id: tbl_agent_upgrade.agent_id,
+            from_version: tbl_agent_upgrade.from_version,
+            version: tbl_agent_upgrade.version,
+            download_ref: tbl_agent_upgrade.download_ref,
+            status: tbl_agent_upgrade.status,
+            message: tbl_agent_upgrade.message,
+            created_at: tbl_agent_upgrade.created_at.and_utc().timestamp_millis(),
+            updated_at: tbl_agent_upgrade.updated_at.and_utc().timestamp_millis(),
+        });
+    }
+    (
+        StatusCode::OK,
+        Json(json!(
+            {
+            "page":{
+              "size":query_input_dto.size,
+              "total_elements":num_items,
+              "total_pages":num_pages
+            },
+            "_embedded":{
+                "agent_upgrade":agent_upgrades
+            }
+           }
+        )),
+    )
+        .into_response()
+}
@@ -0,0 +1,401 @@
+use crate::{AppState, config::UI_SERVICE_TOML};
+use axum::{
+    Json, Router,
+    extract::{DefaultBodyLimit, Multipart, Path, Query, State, multipart::Field},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{delete, get},
+};
+use ed25519_dalek::{Signature, VerifyingKey, pkcs8::DecodePublicKey};
+use entity::{tbl_agent_upgrade, tbl_artifact};
+use once_cell::sync::Lazy;
+use sea_orm::{
+    ActiveValue::Set, ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+use tokio::{fs, io::AsyncWriteExt};
+use validator::Validate;
+
+// 发布公钥，未配置或读取失败时不允许上传；私钥只在离线签名的机器上
+static PUBLIC_KEY: Lazy<Option<VerifyingKey>> = Lazy::new(|| {
+    let path = &UI_SERVICE_TOML.artifact.public_key;
+    let pem = match std::fs::read_to_string(path) {
+        Ok(v) => v,
+        Err(e) => {
+            log::error!("read release public key {path} err: {e}");
+            return None;
+        }
+    };
+    match VerifyingKey::from_public_key_pem(&pem) {
+        Ok(v) => Some(v),
+        Err(e) => {
+            log::error!("parse release public key {path} err: {e}");
+            None
+        }
+    }
+});
+
+pub fn routers(state: AppState) -> Router {
+    Router::new()
+        .route(
+            "/artifacts",
+            get(query)
+                .post(upload)
+                .layer(DefaultBodyLimit::max(UI_SERVICE_TOML.artifact.max_size)),
+        )
+        .route("/artifacts/{id}", delete(remove))
+        .with_state(state)
+}
+
+#[derive(Serialize, Debug)]
+struct ArtifactOutputDto {
+    id: String,
+    name: String,
+    version: String,
+    size: i64,
+    signature: String,
+    created_at: i64,
+}
+
+impl From<tbl_artifact::Model> for ArtifactOutputDto {
+    fn from(tbl_artifact: tbl_artifact::Model) -> Self {
+        Self {
+            id: tbl_artifact.id,
+            name: tbl_artifact.name,
+            version: tbl_artifact.version,
+            size: tbl_artifact.size,
+            signature: tbl_artifact.signature,
+            created_at: tbl_artifact.created_at.and_utc().timestamp_millis(),
+        }
+    }
+}
+
+// 已写入临时文件的上传内容
+struct Uploaded {
+    tmp_path: PathBuf,
+    sha256: [u8; 32],
+    size: i64,
+}
+
+/// 上传制品，multipart 字段为 version、signature 和 file，校验离线签名后按 sha256 存放
+async fn upload(app_state: State<AppState>, mut multipart: Multipart) -> impl IntoResponse {
+    let Some(public_key) = PUBLIC_KEY.as_ref() else {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            "release public key not configured",
+        )
+            .into_response();
+    };
+    let mut version = String::new();
+    let mut name = String::new();
+    let mut signature = Vec::new();
+    let mut uploaded = None;
+    loop {
+        let field = match multipart.next_field().await {
+            Ok(Some(v)) => v,
+            Ok(None) => break,
+            Err(e) => {
+                log::error!("multipart.next_field err: {}", e);
+                if let Some(v) = uploaded {
+                    remove_tmp(v).await;
+                }
+                return StatusCode::BAD_REQUEST.into_response();
+            }
+        };
+        match field.name() {
+            Some("version") => match field.text().await {
+                Ok(v) => version = v.trim().to_string(),
+                Err(e) => {
+                    log::error!("get field text err: {}", e);
+                    if let Some(v) = uploaded {
+                        remove_tmp(v).await;
+                    }
+                    return StatusCode::BAD_REQUEST.into_response();
+                }
+            },
+            Some("signature") => match field.bytes().await {
+                Ok(v) => signature = v.to_vec(),
+                Err(e) => {
+                    log::error!("get field bytes err: {}", e);
+                    if let Some(v) = uploaded {
+                        remove_tmp(v).await;
+                    }
+                    return StatusCode::BAD_REQUEST.into_response();
+                }
+            },
+            Some("file") if uploaded.is_none() => {
+                name = field.file_name().unwrap_or_default().to_string();
+                match save_tmp(field).await {
+                    Ok(v) => uploaded = Some(v),
+                    Err(e) => {
+                        log::error!("save artifact err: {}", e);
+                        return StatusCode::BAD_REQUEST.into_response();
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    let Some(uploaded) = uploaded else {
+        return (StatusCode::BAD_REQUEST, "file is required").into_response();
+    };
+    if version.is_empty() || name.is_empty() {
+        remove_tmp(uploaded).await;
+        return (StatusCode::BAD_REQUEST, "name and version are required").into_response();
+    }
+    let signature = match verify(public_key, &uploaded.sha256, &signature) {
+        Ok(v) => v,
+        Err(e) => {
+            log::warn!("artifact {} {}", hex(&uploaded.sha256), e);
+            remove_tmp(uploaded).await;
+            return (StatusCode::BAD_REQUEST, e.to_string()).into_response();
+        }
+    };
+
+    let id = hex(&uploaded.sha256);
+    // 内容相同的制品只存一份
+    match tbl_artifact::Entity::find_by_id(&id)
+        .one(&app_state.db_conn)
+        .await
+    {
+        Ok(Some(tbl_artifact)) => {
+            remove_tmp(uploaded).await;
+            return (StatusCode::OK, Json(ArtifactOutputDto::from(tbl_artifact))).into_response();
+        }
+        Ok(None) => {}
+        Err(e) => {
+            log::error!("tbl_artifact find err: {}", e);
+            remove_tmp(uploaded).await;
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    }
+    let path = pub_lib::artifact_path(&id);
+    if let Some(parent) = path.parent()
+        && let Err(e) = fs::create_dir_all(parent).await
+    {
+        log::error!("create {} err: {}", parent.display(), e);
+        remove_tmp(uploaded).await;
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+    if let Err(e) = fs::rename(&uploaded.tmp_path, &path).await {
+        log::error!("rename to {} err: {}", path.display(), e);
+        remove_tmp(uploaded).await;
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+
+    let tbl_artifact_am = tbl_artifact::ActiveModel {
+        id: Set(id.clone()),
+        name: Set(name),
+        version: Set(version),
+        size: Set(uploaded.size),
+        signature: Set(hex(&signature.to_bytes())),
+        ..Default::default()
+    };
+    match tbl_artifact::Entity::insert(tbl_artifact_am)
+        .exec_with_returning(&app_state.db_conn)
+        .await
+    {
+        Ok(tbl_artifact) => {
+            log::info!("artifact {} {} uploaded", tbl_artifact.name, id);
+            (StatusCode::OK, Json(ArtifactOutputDto::from(tbl_artifact))).into_response()
+        }
+        Err(e) => {
+            log::error!("tbl_artifact insert err: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+/// 边接收边计算 sha256，写入制品目录下的临时文件
+async fn save_tmp(mut field: Field<'_>) -> anyhow::Result<Uploaded> {
+    let tmp_dir = PathBuf::from(pub_lib::ARTIFACT_DIR).join("tmp");
+    fs::create_dir_all(&tmp_dir).await?;
+    let tmp_path = tmp_dir.join(uuid::Uuid::new_v4().to_string());
+    let mut file = fs::File::create(&tmp_path).await?;
+    let mut hasher = Sha256::new();
+    let mut size = 0;
+    let r = async {
+        while let Some(chunk) = field.chunk().await? {
+            hasher.update(&chunk);
+            size += chunk.len() as i64;
+            file.write_all(&chunk).await?;
+        }
+        file.sync_all().await?;
+        anyhow::Ok(())
+    }
+    .await;
+    if let Err(e) = r {
+        let _ = fs::remove_file(&tmp_path).await;
+        return Err(e);
+    }
+    Ok(Uploaded {
+        tmp_path,
+        sha256: hasher.finalize().into(),
+        size,
+    })
+}
+
+async fn remove_tmp(uploaded: Uploaded) {
+    if let Err(e) = fs::remove_file(&uploaded.tmp_path).await {
+        log::warn!("remove {} err: {}", uploaded.tmp_path.display(), e);
+    }
+}
+
+/// 校验发布私钥对 sha256 摘要的签名，签名为 64 字节原始格式或其十六进制文本
+fn verify(
+    public_key: &VerifyingKey,
+    digest: &[u8; 32],
+    signature: &[u8],
+) -> anyhow::Result<Signature> {
+    let text = std::str::from_utf8(signature).unwrap_or_default().trim();
+    let signature = if text.len() == 128 {
+        (0..text.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&text[i..i + 2], 16))
+            .collect::<Result<Vec<u8>, _>>()
+            .map_err(|_| anyhow::anyhow!("signature malformed"))?
+    } else {
+        signature.to_vec()
+    };
+    let signature = Signature::from_slice(&signature)
+        .map_err(|_| anyhow::anyhow!("signature missing or malformed"))?;
+    public_key
+        .verify_strict(digest, &signature)
+        .map_err(|_| anyhow::anyhow!("signature verify failed"))?;
+    Ok(signature)
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[derive(Deserialize, Debug, Validate)]
+struct QueryInputDto {
+    name: Option<String>,
+    version: Option<String>,
+    size: u64,
+    page: u64,
+}
+
+/// 制品列表，按上传时间倒序
+async fn query(
+    app_state: State<AppState>,
+    Query(query_input_dto): Query<QueryInputDto>,
+) -> impl IntoResponse {
+    let mut select = tbl_artifact::Entity::find();
+    if let Some(v) = query_input_dto.name.filter(|v| !v.is_empty()) {
+        select = select.filter(tbl_artifact::Column::Name.contains(v));
+    }
+    if let Some(v) = query_input_dto.version.filter(|v| !v.is_empty()) {
+        select = select.filter(tbl_artifact::Column::Version.eq(v));
+    }
+    let paginator = select
+        .order_by_desc(tbl_artifact::Column::CreatedAt)
+        .paginate(&app_state.db_conn, query_input_dto.size);
+    let num_pages = match paginator.num_pages().await {
+        Ok(v) => v,
+        Err(e) => {
+            log::error!("num_pages err: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let num_items = match paginator.num_items().await {
+        Ok(v) => v,
+        Err(e) => {
+            log::error!("num_items err: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let rows = match paginator.fetch_page(query_input_dto.page).await {
+        Ok(v) => v,
+        Err(e) => {
+            log::error!("fetch_page err: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let artifacts: Vec<ArtifactOutputDto> = rows.into_iter().map(Into::into).collect();
+    (
+        StatusCode::OK,
+        Json(json!(
+            {
+            "page":{
+              "size":query_input_dto.size,
+              "total_elements":num_items,
+              "total_pages":num_pages
+            },
+            "_embedded":{
+                "artifact":artifacts
+            }
+           }
+        )),
+    )
+        .into_response()
+}
+
+/// 删除制品，仍有未完成的升级引用时拒绝
+async fn remove(Path(id): Path<String>, State(app_state): State<AppState>) -> impl IntoResponse {
+    if !pub_lib::is_artifact_id(&id) {
+        return StatusCode::BAD_REQUEST.into_response();
+    }
+    match tbl_agent_upgrade::Entity::find()
+        .filter(tbl_agent_upgrade::Column::DownloadRef.eq(&id))
+        .filter(tbl_agent_upgrade::Column::Status.is_in(["pending", "installed"]))
+        .count(&app_state.db_conn)
+        .await
+    {
+        Ok(0) => {}
+        Ok(_) => {
+            return (StatusCode::CONFLICT, "artifact in use by agent upgrade").into_response();
+        }
+        Err(e) => {
+            log::error!("tbl_agent_upgrade count err: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    }
+    match tbl_artifact::Entity::delete_by_id(&id)
+        .exec(&app_state.db_conn)
+        .await
+    {
+        Ok(delete_result) if delete_result.rows_affected == 0 => {
+            return StatusCode::NOT_FOUND.into_response();
+        }
+        Ok(_) => {}
+        Err(e) => {
+            log::error!("delete artifact {id} db err: {e}");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    }
+    let path = pub_lib::artifact_path(&id);
+    if let Err(e) = fs::remove_file(&path).await {
+        log::warn!("remove {} err: {}", path.display(), e);
+    }
+    log::info!("delete artifact {id} success");
+    StatusCode::OK.into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    #[test]
+    fn verify_test() {
+        let signing_key = SigningKey::from_bytes(&[7; 32]);
+        let public_key = signing_key.verifying_key();
+        let digest: [u8; 32] = Sha256::digest(b"artifact").into();
+        let signature = signing_key.sign(&digest).to_bytes();
+        assert!(verify(&public_key, &digest, &signature).is_ok());
+        // 十六进制文本，允许末尾换行
+        let text = format!("{}\n", hex(&signature));
+        assert!(verify(&public_key, &digest, text.as_bytes()).is_ok());
+
+        let tampered: [u8; 32] = Sha256::digest(b"tampered").into();
+        assert!(verify(&public_key, &tampered, &signature).is_err());
+        assert!(verify(&public_key, &digest, &[]).is_err());
+        let other = SigningKey::from_bytes(&[8; 32]).verifying_key();
+        assert!(verify(&other, &digest, &signature).is_err());
+    }
+}
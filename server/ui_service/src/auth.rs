@@ -42,6 +42,106 @@ pub static RESTFUL_APIS: Lazy<Vec<RestfulApi>> = Lazy::new(|| {
             path: "/api/agents/".to_string(),
             name: "Agent删除".to_string(),
         },
+        RestfulApi {
+            method: "PUT".to_string(),
+            path: "/api/agents/".to_string(),
+            name: "Agent分组".to_string(),
+        },
+        RestfulApi {
+            method: "GET".to_string(),
+            path: "/api/agent_profiles".to_string(),
+            name: "Agent配置模板查询".to_string(),
+        },
+        RestfulApi {
+            method: "POST".to_string(),
+            path: "/api/agent_profiles".to_string(),
+            name: "Agent配置模板新增".to_string(),
+        },
+        RestfulApi {
+            method: "PATCH".to_string(),
+            path: "/api/agent_profiles/".to_string(),
+            name: "Agent配置模板修改".to_string(),
+        },
+        RestfulApi {
+            method: "DELETE".to_string(),
+            path: "/api/agent_profiles/".to_string(),
+            name: "Agent配置模板删除".to_string(),
+        },
+        RestfulApi {
+            method: "GET".to_string(),
+            path: "/api/agent_profile_bindings".to_string(),
+            name: "Agent配置模板分配查询".to_string(),
+        },
+        RestfulApi {
+            method: "PUT".to_string(),
+            path: "/api/agent_profile_bindings".to_string(),
+            name: "Agent配置模板分配".to_string(),
+        },
+        RestfulApi {
+            method: "DELETE".to_string(),
+            path: "/api/agent_profile_bindings/".to_string(),
+            name: "Agent配置模板取消分配".to_string(),
+        },
+        RestfulApi {
+            method: "GET".to_string(),
+            path: "/api/agent_conflicts".to_string(),
+            name: "Agent编号冲突查询".to_string(),
+        },
+        RestfulApi {
+            method: "PATCH".to_string(),
+            path: "/api/agent_conflicts/".to_string(),
+            name: "Agent编号冲突处理".to_string(),
+        },
+        RestfulApi {
+            method: "PUT".to_string(),
+            path: "/api/agent_approvals/".to_string(),
+            name: "Agent审批封禁".to_string(),
+        },
+        RestfulApi {
+            method: "GET".to_string(),
+            path: "/api/enrollment_tokens".to_string(),
+            name: "注册令牌查询".to_string(),
+        },
+        RestfulApi {
+            method: "POST".to_string(),
+            path: "/api/enrollment_tokens".to_string(),
+            name: "注册令牌新增".to_string(),
+        },
+        RestfulApi {
+            method: "DELETE".to_string(),
+            path: "/api/enrollment_tokens/".to_string(),
+            name: "注册令牌删除".to_string(),
+        },
+        RestfulApi {
+            method: "GET".to_string(),
+            path: "/api/certs".to_string(),
+            name: "证书查询".to_string(),
+        },
+        RestfulApi {
+            method: "POST".to_string(),
+            path: "/api/agent_upgrades".to_string(),
+            name: "Agent升级".to_string(),
+        },
+        RestfulApi {
+            method: "GET".to_string(),
+            path: "/api/agent_upgrades".to_string(),
+            name: "Agent升级记录查询".to_string(),
+        },
+        RestfulApi {
+            method: "GET".to_string(),
+            path: "/api/artifacts".to_string(),
+            name: "制品查询".to_string(),
+        },
+        RestfulApi {
+            method: "POST".to_string(),
+            path: "/api/artifacts".to_string(),
+            name: "制品上传".to_string(),
+        },
+        RestfulApi {
+            method: "DELETE".to_string(),
+            path: "/api/artifacts/".to_string(),
+            name: "制品删除".to_string(),
+        },
         RestfulApi {
             method: "GET".to_string(),
             path: "/api/roles".to_string(),
@@ -112,6 +212,56 @@ pub static RESTFUL_APIS: Lazy<Vec<RestfulApi>> = Lazy::new(|| {
             path: "/api/hosts/".to_string(),
             name: "主机删除".to_string(),
         },
+        RestfulApi {
+            method: "GET".to_string(),
+            path: "/api/softwares".to_string(),
+            name: "软件查询".to_string(),
+        },
+        RestfulApi {
+            method: "GET".to_string(),
+            path: "/api/listen_ports".to_string(),
+            name: "监听端口查询".to_string(),
+        },
+        RestfulApi {
+            method: "GET".to_string(),
+            path: "/api/accounts".to_string(),
+            name: "账号查询".to_string(),
+        },
+        RestfulApi {
+            method: "GET".to_string(),
+            path: "/api/services".to_string(),
+            name: "服务查询".to_string(),
+        },
+        RestfulApi {
+            method: "POST".to_string(),
+            path: "/api/services/start".to_string(),
+            name: "服务启动".to_string(),
+        },
+        RestfulApi {
+            method: "POST".to_string(),
+            path: "/api/services/stop".to_string(),
+            name: "服务停止".to_string(),
+        },
+        RestfulApi {
+            method: "POST".to_string(),
+            path: "/api/services/restart".to_string(),
+            name: "服务重启".to_string(),
+        },
+        RestfulApi {
+            method: "POST".to_string(),
+            path: "/api/services/enable".to_string(),
+            name: "服务开机启用".to_string(),
+        },
+        RestfulApi {
+            method: "POST".to_string(),
+            path: "/api/services/disable".to_string(),
+            name: "服务开机禁用".to_string(),
+        },
+        RestfulApi {
+            method: "GET".to_string(),
+            path: "/api/service_controls".to_string(),
+            name: "服务控制记录查询".to_string(),
+        },
         RestfulApi {
             method: "GET".to_string(),
             path: "/api/llm_tasks".to_string(),
@@ -127,6 +277,16 @@ pub static RESTFUL_APIS: Lazy<Vec<RestfulApi>> = Lazy::new(|| {
             path: "/api/llm_tasks/".to_string(),
             name: "大语言模型任务删除".to_string(),
         },
+        RestfulApi {
+            method: "DELETE".to_string(),
+            path: "/api/llm_tasks".to_string(),
+            name: "大语言模型任务批量删除".to_string(),
+        },
+        RestfulApi {
+            method: "GET".to_string(),
+            path: "/api/llm_consumers".to_string(),
+            name: "大语言模型消费者查询".to_string(),
+        },
         RestfulApi {
             method: "GET".to_string(),
             path: "/api/restful_apis".to_string(),
@@ -0,0 +1,214 @@
+use crate::{AppState, config::UI_SERVICE_TOML};
+use axum::{Json, Router, http::StatusCode, response::IntoResponse, routing::get};
+use serde::Serialize;
+use serde_json::json;
+use std::{
+    collections::HashSet,
+    fs,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    time::Duration,
+};
+use x509_parser::{
+    extensions::GeneralName,
+    pem::Pem,
+    prelude::{CertificateRevocationList, FromDer, X509Certificate},
+};
+
+pub fn routers(state: AppState) -> Router {
+    Router::new().route("/certs", get(query)).with_state(state)
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CertStatus {
+    Valid,
+    // 剩余有效期少于 warn_days
+    Expiring,
+    Expired,
+    // 序列号在 [cert] crl 中，agent 不检查 CRL，需要重新签发并替换
+    Revoked,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct CertInfo {
+    pub file: String,
+    pub subject: String,
+    pub issuer: String,
+    pub serial: String,
+    pub sans: Vec<String>,
+    pub is_ca: bool,
+    // 毫秒时间戳
+    pub not_before: i64,
+    pub not_after: i64,
+    pub days_left: i64,
+    pub status: CertStatus,
+}
+
+/// 读取 PEM 文件中的全部证书，证书链按文件中的顺序返回
+pub fn read_chain(file: &str, warn_days: i64) -> anyhow::Result<Vec<CertInfo>> {
+    let data = fs::read(file)?;
+    let now = chrono::Utc::now().timestamp();
+    let mut certs = Vec::new();
+    for pem in Pem::iter_from_buffer(&data) {
+        let pem = pem?;
+        let cert = pem.parse_x509()?;
+        let not_after = cert.validity().not_after.timestamp();
+        let (days_left, status) = expiry(not_after, now, warn_days);
+        certs.push(CertInfo {
+            file: file.to_string(),
+            subject: cert.subject().to_string(),
+            issuer: cert.issuer().to_string(),
+            serial: cert
+                .raw_serial()
+                .iter()
+                .map(|b| format!("{b:02x}"))
+                .collect(),
+            sans: sans(&cert),
+            is_ca: cert.is_ca(),
+            not_before: cert.validity().not_before.timestamp() * 1000,
+            not_after: not_after * 1000,
+            days_left,
+            status,
+        });
+    }
+    Ok(certs)
+}
+
+/// 读取 PEM 格式的 CRL，返回已吊销证书的序列号（十六进制）
+pub fn read_crl(file: &str) -> anyhow::Result<HashSet<String>> {
+    let data = fs::read(file)?;
+    let mut serials = HashSet::new();
+    for pem in Pem::iter_from_buffer(&data) {
+        let pem = pem?;
+        let (_, crl) = CertificateRevocationList::from_der(&pem.contents)?;
+        for revoked in crl.iter_revoked_certificates() {
+            serials.insert(
+                revoked
+                    .raw_serial()
+                    .iter()
+                    .map(|b| format!("{b:02x}"))
+                    .collect(),
+            );
+        }
+    }
+    Ok(serials)
+}
+
+/// 按秒级时间戳计算剩余天数（向下取整）和证书状态
+pub fn expiry(not_after: i64, now: i64, warn_days: i64) -> (i64, CertStatus) {
+    let days_left = (not_after - now).div_euclid(86400);
+    let status = if not_after <= now {
+        CertStatus::Expired
+    } else if days_left < warn_days {
+        CertStatus::Expiring
+    } else {
+        CertStatus::Valid
+    };
+    (days_left, status)
+}
+
+fn sans(cert: &X509Certificate<'_>) -> Vec<String> {
+    let Ok(Some(san)) = cert.subject_alternative_name() else {
+        return Vec::new();
+    };
+    san.value
+        .general_names
+        .iter()
+        .filter_map(|v| match v {
+            GeneralName::DNSName(name) => Some(name.to_string()),
+            GeneralName::IPAddress(bytes) => match bytes.len() {
+                4 => <[u8; 4]>::try_from(*bytes)
+                    .ok()
+                    .map(|v| IpAddr::from(Ipv4Addr::from(v)).to_string()),
+                16 => <[u8; 16]>::try_from(*bytes)
+                    .ok()
+                    .map(|v| IpAddr::from(Ipv6Addr::from(v)).to_string()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+/// 配置中所有证书文件，读取失败的文件跳过；出现在 CRL 中的证书标记为已吊销
+fn read_all() -> Vec<CertInfo> {
+    let mut certs = Vec::new();
+    for file in &UI_SERVICE_TOML.cert.files {
+        match read_chain(file, UI_SERVICE_TOML.cert.warn_days) {
+            Ok(v) => certs.extend(v),
+            Err(e) => log::error!("read cert {file} err: {e}"),
+        }
+    }
+    if let Some(file) = &UI_SERVICE_TOML.cert.crl {
+        match read_crl(file) {
+            Ok(revoked) => {
+                for cert in &mut certs {
+                    if revoked.contains(&cert.serial) {
+                        cert.status = CertStatus::Revoked;
+                    }
+                }
+            }
+            Err(e) => log::error!("read crl {file} err: {e}"),
+        }
+    }
+    certs
+}
+
+async fn query() -> impl IntoResponse {
+    (
+        StatusCode::OK,
+        Json(json!({
+            "warn_days": UI_SERVICE_TOML.cert.warn_days,
+            "certs": read_all(),
+        })),
+    )
+        .into_response()
+}
+
+/// 每天检查一次证书有效期，即将过期时打印 warn，已过期或已吊销时打印 error
+pub fn expiry_task() {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(86400));
+        loop {
+            interval.tick().await;
+            for cert in read_all() {
+                match cert.status {
+                    CertStatus::Valid => {}
+                    CertStatus::Expiring => log::warn!(
+                        "cert {} in {} expires in {} days",
+                        cert.subject,
+                        cert.file,
+                        cert.days_left
+                    ),
+                    CertStatus::Expired => {
+                        log::error!("cert {} in {} expired", cert.subject, cert.file)
+                    }
+                    CertStatus::Revoked => {
+                        log::error!("cert {} in {} revoked", cert.subject, cert.file)
+                    }
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expiry_test() {
+        let now = 1_000_000_000;
+        let day = 86400;
+        assert_eq!(expiry(now + 60 * day, now, 30), (60, CertStatus::Valid));
+        assert_eq!(expiry(now + 30 * day, now, 30), (30, CertStatus::Valid));
+        // 不足 30 天按 29 天计
+        assert_eq!(
+            expiry(now + 30 * day - 1, now, 30),
+            (29, CertStatus::Expiring)
+        );
+        assert_eq!(expiry(now + 1, now, 30), (0, CertStatus::Expiring));
+        assert_eq!(expiry(now, now, 30), (0, CertStatus::Expired));
+        assert_eq!(expiry(now - 1, now, 30), (-1, CertStatus::Expired));
+    }
+}
@@ -13,9 +13,87 @@ pub static UI_SERVICE_TOML: Lazy<ServerToml> = Lazy::new(|| {
 #[derive(Debug, Deserialize)]
 pub struct ServerToml {
     pub server: Server,
+    #[serde(default)]
+    pub llm_task: LlmTask,
+    #[serde(default)]
+    pub artifact: Artifact,
+    #[serde(default)]
+    pub cert: Cert,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct Server {
     pub addr: String,
 }
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct LlmTask {
+    // 保留策略的执行间隔，单位秒
+    pub purge_interval: u64,
+    // 删除前归档为 jsonl.gz 的目录，不配置则不归档
+    pub archive_dir: Option<String>,
+    pub retention: Vec<Retention>,
+}
+
+impl Default for LlmTask {
+    fn default() -> Self {
+        Self {
+            purge_interval: 3600,
+            archive_dir: None,
+            retention: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Retention {
+    // 超过天数的任务被清理
+    pub max_age_days: i64,
+    // 只清理该状态的任务：Pending/Processing/Answered/Completed，不配置表示所有状态
+    pub status: Option<String>,
+    // 只清理该模型的任务，不配置表示所有模型
+    pub model: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct Artifact {
+    // ed25519 发布公钥，PEM 格式，用于校验上传时附带的离线签名
+    pub public_key: String,
+    // 上传制品的字节数上限
+    pub max_size: usize,
+}
+
+impl Default for Artifact {
+    fn default() -> Self {
+        Self {
+            public_key: "./config/release.pub".to_string(),
+            max_size: 256 * 1024 * 1024,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct Cert {
+    // 需要检查有效期的证书文件，PEM 格式，可包含证书链
+    pub files: Vec<String>,
+    // 剩余有效天数少于该值时告警
+    pub warn_days: i64,
+    // z11n_server ca revoke 生成的 CRL，其中的证书标记为已吊销，不配置则不检查
+    pub crl: Option<String>,
+}
+
+impl Default for Cert {
+    fn default() -> Self {
+        Self {
+            files: vec![
+                "./config/z11n-ca.crt".to_string(),
+                "./config/sub-ca.crt".to_string(),
+            ],
+            warn_days: 30,
+            crl: Some("./ca/crl.pem".to_string()),
+        }
+    }
+}
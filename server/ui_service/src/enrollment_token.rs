@@ -0,0 +1,188 @@
+use crate::AppState;
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{delete, get},
+};
+use entity::tbl_enrollment_token;
+use rand::{Rng, distributions::Alphanumeric};
+use sea_orm::{ActiveValue::Set, EntityTrait, PaginatorTrait, QueryOrder};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use validator::Validate;
+
+pub fn routers(state: AppState) -> Router {
+    Router::new()
+        .route("/enrollment_tokens", get(query).post(create))
+        .route("/enrollment_tokens/{id}", delete(remove))
+        .with_state(state)
+}
+
+#[derive(Serialize, Debug)]
+struct TokenOutputDto {
+    id: String,
+    name: String,
+    group_name: Option<String>,
+    require_approval: bool,
+    max_uses: i32,
+    uses: i32,
+    expires_at: i64,
+    created_at: i64,
+    // 明文只在创建时返回一次
+    #[serde(skip_serializing_if = "Option::is_none")]
+    token: Option<String>,
+}
+
+impl From<tbl_enrollment_token::Model> for TokenOutputDto {
+    fn from(tbl_enrollment_token: tbl_enrollment_token::Model) -> Self {
+        Self {
+            id: tbl_enrollment_token.id,
+            name: tbl_enrollment_token.name,
+            group_name: tbl_enrollment_token.group_name,
+            require_approval: tbl_enrollment_token.require_approval,
+            max_uses: tbl_enrollment_token.max_uses,
+            uses: tbl_enrollment_token.uses,
+            expires_at: tbl_enrollment_token.expires_at.and_utc().timestamp_millis(),
+            created_at: tbl_enrollment_token.created_at.and_utc().timestamp_millis(),
+            token: None,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Validate)]
+struct QueryInputDto {
+    size: u64,
+    page: u64,
+}
+
+async fn query(
+    app_state: State<AppState>,
+    Query(query_input_dto): Query<QueryInputDto>,
+) -> impl IntoResponse {
+    let paginator = tbl_enrollment_token::Entity::find()
+        .order_by_desc(tbl_enrollment_token::Column::CreatedAt)
+        .paginate(&app_state.db_conn, query_input_dto.size);
+    let num_pages = match paginator.num_pages().await {
+        Ok(v) => v,
+        Err(e) => {
+            log::error!("num_pages err: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let num_items = match paginator.num_items().await {
+        Ok(v) => v,
+        Err(e) => {
+            log::error!("num_items err: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let rows = match paginator.fetch_page(query_input_dto.page).await {
+        Ok(v) => v,
+        Err(e) => {
+            log::error!("fetch_page err: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let tokens: Vec<TokenOutputDto> = rows.into_iter().map(Into::into).collect();
+    (
+        StatusCode::OK,
+        Json(json!(
+            {
+            "page":{
+              "size":query_input_dto.size,
+              "total_elements":num_items,
+              "total_pages":num_pages
+            },
+            "_embedded":{
+                "enrollment_token":tokens
+            }
+           }
+        )),
+    )
+        .into_response()
+}
+
+#[derive(Deserialize, Debug, Validate)]
+struct CreateInputDto {
+    name: String,
+    // 作用范围，注册的 agent 加入该分组
+    group_name: Option<String>,
+    require_approval: bool,
+    max_uses: i32,
+    // 毫秒时间戳
+    expires_at: i64,
+}
+
+/// 创建 enrollment token，只保存 sha256，明文在响应中返回一次
+async fn create(
+    app_state: State<AppState>,
+    Json(create_input_dto): Json<CreateInputDto>,
+) -> impl IntoResponse {
+    if create_input_dto.name.trim().is_empty() {
+        return (StatusCode::BAD_REQUEST, "name is required").into_response();
+    }
+    if create_input_dto.max_uses < 1 {
+        return (StatusCode::BAD_REQUEST, "max_uses must be at least 1").into_response();
+    }
+    let expires_at = match chrono::DateTime::from_timestamp_millis(create_input_dto.expires_at) {
+        Some(v) if v > chrono::Utc::now() => v.naive_utc(),
+        _ => return (StatusCode::BAD_REQUEST, "expires_at must be in the future").into_response(),
+    };
+    let token: String = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(40)
+        .map(char::from)
+        .collect();
+    let tbl_enrollment_token_am = tbl_enrollment_token::ActiveModel {
+        id: Set(uuid::Uuid::new_v4().to_string()),
+        name: Set(create_input_dto.name.trim().to_string()),
+        token_hash: Set(format!("{:x}", Sha256::digest(&token))),
+        group_name: Set(create_input_dto
+            .group_name
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())),
+        require_approval: Set(create_input_dto.require_approval),
+        max_uses: Set(create_input_dto.max_uses),
+        uses: Set(0),
+        expires_at: Set(expires_at),
+        ..Default::default()
+    };
+    match tbl_enrollment_token::Entity::insert(tbl_enrollment_token_am)
+        .exec_with_returning(&app_state.db_conn)
+        .await
+    {
+        Ok(tbl_enrollment_token) => {
+            log::info!("enrollment token {} created", tbl_enrollment_token.name);
+            let mut token_output_dto = TokenOutputDto::from(tbl_enrollment_token);
+            token_output_dto.token = Some(token);
+            (StatusCode::OK, Json(token_output_dto)).into_response()
+        }
+        Err(e) => {
+            log::error!("tbl_enrollment_token insert err: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+/// 删除后该 token 不能再注册新 agent，已注册的 agent 不受影响
+async fn remove(Path(id): Path<String>, State(app_state): State<AppState>) -> impl IntoResponse {
+    match tbl_enrollment_token::Entity::delete_by_id(&id)
+        .exec(&app_state.db_conn)
+        .await
+    {
+        Ok(delete_result) if delete_result.rows_affected == 0 => {
+            StatusCode::NOT_FOUND.into_response()
+        }
+        Ok(_) => {
+            log::info!("delete enrollment token {id} success");
+            StatusCode::OK.into_response()
+        }
+        Err(e) => {
+            log::error!("delete enrollment token {id} db err: {e}");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use crate::{
     AppState,
-    z11n::{HeartbeatRsp, HostReq, UploadHost, heartbeat_rsp::Task, upload_host::InfoType},
+    z11n::{
+        HeartbeatRsp, HostReq, ProcessInfo, UploadHost, heartbeat_rsp::Task, upload_host::InfoType,
+    },
 };
 use axum::{
     Json, Router,
@@ -9,17 +11,20 @@ use axum::{
     response::IntoResponse,
     routing::get,
 };
-use entity::tbl_host;
+use entity::{tbl_host, tbl_host_change};
 use prost::Message;
 use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::collections::{HashMap, HashSet};
 use validator::Validate;
 
 pub fn routers(state: AppState) -> Router {
     Router::new()
         .route("/hosts", get(query).post(upload))
         .route("/hosts/{id}", get(detail).delete(delete))
+        .route("/hosts/{id}/process_tree", get(process_tree))
+        .route("/hosts/{id}/changes", get(changes))
         .with_state(state)
 }
 
@@ -106,37 +111,216 @@ async fn query(
         .into_response()
 }
 
-async fn detail(Path(id): Path<String>, State(app_state): State<AppState>) -> impl IntoResponse {
-    match tbl_host::Entity::find_by_id(&id)
+#[derive(Deserialize, Debug)]
+struct ProcessSortDto {
+    // 进程排序字段：pid、name、user、start_time、cpu_usage、memory、open_files
+    sort: Option<String>,
+    // asc 或 desc，默认 desc
+    order: Option<String>,
+}
+
+impl ProcessSortDto {
+    fn sort(&self, processes: &mut [ProcessInfo]) -> Result<(), String> {
+        let Some(sort) = &self.sort else {
+            return Ok(());
+        };
+        match sort.as_str() {
+            "pid" => processes.sort_by_key(|v| v.pid),
+            "name" => processes.sort_by(|a, b| a.name.cmp(&b.name)),
+            "user" => processes.sort_by(|a, b| a.user.cmp(&b.user)),
+            "start_time" => processes.sort_by_key(|v| v.start_time),
+            "cpu_usage" => processes.sort_by(|a, b| a.cpu_usage.total_cmp(&b.cpu_usage)),
+            "memory" => processes.sort_by_key(|v| v.memory),
+            "open_files" => processes.sort_by_key(|v| v.open_files),
+            _ => return Err(format!("unsupported sort: {sort}")),
+        }
+        match self.order.as_deref() {
+            Some("asc") => {}
+            None | Some("desc") => processes.reverse(),
+            Some(order) => return Err(format!("unsupported order: {order}")),
+        }
+        Ok(())
+    }
+}
+
+async fn find_host_req(app_state: &AppState, id: &str) -> Result<HostReq, StatusCode> {
+    match tbl_host::Entity::find_by_id(id)
         .one(&app_state.db_conn)
         .await
     {
-        Ok(tbl_host_op) => match tbl_host_op {
-            Some(tbl_host) => match HostReq::decode(&*tbl_host.content) {
-                Ok(host_req) => {
-                    let json = match serde_json::to_value(host_req) {
-                        Ok(v) => v,
-                        Err(e) => {
-                            log::error!("host content to json err: {}", e);
-                            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
-                        }
-                    };
-                    (StatusCode::OK, Json(json)).into_response()
-                }
-                Err(e) => {
-                    log::error!("HostReq decode err: {}", e);
-                    StatusCode::INTERNAL_SERVER_ERROR.into_response()
-                }
-            },
-            None => StatusCode::BAD_REQUEST.into_response(),
-        },
+        Ok(Some(tbl_host)) => HostReq::decode(&*tbl_host.content).map_err(|e| {
+            log::error!("HostReq decode err: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        }),
+        Ok(None) => Err(StatusCode::BAD_REQUEST),
         Err(e) => {
             log::error!("find agent {} db err: {}", id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+async fn detail(
+    Path(id): Path<String>,
+    State(app_state): State<AppState>,
+    Query(process_sort_dto): Query<ProcessSortDto>,
+) -> impl IntoResponse {
+    let mut host_req = match find_host_req(&app_state, &id).await {
+        Ok(v) => v,
+        Err(status_code) => return status_code.into_response(),
+    };
+    if let Some(system) = host_req.system.as_mut() {
+        let r = process_sort_dto.sort(&mut system.processes);
+        if let Err(e) = r {
+            return (StatusCode::BAD_REQUEST, e).into_response();
+        }
+    }
+    match serde_json::to_value(host_req) {
+        Ok(json) => (StatusCode::OK, Json(json)).into_response(),
+        Err(e) => {
+            log::error!("host content to json err: {}", e);
             StatusCode::INTERNAL_SERVER_ERROR.into_response()
         }
     }
 }
 
+/// 按 ppid 组织进程树，父进程不在列表中的作为根节点，同级节点按排序参数排序
+async fn process_tree(
+    Path(id): Path<String>,
+    State(app_state): State<AppState>,
+    Query(process_sort_dto): Query<ProcessSortDto>,
+) -> impl IntoResponse {
+    let host_req = match find_host_req(&app_state, &id).await {
+        Ok(v) => v,
+        Err(status_code) => return status_code.into_response(),
+    };
+    let mut processes = host_req.system.map(|v| v.processes).unwrap_or_default();
+    if let Err(e) = process_sort_dto.sort(&mut processes) {
+        return (StatusCode::BAD_REQUEST, e).into_response();
+    }
+    let pids: HashSet<u32> = processes.iter().map(|v| v.pid).collect();
+    let mut children: HashMap<u32, Vec<&ProcessInfo>> = HashMap::new();
+    let mut roots = Vec::new();
+    for process in &processes {
+        match process.ppid {
+            Some(ppid) if ppid != process.pid && pids.contains(&ppid) => {
+                children.entry(ppid).or_default().push(process)
+            }
+            _ => roots.push(process),
+        }
+    }
+    let mut visited = HashSet::new();
+    let tree: Vec<serde_json::Value> = roots
+        .into_iter()
+        .filter_map(|v| process_node(v, &children, &mut visited))
+        .collect();
+    (StatusCode::OK, Json(json!({ "processes": tree }))).into_response()
+}
+
+fn process_node(
+    process: &ProcessInfo,
+    children: &HashMap<u32, Vec<&ProcessInfo>>,
+    visited: &mut HashSet<u32>,
+) -> Option<serde_json::Value> {
+    if !visited.insert(process.pid) {
+        return None;
+    }
+    let mut node = serde_json::to_value(process).ok()?;
+    let child_nodes: Vec<serde_json::Value> = children
+        .get(&process.pid)
+        .map(|v| {
+            v.iter()
+                .filter_map(|child| process_node(child, children, visited))
+                .collect()
+        })
+        .unwrap_or_default();
+    if !child_nodes.is_empty() {
+        node["children"] = json!(child_nodes);
+    }
+    Some(node)
+}
+
+#[derive(Deserialize, Debug, Validate)]
+struct ChangeQueryInputDto {
+    info_type: Option<String>,
+    size: u64,
+    page: u64,
+}
+
+#[derive(Serialize, Debug)]
+struct ChangeQueryOutputDto {
+    version: i64,
+    info_type: String,
+    item: String,
+    change: String,
+    created_at: i64,
+}
+
+/// 主机信息变化记录，按时间倒序
+async fn changes(
+    Path(id): Path<String>,
+    State(app_state): State<AppState>,
+    Query(query_input_dto): Query<ChangeQueryInputDto>,
+) -> impl IntoResponse {
+    let mut select =
+        tbl_host_change::Entity::find().filter(tbl_host_change::Column::AgentId.eq(id));
+    if let Some(v) = query_input_dto.info_type.filter(|v| !v.is_empty()) {
+        select = select.filter(tbl_host_change::Column::InfoType.eq(v));
+    }
+    let paginator = select
+        .order_by_desc(tbl_host_change::Column::CreatedAt)
+        .order_by_asc(tbl_host_change::Column::InfoType)
+        .order_by_asc(tbl_host_change::Column::Item)
+        .paginate(&app_state.db_conn, query_input_dto.size);
+    let num_pages = match paginator.num_pages().await {
+        Ok(v) => v,
+        Err(e) => {
+            log::error!("num_pages err: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let num_items = match paginator.num_items().await {
+        Ok(v) => v,
+        Err(e) => {
+            log::error!("num_items err: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let rows = match paginator.fetch_page(query_input_dto.page).await {
+        Ok(v) => v,
+        Err(e) => {
+            log::error!("fetch_page err: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let mut host_changes = Vec::new();
+    for tbl_host_change in rows {
+        host_changes.push(ChangeQueryOutputDto {
+            version: tbl_host_change.version,
+            info_type: tbl_host_change.info_type,
+            item: tbl_host_change.item,
+            change: tbl_host_change.change,
+            created_at: tbl_host_change.created_at.and_utc().timestamp_millis(),
+        });
+    }
+    (
+        StatusCode::OK,
+        Json(json!(
+            {
+            "page":{
+              "size":query_input_dto.size,
+              "total_elements":num_items,
+              "total_pages":num_pages
+            },
+            "_embedded":{
+                "host_change":host_changes
+            }
+           }
+        )),
+    )
+        .into_response()
+}
+
 async fn delete(Path(id): Path<String>, State(app_state): State<AppState>) -> impl IntoResponse {
     match tbl_host::Entity::delete_by_id(&id)
         .exec(&app_state.db_conn)
@@ -205,6 +389,78 @@ async fn upload(
         log::error!("tx_heartbeat_rsp.send err: {}", e);
         return StatusCode::INTERNAL_SERVER_ERROR.into_response();
     }
+    let heartbeat_rsp = HeartbeatRsp {
+        task: Some(Task::UploadHost(UploadHost {
+            info_type: InfoType::Software.into(),
+        })),
+    };
+    if let Err(e) = app_state
+        .tx_heartbeat_rsp
+        .send((upload_input_dto.agent_id.clone(), heartbeat_rsp))
+    {
+        log::error!("tx_heartbeat_rsp.send err: {}", e);
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+    let heartbeat_rsp = HeartbeatRsp {
+        task: Some(Task::UploadHost(UploadHost {
+            info_type: InfoType::Socket.into(),
+        })),
+    };
+    if let Err(e) = app_state
+        .tx_heartbeat_rsp
+        .send((upload_input_dto.agent_id.clone(), heartbeat_rsp))
+    {
+        log::error!("tx_heartbeat_rsp.send err: {}", e);
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+    let heartbeat_rsp = HeartbeatRsp {
+        task: Some(Task::UploadHost(UploadHost {
+            info_type: InfoType::Hardware.into(),
+        })),
+    };
+    if let Err(e) = app_state
+        .tx_heartbeat_rsp
+        .send((upload_input_dto.agent_id.clone(), heartbeat_rsp))
+    {
+        log::error!("tx_heartbeat_rsp.send err: {}", e);
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+    let heartbeat_rsp = HeartbeatRsp {
+        task: Some(Task::UploadHost(UploadHost {
+            info_type: InfoType::Account.into(),
+        })),
+    };
+    if let Err(e) = app_state
+        .tx_heartbeat_rsp
+        .send((upload_input_dto.agent_id.clone(), heartbeat_rsp))
+    {
+        log::error!("tx_heartbeat_rsp.send err: {}", e);
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+    let heartbeat_rsp = HeartbeatRsp {
+        task: Some(Task::UploadHost(UploadHost {
+            info_type: InfoType::Service.into(),
+        })),
+    };
+    if let Err(e) = app_state
+        .tx_heartbeat_rsp
+        .send((upload_input_dto.agent_id.clone(), heartbeat_rsp))
+    {
+        log::error!("tx_heartbeat_rsp.send err: {}", e);
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+    let heartbeat_rsp = HeartbeatRsp {
+        task: Some(Task::UploadHost(UploadHost {
+            info_type: InfoType::ScheduledTask.into(),
+        })),
+    };
+    if let Err(e) = app_state
+        .tx_heartbeat_rsp
+        .send((upload_input_dto.agent_id.clone(), heartbeat_rsp))
+    {
+        log::error!("tx_heartbeat_rsp.send err: {}", e);
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
     // }
     StatusCode::OK.into_response()
 }
@@ -0,0 +1,304 @@
+use crate::{AppState, z11n::HostReq};
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::get,
+};
+use entity::tbl_host_snapshot;
+use prost::Message;
+use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+use std::collections::BTreeMap;
+use validator::Validate;
+
+pub fn routers(state: AppState) -> Router {
+    Router::new()
+        .route("/hosts/{id}/snapshots", get(query))
+        .route("/hosts/{id}/snapshots/diff", get(diff))
+        .route("/hosts/{id}/snapshots/{snapshot_id}", get(detail))
+        .with_state(state)
+}
+
+#[derive(Deserialize, Debug, Validate)]
+struct QueryInputDto {
+    // 毫秒时间戳，只返回此时间之前的快照
+    before: Option<i64>,
+    size: u64,
+    page: u64,
+}
+
+#[derive(Serialize, Debug)]
+struct QueryOutputDto {
+    id: String,
+    version: i64,
+    hash: String,
+    size: usize,
+    created_at: i64,
+}
+
+/// 主机信息快照列表，按时间倒序；before 配合 size=1 可查询某一时刻的主机信息
+async fn query(
+    Path(id): Path<String>,
+    State(app_state): State<AppState>,
+    Query(query_input_dto): Query<QueryInputDto>,
+) -> impl IntoResponse {
+    let mut select =
+        tbl_host_snapshot::Entity::find().filter(tbl_host_snapshot::Column::AgentId.eq(id));
+    if let Some(v) = query_input_dto.before {
+        let Some(before) = chrono::DateTime::from_timestamp_millis(v) else {
+            return (StatusCode::BAD_REQUEST, "invalid before").into_response();
+        };
+        select = select.filter(tbl_host_snapshot::Column::CreatedAt.lte(before.naive_utc()));
+    }
+    let paginator = select
+        .order_by_desc(tbl_host_snapshot::Column::CreatedAt)
+        .order_by_desc(tbl_host_snapshot::Column::Version)
+        .paginate(&app_state.db_conn, query_input_dto.size);
+    let num_pages = match paginator.num_pages().await {
+        Ok(v) => v,
+        Err(e) => {
+            log::error!("num_pages err: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let num_items = match paginator.num_items().await {
+        Ok(v) => v,
+        Err(e) => {
+            log::error!("num_items err: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let rows = match paginator.fetch_page(query_input_dto.page).await {
+        Ok(v) => v,
+        Err(e) => {
+            log::error!("fetch_page err: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let mut snapshots = Vec::new();
+    for tbl_host_snapshot in rows {
+        snapshots.push(QueryOutputDto {
+            id: tbl_host_snapshot.id,
+            version: tbl_host_snapshot.version,
+            hash: tbl_host_snapshot.hash,
+            size: tbl_host_snapshot.content.len(),
+            created_at: tbl_host_snapshot.created_at.and_utc().timestamp_millis(),
+        });
+    }
+    (
+        StatusCode::OK,
+        Json(json!(
+            {
+            "page":{
+              "size":query_input_dto.size,
+              "total_elements":num_items,
+              "total_pages":num_pages
+            },
+            "_embedded":{
+                "host_snapshot":snapshots
+            }
+           }
+        )),
+    )
+        .into_response()
+}
+
+async fn find_snapshot(
+    app_state: &AppState,
+    id: &str,
+    snapshot_id: &str,
+) -> Result<(tbl_host_snapshot::Model, HostReq), StatusCode> {
+    match tbl_host_snapshot::Entity::find_by_id(snapshot_id)
+        .filter(tbl_host_snapshot::Column::AgentId.eq(id))
+        .one(&app_state.db_conn)
+        .await
+    {
+        Ok(Some(tbl_host_snapshot)) => match HostReq::decode(&*tbl_host_snapshot.content) {
+            Ok(host_req) => Ok((tbl_host_snapshot, host_req)),
+            Err(e) => {
+                log::error!("HostReq decode err: {}", e);
+                Err(StatusCode::INTERNAL_SERVER_ERROR)
+            }
+        },
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            log::error!("find snapshot {} db err: {}", snapshot_id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+async fn detail(
+    Path((id, snapshot_id)): Path<(String, String)>,
+    State(app_state): State<AppState>,
+) -> impl IntoResponse {
+    let (_, host_req) = match find_snapshot(&app_state, &id, &snapshot_id).await {
+        Ok(v) => v,
+        Err(status_code) => return status_code.into_response(),
+    };
+    match serde_json::to_value(host_req) {
+        Ok(json) => (StatusCode::OK, Json(json)).into_response(),
+        Err(e) => {
+            log::error!("snapshot content to json err: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Validate)]
+struct DiffInputDto {
+    from: String,
+    to: String,
+}
+
+/// 比较两个快照的系统字段、进程、磁盘和网卡
+async fn diff(
+    Path(id): Path<String>,
+    State(app_state): State<AppState>,
+    Query(diff_input_dto): Query<DiffInputDto>,
+) -> impl IntoResponse {
+    let (from, old) = match find_snapshot(&app_state, &id, &diff_input_dto.from).await {
+        Ok(v) => v,
+        Err(status_code) => return status_code.into_response(),
+    };
+    let (to, new) = match find_snapshot(&app_state, &id, &diff_input_dto.to).await {
+        Ok(v) => v,
+        Err(status_code) => return status_code.into_response(),
+    };
+    let mut old_system = old.system.unwrap_or_default();
+    let mut new_system = new.system.unwrap_or_default();
+    let old_processes = std::mem::take(&mut old_system.processes);
+    let new_processes = std::mem::take(&mut new_system.processes);
+    let snapshot = |v: &tbl_host_snapshot::Model| {
+        json!({
+            "id": v.id,
+            "version": v.version,
+            "created_at": v.created_at.and_utc().timestamp_millis(),
+        })
+    };
+    (
+        StatusCode::OK,
+        Json(json!({
+            "from": snapshot(&from),
+            "to": snapshot(&to),
+            "system": diff_fields(&to_value(&old_system), &to_value(&new_system)),
+            // pid 可能被复用，与启动时间一起区分进程
+            "processes": diff_items(
+                old_processes.iter().map(|v| (format!("{}:{}", v.pid, v.start_time), to_value(v))),
+                new_processes.iter().map(|v| (format!("{}:{}", v.pid, v.start_time), to_value(v))),
+            ),
+            "disks": diff_items(
+                old.disks.iter().map(|v| (v.mount_point.clone(), to_value(v))),
+                new.disks.iter().map(|v| (v.mount_point.clone(), to_value(v))),
+            ),
+            "networks": diff_items(
+                old.networks.iter().map(|v| (v.interface_name.clone(), to_value(v))),
+                new.networks.iter().map(|v| (v.interface_name.clone(), to_value(v))),
+            ),
+        })),
+    )
+        .into_response()
+}
+
+fn to_value<T: Serialize>(v: &T) -> Value {
+    serde_json::to_value(v).unwrap_or(Value::Null)
+}
+
+/// 逐个字段比较两个对象，返回变化的字段
+fn diff_fields(old: &Value, new: &Value) -> Vec<Value> {
+    let empty = Map::new();
+    let old = old.as_object().unwrap_or(&empty);
+    let new = new.as_object().unwrap_or(&empty);
+    let mut fields: Vec<&String> = old.keys().chain(new.keys()).collect();
+    fields.sort();
+    fields.dedup();
+    fields
+        .into_iter()
+        .filter_map(|field| {
+            let old_value = old.get(field).unwrap_or(&Value::Null);
+            let new_value = new.get(field).unwrap_or(&Value::Null);
+            (old_value != new_value)
+                .then(|| json!({ "field": field, "old": old_value, "new": new_value }))
+        })
+        .collect()
+}
+
+/// 按主键比较两组条目，返回新增、删除的条目和变化条目的字段
+fn diff_items(
+    old: impl Iterator<Item = (String, Value)>,
+    new: impl Iterator<Item = (String, Value)>,
+) -> Value {
+    let old: BTreeMap<String, Value> = old.collect();
+    let mut new: BTreeMap<String, Value> = new.collect();
+    let mut removed = Vec::new();
+    let mut changed = Vec::new();
+    for (key, old_value) in old {
+        match new.remove(&key) {
+            Some(new_value) => {
+                let fields = diff_fields(&old_value, &new_value);
+                if !fields.is_empty() {
+                    changed.push(json!({ "key": key, "fields": fields }));
+                }
+            }
+            None => removed.push(old_value),
+        }
+    }
+    let added: Vec<Value> = new.into_values().collect();
+    json!({ "added": added, "removed": removed, "changed": changed })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::z11n::{DiskInfo, SystemInfo};
+
+    fn disk(mount_point: &str, available_space: u64) -> DiskInfo {
+        DiskInfo {
+            name: "sda1".to_string(),
+            mount_point: mount_point.to_string(),
+            total_space: 100,
+            available_space,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn snapshot_round_trip_test() -> anyhow::Result<()> {
+        let host_req = HostReq {
+            system: Some(SystemInfo {
+                host_name: Some("vm".to_string()),
+                cpu_arch: "x86_64".to_string(),
+                ..Default::default()
+            }),
+            disks: vec![disk("/", 50), disk("/data", 10)],
+            ..Default::default()
+        };
+        let content = host_req.encode_to_vec();
+        // 相同内容的编码必须一致，保存时按编码的哈希去重
+        assert_eq!(content, host_req.clone().encode_to_vec());
+        assert_eq!(HostReq::decode(&*content)?, host_req);
+        Ok(())
+    }
+
+    #[test]
+    fn diff_items_test() {
+        let old = [disk("/", 50), disk("/boot", 5)];
+        let new = [disk("/", 40), disk("/data", 10)];
+        let r = diff_items(
+            old.iter().map(|v| (v.mount_point.clone(), to_value(v))),
+            new.iter().map(|v| (v.mount_point.clone(), to_value(v))),
+        );
+        assert_eq!(r["added"][0]["mount_point"], "/data");
+        assert_eq!(r["removed"][0]["mount_point"], "/boot");
+        assert_eq!(
+            r["changed"],
+            json!([{
+                "key": "/",
+                "fields": [{ "field": "available_space", "old": 50, "new": 40 }],
+            }])
+        );
+    }
+}
@@ -4,13 +4,27 @@ use tokio::sync::broadcast;
 
 use crate::{auth::CaptchaEntry, z11n::HeartbeatRsp};
 
+pub mod account;
 pub mod agent;
+pub mod agent_conflict;
+pub mod agent_profile;
+pub mod agent_upgrade;
+pub mod artifact;
 pub mod auth;
+pub mod cert;
 pub mod config;
+pub mod enrollment_token;
 pub mod host;
+pub mod host_snapshot;
+pub mod listen_port;
+pub mod llm_consumer;
 pub mod llm_task;
+pub mod llm_task_retention;
+pub mod metrics;
 pub mod role;
 pub mod server;
+pub mod service;
+pub mod software;
 pub mod system;
 pub mod uds;
 pub mod user;
@@ -0,0 +1,116 @@
+use axum::{
+    Json, Router,
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::get,
+};
+use entity::{tbl_host, tbl_host_listen_port};
+use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use validator::Validate;
+
+use crate::AppState;
+
+pub fn routers(state: AppState) -> Router {
+    Router::new()
+        .route("/listen_ports", get(query))
+        .with_state(state)
+}
+
+#[derive(Deserialize, Debug, Validate)]
+struct QueryInputDto {
+    port: Option<i32>,
+    protocol: Option<String>,
+    process_name: Option<String>,
+    agent_id: Option<String>,
+    size: u64,
+    page: u64,
+}
+
+#[derive(Serialize, Debug)]
+struct QueryOutputDto {
+    agent_id: String,
+    host_name: Option<String>,
+    protocol: String,
+    addr: String,
+    port: i32,
+    pid: Option<i32>,
+    process_name: Option<String>,
+}
+
+/// 按端口查找正在监听该端口的主机
+async fn query(
+    app_state: State<AppState>,
+    Query(query_input_dto): Query<QueryInputDto>,
+) -> impl IntoResponse {
+    let mut select = tbl_host_listen_port::Entity::find().find_also_related(tbl_host::Entity);
+    if let Some(v) = query_input_dto.port {
+        select = select.filter(tbl_host_listen_port::Column::Port.eq(v));
+    }
+    if let Some(v) = query_input_dto.protocol.filter(|v| !v.is_empty()) {
+        select = select.filter(tbl_host_listen_port::Column::Protocol.eq(v));
+    }
+    if let Some(v) = query_input_dto.process_name.filter(|v| !v.is_empty()) {
+        let like_pattern = format!("%{v}%");
+        select = select.filter(tbl_host_listen_port::Column::ProcessName.like(like_pattern));
+    }
+    if let Some(v) = query_input_dto.agent_id.filter(|v| !v.is_empty()) {
+        select = select.filter(tbl_host_listen_port::Column::AgentId.eq(v));
+    }
+
+    let paginator = select
+        .order_by_asc(tbl_host_listen_port::Column::Port)
+        .order_by_asc(tbl_host_listen_port::Column::AgentId)
+        .paginate(&app_state.db_conn, query_input_dto.size);
+    let num_pages = match paginator.num_pages().await {
+        Ok(v) => v,
+        Err(e) => {
+            log::error!("num_pages err: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let num_items = match paginator.num_items().await {
+        Ok(v) => v,
+        Err(e) => {
+            log::error!("num_items err: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let rows = match paginator.fetch_page(query_input_dto.page).await {
+        Ok(v) => v,
+        Err(e) => {
+            log::error!("fetch_page err: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let mut listen_ports = Vec::new();
+    for (tbl_host_listen_port, tbl_host) in rows {
+        listen_ports.push(QueryOutputDto {
+            agent_id: tbl_host_listen_port.agent_id,
+            host_name: tbl_host.and_then(|v| v.host_name),
+            protocol: tbl_host_listen_port.protocol,
+            addr: tbl_host_listen_port.addr,
+            port: tbl_host_listen_port.port,
+            pid: tbl_host_listen_port.pid,
+            process_name: tbl_host_listen_port.process_name,
+        });
+    }
+    (
+        StatusCode::OK,
+        Json(json!(
+            {
+            "page":{
+              "size":query_input_dto.size,
+              "total_elements":num_items,
+              "total_pages":num_pages
+            },
+            "_embedded":{
+                "listen_port":listen_ports
+            }
+           }
+        )),
+    )
+        .into_response()
+}
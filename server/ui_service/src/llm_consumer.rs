@@ -0,0 +1,116 @@
+use axum::{
+    Json, Router,
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::get,
+};
+use entity::{tbl_agent, tbl_llm_consumer};
+use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use validator::Validate;
+
+use crate::AppState;
+
+pub fn routers(state: AppState) -> Router {
+    Router::new()
+        .route("/llm_consumers", get(query))
+        .with_state(state)
+}
+
+#[derive(Deserialize, Debug, Validate)]
+struct QueryInputDto {
+    agent_id: Option<String>,
+    size: u64,
+    page: u64,
+}
+
+#[derive(Serialize, Debug)]
+struct QueryOutputDto {
+    agent_id: String,
+    agent_state: Option<String>,
+    models: Vec<String>,
+    loaded_models: Vec<String>,
+    total_slots: i32,
+    free_slots: i32,
+    tokens_per_second: f64,
+    backend_reachable: bool,
+    updated_at: i64,
+}
+
+async fn query(
+    app_state: State<AppState>,
+    Query(query_input_dto): Query<QueryInputDto>,
+) -> impl IntoResponse {
+    let mut select = tbl_llm_consumer::Entity::find().find_also_related(tbl_agent::Entity);
+    if let Some(agent_id) = query_input_dto.agent_id.filter(|v| !v.is_empty()) {
+        let like_pattern = format!("%{agent_id}%");
+        select = select.filter(tbl_llm_consumer::Column::AgentId.like(like_pattern));
+    }
+
+    let paginator = select
+        .order_by_desc(tbl_llm_consumer::Column::UpdatedAt)
+        .paginate(&app_state.db_conn, query_input_dto.size);
+    let num_pages = match paginator.num_pages().await {
+        Ok(v) => v,
+        Err(e) => {
+            log::error!("num_pages err: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let num_items = match paginator.num_items().await {
+        Ok(v) => v,
+        Err(e) => {
+            log::error!("num_items err: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let rows = match paginator.fetch_page(query_input_dto.page).await {
+        Ok(v) => v,
+        Err(e) => {
+            log::error!("fetch_page err: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let mut llm_consumers = Vec::new();
+    for (tbl_llm_consumer, tbl_agent) in rows {
+        llm_consumers.push(QueryOutputDto {
+            agent_id: tbl_llm_consumer.agent_id,
+            agent_state: tbl_agent.map(|v| v.state),
+            models: decode_models(&tbl_llm_consumer.models),
+            loaded_models: decode_models(&tbl_llm_consumer.loaded_models),
+            total_slots: tbl_llm_consumer.total_slots,
+            free_slots: tbl_llm_consumer.free_slots,
+            tokens_per_second: tbl_llm_consumer.tokens_per_second,
+            backend_reachable: tbl_llm_consumer.backend_reachable,
+            updated_at: tbl_llm_consumer.updated_at.and_utc().timestamp_millis(),
+        });
+    }
+    (
+        StatusCode::OK,
+        Json(json!(
+            {
+            "page":{
+              "size":query_input_dto.size,
+              "total_elements":num_items,
+              "total_pages":num_pages
+            },
+            "_embedded":{
+                "llm_consumer":llm_consumers
+            }
+           }
+        )),
+    )
+        .into_response()
+}
+
+fn decode_models(encoded: &[u8]) -> Vec<String> {
+    match bincode::decode_from_slice::<Vec<String>, _>(encoded, bincode::config::standard()) {
+        Ok((v, _len)) => v,
+        Err(e) => {
+            log::error!("bincode::decode_from_slice err: {}", e);
+            Vec::new()
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use std::{io::Cursor, str::FromStr};
+
 use axum::{
     Json, Router,
     extract::{Path, Query, State},
@@ -5,17 +7,25 @@ use axum::{
     response::IntoResponse,
     routing::get,
 };
-use entity::tbl_llm_task;
+use base64::{Engine, prelude::BASE64_STANDARD};
+use chrono::DateTime;
+use entity::{tbl_llm_task, tbl_llm_task_attachment};
+use image::ImageFormat;
+use pub_lib::LlmTaskStatus;
 use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use validator::Validate;
 
-use crate::AppState;
+use crate::{
+    AppState,
+    config::UI_SERVICE_TOML,
+    llm_task_retention::{self, PurgeFilter},
+};
 
 pub fn routers(state: AppState) -> Router {
     Router::new()
-        .route("/llm_tasks", get(query))
+        .route("/llm_tasks", get(query).delete(purge))
         .route("/llm_tasks/{id}", get(detail).delete(delete))
         .with_state(state)
 }
@@ -33,6 +43,7 @@ struct QueryInputDto {
 #[derive(Serialize, Debug)]
 struct QueryOutputDto {
     id: String,
+    kind: String,
     model: String,
     prompt: String,
     req_content: String,
@@ -108,6 +119,7 @@ async fn query(
             .map(|v| v.and_utc().timestamp_millis());
         llm_tasks.push(QueryOutputDto {
             id: tbl_llm_task.id,
+            kind: tbl_llm_task.kind,
             model: tbl_llm_task.model,
             prompt: tbl_llm_task.prompt,
             req_content: tbl_llm_task.req_content,
@@ -152,16 +164,73 @@ async fn detail(Path(id): Path<String>, State(app_state): State<AppState>) -> im
                 let rsp_pull_at = tbl_llm_task
                     .rsp_pull_at
                     .map(|v| v.and_utc().timestamp_millis());
+                let req_inputs = match &tbl_llm_task.req_inputs {
+                    Some(encoded) => match bincode::decode_from_slice::<Vec<String>, _>(
+                        &encoded[..],
+                        bincode::config::standard(),
+                    ) {
+                        Ok((v, _len)) => Some(v),
+                        Err(e) => {
+                            log::error!("bincode::decode_from_slice err: {}", e);
+                            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+                        }
+                    },
+                    None => None,
+                };
+                let rsp_embeddings = match &tbl_llm_task.rsp_embeddings {
+                    Some(encoded) => match bincode::decode_from_slice::<Vec<Vec<f32>>, _>(
+                        &encoded[..],
+                        bincode::config::standard(),
+                    ) {
+                        Ok((v, _len)) => Some(v),
+                        Err(e) => {
+                            log::error!("bincode::decode_from_slice err: {}", e);
+                            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+                        }
+                    },
+                    None => None,
+                };
+                let attachments = match tbl_llm_task_attachment::Entity::find()
+                    .filter(tbl_llm_task_attachment::Column::LlmTaskId.eq(&tbl_llm_task.id))
+                    .order_by_asc(tbl_llm_task_attachment::Column::CreatedAt)
+                    .all(&app_state.db_conn)
+                    .await
+                {
+                    Ok(v) => v,
+                    Err(e) => {
+                        log::error!("find llm_task {} attachments db err: {}", id, e);
+                        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+                    }
+                };
+                // 图片解码和缩放占用 cpu，放到阻塞线程池中执行
+                let attachment_dtos = match tokio::task::spawn_blocking(move || {
+                    attachments
+                        .into_iter()
+                        .map(attachment_output_dto)
+                        .collect::<Vec<_>>()
+                })
+                .await
+                {
+                    Ok(v) => v,
+                    Err(e) => {
+                        log::error!("llm_task {} thumbnail join err: {}", id, e);
+                        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+                    }
+                };
                 (
                     StatusCode::OK,
                     Json(json!({
                         "id":tbl_llm_task.id,
+                        "kind":tbl_llm_task.kind,
                         "model":tbl_llm_task.model,
                         "prompt":tbl_llm_task.prompt,
                         "req_content":tbl_llm_task.req_content,
+                        "req_inputs":req_inputs,
+                        "attachments":attachment_dtos,
                         "req_push_at":tbl_llm_task.req_push_at,
                         "req_pull_at":req_pull_at,
                         "rsp_content":tbl_llm_task.rsp_content,
+                        "rsp_embeddings":rsp_embeddings,
                         "rsp_push_at":rsp_push_at,
                         "rsp_pull_at":rsp_pull_at
                     })),
@@ -177,6 +246,49 @@ async fn detail(Path(id): Path<String>, State(app_state): State<AppState>) -> im
     }
 }
 
+#[derive(Serialize, Debug)]
+struct AttachmentOutputDto {
+    id: String,
+    mime_type: String,
+    size: i64,
+    // data url，解码失败时为空
+    thumbnail: Option<String>,
+}
+
+fn attachment_output_dto(
+    tbl_llm_task_attachment: tbl_llm_task_attachment::Model,
+) -> AttachmentOutputDto {
+    let thumbnail = match thumbnail(&tbl_llm_task_attachment.content) {
+        Ok(v) => Some(format!(
+            "data:image/png;base64,{}",
+            BASE64_STANDARD.encode(v)
+        )),
+        Err(e) => {
+            log::warn!(
+                "attachment {} thumbnail err: {}",
+                tbl_llm_task_attachment.id,
+                e
+            );
+            None
+        }
+    };
+    AttachmentOutputDto {
+        id: tbl_llm_task_attachment.id,
+        mime_type: tbl_llm_task_attachment.mime_type,
+        size: tbl_llm_task_attachment.size,
+        thumbnail,
+    }
+}
+
+// 缩略图统一输出为 png，长边不超过 128 像素
+fn thumbnail(content: &[u8]) -> anyhow::Result<Vec<u8>> {
+    let image = image::load_from_memory(content)?;
+    let thumbnail = image.thumbnail(128, 128);
+    let mut buf = Cursor::new(Vec::new());
+    thumbnail.write_to(&mut buf, ImageFormat::Png)?;
+    Ok(buf.into_inner())
+}
+
 async fn delete(Path(id): Path<String>, State(app_state): State<AppState>) -> impl IntoResponse {
     match tbl_llm_task::Entity::delete_by_id(&id)
         .exec(&app_state.db_conn)
@@ -199,3 +311,75 @@ async fn delete(Path(id): Path<String>, State(app_state): State<AppState>) -> im
         }
     }
 }
+
+#[derive(Deserialize, Debug, Validate)]
+struct PurgeInputDto {
+    status: Option<String>,
+    model: Option<String>,
+    // 只删除早于该时间提交的任务，毫秒时间戳
+    before: Option<i64>,
+    // 删除前是否归档，默认按配置的 archive_dir 决定
+    archive: Option<bool>,
+}
+
+async fn purge(
+    State(app_state): State<AppState>,
+    Query(purge_input_dto): Query<PurgeInputDto>,
+) -> impl IntoResponse {
+    let status = match purge_input_dto.status.filter(|v| !v.is_empty()) {
+        Some(v) => match LlmTaskStatus::from_str(&v) {
+            Ok(status) => Some(status),
+            Err(e) => {
+                log::warn!("llm task status {v} err: {e}");
+                return StatusCode::BAD_REQUEST.into_response();
+            }
+        },
+        None => None,
+    };
+    let before = match purge_input_dto.before {
+        Some(v) => match DateTime::from_timestamp_millis(v) {
+            Some(before) => Some(before.naive_utc()),
+            None => {
+                log::warn!("llm task before {v} out of range");
+                return StatusCode::BAD_REQUEST.into_response();
+            }
+        },
+        None => None,
+    };
+    let filter = PurgeFilter {
+        status,
+        model: purge_input_dto.model.filter(|v| !v.is_empty()),
+        before,
+    };
+    // 不允许无条件清空
+    if filter.is_empty() {
+        log::warn!("purge llm task without filter");
+        return StatusCode::BAD_REQUEST.into_response();
+    }
+    let archive_dir = UI_SERVICE_TOML.llm_task.archive_dir.as_deref();
+    let archive_dir = match purge_input_dto.archive {
+        Some(true) if archive_dir.is_none() => {
+            log::warn!("purge llm task archive_dir not configured");
+            return StatusCode::BAD_REQUEST.into_response();
+        }
+        Some(false) => None,
+        _ => archive_dir,
+    };
+    match llm_task_retention::purge(&app_state.db_conn, &filter, archive_dir).await {
+        Ok((deleted, archive_path)) => {
+            log::info!("purge llm task {filter:?} deleted {deleted}");
+            (
+                StatusCode::OK,
+                Json(json!({
+                    "deleted": deleted,
+                    "archive": archive_path.map(|v| v.to_string_lossy().to_string()),
+                })),
+            )
+                .into_response()
+        }
+        Err(e) => {
+            log::error!("purge llm task {filter:?} err: {e}");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
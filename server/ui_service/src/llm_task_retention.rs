@@ -0,0 +1,277 @@
+use std::{
+    fs::{self, File},
+    io::Write,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use base64::{Engine, prelude::BASE64_STANDARD};
+use chrono::NaiveDateTime;
+use entity::{tbl_llm_task, tbl_llm_task_attachment};
+use flate2::{Compression, write::GzEncoder};
+use pub_lib::LlmTaskStatus;
+use sea_orm::{
+    ColumnTrait, Condition, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, QuerySelect,
+};
+use serde_json::json;
+
+use crate::config::UI_SERVICE_TOML;
+
+// 每批删除的任务数
+const PURGE_BATCH_SIZE: u64 = 500;
+
+#[derive(Debug, Clone, Default)]
+pub struct PurgeFilter {
+    pub status: Option<LlmTaskStatus>,
+    pub model: Option<String>,
+    // 只清理早于该时间提交的任务
+    pub before: Option<NaiveDateTime>,
+}
+
+impl PurgeFilter {
+    pub fn is_empty(&self) -> bool {
+        self.status.is_none() && self.model.is_none() && self.before.is_none()
+    }
+
+    fn condition(&self) -> Condition {
+        let mut condition = Condition::all();
+        if let Some(status) = &self.status {
+            condition = match status {
+                LlmTaskStatus::Pending => condition.add(tbl_llm_task::Column::ReqPullAt.is_null()),
+                LlmTaskStatus::Processing => condition
+                    .add(tbl_llm_task::Column::ReqPullAt.is_not_null())
+                    .add(tbl_llm_task::Column::RspPushAt.is_null()),
+                LlmTaskStatus::Answered => condition
+                    .add(tbl_llm_task::Column::RspPushAt.is_not_null())
+                    .add(tbl_llm_task::Column::RspPullAt.is_null()),
+                LlmTaskStatus::Completed => {
+                    condition.add(tbl_llm_task::Column::RspPullAt.is_not_null())
+                }
+            };
+        }
+        if let Some(model) = &self.model {
+            condition = condition.add(tbl_llm_task::Column::Model.eq(model));
+        }
+        if let Some(before) = self.before {
+            condition = condition.add(tbl_llm_task::Column::ReqPushAt.lt(before));
+        }
+        condition
+    }
+}
+
+/// 按条件分批删除任务，附件随任务级联删除
+///
+/// archive_dir 不为空时，删除前将任务写入该目录下的 jsonl.gz 文件，返回删除数量和归档文件
+pub async fn purge(
+    db_conn: &DatabaseConnection,
+    filter: &PurgeFilter,
+    archive_dir: Option<&str>,
+) -> anyhow::Result<(u64, Option<PathBuf>)> {
+    let mut deleted = 0;
+    let mut archive: Option<(PathBuf, GzEncoder<File>)> = None;
+    loop {
+        let tbl_llm_tasks = tbl_llm_task::Entity::find()
+            .filter(filter.condition())
+            .order_by_asc(tbl_llm_task::Column::ReqPushAt)
+            .limit(PURGE_BATCH_SIZE)
+            .all(db_conn)
+            .await?;
+        if tbl_llm_tasks.is_empty() {
+            break;
+        }
+        let ids: Vec<String> = tbl_llm_tasks.iter().map(|v| v.id.clone()).collect();
+        if let Some(archive_dir) = archive_dir {
+            if archive.is_none() {
+                archive = Some(create_archive(Path::new(archive_dir))?);
+            }
+            if let Some((_, encoder)) = archive.as_mut() {
+                let tbl_llm_task_attachments = tbl_llm_task_attachment::Entity::find()
+                    .filter(tbl_llm_task_attachment::Column::LlmTaskId.is_in(ids.clone()))
+                    .all(db_conn)
+                    .await?;
+                for tbl_llm_task in &tbl_llm_tasks {
+                    let attachments: Vec<&tbl_llm_task_attachment::Model> =
+                        tbl_llm_task_attachments
+                            .iter()
+                            .filter(|v| v.llm_task_id.eq(&tbl_llm_task.id))
+                            .collect();
+                    let line = archive_line(tbl_llm_task, &attachments)?;
+                    encoder.write_all(line.as_bytes())?;
+                    encoder.write_all(b"\n")?;
+                }
+                // 本批写入磁盘后再删除，避免进程退出时已删除的任务未归档
+                encoder.flush()?;
+                encoder.get_ref().sync_all()?;
+            }
+        }
+        let delete_result = tbl_llm_task::Entity::delete_many()
+            .filter(tbl_llm_task::Column::Id.is_in(ids))
+            .exec(db_conn)
+            .await?;
+        deleted += delete_result.rows_affected;
+    }
+    let archive_path = match archive {
+        Some((path, encoder)) => {
+            encoder.finish()?.sync_all()?;
+            log::info!("archive llm task to {}", path.to_string_lossy());
+            Some(path)
+        }
+        None => None,
+    };
+    Ok((deleted, archive_path))
+}
+
+fn create_archive(archive_dir: &Path) -> anyhow::Result<(PathBuf, GzEncoder<File>)> {
+    if !archive_dir.exists() {
+        fs::create_dir_all(archive_dir)?;
+        log::info!("create dir: {}", archive_dir.to_string_lossy());
+    }
+    let name = format!(
+        "llm_task_{}_{}.jsonl.gz",
+        chrono::Utc::now().format("%Y%m%d%H%M%S"),
+        &uuid::Uuid::new_v4().to_string()[..8]
+    );
+    let path = archive_dir.join(name);
+    let file = File::create(&path)?;
+    Ok((path, GzEncoder::new(file, Compression::default())))
+}
+
+fn archive_line(
+    tbl_llm_task: &tbl_llm_task::Model,
+    attachments: &[&tbl_llm_task_attachment::Model],
+) -> anyhow::Result<String> {
+    let req_inputs = match &tbl_llm_task.req_inputs {
+        Some(encoded) => Some(
+            bincode::decode_from_slice::<Vec<String>, _>(
+                &encoded[..],
+                bincode::config::standard(),
+            )?
+            .0,
+        ),
+        None => None,
+    };
+    let rsp_embeddings = match &tbl_llm_task.rsp_embeddings {
+        Some(encoded) => Some(
+            bincode::decode_from_slice::<Vec<Vec<f32>>, _>(
+                &encoded[..],
+                bincode::config::standard(),
+            )?
+            .0,
+        ),
+        None => None,
+    };
+    let attachments: Vec<serde_json::Value> = attachments
+        .iter()
+        .map(|attachment| {
+            json!({
+                "mime_type": attachment.mime_type,
+                "content": BASE64_STANDARD.encode(&attachment.content),
+            })
+        })
+        .collect();
+    let line = json!({
+        "id": tbl_llm_task.id,
+        "kind": tbl_llm_task.kind,
+        "model": tbl_llm_task.model,
+        "prompt": tbl_llm_task.prompt,
+        "req_agent_id": tbl_llm_task.req_agent_id,
+        "req_content": tbl_llm_task.req_content,
+        "req_inputs": req_inputs,
+        "req_push_at": tbl_llm_task.req_push_at.and_utc().timestamp_millis(),
+        "req_pull_at": tbl_llm_task.req_pull_at.map(|v| v.and_utc().timestamp_millis()),
+        "rsp_agent_id": tbl_llm_task.rsp_agent_id,
+        "rsp_content": tbl_llm_task.rsp_content,
+        "rsp_embeddings": rsp_embeddings,
+        "rsp_push_at": tbl_llm_task.rsp_push_at.map(|v| v.and_utc().timestamp_millis()),
+        "rsp_pull_at": tbl_llm_task.rsp_pull_at.map(|v| v.and_utc().timestamp_millis()),
+        "attachments": attachments,
+    });
+    Ok(line.to_string())
+}
+
+/// 按 ui_service.toml 中的 [[llm_task.retention]] 定时清理任务
+pub async fn retention_task(db_conn: DatabaseConnection) -> anyhow::Result<()> {
+    let llm_task_config = &UI_SERVICE_TOML.llm_task;
+    if llm_task_config.retention.is_empty() {
+        log::info!("llm task retention not configured");
+        return Ok(());
+    }
+    let mut rules = Vec::new();
+    for retention in &llm_task_config.retention {
+        let status = match &retention.status {
+            Some(v) => Some(LlmTaskStatus::from_str(v)?),
+            None => None,
+        };
+        let filter = PurgeFilter {
+            status,
+            model: retention.model.clone(),
+            before: None,
+        };
+        rules.push((retention.max_age_days, filter));
+    }
+    tokio::spawn(async move {
+        log::info!("llm task retention_task running");
+        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(
+            llm_task_config.purge_interval.max(60),
+        ));
+        loop {
+            interval.tick().await;
+            for (max_age_days, rule) in &rules {
+                let before = chrono::Utc::now().naive_utc() - chrono::Duration::days(*max_age_days);
+                let filter = PurgeFilter {
+                    before: Some(before),
+                    ..rule.clone()
+                };
+                match purge(&db_conn, &filter, llm_task_config.archive_dir.as_deref()).await {
+                    Ok((deleted, _)) => {
+                        if deleted > 0 {
+                            log::info!("llm task retention {filter:?} deleted {deleted}");
+                        }
+                    }
+                    Err(e) => log::error!("llm task retention {filter:?} err: {}", e),
+                }
+            }
+        }
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::{DbBackend, QueryTrait};
+
+    fn where_clause(filter: &PurgeFilter) -> String {
+        let sql = tbl_llm_task::Entity::find()
+            .filter(filter.condition())
+            .build(DbBackend::Sqlite)
+            .to_string();
+        sql.split_once(" WHERE ")
+            .map(|v| v.1.to_string())
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn purge_filter_test() {
+        assert!(PurgeFilter::default().is_empty());
+        let filter = PurgeFilter {
+            status: Some(LlmTaskStatus::Processing),
+            model: Some("qwen3".to_string()),
+            before: None,
+        };
+        assert!(!filter.is_empty());
+        let sql = where_clause(&filter);
+        assert!(sql.contains(r#""req_pull_at" IS NOT NULL"#), "{sql}");
+        assert!(sql.contains(r#""rsp_push_at" IS NULL"#), "{sql}");
+        assert!(sql.contains(r#""model" = 'qwen3'"#), "{sql}");
+        assert!(!sql.contains("req_push_at"), "{sql}");
+        let filter = PurgeFilter {
+            status: Some(LlmTaskStatus::Completed),
+            before: chrono::DateTime::from_timestamp(0, 0).map(|v| v.naive_utc()),
+            ..Default::default()
+        };
+        let sql = where_clause(&filter);
+        assert!(sql.contains(r#""rsp_pull_at" IS NOT NULL"#), "{sql}");
+        assert!(sql.contains(r#""req_push_at" < '1970-01-01"#), "{sql}");
+    }
+}
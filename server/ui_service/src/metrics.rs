@@ -0,0 +1,123 @@
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::get,
+};
+use entity::tbl_host_metric;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::AppState;
+
+// 与 client_service 的降采样粒度一致
+const MINUTE: i32 = 60;
+const HOUR: i32 = 3600;
+
+pub fn routers(state: AppState) -> Router {
+    Router::new()
+        .route("/hosts/{id}/metrics", get(query))
+        .with_state(state)
+}
+
+#[derive(Deserialize, Debug)]
+struct QueryInputDto {
+    // 毫秒时间戳，默认最近一小时
+    start: Option<i64>,
+    end: Option<i64>,
+    // 60 或 3600，默认按时间跨度选择
+    resolution: Option<i32>,
+}
+
+#[derive(Serialize, Debug)]
+struct QueryOutputDto {
+    timestamp: i64,
+    samples: i32,
+    cpu_avg: f64,
+    cpu_max: f64,
+    used_memory: i64,
+    total_memory: i64,
+    used_swap: i64,
+    total_swap: i64,
+    load_one: f64,
+    load_five: f64,
+    load_fifteen: f64,
+    disk_used: i64,
+    disk_total: i64,
+    rx_rate: f64,
+    tx_rate: f64,
+}
+
+/// 查询主机资源指标时间序列，跨度不超过一天时返回分钟粒度，否则返回小时粒度
+async fn query(
+    Path(id): Path<String>,
+    State(app_state): State<AppState>,
+    Query(query_input_dto): Query<QueryInputDto>,
+) -> impl IntoResponse {
+    let end = query_input_dto
+        .end
+        .unwrap_or_else(|| chrono::Utc::now().timestamp_millis());
+    let start = query_input_dto.start.unwrap_or(end - 3_600_000);
+    if start >= end {
+        return (StatusCode::BAD_REQUEST, "start must be less than end").into_response();
+    }
+    let resolution = match query_input_dto.resolution {
+        Some(v) if v == MINUTE || v == HOUR => v,
+        Some(_) => {
+            return (StatusCode::BAD_REQUEST, "resolution must be 60 or 3600").into_response();
+        }
+        None if end - start <= 86_400_000 => MINUTE,
+        None => HOUR,
+    };
+    let (Some(start_at), Some(end_at)) = (
+        chrono::DateTime::from_timestamp_millis(start),
+        chrono::DateTime::from_timestamp_millis(end),
+    ) else {
+        return (StatusCode::BAD_REQUEST, "invalid start or end").into_response();
+    };
+    let tbl_host_metrics = match tbl_host_metric::Entity::find()
+        .filter(tbl_host_metric::Column::AgentId.eq(&id))
+        .filter(tbl_host_metric::Column::Resolution.eq(resolution))
+        .filter(tbl_host_metric::Column::Bucket.gte(start_at.naive_utc()))
+        .filter(tbl_host_metric::Column::Bucket.lte(end_at.naive_utc()))
+        .order_by_asc(tbl_host_metric::Column::Bucket)
+        .all(&app_state.db_conn)
+        .await
+    {
+        Ok(v) => v,
+        Err(e) => {
+            log::error!("{id} tbl_host_metric find err: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let metrics: Vec<QueryOutputDto> = tbl_host_metrics
+        .into_iter()
+        .map(|tbl_host_metric| QueryOutputDto {
+            timestamp: tbl_host_metric.bucket.and_utc().timestamp_millis(),
+            samples: tbl_host_metric.samples,
+            cpu_avg: tbl_host_metric.cpu_avg,
+            cpu_max: tbl_host_metric.cpu_max,
+            used_memory: tbl_host_metric.used_memory,
+            total_memory: tbl_host_metric.total_memory,
+            used_swap: tbl_host_metric.used_swap,
+            total_swap: tbl_host_metric.total_swap,
+            load_one: tbl_host_metric.load_one,
+            load_five: tbl_host_metric.load_five,
+            load_fifteen: tbl_host_metric.load_fifteen,
+            disk_used: tbl_host_metric.disk_used,
+            disk_total: tbl_host_metric.disk_total,
+            rx_rate: tbl_host_metric.rx_rate,
+            tx_rate: tbl_host_metric.tx_rate,
+        })
+        .collect();
+    (
+        StatusCode::OK,
+        Json(json!({
+            "resolution": resolution,
+            "metrics": metrics,
+        })),
+    )
+        .into_response()
+}
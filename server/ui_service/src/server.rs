@@ -11,10 +11,12 @@ use tokio::sync::broadcast;
 use tower_http::services::{ServeDir, ServeFile};
 
 use crate::{
-    AppState, agent,
+    AppState, account, agent, agent_conflict, agent_profile, agent_upgrade, artifact,
     auth::{self, RequireAuth, auth_init},
+    cert,
     config::UI_SERVICE_TOML,
-    host, llm_task, role, system, user,
+    enrollment_token, host, host_snapshot, listen_port, llm_consumer, llm_task, llm_task_retention,
+    metrics, role, service, software, system, user,
     z11n::HeartbeatRsp,
 };
 
@@ -26,6 +28,8 @@ pub async fn serve(
     auth_init(db_conn.clone()).await?;
     auth::token_expired_task(sled_db.clone()).await?;
     let captcha_cache = auth::captcha_cache_init()?;
+    llm_task_retention::retention_task(db_conn.clone()).await?;
+    cert::expiry_task();
     let app_state = AppState {
         db_conn,
         sled_db,
@@ -44,11 +48,24 @@ pub async fn serve(
             ServeDir::new(dist_path).fallback(ServeFile::new(format!("{dist_path}/index.html"))),
         )
         .nest("/api", agent::routers(app_state.clone()))
+        .nest("/api", agent_conflict::routers(app_state.clone()))
+        .nest("/api", agent_profile::routers(app_state.clone()))
+        .nest("/api", agent_upgrade::routers(app_state.clone()))
+        .nest("/api", artifact::routers(app_state.clone()))
+        .nest("/api", enrollment_token::routers(app_state.clone()))
         .nest("/api", auth::routers(app_state.clone()))
+        .nest("/api", cert::routers(app_state.clone()))
         .nest("/api", role::routers(app_state.clone()))
         .nest("/api", user::routers(app_state.clone()))
         .nest("/api", host::routers(app_state.clone()))
+        .nest("/api", host_snapshot::routers(app_state.clone()))
+        .nest("/api", metrics::routers(app_state.clone()))
+        .nest("/api", software::routers(app_state.clone()))
+        .nest("/api", listen_port::routers(app_state.clone()))
+        .nest("/api", account::routers(app_state.clone()))
+        .nest("/api", service::routers(app_state.clone()))
         .nest("/api", llm_task::routers(app_state.clone()))
+        .nest("/api", llm_consumer::routers(app_state.clone()))
         .nest("/api", system::routers(app_state.clone()))
         .layer(from_extractor_with_state::<RequireAuth, _>(Arc::new(
             app_state,
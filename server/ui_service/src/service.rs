@@ -0,0 +1,280 @@
+use crate::{
+    AppState,
+    z11n::{HeartbeatRsp, ServiceControl, heartbeat_rsp::Task, service_control::Action},
+};
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{get, post},
+};
+use entity::{tbl_agent, tbl_host, tbl_host_service, tbl_service_control};
+use sea_orm::{
+    ActiveValue::Set, ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use validator::Validate;
+
+pub fn routers(state: AppState) -> Router {
+    Router::new()
+        .route("/services", get(query))
+        .route("/services/{action}", post(control))
+        .route("/service_controls", get(control_query))
+        .with_state(state)
+}
+
+#[derive(Deserialize, Debug, Validate)]
+struct QueryInputDto {
+    name: Option<String>,
+    active_state: Option<String>,
+    unit_file_state: Option<String>,
+    agent_id: Option<String>,
+    size: u64,
+    page: u64,
+}
+
+#[derive(Serialize, Debug)]
+struct QueryOutputDto {
+    agent_id: String,
+    host_name: Option<String>,
+    name: String,
+    load_state: String,
+    active_state: String,
+    sub_state: String,
+    unit_file_state: String,
+    main_pid: Option<i32>,
+}
+
+/// 按服务名查找主机上的 systemd 服务及其运行状态
+async fn query(
+    app_state: State<AppState>,
+    Query(query_input_dto): Query<QueryInputDto>,
+) -> impl IntoResponse {
+    let mut select = tbl_host_service::Entity::find().find_also_related(tbl_host::Entity);
+    if let Some(v) = query_input_dto.name.filter(|v| !v.is_empty()) {
+        let like_pattern = format!("%{v}%");
+        select = select.filter(tbl_host_service::Column::Name.like(like_pattern));
+    }
+    if let Some(v) = query_input_dto.active_state.filter(|v| !v.is_empty()) {
+        select = select.filter(tbl_host_service::Column::ActiveState.eq(v));
+    }
+    if let Some(v) = query_input_dto.unit_file_state.filter(|v| !v.is_empty()) {
+        select = select.filter(tbl_host_service::Column::UnitFileState.eq(v));
+    }
+    if let Some(v) = query_input_dto.agent_id.filter(|v| !v.is_empty()) {
+        select = select.filter(tbl_host_service::Column::AgentId.eq(v));
+    }
+
+    let paginator = select
+        .order_by_asc(tbl_host_service::Column::Name)
+        .order_by_asc(tbl_host_service::Column::AgentId)
+        .paginate(&app_state.db_conn, query_input_dto.size);
+    let num_pages = match paginator.num_pages().await {
+        Ok(v) => v,
+        Err(e) => {
+            log::error!("num_pages err: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let num_items = match paginator.num_items().await {
+        Ok(v) => v,
+        Err(e) => {
+            log::error!("num_items err: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let rows = match paginator.fetch_page(query_input_dto.page).await {
+        Ok(v) => v,
+        Err(e) => {
+            log::error!("fetch_page err: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let mut services = Vec::new();
+    for (tbl_host_service, tbl_host) in rows {
+        services.push(QueryOutputDto {
+            agent_id: tbl_host_service.agent_id,
+            host_name: tbl_host.and_then(|v| v.host_name),
+            name: tbl_host_service.name,
+            load_state: tbl_host_service.load_state,
+            active_state: tbl_host_service.active_state,
+            sub_state: tbl_host_service.sub_state,
+            unit_file_state: tbl_host_service.unit_file_state,
+            main_pid: tbl_host_service.main_pid,
+        });
+    }
+    (
+        StatusCode::OK,
+        Json(json!(
+            {
+            "page":{
+              "size":query_input_dto.size,
+              "total_elements":num_items,
+              "total_pages":num_pages
+            },
+            "_embedded":{
+                "service":services
+            }
+           }
+        )),
+    )
+        .into_response()
+}
+
+#[derive(Deserialize, Debug, Validate)]
+struct ControlInputDto {
+    agent_id: String,
+    unit: String,
+}
+
+/// 下发服务控制任务，每种操作对应单独的权限路径 /api/services/{action}
+async fn control(
+    Path(action): Path<String>,
+    app_state: State<AppState>,
+    Json(control_input_dto): Json<ControlInputDto>,
+) -> impl IntoResponse {
+    let action_enum = match action.as_str() {
+        "start" => Action::Start,
+        "stop" => Action::Stop,
+        "restart" => Action::Restart,
+        "enable" => Action::Enable,
+        "disable" => Action::Disable,
+        _ => {
+            log::warn!("unsupported service action: {action}");
+            return StatusCode::NOT_FOUND.into_response();
+        }
+    };
+    if !control_input_dto.unit.ends_with(".service") {
+        return (StatusCode::BAD_REQUEST, "unit must be a .service").into_response();
+    }
+    match tbl_agent::Entity::find_by_id(&control_input_dto.agent_id)
+        .one(&app_state.db_conn)
+        .await
+    {
+        Ok(Some(_)) => {}
+        Ok(None) => return StatusCode::BAD_REQUEST.into_response(),
+        Err(e) => {
+            log::error!("tbl_agent find err: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    }
+
+    let id = uuid::Uuid::new_v4().to_string();
+    let tbl_service_control_am = tbl_service_control::ActiveModel {
+        id: Set(id.clone()),
+        agent_id: Set(control_input_dto.agent_id.clone()),
+        unit: Set(control_input_dto.unit.clone()),
+        action: Set(action),
+        status: Set("pending".to_string()),
+        ..Default::default()
+    };
+    if let Err(e) = tbl_service_control::Entity::insert(tbl_service_control_am)
+        .exec(&app_state.db_conn)
+        .await
+    {
+        log::error!("tbl_service_control insert err: {}", e);
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+    let heartbeat_rsp = HeartbeatRsp {
+        task: Some(Task::ServiceControl(ServiceControl {
+            id: id.clone(),
+            unit: control_input_dto.unit,
+            action: action_enum.into(),
+        })),
+    };
+    if let Err(e) = app_state
+        .tx_heartbeat_rsp
+        .send((control_input_dto.agent_id, heartbeat_rsp))
+    {
+        log::error!("tx_heartbeat_rsp.send err: {}", e);
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+    (StatusCode::OK, Json(json!({ "id": id }))).into_response()
+}
+
+#[derive(Deserialize, Debug, Validate)]
+struct ControlQueryInputDto {
+    agent_id: Option<String>,
+    size: u64,
+    page: u64,
+}
+
+#[derive(Serialize, Debug)]
+struct ControlQueryOutputDto {
+    id: String,
+    agent_id: String,
+    unit: String,
+    action: String,
+    status: String,
+    output: Option<String>,
+    created_at: i64,
+    finished_at: Option<i64>,
+}
+
+/// 服务控制记录，按下发时间倒序
+async fn control_query(
+    app_state: State<AppState>,
+    Query(query_input_dto): Query<ControlQueryInputDto>,
+) -> impl IntoResponse {
+    let mut select = tbl_service_control::Entity::find();
+    if let Some(v) = query_input_dto.agent_id.filter(|v| !v.is_empty()) {
+        select = select.filter(tbl_service_control::Column::AgentId.eq(v));
+    }
+    let paginator = select
+        .order_by_desc(tbl_service_control::Column::CreatedAt)
+        .paginate(&app_state.db_conn, query_input_dto.size);
+    let num_pages = match paginator.num_pages().await {
+        Ok(v) => v,
+        Err(e) => {
+            log::error!("num_pages err: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let num_items = match paginator.num_items().await {
+        Ok(v) => v,
+        Err(e) => {
+            log::error!("num_items err: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let rows = match paginator.fetch_page(query_input_dto.page).await {
+        Ok(v) => v,
+        Err(e) => {
+            log::error!("fetch_page err: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let mut service_controls = Vec::new();
+    for tbl_service_control in rows {
+        service_controls.push(ControlQueryOutputDto {
+            id: tbl_service_control.id,
+            agent_id: tbl_service_control.agent_id,
+            unit: tbl_service_control.unit,
+            action: tbl_service_control.action,
+            status: tbl_service_control.status,
+            output: tbl_service_control.output,
+            created_at: tbl_service_control.created_at.and_utc().timestamp_millis(),
+            finished_at: tbl_service_control
+                .finished_at
+                .map(|v| v.and_utc().timestamp_millis()),
+        });
+    }
+    (
+        StatusCode::OK,
+        Json(json!(
+            {
+            "page":{
+              "size":query_input_dto.size,
+              "total_elements":num_items,
+              "total_pages":num_pages
+            },
+            "_embedded":{
+                "service_control":service_controls
+            }
+           }
+        )),
+    )
+        .into_response()
+}
@@ -0,0 +1,121 @@
+use axum::{
+    Json, Router,
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::get,
+};
+use entity::{tbl_host, tbl_host_software};
+use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use validator::Validate;
+
+use crate::AppState;
+
+pub fn routers(state: AppState) -> Router {
+    Router::new()
+        .route("/softwares", get(query))
+        .with_state(state)
+}
+
+#[derive(Deserialize, Debug, Validate)]
+struct QueryInputDto {
+    name: Option<String>,
+    version: Option<String>,
+    source: Option<String>,
+    agent_id: Option<String>,
+    size: u64,
+    page: u64,
+}
+
+#[derive(Serialize, Debug)]
+struct QueryOutputDto {
+    agent_id: String,
+    host_name: Option<String>,
+    name: String,
+    version: String,
+    arch: String,
+    source: String,
+    installed_at: Option<i64>,
+    path: Option<String>,
+}
+
+/// 按包名查找安装了该软件的主机
+async fn query(
+    app_state: State<AppState>,
+    Query(query_input_dto): Query<QueryInputDto>,
+) -> impl IntoResponse {
+    let mut select = tbl_host_software::Entity::find().find_also_related(tbl_host::Entity);
+    if let Some(v) = query_input_dto.name.filter(|v| !v.is_empty()) {
+        let like_pattern = format!("%{v}%");
+        select = select.filter(tbl_host_software::Column::Name.like(like_pattern));
+    }
+    if let Some(v) = query_input_dto.version.filter(|v| !v.is_empty()) {
+        let like_pattern = format!("%{v}%");
+        select = select.filter(tbl_host_software::Column::Version.like(like_pattern));
+    }
+    if let Some(v) = query_input_dto.source.filter(|v| !v.is_empty()) {
+        select = select.filter(tbl_host_software::Column::Source.eq(v));
+    }
+    if let Some(v) = query_input_dto.agent_id.filter(|v| !v.is_empty()) {
+        select = select.filter(tbl_host_software::Column::AgentId.eq(v));
+    }
+
+    let paginator = select
+        .order_by_asc(tbl_host_software::Column::Name)
+        .order_by_asc(tbl_host_software::Column::AgentId)
+        .paginate(&app_state.db_conn, query_input_dto.size);
+    let num_pages = match paginator.num_pages().await {
+        Ok(v) => v,
+        Err(e) => {
+            log::error!("num_pages err: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let num_items = match paginator.num_items().await {
+        Ok(v) => v,
+        Err(e) => {
+            log::error!("num_items err: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let rows = match paginator.fetch_page(query_input_dto.page).await {
+        Ok(v) => v,
+        Err(e) => {
+            log::error!("fetch_page err: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let mut softwares = Vec::new();
+    for (tbl_host_software, tbl_host) in rows {
+        softwares.push(QueryOutputDto {
+            agent_id: tbl_host_software.agent_id,
+            host_name: tbl_host.and_then(|v| v.host_name),
+            name: tbl_host_software.name,
+            version: tbl_host_software.version,
+            arch: tbl_host_software.arch,
+            source: tbl_host_software.source,
+            installed_at: tbl_host_software
+                .installed_at
+                .map(|v| v.and_utc().timestamp_millis()),
+            path: tbl_host_software.path,
+        });
+    }
+    (
+        StatusCode::OK,
+        Json(json!(
+            {
+            "page":{
+              "size":query_input_dto.size,
+              "total_elements":num_items,
+              "total_pages":num_pages
+            },
+            "_embedded":{
+                "software":softwares
+            }
+           }
+        )),
+    )
+        .into_response()
+}
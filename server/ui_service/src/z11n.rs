@@ -12,6 +12,15 @@ pub struct LlmTaskAnswer {
     pub id: ::prost::alloc::string::String,
     #[prost(string, tag = "2")]
     pub content: ::prost::alloc::string::String,
+    /// 向量结果，与 inputs 一一对应
+    #[prost(message, repeated, tag = "3")]
+    pub embeddings: ::prost::alloc::vec::Vec<Embedding>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Embedding {
+    #[prost(float, repeated, tag = "1")]
+    pub values: ::prost::alloc::vec::Vec<f32>,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -28,6 +37,23 @@ pub struct LlmTaskQuestionReq {
     pub prompt: ::prost::alloc::string::String,
     #[prost(string, tag = "3")]
     pub content: ::prost::alloc::string::String,
+    #[prost(enumeration = "LlmTaskKind", tag = "4")]
+    pub kind: i32,
+    /// 向量嵌入的输入文本，kind 为 EMBEDDING 时必填
+    #[prost(string, repeated, tag = "5")]
+    pub inputs: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// 多模态附件，目前支持图片
+    #[prost(message, repeated, tag = "6")]
+    pub attachments: ::prost::alloc::vec::Vec<Attachment>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Attachment {
+    /// 例如 image/png, image/jpeg
+    #[prost(string, tag = "1")]
+    pub mime_type: ::prost::alloc::string::String,
+    #[prost(bytes = "vec", tag = "2")]
+    pub content: ::prost::alloc::vec::Vec<u8>,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -46,23 +72,134 @@ pub struct LlmTaskQuestion {
     pub prompt: ::prost::alloc::string::String,
     #[prost(string, tag = "4")]
     pub content: ::prost::alloc::string::String,
+    #[prost(enumeration = "LlmTaskKind", tag = "5")]
+    pub kind: i32,
+    #[prost(string, repeated, tag = "6")]
+    pub inputs: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    #[prost(message, repeated, tag = "7")]
+    pub attachments: ::prost::alloc::vec::Vec<Attachment>,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
 pub struct Empty {}
 #[derive(serde::Serialize, serde::Deserialize)]
-#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HeartbeatReq {
+    /// LLM consumer 的负载与健康状态，其他 agent 不填
+    #[prost(message, optional, tag = "1")]
+    pub llm_consumer_status: ::core::option::Option<LlmConsumerStatus>,
+    /// 正在使用的配置模板，未分配时为空
+    #[prost(string, tag = "2")]
+    pub profile_id: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "3")]
+    pub profile_version: u64,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct LlmConsumerStatus {
+    /// 推理后端可用的模型
+    #[prost(string, repeated, tag = "1")]
+    pub models: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// 已加载到内存的模型
+    #[prost(string, repeated, tag = "2")]
+    pub loaded_models: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    #[prost(uint32, tag = "3")]
+    pub total_slots: u32,
+    #[prost(uint32, tag = "4")]
+    pub free_slots: u32,
+    /// 最近的生成速度
+    #[prost(double, tag = "5")]
+    pub tokens_per_second: f64,
+    /// 推理后端是否可达
+    #[prost(bool, tag = "6")]
+    pub backend_reachable: bool,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct HeartbeatRsp {
-    #[prost(oneof = "heartbeat_rsp::Task", tags = "1")]
+    #[prost(oneof = "heartbeat_rsp::Task", tags = "1, 2, 3, 4")]
     pub task: ::core::option::Option<heartbeat_rsp::Task>,
 }
 /// Nested message and enum types in `HeartbeatRsp`.
 pub mod heartbeat_rsp {
     #[derive(serde::Serialize, serde::Deserialize)]
-    #[derive(Clone, Copy, PartialEq, ::prost::Oneof)]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
     pub enum Task {
         #[prost(message, tag = "1")]
         UploadHost(super::UploadHost),
+        #[prost(message, tag = "2")]
+        ServiceControl(super::ServiceControl),
+        #[prost(message, tag = "3")]
+        Upgrade(super::Upgrade),
+        #[prost(message, tag = "4")]
+        Profile(super::AgentProfile),
+    }
+}
+/// 服务端管理的 agent 配置模板，agent 收到后立即生效并保存在本地
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AgentProfile {
+    /// tbl_agent_profile 主键
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+    /// 每次修改加一
+    #[prost(uint64, tag = "2")]
+    pub version: u64,
+    /// 心跳间隔秒数
+    #[prost(uint32, tag = "3")]
+    pub heartbeat_interval: u32,
+    /// 资源指标采样间隔秒数
+    #[prost(uint32, tag = "4")]
+    pub metrics_interval: u32,
+    /// 每攒够多少个采样批量上报一次
+    #[prost(uint32, tag = "5")]
+    pub metrics_batch: u32,
+    /// 定时采集上报的主机信息类型
+    #[prost(enumeration = "upload_host::InfoType", repeated, tag = "6")]
+    pub collect: ::prost::alloc::vec::Vec<i32>,
+    /// 定时采集的间隔秒数，0 表示只在启动和收到任务时采集
+    #[prost(uint32, tag = "7")]
+    pub collect_interval: u32,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ControlReq {
+    /// 任务结果的去重 id，服务端入库后通过 ControlRsp.ack 确认，心跳不填
+    #[prost(string, tag = "1")]
+    pub report_id: ::prost::alloc::string::String,
+    #[prost(oneof = "control_req::Msg", tags = "2, 3, 4")]
+    pub msg: ::core::option::Option<control_req::Msg>,
+}
+/// Nested message and enum types in `ControlReq`.
+pub mod control_req {
+    #[derive(serde::Serialize, serde::Deserialize)]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Msg {
+        #[prost(message, tag = "2")]
+        Heartbeat(super::HeartbeatReq),
+        #[prost(message, tag = "3")]
+        ServiceControlResult(super::ServiceControlResult),
+        #[prost(message, tag = "4")]
+        UpgradeResult(super::UpgradeResult),
+    }
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ControlRsp {
+    #[prost(oneof = "control_rsp::Msg", tags = "1, 2")]
+    pub msg: ::core::option::Option<control_rsp::Msg>,
+}
+/// Nested message and enum types in `ControlRsp`.
+pub mod control_rsp {
+    #[derive(serde::Serialize, serde::Deserialize)]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Msg {
+        /// ui_service 下发的任务
+        #[prost(message, tag = "1")]
+        Task(super::HeartbeatRsp),
+        /// 已入库的任务结果的 report_id
+        #[prost(string, tag = "2")]
+        Ack(::prost::alloc::string::String),
     }
 }
 #[derive(serde::Serialize, serde::Deserialize)]
@@ -90,6 +227,12 @@ pub mod upload_host {
         System = 0,
         Disk = 1,
         Network = 2,
+        Software = 3,
+        Socket = 4,
+        Hardware = 5,
+        Account = 6,
+        Service = 7,
+        ScheduledTask = 8,
     }
     impl InfoType {
         /// String value of the enum field names used in the ProtoBuf definition.
@@ -101,6 +244,12 @@ pub mod upload_host {
                 Self::System => "SYSTEM",
                 Self::Disk => "DISK",
                 Self::Network => "NETWORK",
+                Self::Software => "SOFTWARE",
+                Self::Socket => "SOCKET",
+                Self::Hardware => "HARDWARE",
+                Self::Account => "ACCOUNT",
+                Self::Service => "SERVICE",
+                Self::ScheduledTask => "SCHEDULED_TASK",
             }
         }
         /// Creates an enum from field names used in the ProtoBuf definition.
@@ -109,6 +258,12 @@ pub mod upload_host {
                 "SYSTEM" => Some(Self::System),
                 "DISK" => Some(Self::Disk),
                 "NETWORK" => Some(Self::Network),
+                "SOFTWARE" => Some(Self::Software),
+                "SOCKET" => Some(Self::Socket),
+                "HARDWARE" => Some(Self::Hardware),
+                "ACCOUNT" => Some(Self::Account),
+                "SERVICE" => Some(Self::Service),
+                "SCHEDULED_TASK" => Some(Self::ScheduledTask),
                 _ => None,
             }
         }
@@ -118,12 +273,39 @@ pub mod upload_host {
 #[derive(serde::Serialize, serde::Deserialize)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct RegisterReq {
-    /// agent唯一编号，必填
+    /// agent唯一编号，必填；本地没有编号时为随机值，以服务端返回的为准
     #[prost(string, tag = "1")]
     pub agent_id: ::prost::alloc::string::String,
     /// agent版本，必填
     #[prost(string, tag = "2")]
     pub agent_version: ::prost::alloc::string::String,
+    /// 机器指纹，服务端据此分配编号并发现克隆
+    #[prost(message, optional, tag = "3")]
+    pub fingerprint: ::core::option::Option<Fingerprint>,
+    /// 注册新 agent 时必填，已注册的 agent 重新注册时不校验
+    #[prost(string, tag = "4")]
+    pub enrollment_token: ::prost::alloc::string::String,
+    /// 客户端证书签名请求，PEM 格式，服务端按需签发
+    #[prost(string, tag = "5")]
+    pub csr: ::prost::alloc::string::String,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Fingerprint {
+    /// /etc/machine-id
+    #[prost(string, tag = "1")]
+    pub machine_id: ::prost::alloc::string::String,
+    /// /sys/class/dmi/id/product_uuid
+    #[prost(string, tag = "2")]
+    pub dmi_uuid: ::prost::alloc::string::String,
+    /// 物理网卡的 mac 地址，小写并排序
+    #[prost(string, repeated, tag = "3")]
+    pub macs: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    #[prost(string, tag = "4")]
+    pub hostname: ::prost::alloc::string::String,
+    /// 运行在容器中时的容器 id
+    #[prost(string, tag = "5")]
+    pub container_id: ::prost::alloc::string::String,
 }
 /// 心跳消息请求结构体
 #[derive(serde::Serialize, serde::Deserialize)]
@@ -132,6 +314,29 @@ pub struct RegisterRsp {
     /// 认证token
     #[prost(string, tag = "1")]
     pub token: ::prost::alloc::string::String,
+    /// 服务端分配的 agent 编号，agent 保存后用于之后的请求
+    #[prost(string, tag = "2")]
+    pub agent_id: ::prost::alloc::string::String,
+    /// 等待审批，审批前只能心跳
+    #[prost(bool, tag = "3")]
+    pub pending_approval: bool,
+    /// 新签发的客户端证书，PEM 格式，SAN 为 agent 编号；为空时继续使用当前证书
+    #[prost(string, tag = "4")]
+    pub cert: ::prost::alloc::string::String,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CertReq {
+    /// 客户端证书签名请求，PEM 格式
+    #[prost(string, tag = "1")]
+    pub csr: ::prost::alloc::string::String,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CertRsp {
+    /// 新签发的客户端证书，为空表示无需续期
+    #[prost(string, tag = "1")]
+    pub cert: ::prost::alloc::string::String,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -142,6 +347,37 @@ pub struct HostReq {
     pub disks: ::prost::alloc::vec::Vec<DiskInfo>,
     #[prost(message, repeated, tag = "3")]
     pub networks: ::prost::alloc::vec::Vec<NetworkInfo>,
+    #[prost(message, repeated, tag = "4")]
+    pub softwares: ::prost::alloc::vec::Vec<SoftwareInfo>,
+    #[prost(message, repeated, tag = "5")]
+    pub sockets: ::prost::alloc::vec::Vec<SocketInfo>,
+    #[prost(message, optional, tag = "6")]
+    pub hardware: ::core::option::Option<HardwareInfo>,
+    #[prost(message, optional, tag = "7")]
+    pub account: ::core::option::Option<AccountInfo>,
+    #[prost(message, repeated, tag = "8")]
+    pub services: ::prost::alloc::vec::Vec<ServiceInfo>,
+    #[prost(message, repeated, tag = "9")]
+    pub scheduled_tasks: ::prost::alloc::vec::Vec<ScheduledTask>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HostDiffReq {
+    /// agent 上次确认的版本，为空时不校验，直接覆盖 info_types 中的部分
+    #[prost(uint64, optional, tag = "1")]
+    pub base_version: ::core::option::Option<u64>,
+    /// 发生变化的部分，host 中对应字段为新值，为空表示已清空
+    #[prost(enumeration = "upload_host::InfoType", repeated, tag = "2")]
+    pub info_types: ::prost::alloc::vec::Vec<i32>,
+    #[prost(message, optional, tag = "3")]
+    pub host: ::core::option::Option<HostReq>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct HostDiffRsp {
+    /// 应用差量后的版本
+    #[prost(uint64, tag = "1")]
+    pub version: u64,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -176,6 +412,29 @@ pub struct ProcessInfo {
     pub exe: ::core::option::Option<::prost::alloc::string::String>,
     #[prost(string, tag = "4")]
     pub status: ::prost::alloc::string::String,
+    #[prost(string, repeated, tag = "5")]
+    pub cmd: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    #[prost(uint32, optional, tag = "6")]
+    pub ppid: ::core::option::Option<u32>,
+    #[prost(uint32, optional, tag = "7")]
+    pub uid: ::core::option::Option<u32>,
+    #[prost(string, optional, tag = "8")]
+    pub user: ::core::option::Option<::prost::alloc::string::String>,
+    /// 启动时间，unix 秒
+    #[prost(uint64, tag = "9")]
+    pub start_time: u64,
+    /// 占单个 cpu 的百分比，多核时可超过 100
+    #[prost(float, tag = "10")]
+    pub cpu_usage: f32,
+    /// 常驻内存，字节
+    #[prost(uint64, tag = "11")]
+    pub memory: u64,
+    #[prost(uint32, optional, tag = "12")]
+    pub open_files: ::core::option::Option<u32>,
+    #[prost(string, optional, tag = "13")]
+    pub cgroup: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "14")]
+    pub container_id: ::core::option::Option<::prost::alloc::string::String>,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -209,3 +468,506 @@ pub struct NetworkInfo {
     #[prost(string, repeated, tag = "4")]
     pub addrs: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
 }
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SoftwareInfo {
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub version: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub arch: ::prost::alloc::string::String,
+    /// 来源：dpkg、rpm、process(未归属任何包的运行中程序)
+    #[prost(string, tag = "4")]
+    pub source: ::prost::alloc::string::String,
+    /// 安装时间，unix 秒
+    #[prost(int64, optional, tag = "5")]
+    pub installed_at: ::core::option::Option<i64>,
+    /// process 来源的程序路径
+    #[prost(string, optional, tag = "6")]
+    pub path: ::core::option::Option<::prost::alloc::string::String>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SocketInfo {
+    /// tcp、tcp6、udp、udp6、unix
+    #[prost(string, tag = "1")]
+    pub protocol: ::prost::alloc::string::String,
+    /// LISTEN、ESTABLISHED，未连接的 udp 为 UNCONN
+    #[prost(string, tag = "2")]
+    pub state: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub local_addr: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "4")]
+    pub local_port: u32,
+    #[prost(string, tag = "5")]
+    pub remote_addr: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "6")]
+    pub remote_port: u32,
+    #[prost(uint64, tag = "7")]
+    pub inode: u64,
+    #[prost(uint32, optional, tag = "8")]
+    pub pid: ::core::option::Option<u32>,
+    #[prost(string, optional, tag = "9")]
+    pub process_name: ::core::option::Option<::prost::alloc::string::String>,
+    /// unix socket 路径
+    #[prost(string, optional, tag = "10")]
+    pub path: ::core::option::Option<::prost::alloc::string::String>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HardwareInfo {
+    /// /sys/class/dmi/id
+    #[prost(string, optional, tag = "1")]
+    pub sys_vendor: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "2")]
+    pub product_name: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "3")]
+    pub product_version: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "4")]
+    pub product_serial: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "5")]
+    pub board_vendor: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "6")]
+    pub board_name: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "7")]
+    pub bios_vendor: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "8")]
+    pub bios_version: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "9")]
+    pub bios_date: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "10")]
+    pub chassis_vendor: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "11")]
+    pub chassis_serial: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "12")]
+    pub chassis_asset_tag: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "13")]
+    pub cpu_model: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(uint32, tag = "14")]
+    pub cpu_sockets: u32,
+    #[prost(uint32, tag = "15")]
+    pub cpu_cores: u32,
+    #[prost(uint32, tag = "16")]
+    pub cpu_threads: u32,
+    /// 最大频率，MHz
+    #[prost(uint64, tag = "17")]
+    pub cpu_frequency: u64,
+    /// 已安装的内存条数量，读取 dmi 失败时为空
+    #[prost(uint32, optional, tag = "18")]
+    pub memory_modules: ::core::option::Option<u32>,
+    #[prost(message, repeated, tag = "19")]
+    pub pci_devices: ::prost::alloc::vec::Vec<PciDevice>,
+    #[prost(message, repeated, tag = "20")]
+    pub usb_devices: ::prost::alloc::vec::Vec<UsbDevice>,
+    #[prost(message, repeated, tag = "21")]
+    pub block_devices: ::prost::alloc::vec::Vec<BlockDevice>,
+    /// none、kvm、vmware、virtualbox、hyper-v、xen、container 等
+    #[prost(string, tag = "22")]
+    pub virtualization: ::prost::alloc::string::String,
+    /// aws、azure、gcp、aliyun、tencent、openstack 等
+    #[prost(string, optional, tag = "23")]
+    pub cloud_provider: ::core::option::Option<::prost::alloc::string::String>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PciDevice {
+    #[prost(string, tag = "1")]
+    pub slot: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub class_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub vendor_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub device_id: ::prost::alloc::string::String,
+    #[prost(string, optional, tag = "5")]
+    pub vendor: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "6")]
+    pub device: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "7")]
+    pub driver: ::core::option::Option<::prost::alloc::string::String>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UsbDevice {
+    #[prost(string, tag = "1")]
+    pub bus_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub vendor_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub product_id: ::prost::alloc::string::String,
+    #[prost(string, optional, tag = "4")]
+    pub manufacturer: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "5")]
+    pub product: ::core::option::Option<::prost::alloc::string::String>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BlockDevice {
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(string, optional, tag = "2")]
+    pub vendor: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "3")]
+    pub model: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "4")]
+    pub serial: ::core::option::Option<::prost::alloc::string::String>,
+    /// 字节
+    #[prost(uint64, tag = "5")]
+    pub size: u64,
+    #[prost(bool, tag = "6")]
+    pub rotational: bool,
+    #[prost(bool, tag = "7")]
+    pub removable: bool,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AccountInfo {
+    #[prost(message, repeated, tag = "1")]
+    pub users: ::prost::alloc::vec::Vec<UserAccount>,
+    #[prost(message, repeated, tag = "2")]
+    pub groups: ::prost::alloc::vec::Vec<UserGroup>,
+    /// utmp 中当前登录的会话
+    #[prost(message, repeated, tag = "3")]
+    pub sessions: ::prost::alloc::vec::Vec<LoginRecord>,
+    /// wtmp 中最近的登录记录
+    #[prost(message, repeated, tag = "4")]
+    pub logins: ::prost::alloc::vec::Vec<LoginRecord>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UserAccount {
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "2")]
+    pub uid: u32,
+    #[prost(uint32, tag = "3")]
+    pub gid: u32,
+    #[prost(string, tag = "4")]
+    pub home: ::prost::alloc::string::String,
+    #[prost(string, tag = "5")]
+    pub shell: ::prost::alloc::string::String,
+    #[prost(string, optional, tag = "6")]
+    pub gecos: ::core::option::Option<::prost::alloc::string::String>,
+    /// 主组和附加组
+    #[prost(string, repeated, tag = "7")]
+    pub groups: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// 属于 sudo、wheel、admin 组
+    #[prost(bool, tag = "8")]
+    pub sudo: bool,
+    /// shell 为 nologin、false 时不可登录
+    #[prost(bool, tag = "9")]
+    pub login: bool,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UserGroup {
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "2")]
+    pub gid: u32,
+    #[prost(string, repeated, tag = "3")]
+    pub members: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct LoginRecord {
+    #[prost(string, tag = "1")]
+    pub user: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub tty: ::prost::alloc::string::String,
+    #[prost(string, optional, tag = "3")]
+    pub host: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(uint32, optional, tag = "4")]
+    pub pid: ::core::option::Option<u32>,
+    /// unix 秒
+    #[prost(int64, tag = "5")]
+    pub login_at: i64,
+    #[prost(int64, optional, tag = "6")]
+    pub logout_at: ::core::option::Option<i64>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ServiceInfo {
+    /// unit 名称，如 nginx.service
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub description: ::prost::alloc::string::String,
+    /// loaded、not-found、masked 等
+    #[prost(string, tag = "3")]
+    pub load_state: ::prost::alloc::string::String,
+    /// active、inactive、failed 等
+    #[prost(string, tag = "4")]
+    pub active_state: ::prost::alloc::string::String,
+    /// running、exited、dead 等
+    #[prost(string, tag = "5")]
+    pub sub_state: ::prost::alloc::string::String,
+    /// enabled、disabled、static 等
+    #[prost(string, tag = "6")]
+    pub unit_file_state: ::prost::alloc::string::String,
+    #[prost(uint32, optional, tag = "7")]
+    pub main_pid: ::core::option::Option<u32>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ScheduledTask {
+    /// cron(/etc/crontab、/etc/cron.d)、user_cron、cron_script(/etc/cron.*)、systemd_timer
+    #[prost(string, tag = "1")]
+    pub source: ::prost::alloc::string::String,
+    /// 定义所在的文件，timer 为 unit 名称
+    #[prost(string, tag = "2")]
+    pub path: ::prost::alloc::string::String,
+    #[prost(string, optional, tag = "3")]
+    pub user: ::core::option::Option<::prost::alloc::string::String>,
+    /// cron 表达式、hourly 等周期或 timer 的 OnCalendar 等规则
+    #[prost(string, tag = "4")]
+    pub schedule: ::prost::alloc::string::String,
+    /// 执行的命令，timer 为空
+    #[prost(string, tag = "5")]
+    pub command: ::prost::alloc::string::String,
+    /// timer 触发的 unit
+    #[prost(string, optional, tag = "6")]
+    pub unit: ::core::option::Option<::prost::alloc::string::String>,
+    /// 上次、下次执行时间，unix 秒
+    #[prost(int64, optional, tag = "7")]
+    pub last_run: ::core::option::Option<i64>,
+    #[prost(int64, optional, tag = "8")]
+    pub next_run: ::core::option::Option<i64>,
+    /// cron 脚本的修改时间，unix 秒
+    #[prost(int64, optional, tag = "9")]
+    pub modified_at: ::core::option::Option<i64>,
+    /// 与上次采集相比新增或修改
+    #[prost(bool, tag = "10")]
+    pub changed: bool,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ServiceControl {
+    /// tbl_service_control 主键，结果上报时带回
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub unit: ::prost::alloc::string::String,
+    #[prost(enumeration = "service_control::Action", tag = "3")]
+    pub action: i32,
+}
+/// Nested message and enum types in `ServiceControl`.
+pub mod service_control {
+    #[derive(serde::Serialize, serde::Deserialize)]
+    #[derive(
+        Clone,
+        Copy,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash,
+        PartialOrd,
+        Ord,
+        ::prost::Enumeration
+    )]
+    #[repr(i32)]
+    pub enum Action {
+        Start = 0,
+        Stop = 1,
+        Restart = 2,
+        Enable = 3,
+        Disable = 4,
+    }
+    impl Action {
+        /// String value of the enum field names used in the ProtoBuf definition.
+        ///
+        /// The values are not transformed in any way and thus are considered stable
+        /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+        pub fn as_str_name(&self) -> &'static str {
+            match self {
+                Self::Start => "START",
+                Self::Stop => "STOP",
+                Self::Restart => "RESTART",
+                Self::Enable => "ENABLE",
+                Self::Disable => "DISABLE",
+            }
+        }
+        /// Creates an enum from field names used in the ProtoBuf definition.
+        pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+            match value {
+                "START" => Some(Self::Start),
+                "STOP" => Some(Self::Stop),
+                "RESTART" => Some(Self::Restart),
+                "ENABLE" => Some(Self::Enable),
+                "DISABLE" => Some(Self::Disable),
+                _ => None,
+            }
+        }
+    }
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ServiceControlResult {
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+    #[prost(bool, tag = "2")]
+    pub success: bool,
+    /// systemctl 的标准输出与错误输出
+    #[prost(string, tag = "3")]
+    pub output: ::prost::alloc::string::String,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MetricsReq {
+    #[prost(message, repeated, tag = "1")]
+    pub samples: ::prost::alloc::vec::Vec<MetricSample>,
+}
+/// 一次资源采样
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct MetricSample {
+    /// 采样时间，unix 毫秒
+    #[prost(int64, tag = "1")]
+    pub timestamp: i64,
+    /// CPU 使用率，百分比
+    #[prost(float, tag = "2")]
+    pub cpu_usage: f32,
+    #[prost(uint64, tag = "3")]
+    pub used_memory: u64,
+    #[prost(uint64, tag = "4")]
+    pub total_memory: u64,
+    #[prost(uint64, tag = "5")]
+    pub used_swap: u64,
+    #[prost(uint64, tag = "6")]
+    pub total_swap: u64,
+    #[prost(double, tag = "7")]
+    pub load_one: f64,
+    #[prost(double, tag = "8")]
+    pub load_five: f64,
+    #[prost(double, tag = "9")]
+    pub load_fifteen: f64,
+    /// 所有磁盘合计
+    #[prost(uint64, tag = "10")]
+    pub disk_used: u64,
+    #[prost(uint64, tag = "11")]
+    pub disk_total: u64,
+    /// 所有网卡合计，字节每秒
+    #[prost(double, tag = "12")]
+    pub rx_rate: f64,
+    #[prost(double, tag = "13")]
+    pub tx_rate: f64,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Upgrade {
+    /// tbl_agent_upgrade 主键，结果上报时带回
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+    /// 目标版本
+    #[prost(string, tag = "2")]
+    pub version: ::prost::alloc::string::String,
+    /// 升级包的制品 id，通过 Download 获取
+    #[prost(string, tag = "3")]
+    pub download_ref: ::prost::alloc::string::String,
+    /// 升级包的 sha256，十六进制小写
+    #[prost(string, tag = "4")]
+    pub sha256: ::prost::alloc::string::String,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpgradeResult {
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+    #[prost(enumeration = "upgrade_result::Status", tag = "2")]
+    pub status: i32,
+    #[prost(string, tag = "3")]
+    pub message: ::prost::alloc::string::String,
+}
+/// Nested message and enum types in `UpgradeResult`.
+pub mod upgrade_result {
+    #[derive(serde::Serialize, serde::Deserialize)]
+    #[derive(
+        Clone,
+        Copy,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash,
+        PartialOrd,
+        Ord,
+        ::prost::Enumeration
+    )]
+    #[repr(i32)]
+    pub enum Status {
+        /// 已替换可执行文件，正在重启
+        Installed = 0,
+        /// 下载、校验或替换失败，未重启
+        Failed = 1,
+        /// 新版本未能注册，已恢复原版本
+        RolledBack = 2,
+    }
+    impl Status {
+        /// String value of the enum field names used in the ProtoBuf definition.
+        ///
+        /// The values are not transformed in any way and thus are considered stable
+        /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+        pub fn as_str_name(&self) -> &'static str {
+            match self {
+                Self::Installed => "INSTALLED",
+                Self::Failed => "FAILED",
+                Self::RolledBack => "ROLLED_BACK",
+            }
+        }
+        /// Creates an enum from field names used in the ProtoBuf definition.
+        pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+            match value {
+                "INSTALLED" => Some(Self::Installed),
+                "FAILED" => Some(Self::Failed),
+                "ROLLED_BACK" => Some(Self::RolledBack),
+                _ => None,
+            }
+        }
+    }
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DownloadReq {
+    /// 制品 id，即内容的 sha256
+    #[prost(string, tag = "1")]
+    pub download_ref: ::prost::alloc::string::String,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DownloadChunk {
+    #[prost(bytes = "vec", tag = "1")]
+    pub data: ::prost::alloc::vec::Vec<u8>,
+    /// 发布密钥对 sha256 摘要的 ed25519 签名，仅第一个分块携带
+    #[prost(bytes = "vec", tag = "2")]
+    pub signature: ::prost::alloc::vec::Vec<u8>,
+}
+/// LLM 任务类型
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum LlmTaskKind {
+    /// 对话补全
+    Chat = 0,
+    /// 向量嵌入
+    Embedding = 1,
+}
+impl LlmTaskKind {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Chat => "CHAT",
+            Self::Embedding => "EMBEDDING",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "CHAT" => Some(Self::Chat),
+            "EMBEDDING" => Some(Self::Embedding),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,178 @@
+import React, { useEffect, useState } from "react";
+import { Button, Form, Input, message, Select, Table } from "antd";
+import restful_api from "./utils/restful_api.ts";
+import { useNavigate } from "react-router-dom";
+
+type Account = {
+  agent_id: string;
+  host_name?: string;
+  name: string;
+  uid: number;
+  gid: number;
+  home: string;
+  shell: string;
+  sudo: boolean;
+  login: boolean;
+};
+
+type Page = {
+  size: number;
+  total_elements: number;
+  total_pages: number;
+};
+
+type Filters = {
+  name?: string;
+  sudo?: boolean;
+  login?: boolean;
+};
+
+const App: React.FC = () => {
+  const navigate = useNavigate();
+  const [accounts, setAccounts] = useState<Account[]>([]);
+  const [current, setCurrent] = useState(1);
+  const [page_size, setPageSize] = useState(10);
+  const [page, setPage] = useState<Page>();
+  const [filters, setFilters] = useState<Filters>({});
+  const [loading, setLoading] = useState(false);
+
+  const handleQuery = async (page = current, size = page_size, f = filters) => {
+    const params = new URLSearchParams();
+    params.append("size", size.toString());
+    params.append("page", (page - 1).toString());
+    if (f.name) params.append("name", f.name);
+    if (f.sudo !== undefined) params.append("sudo", f.sudo.toString());
+    if (f.login !== undefined) params.append("login", f.login.toString());
+    setLoading(true);
+    try {
+      const response = await restful_api.get(
+        `/api/accounts?${params.toString()}`
+      );
+      setAccounts(response.data._embedded?.account || []);
+      setPage(response.data.page);
+      setCurrent(page);
+      setPageSize(size);
+      setFilters(f);
+      message.success("查询成功");
+    } catch (e) {
+      console.error("查询失败: ", e);
+      message.error("查询失败");
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const columns = [
+    {
+      title: "AgentId",
+      dataIndex: "agent_id",
+      key: "agent_id",
+      render: (agent_id: string) => (
+        <Button type="link" onClick={() => navigate(`/hosts/${agent_id}`)}>
+          {agent_id}
+        </Button>
+      ),
+    },
+    {
+      title: "Host Name",
+      dataIndex: "host_name",
+      key: "host_name",
+    },
+    {
+      title: "Name",
+      dataIndex: "name",
+      key: "name",
+    },
+    {
+      title: "Uid",
+      dataIndex: "uid",
+      key: "uid",
+    },
+    {
+      title: "Gid",
+      dataIndex: "gid",
+      key: "gid",
+    },
+    {
+      title: "Home",
+      dataIndex: "home",
+      key: "home",
+    },
+    {
+      title: "Shell",
+      dataIndex: "shell",
+      key: "shell",
+    },
+    {
+      title: "Sudo",
+      dataIndex: "sudo",
+      key: "sudo",
+      render: (v: boolean) => String(v),
+    },
+    {
+      title: "Login",
+      dataIndex: "login",
+      key: "login",
+      render: (v: boolean) => String(v),
+    },
+  ];
+
+  useEffect(() => {
+    handleQuery();
+  }, []);
+
+  return (
+    <>
+      <Form
+        layout="inline"
+        onFinish={(values) => handleQuery(1, page_size, values)}
+        style={{ marginTop: 16 }}
+      >
+        <Form.Item name="name" label="用户名">
+          <Input placeholder="请输入用户名关键字" />
+        </Form.Item>
+        <Form.Item name="sudo" label="管理员">
+          <Select
+            allowClear
+            style={{ width: 120 }}
+            options={[
+              { value: true, label: "是" },
+              { value: false, label: "否" },
+            ]}
+          />
+        </Form.Item>
+        <Form.Item name="login" label="可登录">
+          <Select
+            allowClear
+            style={{ width: 120 }}
+            options={[
+              { value: true, label: "是" },
+              { value: false, label: "否" },
+            ]}
+          />
+        </Form.Item>
+        <Form.Item>
+          <Button type="primary" htmlType="submit">
+            查询
+          </Button>
+        </Form.Item>
+      </Form>
+
+      <Table
+        dataSource={accounts}
+        columns={columns}
+        rowKey={(record) => `${record.agent_id}:${record.name}`}
+        loading={loading}
+        pagination={{
+          current: current,
+          pageSize: page_size,
+          total: page?.total_elements,
+          onChange: (page, size) => handleQuery(page, size),
+        }}
+        style={{ marginTop: 24 }}
+      />
+    </>
+  );
+};
+
+export default App;
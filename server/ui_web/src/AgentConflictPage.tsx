@@ -0,0 +1,206 @@
+import React, { useEffect, useState } from "react";
+import { Button, Form, Input, message, Select, Table, Tag } from "antd";
+import restful_api from "./utils/restful_api.ts";
+import dayjs from "dayjs";
+import { useNavigate } from "react-router-dom";
+import { hasPermission } from "./utils/permission";
+
+type Conflict = {
+  id: string;
+  agent_id: string;
+  assigned_agent_id: string;
+  machine_id?: string;
+  dmi_uuid?: string;
+  macs?: string;
+  container_id?: string;
+  hostname?: string;
+  peer_addr?: string;
+  online: boolean;
+  status: string;
+  created_at: number;
+  resolved_at?: number;
+};
+
+type Page = {
+  size: number;
+  total_elements: number;
+  total_pages: number;
+};
+
+type Filters = {
+  agent_id?: string;
+  status?: string;
+};
+
+const App: React.FC = () => {
+  const navigate = useNavigate();
+  const [conflicts, setConflicts] = useState<Conflict[]>([]);
+  const [current, setCurrent] = useState(1);
+  const [page_size, setPageSize] = useState(10);
+  const [page, setPage] = useState<Page>();
+  const [filters, setFilters] = useState<Filters>({ status: "open" });
+  const [loading, setLoading] = useState(false);
+
+  const handleQuery = async (page = current, size = page_size, f = filters) => {
+    const params = new URLSearchParams();
+    params.append("size", size.toString());
+    params.append("page", (page - 1).toString());
+    if (f.agent_id) params.append("agent_id", f.agent_id);
+    if (f.status) params.append("status", f.status);
+    setLoading(true);
+    try {
+      const response = await restful_api.get(
+        `/api/agent_conflicts?${params.toString()}`
+      );
+      setConflicts(response.data._embedded?.agent_conflict || []);
+      setPage(response.data.page);
+      setCurrent(page);
+      setPageSize(size);
+      setFilters(f);
+    } catch (e) {
+      console.error("查询失败: ", e);
+      message.error("查询失败");
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const handleStatus = async (id: string, status: string) => {
+    try {
+      await restful_api.patch(`/api/agent_conflicts/${id}`, { status });
+      message.success("修改成功");
+      handleQuery();
+    } catch (e) {
+      console.error("修改失败: ", e);
+      message.error("修改失败");
+    }
+  };
+
+  const agentLink = (id: string) =>
+    hasPermission("GET", "/api/agents/") ? (
+      <Button type="link" onClick={() => navigate(`/agents/${id}`)}>
+        {id}
+      </Button>
+    ) : (
+      id
+    );
+
+  const columns = [
+    {
+      title: "声明的编号",
+      dataIndex: "agent_id",
+      key: "agent_id",
+      render: agentLink,
+    },
+    {
+      title: "分配的编号",
+      dataIndex: "assigned_agent_id",
+      key: "assigned_agent_id",
+      render: agentLink,
+    },
+    {
+      title: "主机名",
+      dataIndex: "hostname",
+      key: "hostname",
+    },
+    {
+      title: "来源地址",
+      dataIndex: "peer_addr",
+      key: "peer_addr",
+    },
+    {
+      title: "类型",
+      dataIndex: "online",
+      key: "online",
+      render: (online: boolean) =>
+        online ? <Tag color="red">克隆</Tag> : <Tag>迁移或重装</Tag>,
+    },
+    {
+      title: "指纹",
+      key: "fingerprint",
+      render: (_: unknown, record: Conflict) => (
+        <>
+          <div>machine-id: {record.machine_id ?? "--"}</div>
+          <div>dmi uuid: {record.dmi_uuid ?? "--"}</div>
+          <div>mac: {record.macs ?? "--"}</div>
+          {record.container_id && <div>容器: {record.container_id}</div>}
+        </>
+      ),
+    },
+    {
+      title: "发现时间",
+      dataIndex: "created_at",
+      key: "created_at",
+      render: (timestamp: number) =>
+        dayjs(timestamp).format("YYYY-MM-DD HH:mm:ss"),
+    },
+    {
+      title: "操作",
+      key: "action",
+      render: (_: unknown, record: Conflict) =>
+        hasPermission("PATCH", "/api/agent_conflicts/") &&
+        (record.status === "open" ? (
+          <Button
+            type="link"
+            onClick={() => handleStatus(record.id, "resolved")}
+          >
+            标记已处理
+          </Button>
+        ) : (
+          <Button type="link" onClick={() => handleStatus(record.id, "open")}>
+            重新打开
+          </Button>
+        )),
+    },
+  ];
+
+  useEffect(() => {
+    handleQuery();
+  }, []);
+
+  return (
+    <>
+      <Form
+        layout="inline"
+        initialValues={filters}
+        onFinish={(values) => handleQuery(1, page_size, values)}
+        style={{ marginTop: 16 }}
+      >
+        <Form.Item name="agent_id" label="Agent ID">
+          <Input />
+        </Form.Item>
+        <Form.Item name="status" label="状态">
+          <Select
+            allowClear
+            style={{ width: 120 }}
+            options={[
+              { value: "open", label: "未处理" },
+              { value: "resolved", label: "已处理" },
+            ]}
+          />
+        </Form.Item>
+        <Form.Item>
+          <Button type="primary" htmlType="submit">
+            查询
+          </Button>
+        </Form.Item>
+      </Form>
+
+      <Table
+        dataSource={conflicts}
+        columns={columns}
+        rowKey="id"
+        loading={loading}
+        pagination={{
+          current: current,
+          pageSize: page_size,
+          total: page?.total_elements,
+          onChange: (page, size) => handleQuery(page, size),
+        }}
+        style={{ marginTop: 24 }}
+      />
+    </>
+  );
+};
+
+export default App;
@@ -0,0 +1,387 @@
+import React, { useEffect, useState } from "react";
+import {
+  Button,
+  Form,
+  Input,
+  InputNumber,
+  message,
+  Modal,
+  Popconfirm,
+  Select,
+  Table,
+  Typography,
+} from "antd";
+import restful_api from "./utils/restful_api.ts";
+import dayjs from "dayjs";
+import { hasPermission } from "./utils/permission";
+
+type Profile = {
+  id: string;
+  name: string;
+  version: number;
+  heartbeat_interval: number;
+  metrics_interval: number;
+  metrics_batch: number;
+  collect: string[];
+  collect_interval: number;
+  updated_at: number;
+};
+
+type ProfileInput = Omit<Profile, "id" | "version" | "updated_at">;
+
+type Binding = {
+  target_type: string;
+  target: string;
+  profile_id: string;
+  profile_name?: string;
+  created_at: number;
+};
+
+type Page = {
+  size: number;
+  total_elements: number;
+  total_pages: number;
+};
+
+const INFO_TYPES = [
+  "SYSTEM",
+  "DISK",
+  "NETWORK",
+  "SOFTWARE",
+  "SOCKET",
+  "HARDWARE",
+  "ACCOUNT",
+  "SERVICE",
+  "SCHEDULED_TASK",
+];
+
+const DEFAULT_PROFILE: ProfileInput = {
+  name: "",
+  heartbeat_interval: 5,
+  metrics_interval: 10,
+  metrics_batch: 6,
+  collect: [],
+  collect_interval: 0,
+};
+
+const App: React.FC = () => {
+  const [profileForm] = Form.useForm<ProfileInput>();
+  const [bindForm] = Form.useForm<Omit<Binding, "created_at">>();
+  const [profiles, setProfiles] = useState<Profile[]>([]);
+  const [bindings, setBindings] = useState<Binding[]>([]);
+  const [current, setCurrent] = useState(1);
+  const [page_size, setPageSize] = useState(10);
+  const [page, setPage] = useState<Page>();
+  const [loading, setLoading] = useState(false);
+  // undefined 表示未打开，null 表示新建
+  const [editing, setEditing] = useState<Profile | null>();
+
+  const handleQuery = async (page = current, size = page_size) => {
+    const params = new URLSearchParams();
+    params.append("size", size.toString());
+    params.append("page", (page - 1).toString());
+    setLoading(true);
+    try {
+      const response = await restful_api.get(
+        `/api/agent_profiles?${params.toString()}`
+      );
+      setProfiles(response.data._embedded?.agent_profile || []);
+      setPage(response.data.page);
+      setCurrent(page);
+      setPageSize(size);
+    } catch (e) {
+      console.error("查询失败: ", e);
+      message.error("查询失败");
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const handleBindingQuery = async () => {
+    try {
+      const response = await restful_api.get("/api/agent_profile_bindings");
+      setBindings(response.data.bindings || []);
+    } catch (e) {
+      console.error("查询分配失败: ", e);
+      message.error("查询分配失败");
+    }
+  };
+
+  const openEditor = (profile: Profile | null) => {
+    profileForm.setFieldsValue(profile ?? DEFAULT_PROFILE);
+    setEditing(profile);
+  };
+
+  const handleSave = async (values: ProfileInput) => {
+    try {
+      if (editing) {
+        await restful_api.patch(`/api/agent_profiles/${editing.id}`, values);
+      } else {
+        await restful_api.post("/api/agent_profiles", values);
+      }
+      message.success("保存成功");
+      setEditing(undefined);
+      handleQuery();
+    } catch (e) {
+      console.error("保存失败: ", e);
+      message.error("保存失败，请检查取值范围");
+    }
+  };
+
+  const handleDelete = async (id: string) => {
+    try {
+      await restful_api.delete(`/api/agent_profiles/${id}`);
+      message.success("删除成功");
+      handleQuery();
+    } catch (e) {
+      console.error("删除失败: ", e);
+      message.error("删除失败，可能仍分配给 agent 或分组");
+    }
+  };
+
+  const handleBind = async (values: Omit<Binding, "created_at">) => {
+    try {
+      await restful_api.put("/api/agent_profile_bindings", values);
+      message.success("分配成功");
+      bindForm.resetFields();
+      handleBindingQuery();
+    } catch (e) {
+      console.error("分配失败: ", e);
+      message.error("分配失败");
+    }
+  };
+
+  const handleUnbind = async (binding: Binding) => {
+    try {
+      await restful_api.delete(
+        `/api/agent_profile_bindings/${binding.target_type}/` +
+          encodeURIComponent(binding.target)
+      );
+      message.success("取消分配成功");
+      handleBindingQuery();
+    } catch (e) {
+      console.error("取消分配失败: ", e);
+      message.error("取消分配失败");
+    }
+  };
+
+  const columns = [
+    {
+      title: "名称",
+      dataIndex: "name",
+      key: "name",
+    },
+    {
+      title: "版本",
+      dataIndex: "version",
+      key: "version",
+    },
+    {
+      title: "心跳(秒)",
+      dataIndex: "heartbeat_interval",
+      key: "heartbeat_interval",
+    },
+    {
+      title: "指标采样(秒)/批量",
+      key: "metrics",
+      render: (_: unknown, record: Profile) =>
+        `${record.metrics_interval} / ${record.metrics_batch}`,
+    },
+    {
+      title: "定期采集",
+      key: "collect",
+      render: (_: unknown, record: Profile) =>
+        record.collect_interval > 0 && record.collect.length > 0
+          ? `${record.collect.join(", ")} 每 ${record.collect_interval} 秒`
+          : "-",
+    },
+    {
+      title: "修改时间",
+      dataIndex: "updated_at",
+      key: "updated_at",
+      render: (timestamp: number) =>
+        dayjs(timestamp).format("YYYY-MM-DD HH:mm:ss"),
+    },
+    {
+      title: "操作",
+      key: "action",
+      render: (_: unknown, record: Profile) => (
+        <>
+          {hasPermission("PATCH", "/api/agent_profiles/") && (
+            <Button type="link" onClick={() => openEditor(record)}>
+              修改
+            </Button>
+          )}
+          {hasPermission("DELETE", "/api/agent_profiles/") && (
+            <Popconfirm
+              title="确认删除该配置模板？"
+              onConfirm={() => handleDelete(record.id)}
+            >
+              <Button type="link" danger>
+                删除
+              </Button>
+            </Popconfirm>
+          )}
+        </>
+      ),
+    },
+  ];
+
+  const bindingColumns = [
+    {
+      title: "类型",
+      dataIndex: "target_type",
+      key: "target_type",
+      render: (v: string) => (v === "agent" ? "Agent" : "分组"),
+    },
+    {
+      title: "对象",
+      dataIndex: "target",
+      key: "target",
+    },
+    {
+      title: "配置模板",
+      key: "profile",
+      render: (_: unknown, record: Binding) =>
+        record.profile_name ?? record.profile_id,
+    },
+    {
+      title: "操作",
+      key: "action",
+      render: (_: unknown, record: Binding) =>
+        hasPermission("DELETE", "/api/agent_profile_bindings/") && (
+          <Popconfirm
+            title="确认取消分配？"
+            onConfirm={() => handleUnbind(record)}
+          >
+            <Button type="link" danger>
+              取消分配
+            </Button>
+          </Popconfirm>
+        ),
+    },
+  ];
+
+  useEffect(() => {
+    handleQuery();
+    handleBindingQuery();
+  }, []);
+
+  return (
+    <>
+      {hasPermission("POST", "/api/agent_profiles") && (
+        <Button
+          type="primary"
+          onClick={() => openEditor(null)}
+          style={{ marginTop: 16 }}
+        >
+          新建配置模板
+        </Button>
+      )}
+
+      <Table
+        dataSource={profiles}
+        columns={columns}
+        rowKey="id"
+        loading={loading}
+        pagination={{
+          current: current,
+          pageSize: page_size,
+          total: page?.total_elements,
+          onChange: (page, size) => handleQuery(page, size),
+        }}
+        style={{ marginTop: 16 }}
+      />
+
+      <Typography.Title level={5}>分配</Typography.Title>
+      {hasPermission("PUT", "/api/agent_profile_bindings") && (
+        <Form
+          form={bindForm}
+          layout="inline"
+          onFinish={handleBind}
+          initialValues={{ target_type: "group" }}
+        >
+          <Form.Item name="target_type" label="类型">
+            <Select
+              style={{ width: 100 }}
+              options={[
+                { value: "group", label: "分组" },
+                { value: "agent", label: "Agent" },
+              ]}
+            />
+          </Form.Item>
+          <Form.Item
+            name="target"
+            label="对象"
+            rules={[{ required: true, message: "请输入分组名或 agent_id" }]}
+          >
+            <Input placeholder="分组名或 agent_id" />
+          </Form.Item>
+          <Form.Item
+            name="profile_id"
+            label="配置模板"
+            rules={[{ required: true, message: "请选择配置模板" }]}
+          >
+            <Select
+              style={{ width: 200 }}
+              options={profiles.map((v) => ({ value: v.id, label: v.name }))}
+            />
+          </Form.Item>
+          <Form.Item>
+            <Button type="primary" htmlType="submit">
+              分配
+            </Button>
+          </Form.Item>
+        </Form>
+      )}
+      <Table
+        dataSource={bindings}
+        columns={bindingColumns}
+        rowKey={(v) => `${v.target_type}/${v.target}`}
+        pagination={false}
+        style={{ marginTop: 16 }}
+      />
+
+      <Modal
+        title={editing ? "修改配置模板" : "新建配置模板"}
+        open={editing !== undefined}
+        onOk={() => profileForm.submit()}
+        onCancel={() => setEditing(undefined)}
+        destroyOnClose
+      >
+        <Form form={profileForm} layout="vertical" onFinish={handleSave}>
+          <Form.Item
+            name="name"
+            label="名称"
+            rules={[{ required: true, message: "请输入名称" }]}
+          >
+            <Input />
+          </Form.Item>
+          <Form.Item name="heartbeat_interval" label="心跳间隔(秒)">
+            <InputNumber min={1} max={300} />
+          </Form.Item>
+          <Form.Item name="metrics_interval" label="指标采样间隔(秒)">
+            <InputNumber min={1} max={3600} />
+          </Form.Item>
+          <Form.Item name="metrics_batch" label="指标批量上报个数">
+            <InputNumber min={1} max={1000} />
+          </Form.Item>
+          <Form.Item name="collect" label="定期采集的主机信息">
+            <Select
+              mode="multiple"
+              options={INFO_TYPES.map((v) => ({ value: v, label: v }))}
+            />
+          </Form.Item>
+          <Form.Item
+            name="collect_interval"
+            label="定期采集间隔(秒)，0 表示不采集，否则不小于 60"
+          >
+            <InputNumber min={0} />
+          </Form.Item>
+        </Form>
+      </Modal>
+    </>
+  );
+};
+
+export default App;
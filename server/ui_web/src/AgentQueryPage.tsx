@@ -1,5 +1,15 @@
 import React, { useEffect, useState } from "react";
-import { Button, Form, Input, message, Table, Popconfirm } from "antd";
+import {
+  Button,
+  Form,
+  Input,
+  message,
+  Modal,
+  Table,
+  Tag,
+  Popconfirm,
+  Select,
+} from "antd";
 import restful_api from "./utils/restful_api.ts";
 import dayjs from "dayjs";
 import { useNavigate } from "react-router-dom";
@@ -7,6 +17,16 @@ import { hasPermission } from "./utils/permission";
 
 type Agent = {
   id: string;
+  group_name?: string;
+  profile_id?: string;
+  profile_version?: number;
+  approval_state: string;
+};
+
+const approvalStates: Record<string, { label: string; color: string }> = {
+  pending: { label: "待审批", color: "orange" },
+  approved: { label: "已批准", color: "green" },
+  blocked: { label: "已封禁", color: "red" },
 };
 
 type Page = {
@@ -22,16 +42,25 @@ const App: React.FC = () => {
   const [page_size, setPageSize] = useState(5);
   const [page, setPage] = useState<Page>();
   const [loading, setLoading] = useState(false);
+  const [grouping, setGrouping] = useState<Agent>();
+  const [groupName, setGroupName] = useState("");
 
   const handleQuery = async (
     page = current,
     size = page_size,
-    filters?: { title?: string; content?: string }
+    filters?: {
+      title?: string;
+      group_name?: string;
+      approval_state?: string;
+    }
   ) => {
     const params = new URLSearchParams();
     params.append("size", size.toString());
     params.append("page", (page - 1).toString());
     if (filters?.title) params.append("title", filters.title);
+    if (filters?.group_name) params.append("group_name", filters.group_name);
+    if (filters?.approval_state)
+      params.append("approval_state", filters.approval_state);
     setLoading(true);
     try {
       const response = await restful_api.get(
@@ -60,6 +89,32 @@ const App: React.FC = () => {
       message.error("删除失败");
     }
   };
+  const handleGroup = async () => {
+    if (!grouping) return;
+    try {
+      await restful_api.put(`/api/agents/${grouping.id}/group`, {
+        group_name: groupName.trim() || null,
+      });
+      message.success("修改分组成功");
+      setGrouping(undefined);
+      handleQuery();
+    } catch (error) {
+      console.error("修改分组失败:", error);
+      message.error("修改分组失败");
+    }
+  };
+
+  const handleApproval = async (id: string, approval_state: string) => {
+    try {
+      await restful_api.put(`/api/agent_approvals/${id}`, { approval_state });
+      message.success("修改成功");
+      handleQuery();
+    } catch (error) {
+      console.error("修改失败:", error);
+      message.error("修改失败");
+    }
+  };
+
   const columns = [
     {
       title: "ID",
@@ -76,6 +131,36 @@ const App: React.FC = () => {
       dataIndex: "state",
       key: "state",
     },
+    {
+      title: "审批",
+      dataIndex: "approval_state",
+      key: "approval_state",
+      render: (v: string) =>
+        approvalStates[v] ? (
+          <Tag color={approvalStates[v].color}>{approvalStates[v].label}</Tag>
+        ) : (
+          v
+        ),
+    },
+    {
+      title: "主机名",
+      dataIndex: "hostname",
+      key: "hostname",
+    },
+    {
+      title: "分组",
+      dataIndex: "group_name",
+      key: "group_name",
+      render: (v?: string) => v ?? "--",
+    },
+    {
+      title: "配置模板",
+      key: "profile",
+      render: (_: unknown, record: Agent) =>
+        record.profile_id
+          ? `${record.profile_id.slice(0, 8)} v${record.profile_version}`
+          : "本地配置",
+    },
     {
       title: "创建时间",
       dataIndex: "created_at",
@@ -103,10 +188,45 @@ const App: React.FC = () => {
               查看
             </Button>
           )}
+          {hasPermission("PUT", "/api/agents/") && (
+            <Button
+              type="link"
+              onClick={() => {
+                setGroupName(record.group_name ?? "");
+                setGrouping(record);
+              }}
+            >
+              分组
+            </Button>
+          )}
+          {hasPermission("PUT", "/api/agent_approvals/") && (
+            <>
+              {record.approval_state !== "approved" && (
+                <Button
+                  type="link"
+                  onClick={() => handleApproval(record.id, "approved")}
+                >
+                  批准
+                </Button>
+              )}
+              {record.approval_state !== "blocked" && (
+                <Popconfirm
+                  title="封禁后该 Agent 的所有请求将被拒绝，确定吗？"
+                  onConfirm={() => handleApproval(record.id, "blocked")}
+                  okText="确定"
+                  cancelText="取消"
+                >
+                  <Button danger type="link">
+                    封禁
+                  </Button>
+                </Popconfirm>
+              )}
+            </>
+          )}
           {hasPermission("DELETE", "/api/agents/") && (
             <>
               <Popconfirm
-                title="确定要删除这条记录吗？"
+                title="删除后吊销其客户端证书，确定吗？"
                 onConfirm={() => handleDelete(record.id)}
                 okText="确定"
                 cancelText="取消"
@@ -136,6 +256,19 @@ const App: React.FC = () => {
         <Form.Item name="title" label="标题">
           <Input placeholder="请输入标题关键字" />
         </Form.Item>
+        <Form.Item name="group_name" label="分组">
+          <Input />
+        </Form.Item>
+        <Form.Item name="approval_state" label="审批">
+          <Select
+            allowClear
+            style={{ width: 120 }}
+            options={Object.entries(approvalStates).map(([value, v]) => ({
+              value,
+              label: v.label,
+            }))}
+          />
+        </Form.Item>
         <Form.Item>
           <Button type="primary" htmlType="submit">
             查询
@@ -156,6 +289,19 @@ const App: React.FC = () => {
         }}
         style={{ marginTop: 24 }}
       />
+
+      <Modal
+        title="修改分组"
+        open={grouping !== undefined}
+        onOk={handleGroup}
+        onCancel={() => setGrouping(undefined)}
+      >
+        <Input
+          value={groupName}
+          onChange={(e) => setGroupName(e.target.value)}
+          placeholder="留空表示移出分组"
+        />
+      </Modal>
     </>
   );
 };
@@ -0,0 +1,262 @@
+import React, { useEffect, useState } from "react";
+import { Button, Form, Input, message, Select, Table, Tag } from "antd";
+import restful_api from "./utils/restful_api.ts";
+import dayjs from "dayjs";
+import { useNavigate } from "react-router-dom";
+import { hasPermission } from "./utils/permission";
+
+type AgentUpgrade = {
+  id: string;
+  agent_id: string;
+  from_version: string;
+  version: string;
+  download_ref: string;
+  status: string;
+  message?: string;
+  created_at: number;
+  updated_at: number;
+};
+
+type Page = {
+  size: number;
+  total_elements: number;
+  total_pages: number;
+};
+
+type Filters = {
+  agent_id?: string;
+  version?: string;
+  status?: string;
+};
+
+type UpgradeInput = {
+  agent_ids: string[];
+  artifact_id: string;
+};
+
+type Artifact = {
+  id: string;
+  name: string;
+  version: string;
+};
+
+const STATUSES = ["pending", "installed", "success", "failed", "rolled_back"];
+
+const status_colors: Record<string, string> = {
+  pending: "blue",
+  installed: "cyan",
+  success: "green",
+  failed: "red",
+  rolled_back: "orange",
+};
+
+const App: React.FC = () => {
+  const navigate = useNavigate();
+  const [upgradeForm] = Form.useForm<UpgradeInput>();
+  const [upgrades, setUpgrades] = useState<AgentUpgrade[]>([]);
+  const [current, setCurrent] = useState(1);
+  const [page_size, setPageSize] = useState(10);
+  const [page, setPage] = useState<Page>();
+  const [filters, setFilters] = useState<Filters>({});
+  const [loading, setLoading] = useState(false);
+  const [artifacts, setArtifacts] = useState<Artifact[]>([]);
+
+  const loadArtifacts = async () => {
+    try {
+      const response = await restful_api.get("/api/artifacts?size=100&page=0");
+      setArtifacts(response.data._embedded?.artifact || []);
+    } catch (e) {
+      console.error("制品查询失败: ", e);
+    }
+  };
+
+  const handleQuery = async (page = current, size = page_size, f = filters) => {
+    const params = new URLSearchParams();
+    params.append("size", size.toString());
+    params.append("page", (page - 1).toString());
+    if (f.agent_id) params.append("agent_id", f.agent_id);
+    if (f.version) params.append("version", f.version);
+    if (f.status) params.append("status", f.status);
+    setLoading(true);
+    try {
+      const response = await restful_api.get(
+        `/api/agent_upgrades?${params.toString()}`
+      );
+      setUpgrades(response.data._embedded?.agent_upgrade || []);
+      setPage(response.data.page);
+      setCurrent(page);
+      setPageSize(size);
+      setFilters(f);
+    } catch (e) {
+      console.error("查询失败: ", e);
+      message.error("查询失败");
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const handleUpgrade = async (values: UpgradeInput) => {
+    try {
+      await restful_api.post("/api/agent_upgrades", values);
+      message.success(
+        `已向 ${values.agent_ids.length} 个 Agent 下发升级`
+      );
+      upgradeForm.resetFields();
+      handleQuery(1);
+    } catch (e) {
+      console.error("下发失败: ", e);
+      message.error("下发失败");
+    }
+  };
+
+  const columns = [
+    {
+      title: "AgentId",
+      dataIndex: "agent_id",
+      key: "agent_id",
+      render: (agent_id: string) => (
+        <Button type="link" onClick={() => navigate(`/agents/${agent_id}`)}>
+          {agent_id}
+        </Button>
+      ),
+    },
+    {
+      title: "原版本",
+      dataIndex: "from_version",
+      key: "from_version",
+    },
+    {
+      title: "目标版本",
+      dataIndex: "version",
+      key: "version",
+    },
+    {
+      title: "升级包",
+      dataIndex: "download_ref",
+      key: "download_ref",
+      render: (download_ref: string) => download_ref.slice(0, 16),
+    },
+    {
+      title: "状态",
+      dataIndex: "status",
+      key: "status",
+      render: (status: string) => (
+        <Tag color={status_colors[status]}>{status}</Tag>
+      ),
+    },
+    {
+      title: "信息",
+      dataIndex: "message",
+      key: "message",
+      render: (message?: string) => message || "--",
+    },
+    {
+      title: "下发时间",
+      dataIndex: "created_at",
+      key: "created_at",
+      render: (timestamp: number) =>
+        dayjs(timestamp).format("YYYY-MM-DD HH:mm:ss"),
+    },
+    {
+      title: "更新时间",
+      dataIndex: "updated_at",
+      key: "updated_at",
+      render: (timestamp: number) =>
+        dayjs(timestamp).format("YYYY-MM-DD HH:mm:ss"),
+    },
+  ];
+
+  useEffect(() => {
+    handleQuery();
+    if (hasPermission("GET", "/api/artifacts")) {
+      loadArtifacts();
+    }
+  }, []);
+
+  return (
+    <>
+      {hasPermission("POST", "/api/agent_upgrades") && (
+        <Form
+          form={upgradeForm}
+          layout="inline"
+          onFinish={handleUpgrade}
+          style={{ marginTop: 16 }}
+        >
+          <Form.Item
+            name="agent_ids"
+            label="Agent"
+            rules={[{ required: true, message: "请输入 AgentId" }]}
+          >
+            <Select
+              mode="tags"
+              style={{ width: 320 }}
+              placeholder="输入或粘贴 AgentId"
+              tokenSeparators={[",", " ", "\n"]}
+            />
+          </Form.Item>
+          <Form.Item
+            name="artifact_id"
+            label="升级包"
+            rules={[{ required: true, message: "请选择升级包" }]}
+          >
+            <Select
+              style={{ width: 320 }}
+              showSearch
+              optionFilterProp="label"
+              options={artifacts.map((v) => ({
+                value: v.id,
+                label: `${v.name} ${v.version} (${v.id.slice(0, 8)})`,
+              }))}
+            />
+          </Form.Item>
+          <Form.Item>
+            <Button type="primary" htmlType="submit">
+              升级
+            </Button>
+          </Form.Item>
+        </Form>
+      )}
+
+      <Form
+        layout="inline"
+        onFinish={(values) => handleQuery(1, page_size, values)}
+        style={{ marginTop: 16 }}
+      >
+        <Form.Item name="agent_id" label="AgentId">
+          <Input />
+        </Form.Item>
+        <Form.Item name="version" label="目标版本">
+          <Input style={{ width: 120 }} />
+        </Form.Item>
+        <Form.Item name="status" label="状态">
+          <Select
+            allowClear
+            style={{ width: 140 }}
+            options={STATUSES.map((v) => ({ value: v, label: v }))}
+          />
+        </Form.Item>
+        <Form.Item>
+          <Button type="primary" htmlType="submit">
+            查询
+          </Button>
+        </Form.Item>
+      </Form>
+
+      <Table
+        dataSource={upgrades}
+        columns={columns}
+        rowKey="id"
+        loading={loading}
+        pagination={{
+          current: current,
+          pageSize: page_size,
+          total: page?.total_elements,
+          onChange: (page, size) => handleQuery(page, size),
+        }}
+        style={{ marginTop: 24 }}
+      />
+    </>
+  );
+};
+
+export default App;
@@ -14,18 +14,84 @@ const menuConfig = [
     label: "Agent管理",
     perm: ["GET", "/api/agents"],
   },
+  {
+    key: "/agent_upgrades",
+    icon: <UserOutlined />,
+    label: "Agent升级",
+    perm: ["GET", "/api/agent_upgrades"],
+  },
+  {
+    key: "/agent_conflicts",
+    icon: <UserOutlined />,
+    label: "Agent编号冲突",
+    perm: ["GET", "/api/agent_conflicts"],
+  },
+  {
+    key: "/agent_profiles",
+    icon: <UserOutlined />,
+    label: "Agent配置模板",
+    perm: ["GET", "/api/agent_profiles"],
+  },
+  {
+    key: "/enrollment_tokens",
+    icon: <UserOutlined />,
+    label: "注册令牌",
+    perm: ["GET", "/api/enrollment_tokens"],
+  },
+  {
+    key: "/certs",
+    icon: <UserOutlined />,
+    label: "证书管理",
+    perm: ["GET", "/api/certs"],
+  },
+  {
+    key: "/artifacts",
+    icon: <UserOutlined />,
+    label: "制品库",
+    perm: ["GET", "/api/artifacts"],
+  },
   {
     key: "/hosts",
     icon: <UserOutlined />,
     label: "主机管理",
     perm: ["GET", "/api/hosts"],
   },
+  {
+    key: "/softwares",
+    icon: <UserOutlined />,
+    label: "软件查询",
+    perm: ["GET", "/api/softwares"],
+  },
+  {
+    key: "/listen_ports",
+    icon: <UserOutlined />,
+    label: "端口查询",
+    perm: ["GET", "/api/listen_ports"],
+  },
+  {
+    key: "/accounts",
+    icon: <UserOutlined />,
+    label: "账号查询",
+    perm: ["GET", "/api/accounts"],
+  },
+  {
+    key: "/services",
+    icon: <UserOutlined />,
+    label: "服务查询",
+    perm: ["GET", "/api/services"],
+  },
   {
     key: "/llm_tasks",
     icon: <UserOutlined />,
     label: "任务管理",
     perm: ["GET", "/api/llm_tasks"],
   },
+  {
+    key: "/llm_consumers",
+    icon: <UserOutlined />,
+    label: "消费者管理",
+    perm: ["GET", "/api/llm_consumers"],
+  },
   {
     key: "/roles",
     icon: <UserOutlined />,
@@ -0,0 +1,258 @@
+import React, { useEffect, useState } from "react";
+import {
+  Button,
+  Form,
+  Input,
+  message,
+  Popconfirm,
+  Table,
+  Typography,
+  Upload,
+} from "antd";
+import { UploadOutlined } from "@ant-design/icons";
+import type { UploadFile } from "antd";
+import restful_api from "./utils/restful_api.ts";
+import dayjs from "dayjs";
+import { hasPermission } from "./utils/permission";
+
+type Artifact = {
+  id: string;
+  name: string;
+  version: string;
+  size: number;
+  signature: string;
+  created_at: number;
+};
+
+type Page = {
+  size: number;
+  total_elements: number;
+  total_pages: number;
+};
+
+type Filters = {
+  name?: string;
+  version?: string;
+};
+
+const formatSize = (size: number) => {
+  if (size < 1024) return `${size} B`;
+  if (size < 1024 * 1024) return `${(size / 1024).toFixed(1)} KB`;
+  return `${(size / 1024 / 1024).toFixed(1)} MB`;
+};
+
+const App: React.FC = () => {
+  const [uploadForm] = Form.useForm<{ version: string }>();
+  const [files, setFiles] = useState<UploadFile[]>([]);
+  const [signatures, setSignatures] = useState<UploadFile[]>([]);
+  const [uploading, setUploading] = useState(false);
+  const [artifacts, setArtifacts] = useState<Artifact[]>([]);
+  const [current, setCurrent] = useState(1);
+  const [page_size, setPageSize] = useState(10);
+  const [page, setPage] = useState<Page>();
+  const [filters, setFilters] = useState<Filters>({});
+  const [loading, setLoading] = useState(false);
+
+  const handleQuery = async (page = current, size = page_size, f = filters) => {
+    const params = new URLSearchParams();
+    params.append("size", size.toString());
+    params.append("page", (page - 1).toString());
+    if (f.name) params.append("name", f.name);
+    if (f.version) params.append("version", f.version);
+    setLoading(true);
+    try {
+      const response = await restful_api.get(
+        `/api/artifacts?${params.toString()}`
+      );
+      setArtifacts(response.data._embedded?.artifact || []);
+      setPage(response.data.page);
+      setCurrent(page);
+      setPageSize(size);
+      setFilters(f);
+    } catch (e) {
+      console.error("查询失败: ", e);
+      message.error("查询失败");
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const handleUpload = async (values: { version: string }) => {
+    const file = files[0]?.originFileObj;
+    if (!file) {
+      message.warning("请选择文件");
+      return;
+    }
+    const signature = signatures[0]?.originFileObj;
+    if (!signature) {
+      message.warning("请选择签名文件");
+      return;
+    }
+    setUploading(true);
+    try {
+      const formData = new FormData();
+      formData.append("version", values.version);
+      formData.append("signature", signature);
+      formData.append("file", file);
+      await restful_api.post("/api/artifacts", formData, {
+        headers: { "Content-Type": "multipart/form-data" },
+      });
+      message.success("上传成功");
+      uploadForm.resetFields();
+      setFiles([]);
+      setSignatures([]);
+      handleQuery(1);
+    } catch (e) {
+      console.error("上传失败: ", e);
+      message.error("上传失败");
+    } finally {
+      setUploading(false);
+    }
+  };
+
+  const handleDelete = async (id: string) => {
+    try {
+      await restful_api.delete(`/api/artifacts/${id}`);
+      message.success("删除成功");
+      handleQuery();
+    } catch (e) {
+      console.error("删除失败: ", e);
+      message.error("删除失败，可能仍有升级任务在使用");
+    }
+  };
+
+  const columns = [
+    {
+      title: "名称",
+      dataIndex: "name",
+      key: "name",
+    },
+    {
+      title: "版本",
+      dataIndex: "version",
+      key: "version",
+    },
+    {
+      title: "大小",
+      dataIndex: "size",
+      key: "size",
+      render: (size: number) => formatSize(size),
+    },
+    {
+      title: "SHA-256",
+      dataIndex: "id",
+      key: "id",
+      render: (id: string) => (
+        <Typography.Text copyable={{ text: id }}>
+          {id.slice(0, 16)}…
+        </Typography.Text>
+      ),
+    },
+    {
+      title: "上传时间",
+      dataIndex: "created_at",
+      key: "created_at",
+      render: (timestamp: number) =>
+        dayjs(timestamp).format("YYYY-MM-DD HH:mm:ss"),
+    },
+    {
+      title: "操作",
+      key: "action",
+      render: (_: unknown, record: Artifact) =>
+        hasPermission("DELETE", "/api/artifacts/") && (
+          <Popconfirm
+            title="确认删除该制品？"
+            onConfirm={() => handleDelete(record.id)}
+          >
+            <Button type="link" danger>
+              删除
+            </Button>
+          </Popconfirm>
+        ),
+    },
+  ];
+
+  useEffect(() => {
+    handleQuery();
+  }, []);
+
+  return (
+    <>
+      {hasPermission("POST", "/api/artifacts") && (
+        <Form
+          form={uploadForm}
+          layout="inline"
+          onFinish={handleUpload}
+          style={{ marginTop: 16 }}
+        >
+          <Form.Item
+            name="version"
+            label="版本"
+            rules={[{ required: true, message: "请输入版本" }]}
+          >
+            <Input style={{ width: 120 }} />
+          </Form.Item>
+          <Form.Item>
+            <Upload
+              beforeUpload={() => false}
+              fileList={files}
+              onChange={({ fileList }) => setFiles(fileList.slice(-1))}
+              maxCount={1}
+            >
+              <Button icon={<UploadOutlined />}>选择文件</Button>
+            </Upload>
+          </Form.Item>
+          <Form.Item>
+            <Upload
+              beforeUpload={() => false}
+              fileList={signatures}
+              onChange={({ fileList }) => setSignatures(fileList.slice(-1))}
+              maxCount={1}
+            >
+              <Button icon={<UploadOutlined />}>选择签名</Button>
+            </Upload>
+          </Form.Item>
+          <Form.Item>
+            <Button type="primary" htmlType="submit" loading={uploading}>
+              上传
+            </Button>
+          </Form.Item>
+        </Form>
+      )}
+
+      <Form
+        layout="inline"
+        onFinish={(values) => handleQuery(1, page_size, values)}
+        style={{ marginTop: 16 }}
+      >
+        <Form.Item name="name" label="名称">
+          <Input />
+        </Form.Item>
+        <Form.Item name="version" label="版本">
+          <Input style={{ width: 120 }} />
+        </Form.Item>
+        <Form.Item>
+          <Button type="primary" htmlType="submit">
+            查询
+          </Button>
+        </Form.Item>
+      </Form>
+
+      <Table
+        dataSource={artifacts}
+        columns={columns}
+        rowKey="id"
+        loading={loading}
+        pagination={{
+          current: current,
+          pageSize: page_size,
+          total: page?.total_elements,
+          onChange: (page, size) => handleQuery(page, size),
+        }}
+        style={{ marginTop: 24 }}
+      />
+    </>
+  );
+};
+
+export default App;
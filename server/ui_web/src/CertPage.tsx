@@ -0,0 +1,151 @@
+import React, { useEffect, useState } from "react";
+import { Alert, message, Table, Tag } from "antd";
+import restful_api from "./utils/restful_api.ts";
+import dayjs from "dayjs";
+
+type Cert = {
+  file: string;
+  subject: string;
+  issuer: string;
+  serial: string;
+  sans: string[];
+  is_ca: boolean;
+  not_before: number;
+  not_after: number;
+  days_left: number;
+  status: "valid" | "expiring" | "expired" | "revoked";
+};
+
+const STATUS_TAGS = {
+  valid: <Tag color="green">有效</Tag>,
+  expiring: <Tag color="orange">即将过期</Tag>,
+  expired: <Tag color="red">已过期</Tag>,
+  revoked: <Tag color="red">已吊销</Tag>,
+};
+
+const App: React.FC = () => {
+  const [certs, setCerts] = useState<Cert[]>([]);
+  const [warnDays, setWarnDays] = useState(30);
+  const [loading, setLoading] = useState(false);
+
+  const handleQuery = async () => {
+    setLoading(true);
+    try {
+      const response = await restful_api.get("/api/certs");
+      setCerts(response.data.certs);
+      setWarnDays(response.data.warn_days);
+    } catch (e) {
+      console.error("查询失败: ", e);
+      message.error("查询失败");
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const columns = [
+    {
+      title: "文件",
+      dataIndex: "file",
+      key: "file",
+    },
+    {
+      title: "主题",
+      dataIndex: "subject",
+      key: "subject",
+      render: (v: string, record: Cert) => (
+        <>
+          {v}
+          {record.is_ca && <Tag style={{ marginLeft: 8 }}>CA</Tag>}
+        </>
+      ),
+    },
+    {
+      title: "颁发者",
+      dataIndex: "issuer",
+      key: "issuer",
+    },
+    {
+      title: "SAN",
+      dataIndex: "sans",
+      key: "sans",
+      render: (v: string[]) => (v.length > 0 ? v.join(", ") : "--"),
+    },
+    {
+      title: "序列号",
+      dataIndex: "serial",
+      key: "serial",
+    },
+    {
+      title: "过期时间",
+      dataIndex: "not_after",
+      key: "not_after",
+      render: (timestamp: number) =>
+        dayjs(timestamp).format("YYYY-MM-DD HH:mm:ss"),
+    },
+    {
+      title: "剩余天数",
+      dataIndex: "days_left",
+      key: "days_left",
+    },
+    {
+      title: "状态",
+      dataIndex: "status",
+      key: "status",
+      render: (v: Cert["status"]) => STATUS_TAGS[v],
+    },
+  ];
+
+  useEffect(() => {
+    handleQuery();
+  }, []);
+
+  const expired = certs.filter((v) => v.status === "expired");
+  const expiring = certs.filter((v) => v.status === "expiring");
+  const revoked = certs.filter((v) => v.status === "revoked");
+
+  return (
+    <>
+      {expired.length > 0 && (
+        <Alert
+          type="error"
+          showIcon
+          message={
+            `${expired.length} 个证书已过期，` +
+            "请用 z11n_server ca 重新签发并导出"
+          }
+          style={{ marginTop: 16 }}
+        />
+      )}
+      {revoked.length > 0 && (
+        <Alert
+          type="error"
+          showIcon
+          message={
+            `${revoked.length} 个证书已吊销，agent 仍会信任，` +
+            "请用 z11n_server ca 重新签发并导出替换"
+          }
+          style={{ marginTop: 16 }}
+        />
+      )}
+      {expiring.length > 0 && (
+        <Alert
+          type="warning"
+          showIcon
+          message={`${expiring.length} 个证书将在 ${warnDays} 天内过期`}
+          style={{ marginTop: 16 }}
+        />
+      )}
+
+      <Table
+        dataSource={certs}
+        columns={columns}
+        rowKey={(record) => `${record.file}/${record.serial}`}
+        loading={loading}
+        pagination={false}
+        style={{ marginTop: 24 }}
+      />
+    </>
+  );
+};
+
+export default App;
@@ -0,0 +1,252 @@
+import React, { useEffect, useState } from "react";
+import {
+  Button,
+  Form,
+  Input,
+  InputNumber,
+  message,
+  Modal,
+  Popconfirm,
+  Switch,
+  Table,
+  Tag,
+  Typography,
+} from "antd";
+import restful_api from "./utils/restful_api.ts";
+import dayjs from "dayjs";
+import { hasPermission } from "./utils/permission";
+
+type Token = {
+  id: string;
+  name: string;
+  group_name?: string;
+  require_approval: boolean;
+  max_uses: number;
+  uses: number;
+  expires_at: number;
+  created_at: number;
+};
+
+type TokenInput = {
+  name: string;
+  group_name?: string;
+  require_approval: boolean;
+  max_uses: number;
+  // 有效天数，提交时换算为 expires_at
+  days: number;
+};
+
+type Page = {
+  size: number;
+  total_elements: number;
+  total_pages: number;
+};
+
+const DEFAULT_TOKEN: TokenInput = {
+  name: "",
+  require_approval: false,
+  max_uses: 100,
+  days: 7,
+};
+
+const App: React.FC = () => {
+  const [tokenForm] = Form.useForm<TokenInput>();
+  const [tokens, setTokens] = useState<Token[]>([]);
+  const [current, setCurrent] = useState(1);
+  const [page_size, setPageSize] = useState(10);
+  const [page, setPage] = useState<Page>();
+  const [loading, setLoading] = useState(false);
+  const [creating, setCreating] = useState(false);
+
+  const handleQuery = async (page = current, size = page_size) => {
+    const params = new URLSearchParams();
+    params.append("size", size.toString());
+    params.append("page", (page - 1).toString());
+    setLoading(true);
+    try {
+      const response = await restful_api.get(
+        `/api/enrollment_tokens?${params.toString()}`
+      );
+      setTokens(response.data._embedded?.enrollment_token || []);
+      setPage(response.data.page);
+      setCurrent(page);
+      setPageSize(size);
+    } catch (e) {
+      console.error("查询失败: ", e);
+      message.error("查询失败");
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const handleCreate = async (values: TokenInput) => {
+    try {
+      const response = await restful_api.post("/api/enrollment_tokens", {
+        name: values.name,
+        group_name: values.group_name?.trim() || null,
+        require_approval: values.require_approval,
+        max_uses: values.max_uses,
+        expires_at: dayjs().add(values.days, "day").valueOf(),
+      });
+      setCreating(false);
+      // 明文只返回一次
+      Modal.success({
+        title: "注册令牌已创建，请立即复制，关闭后无法再次查看",
+        content: (
+          <Typography.Paragraph copyable code>
+            {response.data.token}
+          </Typography.Paragraph>
+        ),
+      });
+      handleQuery();
+    } catch (e) {
+      console.error("创建失败: ", e);
+      message.error("创建失败，请检查取值范围");
+    }
+  };
+
+  const handleDelete = async (id: string) => {
+    try {
+      await restful_api.delete(`/api/enrollment_tokens/${id}`);
+      message.success("删除成功");
+      handleQuery();
+    } catch (e) {
+      console.error("删除失败: ", e);
+      message.error("删除失败");
+    }
+  };
+
+  const columns = [
+    {
+      title: "名称",
+      dataIndex: "name",
+      key: "name",
+    },
+    {
+      title: "分组",
+      dataIndex: "group_name",
+      key: "group_name",
+      render: (v?: string) => v ?? "--",
+    },
+    {
+      title: "需要审批",
+      dataIndex: "require_approval",
+      key: "require_approval",
+      render: (v: boolean) => (v ? <Tag color="orange">是</Tag> : "否"),
+    },
+    {
+      title: "使用次数",
+      key: "uses",
+      render: (_: unknown, record: Token) =>
+        `${record.uses} / ${record.max_uses}`,
+    },
+    {
+      title: "过期时间",
+      dataIndex: "expires_at",
+      key: "expires_at",
+      render: (timestamp: number) => (
+        <>
+          {dayjs(timestamp).format("YYYY-MM-DD HH:mm:ss")}
+          {timestamp <= Date.now() && (
+            <Tag style={{ marginLeft: 8 }}>已过期</Tag>
+          )}
+        </>
+      ),
+    },
+    {
+      title: "创建时间",
+      dataIndex: "created_at",
+      key: "created_at",
+      render: (timestamp: number) =>
+        dayjs(timestamp).format("YYYY-MM-DD HH:mm:ss"),
+    },
+    {
+      title: "操作",
+      key: "action",
+      render: (_: unknown, record: Token) =>
+        hasPermission("DELETE", "/api/enrollment_tokens/") && (
+          <Popconfirm
+            title="删除后不能再用它注册新 Agent，确定吗？"
+            onConfirm={() => handleDelete(record.id)}
+            okText="确定"
+            cancelText="取消"
+          >
+            <Button danger type="link">
+              删除
+            </Button>
+          </Popconfirm>
+        ),
+    },
+  ];
+
+  useEffect(() => {
+    handleQuery();
+  }, []);
+
+  return (
+    <>
+      {hasPermission("POST", "/api/enrollment_tokens") && (
+        <Button
+          type="primary"
+          onClick={() => {
+            tokenForm.setFieldsValue(DEFAULT_TOKEN);
+            setCreating(true);
+          }}
+          style={{ marginTop: 16 }}
+        >
+          新建注册令牌
+        </Button>
+      )}
+
+      <Table
+        dataSource={tokens}
+        columns={columns}
+        rowKey="id"
+        loading={loading}
+        pagination={{
+          current: current,
+          pageSize: page_size,
+          total: page?.total_elements,
+          onChange: (page, size) => handleQuery(page, size),
+        }}
+        style={{ marginTop: 24 }}
+      />
+
+      <Modal
+        title="新建注册令牌"
+        open={creating}
+        onOk={() => tokenForm.submit()}
+        onCancel={() => setCreating(false)}
+        destroyOnClose
+      >
+        <Form form={tokenForm} layout="vertical" onFinish={handleCreate}>
+          <Form.Item
+            name="name"
+            label="名称"
+            rules={[{ required: true, message: "请输入名称" }]}
+          >
+            <Input />
+          </Form.Item>
+          <Form.Item name="group_name" label="分组，注册的 Agent 加入该分组">
+            <Input placeholder="留空表示不分组" />
+          </Form.Item>
+          <Form.Item
+            name="require_approval"
+            label="需要审批，审批前只能心跳"
+            valuePropName="checked"
+          >
+            <Switch />
+          </Form.Item>
+          <Form.Item name="max_uses" label="最多注册次数">
+            <InputNumber min={1} />
+          </Form.Item>
+          <Form.Item name="days" label="有效天数">
+            <InputNumber min={1} max={365} />
+          </Form.Item>
+        </Form>
+      </Modal>
+    </>
+  );
+};
+
+export default App;
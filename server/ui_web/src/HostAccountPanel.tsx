@@ -0,0 +1,131 @@
+import React from "react";
+import { Empty, Table, Tag, Typography } from "antd";
+import type { TableProps } from "antd";
+import dayjs from "dayjs";
+
+interface UserAccountType {
+  name: string;
+  uid: number;
+  gid: number;
+  home: string;
+  shell: string;
+  gecos?: string;
+  groups: string[];
+  sudo: boolean;
+  login: boolean;
+}
+
+interface LoginRecordType {
+  user: string;
+  tty: string;
+  host?: string;
+  pid?: number;
+  login_at: number;
+  logout_at?: number;
+}
+
+export interface AccountType {
+  users: UserAccountType[];
+  sessions: LoginRecordType[];
+  logins: LoginRecordType[];
+}
+
+const user_columns: TableProps<UserAccountType>["columns"] = [
+  {
+    title: "Name",
+    dataIndex: "name",
+    key: "name",
+    sorter: (a, b) => a.name.localeCompare(b.name),
+  },
+  {
+    title: "Uid",
+    dataIndex: "uid",
+    key: "uid",
+    sorter: (a, b) => a.uid - b.uid,
+  },
+  { title: "Home", dataIndex: "home", key: "home" },
+  { title: "Shell", dataIndex: "shell", key: "shell" },
+  {
+    title: "Groups",
+    dataIndex: "groups",
+    key: "groups",
+    render: (groups: string[]) => groups.join(", "),
+  },
+  {
+    title: "Sudo",
+    dataIndex: "sudo",
+    key: "sudo",
+    filters: [
+      { text: "true", value: true },
+      { text: "false", value: false },
+    ],
+    onFilter: (value, record) => record.sudo === value,
+    render: (sudo: boolean) => (sudo ? <Tag color="red">sudo</Tag> : "--"),
+  },
+  {
+    title: "Login",
+    dataIndex: "login",
+    key: "login",
+    filters: [
+      { text: "true", value: true },
+      { text: "false", value: false },
+    ],
+    onFilter: (value, record) => record.login === value,
+    render: (login: boolean) => String(login),
+  },
+];
+
+const login_columns: TableProps<LoginRecordType>["columns"] = [
+  { title: "User", dataIndex: "user", key: "user" },
+  { title: "TTY", dataIndex: "tty", key: "tty" },
+  { title: "Host", dataIndex: "host", key: "host" },
+  { title: "Pid", dataIndex: "pid", key: "pid" },
+  {
+    title: "Login At",
+    dataIndex: "login_at",
+    key: "login_at",
+    render: (login_at: number) =>
+      dayjs.unix(login_at).format("YYYY-MM-DD HH:mm:ss"),
+  },
+  {
+    title: "Logout At",
+    dataIndex: "logout_at",
+    key: "logout_at",
+    render: (logout_at?: number) =>
+      logout_at ? dayjs.unix(logout_at).format("YYYY-MM-DD HH:mm:ss") : "--",
+  },
+];
+
+const App: React.FC<{ account?: AccountType }> = ({ account }) => {
+  if (!account) {
+    return <Empty />;
+  }
+  return (
+    <>
+      <Table<UserAccountType>
+        columns={user_columns}
+        dataSource={account.users}
+        rowKey="name"
+      />
+      <Typography.Title level={5} style={{ marginTop: 24 }}>
+        Sessions
+      </Typography.Title>
+      <Table<LoginRecordType>
+        columns={login_columns}
+        dataSource={account.sessions}
+        rowKey={(record) => `${record.tty}:${record.login_at}`}
+        pagination={false}
+      />
+      <Typography.Title level={5} style={{ marginTop: 24 }}>
+        Recent Logins
+      </Typography.Title>
+      <Table<LoginRecordType>
+        columns={login_columns}
+        dataSource={account.logins}
+        rowKey={(record) => `${record.tty}:${record.login_at}`}
+      />
+    </>
+  );
+};
+
+export default App;
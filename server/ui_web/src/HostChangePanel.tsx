@@ -0,0 +1,125 @@
+import React, { useEffect, useState } from "react";
+import { Select, Table, Tag } from "antd";
+import type { TableProps } from "antd";
+import dayjs from "dayjs";
+import restful_api from "./utils/restful_api.ts";
+
+interface HostChangeType {
+  version: number;
+  info_type: string;
+  item: string;
+  change: string;
+  created_at: number;
+}
+
+type Page = {
+  size: number;
+  total_elements: number;
+  total_pages: number;
+};
+
+const INFO_TYPES = [
+  "system",
+  "disk",
+  "network",
+  "software",
+  "socket",
+  "hardware",
+  "account",
+  "service",
+  "scheduled_task",
+];
+
+const change_colors: Record<string, string> = {
+  added: "green",
+  removed: "red",
+  changed: "blue",
+};
+
+const columns: TableProps<HostChangeType>["columns"] = [
+  {
+    title: "Created At",
+    dataIndex: "created_at",
+    key: "created_at",
+    render: (created_at: number) =>
+      dayjs(created_at).format("YYYY-MM-DD HH:mm:ss"),
+  },
+  { title: "Version", dataIndex: "version", key: "version" },
+  { title: "Type", dataIndex: "info_type", key: "info_type" },
+  { title: "Item", dataIndex: "item", key: "item" },
+  {
+    title: "Change",
+    dataIndex: "change",
+    key: "change",
+    render: (change: string) => (
+      <Tag color={change_colors[change]}>{change}</Tag>
+    ),
+  },
+];
+
+const App: React.FC<{ agentId: string }> = ({ agentId }) => {
+  const [changes, setChanges] = useState<HostChangeType[]>([]);
+  const [current, setCurrent] = useState(1);
+  const [page_size, setPageSize] = useState(10);
+  const [page, setPage] = useState<Page>();
+  const [info_type, setInfoType] = useState<string>();
+  const [loading, setLoading] = useState(false);
+
+  const handleQuery = async (
+    page = current,
+    size = page_size,
+    t = info_type
+  ) => {
+    const params = new URLSearchParams();
+    params.append("size", size.toString());
+    params.append("page", (page - 1).toString());
+    if (t) params.append("info_type", t);
+    setLoading(true);
+    try {
+      const response = await restful_api.get(
+        `/api/hosts/${agentId}/changes?${params.toString()}`
+      );
+      setChanges(response.data._embedded?.host_change || []);
+      setPage(response.data.page);
+      setCurrent(page);
+      setPageSize(size);
+      setInfoType(t);
+    } catch (e) {
+      console.error("查询主机变化记录失败: ", e);
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  useEffect(() => {
+    handleQuery(1);
+  }, [agentId]);
+
+  return (
+    <>
+      <Select
+        allowClear
+        placeholder="Type"
+        style={{ width: 200, marginBottom: 16 }}
+        options={INFO_TYPES.map((v) => ({ value: v, label: v }))}
+        onChange={(value?: string) => handleQuery(1, page_size, value)}
+      />
+      <Table<HostChangeType>
+        columns={columns}
+        dataSource={changes}
+        rowKey={(record) =>
+          `${record.version}:${record.info_type}:${record.item}`
+        }
+        loading={loading}
+        pagination={{
+          current: current,
+          pageSize: page_size,
+          total: page?.total_elements,
+          onChange: (page, size) => handleQuery(page, size),
+        }}
+      />
+    </>
+  );
+};
+
+export default App;
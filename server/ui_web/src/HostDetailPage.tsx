@@ -3,13 +3,19 @@ import { useParams } from "react-router-dom";
 import { Descriptions, Spin, Tabs, Table } from "antd";
 import type { DescriptionsProps, TabsProps, TableProps } from "antd";
 import restful_api from "./utils/restful_api.ts";
-
-interface ProcessType {
-  pid: number;
-  name: string;
-  exe: string;
-  status: string;
-}
+import dayjs from "dayjs";
+import HostMetricsPanel from "./HostMetricsPanel.tsx";
+import HostProcessPanel from "./HostProcessPanel.tsx";
+import HostHardwarePanel from "./HostHardwarePanel.tsx";
+import type { HardwareType } from "./HostHardwarePanel.tsx";
+import HostAccountPanel from "./HostAccountPanel.tsx";
+import type { AccountType } from "./HostAccountPanel.tsx";
+import HostServicePanel from "./HostServicePanel.tsx";
+import type { ServiceType } from "./HostServicePanel.tsx";
+import HostScheduledTaskPanel from "./HostScheduledTaskPanel.tsx";
+import type { ScheduledTaskType } from "./HostScheduledTaskPanel.tsx";
+import HostChangePanel from "./HostChangePanel.tsx";
+import HostSnapshotPanel from "./HostSnapshotPanel.tsx";
 
 interface DiskType {
   name: string;
@@ -29,28 +35,27 @@ interface NetworkType {
   addrs: [string];
 }
 
-const process_columns: TableProps<ProcessType>["columns"] = [
-  {
-    title: "Pid",
-    dataIndex: "pid",
-    key: "pid",
-  },
-  {
-    title: "Name",
-    dataIndex: "name",
-    key: "name",
-  },
-  {
-    title: "Exe",
-    dataIndex: "exe",
-    key: "exe",
-  },
-  {
-    title: "Status",
-    dataIndex: "status",
-    key: "status",
-  },
-];
+interface SoftwareType {
+  name: string;
+  version: string;
+  arch: string;
+  source: string;
+  installed_at?: number;
+  path?: string;
+}
+
+interface SocketType {
+  protocol: string;
+  state: string;
+  local_addr: string;
+  local_port: number;
+  remote_addr: string;
+  remote_port: number;
+  inode: number;
+  pid?: number;
+  process_name?: string;
+  path?: string;
+}
 
 const disk_columns: TableProps<DiskType>["columns"] = [
   {
@@ -118,6 +123,99 @@ const network_columns: TableProps<NetworkType>["columns"] = [
   },
 ];
 
+const software_columns: TableProps<SoftwareType>["columns"] = [
+  {
+    title: "Name",
+    dataIndex: "name",
+    key: "name",
+    sorter: (a, b) => a.name.localeCompare(b.name),
+  },
+  {
+    title: "Version",
+    dataIndex: "version",
+    key: "version",
+  },
+  {
+    title: "Arch",
+    dataIndex: "arch",
+    key: "arch",
+  },
+  {
+    title: "Source",
+    dataIndex: "source",
+    key: "source",
+    filters: [
+      { text: "dpkg", value: "dpkg" },
+      { text: "rpm", value: "rpm" },
+      { text: "process", value: "process" },
+    ],
+    onFilter: (value, record) => record.source === value,
+  },
+  {
+    title: "Installed At",
+    dataIndex: "installed_at",
+    key: "installed_at",
+    render: (timestamp?: number) =>
+      timestamp
+        ? dayjs(timestamp * 1000).format("YYYY-MM-DD HH:mm:ss")
+        : "--",
+  },
+  {
+    title: "Path",
+    dataIndex: "path",
+    key: "path",
+  },
+];
+
+const socket_columns: TableProps<SocketType>["columns"] = [
+  {
+    title: "Protocol",
+    dataIndex: "protocol",
+    key: "protocol",
+    filters: ["tcp", "tcp6", "udp", "udp6", "unix"].map((v) => ({
+      text: v,
+      value: v,
+    })),
+    onFilter: (value, record) => record.protocol === value,
+  },
+  {
+    title: "State",
+    dataIndex: "state",
+    key: "state",
+    filters: ["LISTEN", "UNCONN", "ESTABLISHED"].map((v) => ({
+      text: v,
+      value: v,
+    })),
+    onFilter: (value, record) => record.state === value,
+  },
+  {
+    title: "Local",
+    key: "local",
+    render: (_, record) =>
+      record.protocol === "unix"
+        ? (record.path ?? "--")
+        : `${record.local_addr}:${record.local_port}`,
+  },
+  {
+    title: "Remote",
+    key: "remote",
+    render: (_, record) =>
+      record.state === "ESTABLISHED"
+        ? `${record.remote_addr}:${record.remote_port}`
+        : "--",
+  },
+  {
+    title: "Pid",
+    dataIndex: "pid",
+    key: "pid",
+  },
+  {
+    title: "Process",
+    dataIndex: "process_name",
+    key: "process_name",
+  },
+];
+
 const onChange = (key: string) => {
   console.log(key);
 };
@@ -140,9 +238,16 @@ const App: React.FC = () => {
   const [systemItems, setSystemItems] = useState<DescriptionsProps["items"]>(
     []
   );
-  const [processItems, setProcessItems] = useState<ProcessType[]>([]);
   const [diskItems, setDiskItems] = useState<DiskType[]>([]);
   const [networkItems, setNetworkItems] = useState<NetworkType[]>([]);
+  const [softwareItems, setSoftwareItems] = useState<SoftwareType[]>([]);
+  const [socketItems, setSocketItems] = useState<SocketType[]>([]);
+  const [hardware, setHardware] = useState<HardwareType>();
+  const [account, setAccount] = useState<AccountType>();
+  const [serviceItems, setServiceItems] = useState<ServiceType[]>([]);
+  const [scheduledTaskItems, setScheduledTaskItems] = useState<
+    ScheduledTaskType[]
+  >([]);
   const [loading, setLoading] = useState(true);
   const tabItems: TabsProps["items"] = [
     {
@@ -152,15 +257,15 @@ const App: React.FC = () => {
         <Descriptions title="System Info" bordered items={systemItems} />
       ),
     },
+    {
+      key: "hardware",
+      label: "Hardware",
+      children: <HostHardwarePanel hardware={hardware} />,
+    },
     {
       key: "process",
       label: "Process",
-      children: (
-        <Table<ProcessType>
-          columns={process_columns}
-          dataSource={processItems}
-        />
-      ),
+      children: <HostProcessPanel agentId={id ?? ""} />,
     },
     {
       key: "disk",
@@ -179,15 +284,78 @@ const App: React.FC = () => {
         />
       ),
     },
+    {
+      key: "software",
+      label: "Software",
+      children: (
+        <Table<SoftwareType>
+          columns={software_columns}
+          dataSource={softwareItems}
+          rowKey={(record) =>
+            `${record.source}:${record.name}:${record.arch}:${record.path ?? ""}`
+          }
+        />
+      ),
+    },
+    {
+      key: "socket",
+      label: "Socket",
+      children: (
+        <Table<SocketType>
+          columns={socket_columns}
+          dataSource={socketItems}
+          rowKey={(record) => `${record.protocol}:${record.inode}`}
+        />
+      ),
+    },
+    {
+      key: "account",
+      label: "Account",
+      children: <HostAccountPanel account={account} />,
+    },
+    {
+      key: "service",
+      label: "Service",
+      children: (
+        <HostServicePanel agentId={id ?? ""} services={serviceItems} />
+      ),
+    },
+    {
+      key: "scheduled_task",
+      label: "Scheduled Task",
+      children: (
+        <HostScheduledTaskPanel scheduledTasks={scheduledTaskItems} />
+      ),
+    },
+    {
+      key: "change",
+      label: "Change",
+      children: <HostChangePanel agentId={id ?? ""} />,
+    },
+    {
+      key: "snapshot",
+      label: "Snapshot",
+      children: <HostSnapshotPanel agentId={id ?? ""} />,
+    },
+    {
+      key: "metrics",
+      label: "Metrics",
+      children: <HostMetricsPanel agentId={id ?? ""} />,
+    },
   ];
   useEffect(() => {
     restful_api
       .get(`/api/hosts/${id}`)
       .then((res) => {
         setSystemItems(jsonToDescriptionsItems(res.data.system));
-        setProcessItems(res.data.system.processes);
         setDiskItems(res.data.disks);
         setNetworkItems(res.data.networks);
+        setSoftwareItems(res.data.softwares ?? []);
+        setSocketItems(res.data.sockets ?? []);
+        setHardware(res.data.hardware ?? undefined);
+        setAccount(res.data.account ?? undefined);
+        setServiceItems(res.data.services ?? []);
+        setScheduledTaskItems(res.data.scheduled_tasks ?? []);
       })
       .catch((err) => {
         console.error("Failed to fetch system info:", err);
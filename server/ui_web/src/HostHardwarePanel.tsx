@@ -0,0 +1,212 @@
+import React from "react";
+import { Descriptions, Empty, Table, Typography } from "antd";
+import type { DescriptionsProps, TableProps } from "antd";
+
+interface PciDeviceType {
+  slot: string;
+  class_id: string;
+  vendor_id: string;
+  device_id: string;
+  vendor?: string;
+  device?: string;
+  driver?: string;
+}
+
+interface UsbDeviceType {
+  bus_id: string;
+  vendor_id: string;
+  product_id: string;
+  manufacturer?: string;
+  product?: string;
+}
+
+interface BlockDeviceType {
+  name: string;
+  vendor?: string;
+  model?: string;
+  serial?: string;
+  size: number;
+  rotational: boolean;
+  removable: boolean;
+}
+
+export interface HardwareType {
+  sys_vendor?: string;
+  product_name?: string;
+  product_version?: string;
+  product_serial?: string;
+  board_vendor?: string;
+  board_name?: string;
+  bios_vendor?: string;
+  bios_version?: string;
+  bios_date?: string;
+  chassis_vendor?: string;
+  chassis_serial?: string;
+  chassis_asset_tag?: string;
+  cpu_model?: string;
+  cpu_sockets: number;
+  cpu_cores: number;
+  cpu_threads: number;
+  cpu_frequency: number;
+  memory_modules?: number;
+  pci_devices: PciDeviceType[];
+  usb_devices: UsbDeviceType[];
+  block_devices: BlockDeviceType[];
+  virtualization: string;
+  cloud_provider?: string;
+}
+
+const GB = 1000 * 1000 * 1000;
+
+const pci_columns: TableProps<PciDeviceType>["columns"] = [
+  { title: "Slot", dataIndex: "slot", key: "slot" },
+  { title: "Class", dataIndex: "class_id", key: "class_id" },
+  {
+    title: "Vendor",
+    key: "vendor",
+    render: (_, record) => record.vendor ?? record.vendor_id,
+  },
+  {
+    title: "Device",
+    key: "device",
+    render: (_, record) => record.device ?? record.device_id,
+  },
+  { title: "Driver", dataIndex: "driver", key: "driver" },
+];
+
+const usb_columns: TableProps<UsbDeviceType>["columns"] = [
+  { title: "Bus Id", dataIndex: "bus_id", key: "bus_id" },
+  {
+    title: "Id",
+    key: "id",
+    render: (_, record) => `${record.vendor_id}:${record.product_id}`,
+  },
+  { title: "Manufacturer", dataIndex: "manufacturer", key: "manufacturer" },
+  { title: "Product", dataIndex: "product", key: "product" },
+];
+
+const block_columns: TableProps<BlockDeviceType>["columns"] = [
+  { title: "Name", dataIndex: "name", key: "name" },
+  { title: "Vendor", dataIndex: "vendor", key: "vendor" },
+  { title: "Model", dataIndex: "model", key: "model" },
+  { title: "Serial", dataIndex: "serial", key: "serial" },
+  {
+    title: "Size (GB)",
+    dataIndex: "size",
+    key: "size",
+    render: (size: number) => (size / GB).toFixed(1),
+  },
+  {
+    title: "Rotational",
+    dataIndex: "rotational",
+    key: "rotational",
+    render: (v: boolean) => String(v),
+  },
+  {
+    title: "Removable",
+    dataIndex: "removable",
+    key: "removable",
+    render: (v: boolean) => String(v),
+  },
+];
+
+const App: React.FC<{ hardware?: HardwareType }> = ({ hardware }) => {
+  if (!hardware) {
+    return <Empty />;
+  }
+  const items: DescriptionsProps["items"] = [
+    { key: "sys_vendor", label: "Vendor", children: hardware.sys_vendor },
+    { key: "product_name", label: "Product", children: hardware.product_name },
+    {
+      key: "product_version",
+      label: "Product Version",
+      children: hardware.product_version,
+    },
+    {
+      key: "product_serial",
+      label: "Product Serial",
+      children: hardware.product_serial,
+    },
+    {
+      key: "board",
+      label: "Board",
+      children: [hardware.board_vendor, hardware.board_name]
+        .filter(Boolean)
+        .join(" "),
+    },
+    {
+      key: "bios",
+      label: "BIOS",
+      children: [
+        hardware.bios_vendor,
+        hardware.bios_version,
+        hardware.bios_date,
+      ]
+        .filter(Boolean)
+        .join(" "),
+    },
+    {
+      key: "chassis_serial",
+      label: "Chassis Serial",
+      children: hardware.chassis_serial,
+    },
+    { key: "cpu_model", label: "CPU", children: hardware.cpu_model },
+    {
+      key: "cpu_topology",
+      label: "Sockets / Cores / Threads",
+      children: `${hardware.cpu_sockets} / ${hardware.cpu_cores} / ${hardware.cpu_threads}`,
+    },
+    {
+      key: "cpu_frequency",
+      label: "CPU Frequency (MHz)",
+      children: hardware.cpu_frequency,
+    },
+    {
+      key: "memory_modules",
+      label: "Memory Modules",
+      children: hardware.memory_modules ?? "--",
+    },
+    {
+      key: "virtualization",
+      label: "Virtualization",
+      children: hardware.virtualization,
+    },
+    {
+      key: "cloud_provider",
+      label: "Cloud Provider",
+      children: hardware.cloud_provider ?? "--",
+    },
+  ];
+  return (
+    <>
+      <Descriptions title="Hardware" bordered items={items} />
+      <Typography.Title level={5} style={{ marginTop: 24 }}>
+        Block Devices
+      </Typography.Title>
+      <Table<BlockDeviceType>
+        columns={block_columns}
+        dataSource={hardware.block_devices}
+        rowKey="name"
+        pagination={false}
+      />
+      <Typography.Title level={5} style={{ marginTop: 24 }}>
+        PCI Devices
+      </Typography.Title>
+      <Table<PciDeviceType>
+        columns={pci_columns}
+        dataSource={hardware.pci_devices}
+        rowKey="slot"
+      />
+      <Typography.Title level={5} style={{ marginTop: 24 }}>
+        USB Devices
+      </Typography.Title>
+      <Table<UsbDeviceType>
+        columns={usb_columns}
+        dataSource={hardware.usb_devices}
+        rowKey="bus_id"
+      />
+    </>
+  );
+};
+
+export default App;
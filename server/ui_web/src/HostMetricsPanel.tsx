@@ -0,0 +1,151 @@
+import React, { useEffect, useState } from "react";
+import { Card, Col, Empty, Row, Segmented, Spin, message } from "antd";
+import { Line } from "@ant-design/charts";
+import restful_api from "./utils/restful_api.ts";
+import dayjs from "dayjs";
+
+type Metric = {
+  timestamp: number;
+  samples: number;
+  cpu_avg: number;
+  cpu_max: number;
+  used_memory: number;
+  total_memory: number;
+  used_swap: number;
+  total_swap: number;
+  load_one: number;
+  load_five: number;
+  load_fifteen: number;
+  disk_used: number;
+  disk_total: number;
+  rx_rate: number;
+  tx_rate: number;
+};
+
+type Point = {
+  time: string;
+  value: number;
+  type: string;
+};
+
+// 查询时间跨度，单位小时
+const RANGES = [
+  { label: "1小时", value: 1 },
+  { label: "24小时", value: 24 },
+  { label: "7天", value: 24 * 7 },
+  { label: "30天", value: 24 * 30 },
+];
+
+const GB = 1024 * 1024 * 1024;
+const KB = 1024;
+
+function toPoints(
+  metrics: Metric[],
+  format: string,
+  series: Record<string, (metric: Metric) => number>
+): Point[] {
+  const points: Point[] = [];
+  for (const metric of metrics) {
+    const time = dayjs(metric.timestamp).format(format);
+    for (const [type, value] of Object.entries(series)) {
+      points.push({ time, type, value: Number(value(metric).toFixed(2)) });
+    }
+  }
+  return points;
+}
+
+const MetricChart: React.FC<{ title: string; data: Point[] }> = ({
+  title,
+  data,
+}) => (
+  <Card title={title} size="small">
+    {data.length === 0 ? (
+      <Empty />
+    ) : (
+      <Line
+        data={data}
+        xField="time"
+        yField="value"
+        colorField="type"
+        height={240}
+      />
+    )}
+  </Card>
+);
+
+const App: React.FC<{ agentId: string }> = ({ agentId }) => {
+  const [range, setRange] = useState(1);
+  const [metrics, setMetrics] = useState<Metric[]>([]);
+  const [loading, setLoading] = useState(false);
+
+  useEffect(() => {
+    const end = Date.now();
+    const start = end - range * 3600 * 1000;
+    setLoading(true);
+    restful_api
+      .get(`/api/hosts/${agentId}/metrics?start=${start}&end=${end}`)
+      .then((res) => {
+        setMetrics(res.data.metrics ?? []);
+      })
+      .catch((err) => {
+        console.error("Failed to fetch metrics:", err);
+        message.error("查询失败");
+      })
+      .finally(() => {
+        setLoading(false);
+      });
+  }, [agentId, range]);
+
+  const format = range > 24 ? "MM-DD HH:mm" : "HH:mm";
+  return (
+    <Spin spinning={loading}>
+      <Segmented
+        options={RANGES}
+        value={range}
+        onChange={(value) => setRange(value as number)}
+        style={{ marginBottom: 16 }}
+      />
+      <Row gutter={[16, 16]}>
+        <Col span={12}>
+          <MetricChart
+            title="CPU (%)"
+            data={toPoints(metrics, format, {
+              avg: (v) => v.cpu_avg,
+              max: (v) => v.cpu_max,
+            })}
+          />
+        </Col>
+        <Col span={12}>
+          <MetricChart
+            title="Memory (GB)"
+            data={toPoints(metrics, format, {
+              memory: (v) => v.used_memory / GB,
+              swap: (v) => v.used_swap / GB,
+            })}
+          />
+        </Col>
+        <Col span={12}>
+          <MetricChart
+            title="Load"
+            data={toPoints(metrics, format, {
+              "1m": (v) => v.load_one,
+              "5m": (v) => v.load_five,
+              "15m": (v) => v.load_fifteen,
+            })}
+          />
+        </Col>
+        <Col span={12}>
+          <MetricChart
+            title="Network (KB/s)"
+            data={toPoints(metrics, format, {
+              rx: (v) => v.rx_rate / KB,
+              tx: (v) => v.tx_rate / KB,
+            })}
+          />
+        </Col>
+      </Row>
+    </Spin>
+  );
+};
+
+export default App;
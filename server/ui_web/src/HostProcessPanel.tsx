@@ -0,0 +1,184 @@
+import React, { useEffect, useState } from "react";
+import { Segmented, Table, Tooltip, message } from "antd";
+import type { TableProps } from "antd";
+import type { SorterResult } from "antd/es/table/interface";
+import restful_api from "./utils/restful_api.ts";
+import dayjs from "dayjs";
+
+interface ProcessType {
+  pid: number;
+  name: string;
+  exe?: string;
+  status: string;
+  cmd: string[];
+  ppid?: number;
+  uid?: number;
+  user?: string;
+  start_time: number;
+  cpu_usage: number;
+  memory: number;
+  open_files?: number;
+  cgroup?: string;
+  container_id?: string;
+  children?: ProcessType[];
+}
+
+type Sort = {
+  field?: string;
+  order?: "asc" | "desc";
+};
+
+const MB = 1024 * 1024;
+
+const columns: TableProps<ProcessType>["columns"] = [
+  {
+    title: "Pid",
+    dataIndex: "pid",
+    key: "pid",
+    sorter: true,
+  },
+  {
+    title: "Ppid",
+    dataIndex: "ppid",
+    key: "ppid",
+  },
+  {
+    title: "Name",
+    dataIndex: "name",
+    key: "name",
+    sorter: true,
+  },
+  {
+    title: "User",
+    dataIndex: "user",
+    key: "user",
+    sorter: true,
+    render: (user: string | undefined, record) => user ?? record.uid ?? "--",
+  },
+  {
+    title: "Command",
+    dataIndex: "cmd",
+    key: "cmd",
+    ellipsis: { showTitle: false },
+    render: (cmd: string[], record) => {
+      const text = cmd.length > 0 ? cmd.join(" ") : (record.exe ?? "");
+      return (
+        <Tooltip placement="topLeft" title={text}>
+          {text}
+        </Tooltip>
+      );
+    },
+  },
+  {
+    title: "Status",
+    dataIndex: "status",
+    key: "status",
+  },
+  {
+    title: "Start Time",
+    dataIndex: "start_time",
+    key: "start_time",
+    sorter: true,
+    render: (start_time: number) =>
+      dayjs.unix(start_time).format("YYYY-MM-DD HH:mm:ss"),
+  },
+  {
+    title: "CPU (%)",
+    dataIndex: "cpu_usage",
+    key: "cpu_usage",
+    sorter: true,
+    render: (cpu_usage: number) => cpu_usage.toFixed(1),
+  },
+  {
+    title: "Memory (MB)",
+    dataIndex: "memory",
+    key: "memory",
+    sorter: true,
+    render: (memory: number) => (memory / MB).toFixed(1),
+  },
+  {
+    title: "Open Files",
+    dataIndex: "open_files",
+    key: "open_files",
+    sorter: true,
+  },
+  {
+    title: "Container",
+    dataIndex: "container_id",
+    key: "container_id",
+    render: (container_id?: string) => container_id?.slice(0, 12) ?? "--",
+  },
+];
+
+const App: React.FC<{ agentId: string }> = ({ agentId }) => {
+  const [view, setView] = useState<"list" | "tree">("list");
+  const [sort, setSort] = useState<Sort>({});
+  const [processes, setProcesses] = useState<ProcessType[]>([]);
+  const [loading, setLoading] = useState(false);
+
+  useEffect(() => {
+    const params = new URLSearchParams();
+    if (sort.field) params.append("sort", sort.field);
+    if (sort.order) params.append("order", sort.order);
+    const url =
+      view === "tree"
+        ? `/api/hosts/${agentId}/process_tree?${params.toString()}`
+        : `/api/hosts/${agentId}?${params.toString()}`;
+    setLoading(true);
+    restful_api
+      .get(url)
+      .then((res) => {
+        setProcesses(
+          view === "tree"
+            ? res.data.processes
+            : (res.data.system?.processes ?? [])
+        );
+      })
+      .catch((err) => {
+        console.error("Failed to fetch processes:", err);
+        message.error("查询失败");
+      })
+      .finally(() => {
+        setLoading(false);
+      });
+  }, [agentId, view, sort]);
+
+  const handleChange: TableProps<ProcessType>["onChange"] = (
+    _pagination,
+    _filters,
+    sorter
+  ) => {
+    const { columnKey, order } = sorter as SorterResult<ProcessType>;
+    setSort(
+      order
+        ? {
+            field: columnKey as string,
+            order: order === "ascend" ? "asc" : "desc",
+          }
+        : {}
+    );
+  };
+
+  return (
+    <>
+      <Segmented
+        options={[
+          { label: "列表", value: "list" },
+          { label: "进程树", value: "tree" },
+        ]}
+        value={view}
+        onChange={(value) => setView(value as "list" | "tree")}
+        style={{ marginBottom: 16 }}
+      />
+      <Table<ProcessType>
+        columns={columns}
+        dataSource={processes}
+        rowKey="pid"
+        loading={loading}
+        onChange={handleChange}
+      />
+    </>
+  );
+};
+
+export default App;
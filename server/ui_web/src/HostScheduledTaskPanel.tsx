@@ -0,0 +1,84 @@
+import React from "react";
+import { Empty, Table, Tag } from "antd";
+import type { TableProps } from "antd";
+import dayjs from "dayjs";
+
+export interface ScheduledTaskType {
+  source: string;
+  path: string;
+  user?: string;
+  schedule: string;
+  command: string;
+  unit?: string;
+  last_run?: number;
+  next_run?: number;
+  modified_at?: number;
+  changed: boolean;
+}
+
+const formatUnix = (timestamp?: number) =>
+  timestamp ? dayjs.unix(timestamp).format("YYYY-MM-DD HH:mm:ss") : "--";
+
+const columns: TableProps<ScheduledTaskType>["columns"] = [
+  {
+    title: "Source",
+    dataIndex: "source",
+    key: "source",
+    filters: ["cron", "user_cron", "cron_script", "systemd_timer"].map((v) => ({
+      text: v,
+      value: v,
+    })),
+    onFilter: (value, record) => record.source === value,
+  },
+  { title: "Path", dataIndex: "path", key: "path" },
+  { title: "User", dataIndex: "user", key: "user" },
+  { title: "Schedule", dataIndex: "schedule", key: "schedule" },
+  {
+    title: "Command",
+    key: "command",
+    render: (_, record) => record.command || record.unit,
+  },
+  {
+    title: "Last Run",
+    dataIndex: "last_run",
+    key: "last_run",
+    render: formatUnix,
+  },
+  {
+    title: "Next Run",
+    dataIndex: "next_run",
+    key: "next_run",
+    render: formatUnix,
+  },
+  {
+    title: "Changed",
+    dataIndex: "changed",
+    key: "changed",
+    filters: [
+      { text: "true", value: true },
+      { text: "false", value: false },
+    ],
+    onFilter: (value, record) => record.changed === value,
+    render: (changed: boolean) =>
+      changed ? <Tag color="orange">changed</Tag> : "--",
+  },
+];
+
+const App: React.FC<{ scheduledTasks: ScheduledTaskType[] }> = ({
+  scheduledTasks,
+}) => {
+  if (scheduledTasks.length === 0) {
+    return <Empty />;
+  }
+  return (
+    <Table<ScheduledTaskType>
+      columns={columns}
+      dataSource={scheduledTasks}
+      rowKey={(record) =>
+        `${record.source}:${record.path}:${record.schedule}:${record.command}`
+      }
+    />
+  );
+};
+
+export default App;
@@ -0,0 +1,217 @@
+import React, { useEffect, useState } from "react";
+import {
+  Button,
+  Empty,
+  message,
+  Popconfirm,
+  Space,
+  Table,
+  Tag,
+  Typography,
+} from "antd";
+import type { TableProps } from "antd";
+import dayjs from "dayjs";
+import restful_api from "./utils/restful_api.ts";
+import { hasPermission } from "./utils/permission";
+
+export interface ServiceType {
+  name: string;
+  description: string;
+  load_state: string;
+  active_state: string;
+  sub_state: string;
+  unit_file_state: string;
+  main_pid?: number;
+}
+
+interface ServiceControlType {
+  id: string;
+  unit: string;
+  action: string;
+  status: string;
+  output?: string;
+  created_at: number;
+  finished_at?: number;
+}
+
+const ACTIONS = ["start", "stop", "restart", "enable", "disable"];
+// 会中断服务的操作需要二次确认
+const CONFIRM_ACTIONS = ["stop", "restart", "disable"];
+
+const active_colors: Record<string, string> = {
+  active: "green",
+  failed: "red",
+  activating: "blue",
+  deactivating: "orange",
+};
+
+const status_colors: Record<string, string> = {
+  success: "green",
+  failed: "red",
+  pending: "blue",
+};
+
+const control_columns: TableProps<ServiceControlType>["columns"] = [
+  { title: "Unit", dataIndex: "unit", key: "unit" },
+  { title: "Action", dataIndex: "action", key: "action" },
+  {
+    title: "Status",
+    dataIndex: "status",
+    key: "status",
+    render: (status: string) => (
+      <Tag color={status_colors[status]}>{status}</Tag>
+    ),
+  },
+  {
+    title: "Output",
+    dataIndex: "output",
+    key: "output",
+    render: (output?: string) => output || "--",
+  },
+  {
+    title: "Created At",
+    dataIndex: "created_at",
+    key: "created_at",
+    render: (created_at: number) =>
+      dayjs(created_at).format("YYYY-MM-DD HH:mm:ss"),
+  },
+  {
+    title: "Finished At",
+    dataIndex: "finished_at",
+    key: "finished_at",
+    render: (finished_at?: number) =>
+      finished_at ? dayjs(finished_at).format("YYYY-MM-DD HH:mm:ss") : "--",
+  },
+];
+
+const App: React.FC<{ agentId: string; services: ServiceType[] }> = ({
+  agentId,
+  services,
+}) => {
+  const [controls, setControls] = useState<ServiceControlType[]>([]);
+  const actions = ACTIONS.filter((action) =>
+    hasPermission("POST", `/api/services/${action}`)
+  );
+
+  const fetchControls = () => {
+    if (!hasPermission("GET", "/api/service_controls")) {
+      return;
+    }
+    restful_api
+      .get(`/api/service_controls?agent_id=${agentId}&size=10&page=0`)
+      .then((res) => setControls(res.data._embedded?.service_control || []))
+      .catch((err) => console.error("查询服务控制记录失败: ", err));
+  };
+
+  const handleControl = async (unit: string, action: string) => {
+    try {
+      await restful_api.post(`/api/services/${action}`, {
+        agent_id: agentId,
+        unit,
+      });
+      message.success(`已下发 ${action} ${unit}`);
+      fetchControls();
+    } catch (e) {
+      console.error("下发失败: ", e);
+      message.error("下发失败");
+    }
+  };
+
+  useEffect(() => {
+    fetchControls();
+  }, [agentId]);
+
+  const service_columns: TableProps<ServiceType>["columns"] = [
+    {
+      title: "Name",
+      dataIndex: "name",
+      key: "name",
+      sorter: (a, b) => a.name.localeCompare(b.name),
+    },
+    { title: "Description", dataIndex: "description", key: "description" },
+    {
+      title: "Active",
+      dataIndex: "active_state",
+      key: "active_state",
+      filters: ["active", "inactive", "failed"].map((v) => ({
+        text: v,
+        value: v,
+      })),
+      onFilter: (value, record) => record.active_state === value,
+      render: (_, record) => (
+        <Tag color={active_colors[record.active_state]}>
+          {record.active_state} ({record.sub_state})
+        </Tag>
+      ),
+    },
+    {
+      title: "Enabled",
+      dataIndex: "unit_file_state",
+      key: "unit_file_state",
+      filters: ["enabled", "disabled", "static", "masked"].map((v) => ({
+        text: v,
+        value: v,
+      })),
+      onFilter: (value, record) => record.unit_file_state === value,
+    },
+    { title: "Load", dataIndex: "load_state", key: "load_state" },
+    { title: "Main Pid", dataIndex: "main_pid", key: "main_pid" },
+    {
+      title: "Action",
+      key: "action",
+      render: (_, record) => (
+        <Space size={0}>
+          {actions.map((action) =>
+            CONFIRM_ACTIONS.includes(action) ? (
+              <Popconfirm
+                key={action}
+                title={`确定 ${action} ${record.name}？`}
+                onConfirm={() => handleControl(record.name, action)}
+              >
+                <Button type="link" size="small" danger>
+                  {action}
+                </Button>
+              </Popconfirm>
+            ) : (
+              <Button
+                key={action}
+                type="link"
+                size="small"
+                onClick={() => handleControl(record.name, action)}
+              >
+                {action}
+              </Button>
+            )
+          )}
+        </Space>
+      ),
+    },
+  ];
+
+  if (services.length === 0) {
+    return <Empty />;
+  }
+  return (
+    <>
+      <Table<ServiceType>
+        columns={service_columns}
+        dataSource={services}
+        rowKey="name"
+      />
+      <Typography.Title level={5} style={{ marginTop: 24 }}>
+        Recent Controls
+        <Button type="link" onClick={fetchControls}>
+          刷新
+        </Button>
+      </Typography.Title>
+      <Table<ServiceControlType>
+        columns={control_columns}
+        dataSource={controls}
+        rowKey="id"
+        pagination={false}
+      />
+    </>
+  );
+};
+
+export default App;
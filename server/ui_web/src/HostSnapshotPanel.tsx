@@ -0,0 +1,241 @@
+import React, { useEffect, useState } from "react";
+import { Button, Collapse, Empty, message, Table, Tag } from "antd";
+import type { TableProps } from "antd";
+import dayjs from "dayjs";
+import restful_api from "./utils/restful_api.ts";
+
+interface SnapshotType {
+  id: string;
+  version: number;
+  hash: string;
+  size: number;
+  created_at: number;
+}
+
+type Item = Record<string, unknown>;
+
+interface FieldChangeType {
+  field: string;
+  old: unknown;
+  new: unknown;
+}
+
+interface ItemsDiffType {
+  added: Item[];
+  removed: Item[];
+  changed: { key: string; fields: FieldChangeType[] }[];
+}
+
+interface SnapshotDiffType {
+  system: FieldChangeType[];
+  processes: ItemsDiffType;
+  disks: ItemsDiffType;
+  networks: ItemsDiffType;
+}
+
+type Page = {
+  size: number;
+  total_elements: number;
+  total_pages: number;
+};
+
+const formatValue = (value: unknown) =>
+  typeof value === "object" ? JSON.stringify(value) : String(value);
+
+const field_columns: TableProps<FieldChangeType>["columns"] = [
+  { title: "Field", dataIndex: "field", key: "field" },
+  {
+    title: "Old",
+    dataIndex: "old",
+    key: "old",
+    render: (value: unknown) => formatValue(value),
+  },
+  {
+    title: "New",
+    dataIndex: "new",
+    key: "new",
+    render: (value: unknown) => formatValue(value),
+  },
+];
+
+const snapshot_columns: TableProps<SnapshotType>["columns"] = [
+  {
+    title: "Created At",
+    dataIndex: "created_at",
+    key: "created_at",
+    render: (created_at: number) =>
+      dayjs(created_at).format("YYYY-MM-DD HH:mm:ss"),
+  },
+  { title: "Version", dataIndex: "version", key: "version" },
+  { title: "Size", dataIndex: "size", key: "size" },
+  {
+    title: "Hash",
+    dataIndex: "hash",
+    key: "hash",
+    render: (hash: string) => hash.slice(0, 12),
+  },
+];
+
+// 新增、删除的条目只展示名称，变化的条目展开字段
+const ItemsDiff: React.FC<{
+  diff: ItemsDiffType;
+  label: (item: Item) => string;
+}> = ({ diff, label }) => (
+  <>
+    {diff.added.map((item, index) => (
+      <Tag key={`added:${index}`} color="green">
+        + {label(item)}
+      </Tag>
+    ))}
+    {diff.removed.map((item, index) => (
+      <Tag key={`removed:${index}`} color="red">
+        - {label(item)}
+      </Tag>
+    ))}
+    {diff.changed.map((item) => (
+      <Table<FieldChangeType>
+        key={item.key}
+        title={() => item.key}
+        columns={field_columns}
+        dataSource={item.fields}
+        rowKey="field"
+        size="small"
+        pagination={false}
+      />
+    ))}
+  </>
+);
+
+const App: React.FC<{ agentId: string }> = ({ agentId }) => {
+  const [snapshots, setSnapshots] = useState<SnapshotType[]>([]);
+  const [current, setCurrent] = useState(1);
+  const [page_size, setPageSize] = useState(10);
+  const [page, setPage] = useState<Page>();
+  const [selected, setSelected] = useState<SnapshotType[]>([]);
+  const [diff, setDiff] = useState<SnapshotDiffType>();
+  const [loading, setLoading] = useState(false);
+
+  const handleQuery = async (page = current, size = page_size) => {
+    setLoading(true);
+    try {
+      const response = await restful_api.get(
+        `/api/hosts/${agentId}/snapshots?size=${size}&page=${page - 1}`
+      );
+      setSnapshots(response.data._embedded?.host_snapshot || []);
+      setPage(response.data.page);
+      setCurrent(page);
+      setPageSize(size);
+    } catch (e) {
+      console.error("查询主机快照失败: ", e);
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const handleDiff = async () => {
+    // 按时间先后比较
+    const [from, to] = [...selected].sort(
+      (a, b) => a.created_at - b.created_at
+    );
+    try {
+      const response = await restful_api.get(
+        `/api/hosts/${agentId}/snapshots/diff?from=${from.id}&to=${to.id}`
+      );
+      setDiff(response.data);
+    } catch (e) {
+      console.error("比较主机快照失败: ", e);
+      message.error("比较失败");
+    }
+  };
+
+  useEffect(() => {
+    handleQuery(1);
+  }, [agentId]);
+
+  return (
+    <>
+      <Table<SnapshotType>
+        columns={snapshot_columns}
+        dataSource={snapshots}
+        rowKey="id"
+        loading={loading}
+        rowSelection={{
+          selectedRowKeys: selected.map((v) => v.id),
+          onChange: (_, rows) => setSelected(rows.slice(-2)),
+        }}
+        pagination={{
+          current: current,
+          pageSize: page_size,
+          total: page?.total_elements,
+          onChange: (page, size) => handleQuery(page, size),
+        }}
+      />
+      <Button
+        type="primary"
+        disabled={selected.length !== 2}
+        onClick={handleDiff}
+      >
+        Diff
+      </Button>
+      {diff &&
+        (diff.system.length === 0 &&
+        [diff.processes, diff.disks, diff.networks].every(
+          (v) =>
+            v.added.length + v.removed.length + v.changed.length === 0
+        ) ? (
+          <Empty style={{ marginTop: 24 }} />
+        ) : (
+          <Collapse
+            style={{ marginTop: 24 }}
+            items={[
+              {
+                key: "system",
+                label: `System (${diff.system.length})`,
+                children: (
+                  <Table<FieldChangeType>
+                    columns={field_columns}
+                    dataSource={diff.system}
+                    rowKey="field"
+                    size="small"
+                    pagination={false}
+                  />
+                ),
+              },
+              {
+                key: "processes",
+                label: "Processes",
+                children: (
+                  <ItemsDiff
+                    diff={diff.processes}
+                    label={(v) => `${v.pid} ${v.name}`}
+                  />
+                ),
+              },
+              {
+                key: "disks",
+                label: "Disks",
+                children: (
+                  <ItemsDiff
+                    diff={diff.disks}
+                    label={(v) => String(v.mount_point)}
+                  />
+                ),
+              },
+              {
+                key: "networks",
+                label: "Networks",
+                children: (
+                  <ItemsDiff
+                    diff={diff.networks}
+                    label={(v) => String(v.interface_name)}
+                  />
+                ),
+              },
+            ]}
+          />
+        ))}
+    </>
+  );
+};
+
+export default App;
@@ -0,0 +1,170 @@
+import React, { useEffect, useState } from "react";
+import {
+  Button,
+  Form,
+  Input,
+  InputNumber,
+  message,
+  Select,
+  Table,
+} from "antd";
+import restful_api from "./utils/restful_api.ts";
+import { useNavigate } from "react-router-dom";
+
+type ListenPort = {
+  agent_id: string;
+  host_name?: string;
+  protocol: string;
+  addr: string;
+  port: number;
+  pid?: number;
+  process_name?: string;
+};
+
+type Page = {
+  size: number;
+  total_elements: number;
+  total_pages: number;
+};
+
+type Filters = {
+  port?: number;
+  protocol?: string;
+  process_name?: string;
+};
+
+const App: React.FC = () => {
+  const navigate = useNavigate();
+  const [listenPorts, setListenPorts] = useState<ListenPort[]>([]);
+  const [current, setCurrent] = useState(1);
+  const [page_size, setPageSize] = useState(10);
+  const [page, setPage] = useState<Page>();
+  const [filters, setFilters] = useState<Filters>({});
+  const [loading, setLoading] = useState(false);
+
+  const handleQuery = async (page = current, size = page_size, f = filters) => {
+    const params = new URLSearchParams();
+    params.append("size", size.toString());
+    params.append("page", (page - 1).toString());
+    if (f.port !== undefined && f.port !== null)
+      params.append("port", f.port.toString());
+    if (f.protocol) params.append("protocol", f.protocol);
+    if (f.process_name) params.append("process_name", f.process_name);
+    setLoading(true);
+    try {
+      const response = await restful_api.get(
+        `/api/listen_ports?${params.toString()}`
+      );
+      setListenPorts(response.data._embedded?.listen_port || []);
+      setPage(response.data.page);
+      setCurrent(page);
+      setPageSize(size);
+      setFilters(f);
+      message.success("查询成功");
+    } catch (e) {
+      console.error("查询失败: ", e);
+      message.error("查询失败");
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const columns = [
+    {
+      title: "AgentId",
+      dataIndex: "agent_id",
+      key: "agent_id",
+      render: (agent_id: string) => (
+        <Button type="link" onClick={() => navigate(`/hosts/${agent_id}`)}>
+          {agent_id}
+        </Button>
+      ),
+    },
+    {
+      title: "Host Name",
+      dataIndex: "host_name",
+      key: "host_name",
+    },
+    {
+      title: "Protocol",
+      dataIndex: "protocol",
+      key: "protocol",
+    },
+    {
+      title: "Addr",
+      dataIndex: "addr",
+      key: "addr",
+    },
+    {
+      title: "Port",
+      dataIndex: "port",
+      key: "port",
+    },
+    {
+      title: "Pid",
+      dataIndex: "pid",
+      key: "pid",
+    },
+    {
+      title: "Process",
+      dataIndex: "process_name",
+      key: "process_name",
+    },
+  ];
+
+  useEffect(() => {
+    handleQuery();
+  }, []);
+
+  return (
+    <>
+      <Form
+        layout="inline"
+        onFinish={(values) => handleQuery(1, page_size, values)}
+        style={{ marginTop: 16 }}
+      >
+        <Form.Item name="port" label="端口">
+          <InputNumber min={0} max={65535} placeholder="请输入端口" />
+        </Form.Item>
+        <Form.Item name="protocol" label="协议">
+          <Select
+            allowClear
+            style={{ width: 120 }}
+            options={[
+              { value: "tcp", label: "tcp" },
+              { value: "tcp6", label: "tcp6" },
+              { value: "udp", label: "udp" },
+              { value: "udp6", label: "udp6" },
+            ]}
+          />
+        </Form.Item>
+        <Form.Item name="process_name" label="进程">
+          <Input placeholder="请输入进程名关键字" />
+        </Form.Item>
+        <Form.Item>
+          <Button type="primary" htmlType="submit">
+            查询
+          </Button>
+        </Form.Item>
+      </Form>
+
+      <Table
+        dataSource={listenPorts}
+        columns={columns}
+        rowKey={(record) =>
+          `${record.agent_id}:${record.protocol}:${record.addr}:${record.port}:${record.pid ?? ""}`
+        }
+        loading={loading}
+        pagination={{
+          current: current,
+          pageSize: page_size,
+          total: page?.total_elements,
+          onChange: (page, size) => handleQuery(page, size),
+        }}
+        style={{ marginTop: 24 }}
+      />
+    </>
+  );
+};
+
+export default App;
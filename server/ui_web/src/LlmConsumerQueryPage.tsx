@@ -0,0 +1,179 @@
+import React, { useEffect, useRef, useState } from "react";
+import { Button, Form, Input, message, Table, Tag, Progress } from "antd";
+import restful_api from "./utils/restful_api.ts";
+import dayjs from "dayjs";
+
+type LlmConsumer = {
+  agent_id: string;
+  agent_state?: string;
+  models: string[];
+  loaded_models: string[];
+  total_slots: number;
+  free_slots: number;
+  tokens_per_second: number;
+  backend_reachable: boolean;
+  updated_at: number;
+};
+
+type Page = {
+  size: number;
+  total_elements: number;
+  total_pages: number;
+};
+
+// 自动刷新间隔
+const REFRESH_INTERVAL = 5000;
+
+const App: React.FC = () => {
+  const [llmConsumers, setLlmConsumers] = useState<LlmConsumer[]>([]);
+  const [current, setCurrent] = useState(1);
+  const [page_size, setPageSize] = useState(10);
+  const [page, setPage] = useState<Page>();
+  const [loading, setLoading] = useState(false);
+  const filtersRef = useRef<{ agent_id?: string }>({});
+
+  const handleQuery = async (
+    page = current,
+    size = page_size,
+    filters = filtersRef.current,
+    silent = false
+  ) => {
+    const params = new URLSearchParams();
+    params.append("size", size.toString());
+    params.append("page", (page - 1).toString());
+    if (filters?.agent_id) params.append("agent_id", filters.agent_id);
+    filtersRef.current = filters;
+    if (!silent) setLoading(true);
+    try {
+      const response = await restful_api.get(
+        `/api/llm_consumers?${params.toString()}`
+      );
+      setLlmConsumers(response.data._embedded?.llm_consumer);
+      setPage(response.data.page);
+      setCurrent(page);
+      setPageSize(size);
+      if (!silent) message.success("查询成功");
+    } catch (e) {
+      console.error("查询失败: ", e);
+      if (!silent) message.error("查询失败");
+    } finally {
+      if (!silent) setLoading(false);
+    }
+  };
+
+  const columns = [
+    {
+      title: "AgentId",
+      dataIndex: "agent_id",
+      key: "agent_id",
+    },
+    {
+      title: "状态",
+      key: "health",
+      render: (_: unknown, record: LlmConsumer) => {
+        if (record.agent_state !== "Online") {
+          return <Tag>离线</Tag>;
+        }
+        return record.backend_reachable ? (
+          <Tag color="green">正常</Tag>
+        ) : (
+          <Tag color="red">后端不可达</Tag>
+        );
+      },
+    },
+    {
+      title: "Worker(忙/总)",
+      key: "slots",
+      render: (_: unknown, record: LlmConsumer) => {
+        const busy = record.total_slots - record.free_slots;
+        return (
+          <Progress
+            percent={
+              record.total_slots ? (busy * 100) / record.total_slots : 0
+            }
+            format={() => `${busy}/${record.total_slots}`}
+            size="small"
+          />
+        );
+      },
+    },
+    {
+      title: "Tokens/s",
+      dataIndex: "tokens_per_second",
+      key: "tokens_per_second",
+      render: (v: number) => (v ? v.toFixed(1) : "--"),
+    },
+    {
+      title: "已加载模型",
+      dataIndex: "loaded_models",
+      key: "loaded_models",
+      render: (models: string[]) =>
+        models.map((model) => (
+          <Tag color="blue" key={model}>
+            {model}
+          </Tag>
+        )),
+    },
+    {
+      title: "可用模型",
+      dataIndex: "models",
+      key: "models",
+      render: (models: string[]) =>
+        models.map((model) => <Tag key={model}>{model}</Tag>),
+    },
+    {
+      title: "上报时间",
+      dataIndex: "updated_at",
+      key: "updated_at",
+      render: (timestamp: number) =>
+        timestamp ? dayjs(timestamp).format("YYYY-MM-DD HH:mm:ss") : "--",
+    },
+  ];
+
+  useEffect(() => {
+    handleQuery();
+  }, []);
+
+  useEffect(() => {
+    const timer = setInterval(
+      () => handleQuery(current, page_size, filtersRef.current, true),
+      REFRESH_INTERVAL
+    );
+    return () => clearInterval(timer);
+  }, [current, page_size]);
+
+  return (
+    <>
+      <Form
+        layout="inline"
+        onFinish={(values) => handleQuery(1, page_size, values)}
+        style={{ marginTop: 16 }}
+      >
+        <Form.Item name="agent_id" label="AgentId">
+          <Input placeholder="请输入AgentId关键字" />
+        </Form.Item>
+        <Form.Item>
+          <Button type="primary" htmlType="submit">
+            查询
+          </Button>
+        </Form.Item>
+      </Form>
+
+      <Table
+        dataSource={llmConsumers}
+        columns={columns}
+        rowKey="agent_id"
+        loading={loading}
+        pagination={{
+          current: current,
+          pageSize: page_size,
+          total: page?.total_elements,
+          onChange: (page, size) => handleQuery(page, size),
+        }}
+        style={{ marginTop: 24 }}
+      />
+    </>
+  );
+};
+
+export default App;
@@ -1,12 +1,19 @@
 import React, { useState, useEffect } from "react";
 import { useParams } from "react-router-dom";
-import { Descriptions, Spin } from "antd";
+import { Descriptions, Image, Space, Spin } from "antd";
 import type { DescriptionsProps } from "antd";
 import restful_api from "./utils/restful_api.ts";
 
+interface AttachmentType {
+  id: string;
+  mime_type: string;
+  size: number;
+  thumbnail: string | null;
+}
+
 function jsonToDescriptionsItems(obj: Record<string, unknown>) {
-  return Object.entries(obj)
-    .filter(([key]) => key !== "processes")
+  const items: DescriptionsProps["items"] = Object.entries(obj)
+    .filter(([key]) => key !== "processes" && key !== "attachments")
     .map(([key, value], index) => ({
       key: key + index,
       label: key.replace(/_/g, " ").replace(/\b\w/g, (c) => c.toUpperCase()),
@@ -15,6 +22,31 @@ function jsonToDescriptionsItems(obj: Record<string, unknown>) {
           ? JSON.stringify(value, null, 2)
           : String(value),
     }));
+  const attachments = (obj.attachments as AttachmentType[]) ?? [];
+  if (attachments.length > 0) {
+    items.push({
+      key: "attachments",
+      label: "Attachments",
+      children: (
+        <Space wrap>
+          {attachments.map((attachment) =>
+            attachment.thumbnail ? (
+              <Image
+                key={attachment.id}
+                src={attachment.thumbnail}
+                alt={`${attachment.mime_type} ${attachment.size} bytes`}
+              />
+            ) : (
+              <span key={attachment.id}>
+                {attachment.mime_type} {attachment.size} bytes
+              </span>
+            )
+          )}
+        </Space>
+      ),
+    });
+  }
+  return items;
 }
 
 const App: React.FC = () => {
@@ -65,6 +65,11 @@ const App: React.FC = () => {
       dataIndex: "id",
       key: "id",
     },
+    {
+      title: "Kind",
+      dataIndex: "kind",
+      key: "kind",
+    },
     {
       title: "Model",
       dataIndex: "model",
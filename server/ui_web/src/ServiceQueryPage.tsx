@@ -0,0 +1,166 @@
+import React, { useEffect, useState } from "react";
+import { Button, Form, Input, message, Select, Table } from "antd";
+import restful_api from "./utils/restful_api.ts";
+import { useNavigate } from "react-router-dom";
+
+type Service = {
+  agent_id: string;
+  host_name?: string;
+  name: string;
+  load_state: string;
+  active_state: string;
+  sub_state: string;
+  unit_file_state: string;
+  main_pid?: number;
+};
+
+type Page = {
+  size: number;
+  total_elements: number;
+  total_pages: number;
+};
+
+type Filters = {
+  name?: string;
+  active_state?: string;
+  unit_file_state?: string;
+};
+
+const App: React.FC = () => {
+  const navigate = useNavigate();
+  const [services, setServices] = useState<Service[]>([]);
+  const [current, setCurrent] = useState(1);
+  const [page_size, setPageSize] = useState(10);
+  const [page, setPage] = useState<Page>();
+  const [filters, setFilters] = useState<Filters>({});
+  const [loading, setLoading] = useState(false);
+
+  const handleQuery = async (page = current, size = page_size, f = filters) => {
+    const params = new URLSearchParams();
+    params.append("size", size.toString());
+    params.append("page", (page - 1).toString());
+    if (f.name) params.append("name", f.name);
+    if (f.active_state) params.append("active_state", f.active_state);
+    if (f.unit_file_state) params.append("unit_file_state", f.unit_file_state);
+    setLoading(true);
+    try {
+      const response = await restful_api.get(
+        `/api/services?${params.toString()}`
+      );
+      setServices(response.data._embedded?.service || []);
+      setPage(response.data.page);
+      setCurrent(page);
+      setPageSize(size);
+      setFilters(f);
+      message.success("查询成功");
+    } catch (e) {
+      console.error("查询失败: ", e);
+      message.error("查询失败");
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const columns = [
+    {
+      title: "AgentId",
+      dataIndex: "agent_id",
+      key: "agent_id",
+      render: (agent_id: string) => (
+        <Button type="link" onClick={() => navigate(`/hosts/${agent_id}`)}>
+          {agent_id}
+        </Button>
+      ),
+    },
+    {
+      title: "Host Name",
+      dataIndex: "host_name",
+      key: "host_name",
+    },
+    {
+      title: "Name",
+      dataIndex: "name",
+      key: "name",
+    },
+    {
+      title: "Load",
+      dataIndex: "load_state",
+      key: "load_state",
+    },
+    {
+      title: "Active",
+      key: "active_state",
+      render: (_: unknown, record: Service) =>
+        `${record.active_state} (${record.sub_state})`,
+    },
+    {
+      title: "Enabled",
+      dataIndex: "unit_file_state",
+      key: "unit_file_state",
+    },
+    {
+      title: "Main Pid",
+      dataIndex: "main_pid",
+      key: "main_pid",
+    },
+  ];
+
+  useEffect(() => {
+    handleQuery();
+  }, []);
+
+  return (
+    <>
+      <Form
+        layout="inline"
+        onFinish={(values) => handleQuery(1, page_size, values)}
+        style={{ marginTop: 16 }}
+      >
+        <Form.Item name="name" label="服务名">
+          <Input placeholder="请输入服务名关键字" />
+        </Form.Item>
+        <Form.Item name="active_state" label="运行状态">
+          <Select
+            allowClear
+            style={{ width: 120 }}
+            options={["active", "inactive", "failed"].map((v) => ({
+              value: v,
+              label: v,
+            }))}
+          />
+        </Form.Item>
+        <Form.Item name="unit_file_state" label="开机启动">
+          <Select
+            allowClear
+            style={{ width: 120 }}
+            options={["enabled", "disabled", "static", "masked"].map((v) => ({
+              value: v,
+              label: v,
+            }))}
+          />
+        </Form.Item>
+        <Form.Item>
+          <Button type="primary" htmlType="submit">
+            查询
+          </Button>
+        </Form.Item>
+      </Form>
+
+      <Table
+        dataSource={services}
+        columns={columns}
+        rowKey={(record) => `${record.agent_id}:${record.name}`}
+        loading={loading}
+        pagination={{
+          current: current,
+          pageSize: page_size,
+          total: page?.total_elements,
+          onChange: (page, size) => handleQuery(page, size),
+        }}
+        style={{ marginTop: 24 }}
+      />
+    </>
+  );
+};
+
+export default App;
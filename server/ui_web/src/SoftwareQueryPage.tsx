@@ -0,0 +1,169 @@
+import React, { useEffect, useState } from "react";
+import { Button, Form, Input, message, Select, Table } from "antd";
+import restful_api from "./utils/restful_api.ts";
+import dayjs from "dayjs";
+import { useNavigate } from "react-router-dom";
+
+type Software = {
+  agent_id: string;
+  host_name?: string;
+  name: string;
+  version: string;
+  arch: string;
+  source: string;
+  installed_at?: number;
+  path?: string;
+};
+
+type Page = {
+  size: number;
+  total_elements: number;
+  total_pages: number;
+};
+
+type Filters = {
+  name?: string;
+  version?: string;
+  source?: string;
+};
+
+const App: React.FC = () => {
+  const navigate = useNavigate();
+  const [softwares, setSoftwares] = useState<Software[]>([]);
+  const [current, setCurrent] = useState(1);
+  const [page_size, setPageSize] = useState(10);
+  const [page, setPage] = useState<Page>();
+  const [filters, setFilters] = useState<Filters>({});
+  const [loading, setLoading] = useState(false);
+
+  const handleQuery = async (page = current, size = page_size, f = filters) => {
+    const params = new URLSearchParams();
+    params.append("size", size.toString());
+    params.append("page", (page - 1).toString());
+    if (f.name) params.append("name", f.name);
+    if (f.version) params.append("version", f.version);
+    if (f.source) params.append("source", f.source);
+    setLoading(true);
+    try {
+      const response = await restful_api.get(
+        `/api/softwares?${params.toString()}`
+      );
+      setSoftwares(response.data._embedded?.software || []);
+      setPage(response.data.page);
+      setCurrent(page);
+      setPageSize(size);
+      setFilters(f);
+      message.success("查询成功");
+    } catch (e) {
+      console.error("查询失败: ", e);
+      message.error("查询失败");
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const columns = [
+    {
+      title: "AgentId",
+      dataIndex: "agent_id",
+      key: "agent_id",
+      render: (agent_id: string) => (
+        <Button type="link" onClick={() => navigate(`/hosts/${agent_id}`)}>
+          {agent_id}
+        </Button>
+      ),
+    },
+    {
+      title: "Host Name",
+      dataIndex: "host_name",
+      key: "host_name",
+    },
+    {
+      title: "Name",
+      dataIndex: "name",
+      key: "name",
+    },
+    {
+      title: "Version",
+      dataIndex: "version",
+      key: "version",
+    },
+    {
+      title: "Arch",
+      dataIndex: "arch",
+      key: "arch",
+    },
+    {
+      title: "Source",
+      dataIndex: "source",
+      key: "source",
+    },
+    {
+      title: "Installed At",
+      dataIndex: "installed_at",
+      key: "installed_at",
+      render: (timestamp?: number) =>
+        timestamp ? dayjs(timestamp).format("YYYY-MM-DD HH:mm:ss") : "--",
+    },
+    {
+      title: "Path",
+      dataIndex: "path",
+      key: "path",
+    },
+  ];
+
+  useEffect(() => {
+    handleQuery();
+  }, []);
+
+  return (
+    <>
+      <Form
+        layout="inline"
+        onFinish={(values) => handleQuery(1, page_size, values)}
+        style={{ marginTop: 16 }}
+      >
+        <Form.Item name="name" label="包名">
+          <Input placeholder="请输入包名关键字" />
+        </Form.Item>
+        <Form.Item name="version" label="版本">
+          <Input placeholder="请输入版本关键字" />
+        </Form.Item>
+        <Form.Item name="source" label="来源">
+          <Select
+            allowClear
+            style={{ width: 120 }}
+            options={[
+              { value: "dpkg", label: "dpkg" },
+              { value: "rpm", label: "rpm" },
+              { value: "process", label: "process" },
+            ]}
+          />
+        </Form.Item>
+        <Form.Item>
+          <Button type="primary" htmlType="submit">
+            查询
+          </Button>
+        </Form.Item>
+      </Form>
+
+      <Table
+        dataSource={softwares}
+        columns={columns}
+        rowKey={(record) =>
+          `${record.agent_id}:${record.source}:${record.name}:${record.arch}:${record.path ?? ""}`
+        }
+        loading={loading}
+        pagination={{
+          current: current,
+          pageSize: page_size,
+          total: page?.total_elements,
+          onChange: (page, size) => handleQuery(page, size),
+        }}
+        style={{ marginTop: 24 }}
+      />
+    </>
+  );
+};
+
+export default App;
@@ -4,10 +4,21 @@ import AppLayout from "./AppLayout.tsx";
 import LoginPage from "./LoginPage.tsx";
 import AgentQueryPage from "./AgentQueryPage.tsx";
 import AgentDetailPage from "./AgentDetailPage.tsx";
+import AgentUpgradePage from "./AgentUpgradePage.tsx";
+import AgentConflictPage from "./AgentConflictPage.tsx";
+import AgentProfilePage from "./AgentProfilePage.tsx";
+import EnrollmentTokenPage from "./EnrollmentTokenPage.tsx";
+import CertPage from "./CertPage.tsx";
+import ArtifactPage from "./ArtifactPage.tsx";
 import HostQueryPage from "./HostQueryPage.tsx";
 import HostDetailPage from "./HostDetailPage.tsx";
+import SoftwareQueryPage from "./SoftwareQueryPage.tsx";
+import ListenPortQueryPage from "./ListenPortQueryPage.tsx";
+import AccountQueryPage from "./AccountQueryPage.tsx";
+import ServiceQueryPage from "./ServiceQueryPage.tsx";
 import LlmTaskQueryPage from "./LlmTaskQueryPage.tsx";
 import LlmTaskDetailPage from "./LlmTaskDetailPage.tsx";
+import LlmConsumerQueryPage from "./LlmConsumerQueryPage.tsx";
 import RoleQueryPage from "./RoleQueryPage.tsx";
 import RoleDetailPage from "./RoleDetailPage.tsx";
 import RoleModifyPage from "./RoleModifyPage.tsx";
@@ -55,10 +66,24 @@ async function init() {
             <Route index element={<Navigate to="/agents" replace />} />
             <Route path="agents" element={<AgentQueryPage />} />
             <Route path="agents/:id" element={<AgentDetailPage />} />
+            <Route path="agent_upgrades" element={<AgentUpgradePage />} />
+            <Route path="agent_conflicts" element={<AgentConflictPage />} />
+            <Route path="agent_profiles" element={<AgentProfilePage />} />
+            <Route
+              path="enrollment_tokens"
+              element={<EnrollmentTokenPage />}
+            />
+            <Route path="certs" element={<CertPage />} />
+            <Route path="artifacts" element={<ArtifactPage />} />
             <Route path="hosts" element={<HostQueryPage />} />
             <Route path="hosts/:id" element={<HostDetailPage />} />
+            <Route path="softwares" element={<SoftwareQueryPage />} />
+            <Route path="listen_ports" element={<ListenPortQueryPage />} />
+            <Route path="accounts" element={<AccountQueryPage />} />
+            <Route path="services" element={<ServiceQueryPage />} />
             <Route path="llm_tasks" element={<LlmTaskQueryPage />} />
             <Route path="llm_tasks/:id" element={<LlmTaskDetailPage />} />
+            <Route path="llm_consumers" element={<LlmConsumerQueryPage />} />
             <Route path="roles" element={<RoleQueryPage />} />
             <Route path="roles/create" element={<RoleCreatePage />} />
             <Route path="roles/modify/:id" element={<RoleModifyPage />} />
@@ -0,0 +1,506 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    os::unix::fs::{DirBuilderExt, OpenOptionsExt},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, ensure};
+use clap::{Args, Subcommand};
+use rcgen::{
+    BasicConstraints, CertificateParams, CertificateRevocationListParams, DistinguishedName,
+    DnType, ExtendedKeyUsagePurpose, IsCa, Issuer, KeyIdMethod, KeyPair, KeyUsagePurpose,
+    RevokedCertParams, SerialNumber,
+};
+use serde::{Deserialize, Serialize};
+use time::{Duration, OffsetDateTime};
+use ui_service::cert::{CertStatus, expiry, read_chain};
+
+const ROOT_CA: &str = "root-ca";
+const SUB_CA: &str = "sub-ca";
+const INDEX: &str = "index.json";
+const CRL: &str = "crl.pem";
+
+#[derive(Args, Debug)]
+pub struct CaArgs {
+    /// CA 目录，保存根 CA、中间 CA、签发的证书和索引
+    #[arg(long, default_value = "./ca")]
+    dir: PathBuf,
+    #[command(subcommand)]
+    command: CaCommand,
+}
+
+#[derive(Subcommand, Debug)]
+enum CaCommand {
+    /// 生成根 CA 和中间 CA，中间 CA 签发服务端证书和 agent 客户端证书
+    Init {
+        /// 根 CA 有效天数
+        #[arg(long, default_value_t = 3650)]
+        root_days: i64,
+        /// 中间 CA 有效天数
+        #[arg(long, default_value_t = 1825)]
+        days: i64,
+        /// 覆盖已有的 CA，之前签发的证书全部失效
+        #[arg(long)]
+        force: bool,
+    },
+    /// 用中间 CA 签发服务端证书，同名证书已存在时签发新证书替换
+    Issue {
+        /// 证书名称，导出和查看时使用
+        name: String,
+        /// 证书的 SAN，IP 地址或域名，可多次指定，agent 的 domain_name 需在其中
+        #[arg(long = "san", default_value = "z11n.com")]
+        sans: Vec<String>,
+        /// 有效天数，不超过中间 CA 的有效期
+        #[arg(long, default_value_t = 825)]
+        days: i64,
+    },
+    /// 列出 CA 和签发的证书
+    List {
+        /// 剩余有效天数少于该值时标记为即将过期
+        #[arg(long, default_value_t = 30)]
+        warn_days: i64,
+    },
+    /// 按序列号吊销证书并重新生成 CRL，agent 不检查 CRL，吊销后需重新签发并替换服务端证书
+    Revoke { serial: String },
+    /// 导出服务端或 agent 使用的证书文件
+    Export {
+        /// 导出的服务端证书名称
+        #[arg(required_unless_present = "agent")]
+        name: Option<String>,
+        /// 导出目录
+        #[arg(long, default_value = "./config")]
+        out: PathBuf,
+        /// 只导出 agent 校验服务端证书用的中间 CA 证书
+        #[arg(long)]
+        agent: bool,
+    },
+}
+
+/// index.json 中的一条签发记录，时间为秒级时间戳
+#[derive(Serialize, Deserialize, Debug)]
+struct IssuedCert {
+    serial: String,
+    name: String,
+    sans: Vec<String>,
+    not_before: i64,
+    not_after: i64,
+    revoked_at: Option<i64>,
+}
+
+pub fn run(ca_args: CaArgs) -> anyhow::Result<()> {
+    let ca = Ca { dir: ca_args.dir };
+    match ca_args.command {
+        CaCommand::Init {
+            root_days,
+            days,
+            force,
+        } => ca.init(root_days, days, force),
+        CaCommand::Issue { name, sans, days } => ca.issue(&name, sans, days),
+        CaCommand::List { warn_days } => ca.list(warn_days),
+        CaCommand::Revoke { serial } => ca.revoke(&serial),
+        CaCommand::Export { name, out, agent } => ca.export(name.as_deref(), &out, agent),
+    }
+}
+
+struct Ca {
+    dir: PathBuf,
+}
+
+impl Ca {
+    fn init(&self, root_days: i64, days: i64, force: bool) -> anyhow::Result<()> {
+        ensure!(
+            0 < days && days <= root_days,
+            "days must be between 1 and root_days"
+        );
+        if self.crt(ROOT_CA).exists() && !force {
+            bail!(
+                "{} already exists, use --force to overwrite",
+                self.crt(ROOT_CA).display()
+            );
+        }
+        fs::DirBuilder::new()
+            .recursive(true)
+            .mode(0o700)
+            .create(self.dir.join("private"))?;
+        fs::create_dir_all(self.dir.join("certs"))?;
+
+        let now = OffsetDateTime::now_utc();
+        let root_key = KeyPair::generate()?;
+        let mut root_params = ca_params("Z11N Root CA", BasicConstraints::Unconstrained);
+        root_params.not_before = now - Duration::hours(1);
+        root_params.not_after = now + Duration::days(root_days);
+        let root_cert = root_params.self_signed(&root_key)?;
+        let root_key_pem = root_key.serialize_pem();
+        let root_issuer = Issuer::new(root_params, root_key);
+
+        // 中间 CA 不能再签发下级 CA
+        let sub_key = KeyPair::generate()?;
+        let mut sub_params = ca_params("Z11N Sub CA", BasicConstraints::Constrained(0));
+        sub_params.use_authority_key_identifier_extension = true;
+        sub_params.not_before = now - Duration::hours(1);
+        sub_params.not_after = now + Duration::days(days);
+        let sub_cert = sub_params.signed_by(&sub_key, &root_issuer)?;
+
+        write_private(&self.key(ROOT_CA), root_key_pem)?;
+        fs::write(self.crt(ROOT_CA), root_cert.pem())?;
+        write_private(&self.key(SUB_CA), sub_key.serialize_pem())?;
+        fs::write(self.crt(SUB_CA), sub_cert.pem())?;
+        self.save_index(&[])?;
+        self.write_crl(&[])?;
+        println!("root ca: {}", self.crt(ROOT_CA).display());
+        println!("sub ca: {}", self.crt(SUB_CA).display());
+        Ok(())
+    }
+
+    fn issue(&self, name: &str, sans: Vec<String>, days: i64) -> anyhow::Result<()> {
+        // 名称用作文件名
+        ensure!(
+            !name.is_empty()
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'),
+            "name may only contain letters, digits, '-' and '_'"
+        );
+        ensure!(name != ROOT_CA && name != SUB_CA, "name {name} is reserved");
+        ensure!(days > 0, "days must be positive");
+        let issuer = self.sub_issuer()?;
+        let sub_not_after = self.not_after(SUB_CA)?;
+
+        let mut params = CertificateParams::new(sans.clone())?;
+        params.distinguished_name = DistinguishedName::new();
+        params.distinguished_name.push(DnType::CommonName, name);
+        params.is_ca = IsCa::ExplicitNoCa;
+        params.key_usages = vec![
+            KeyUsagePurpose::DigitalSignature,
+            KeyUsagePurpose::KeyEncipherment,
+        ];
+        params.extended_key_usages = vec![ExtendedKeyUsagePurpose::ServerAuth];
+        params.use_authority_key_identifier_extension = true;
+        let now = OffsetDateTime::now_utc();
+        params.not_before = now - Duration::hours(1);
+        params.not_after = (now + Duration::days(days)).min(sub_not_after);
+        let serial = new_serial();
+        params.serial_number = Some(SerialNumber::from_slice(&serial));
+        let key = KeyPair::generate()?;
+        let cert = params.signed_by(&key, &issuer)?;
+
+        let mut index = self.load_index()?;
+        write_private(&self.key(name), key.serialize_pem())?;
+        fs::write(self.crt(name), cert.pem())?;
+        index.push(IssuedCert {
+            serial: hex(&serial),
+            name: name.to_string(),
+            sans,
+            not_before: params.not_before.unix_timestamp(),
+            not_after: params.not_after.unix_timestamp(),
+            revoked_at: None,
+        });
+        self.save_index(&index)?;
+        println!("issued {name}, serial {}", hex(&serial));
+        println!("not after {}", format_ts(params.not_after.unix_timestamp()));
+        Ok(())
+    }
+
+    fn list(&self, warn_days: i64) -> anyhow::Result<()> {
+        for ca_name in [ROOT_CA, SUB_CA] {
+            for cert in read_chain(&self.crt(ca_name).to_string_lossy(), warn_days)? {
+                println!(
+                    "{ca_name:<16} {:<34} {:<20} {:?}",
+                    cert.serial,
+                    format_ts(cert.not_after / 1000),
+                    cert.status
+                );
+            }
+        }
+        let now = chrono::Utc::now().timestamp();
+        for issued_cert in self.load_index()? {
+            let status = if issued_cert.revoked_at.is_some() {
+                CertStatus::Revoked
+            } else {
+                expiry(issued_cert.not_after, now, warn_days).1
+            };
+            println!(
+                "{:<16} {:<34} {:<20} {:<8} {}",
+                issued_cert.name,
+                issued_cert.serial,
+                format_ts(issued_cert.not_after),
+                format!("{status:?}"),
+                issued_cert.sans.join(",")
+            );
+        }
+        Ok(())
+    }
+
+    fn revoke(&self, serial: &str) -> anyhow::Result<()> {
+        let mut index = self.load_index()?;
+        let Some(issued_cert) = index.iter_mut().find(|v| v.serial == serial) else {
+            bail!("cert {serial} not found");
+        };
+        ensure!(
+            issued_cert.revoked_at.is_none(),
+            "cert {serial} already revoked"
+        );
+        issued_cert.revoked_at = Some(chrono::Utc::now().timestamp());
+        let name = issued_cert.name.clone();
+        self.save_index(&index)?;
+        self.write_crl(&index)?;
+        println!("revoked {name}, serial {serial}");
+        println!("crl: {}", self.dir.join(CRL).display());
+        // agent 只校验证书链和有效期，仍会信任已吊销的证书
+        println!(
+            "agents do not check the crl, issue and export a new cert for {name} to replace it"
+        );
+        Ok(())
+    }
+
+    fn export(&self, name: Option<&str>, out: &Path, agent: bool) -> anyhow::Result<()> {
+        fs::create_dir_all(out)?;
+        let sub_crt = fs::read_to_string(self.crt(SUB_CA))?;
+        if agent {
+            fs::write(out.join("sub-ca.crt"), &sub_crt)?;
+            println!("exported sub-ca.crt to {}", out.display());
+            return Ok(());
+        }
+        let Some(name) = name else {
+            bail!("name is required");
+        };
+        // 同名证书以最后签发的为准
+        let index = self.load_index()?;
+        let Some(issued_cert) = index.iter().rev().find(|v| v.name == name) else {
+            bail!("cert {name} not found");
+        };
+        ensure!(
+            issued_cert.revoked_at.is_none(),
+            "cert {name} is revoked, issue a new one first"
+        );
+        // 服务端证书后附中间 CA，agent 只需信任中间 CA
+        let chain = fs::read_to_string(self.crt(name))? + &sub_crt;
+        fs::write(out.join("z11n-ca.crt"), chain)?;
+        write_private(
+            &out.join("z11n-ca.key"),
+            fs::read_to_string(self.key(name))?,
+        )?;
+        // client_service 用中间 CA 签发 agent 客户端证书
+        fs::write(out.join("sub-ca.crt"), &sub_crt)?;
+        write_private(
+            &out.join("sub-ca.key"),
+            fs::read_to_string(self.key(SUB_CA))?,
+        )?;
+        println!(
+            "exported z11n-ca.crt, z11n-ca.key, sub-ca.crt, sub-ca.key to {}",
+            out.display()
+        );
+        Ok(())
+    }
+
+    fn crt(&self, name: &str) -> PathBuf {
+        match name {
+            ROOT_CA | SUB_CA => self.dir.join(format!("{name}.crt")),
+            _ => self.dir.join("certs").join(format!("{name}.crt")),
+        }
+    }
+
+    fn key(&self, name: &str) -> PathBuf {
+        self.dir.join("private").join(format!("{name}.key"))
+    }
+
+    fn sub_issuer(&self) -> anyhow::Result<Issuer<'static, KeyPair>> {
+        let ca_pem = fs::read_to_string(self.crt(SUB_CA))?;
+        let key_pair = KeyPair::from_pem(&fs::read_to_string(self.key(SUB_CA))?)?;
+        Ok(Issuer::from_ca_cert_pem(&ca_pem, key_pair)?)
+    }
+
+    fn not_after(&self, name: &str) -> anyhow::Result<OffsetDateTime> {
+        let Some(cert) = read_chain(&self.crt(name).to_string_lossy(), 0)?
+            .into_iter()
+            .next()
+        else {
+            bail!("no cert in {}", self.crt(name).display());
+        };
+        if cert.status == CertStatus::Expired {
+            bail!("{name} expired, run ca init again");
+        }
+        Ok(OffsetDateTime::from_unix_timestamp(cert.not_after / 1000)?)
+    }
+
+    fn load_index(&self) -> anyhow::Result<Vec<IssuedCert>> {
+        let data = fs::read(self.dir.join(INDEX))?;
+        Ok(serde_json::from_slice(&data)?)
+    }
+
+    fn save_index(&self, index: &[IssuedCert]) -> anyhow::Result<()> {
+        fs::write(self.dir.join(INDEX), serde_json::to_vec_pretty(index)?)?;
+        Ok(())
+    }
+
+    /// 中间 CA 签名的 CRL，包含所有已吊销且未过期的证书
+    fn write_crl(&self, index: &[IssuedCert]) -> anyhow::Result<()> {
+        let now = OffsetDateTime::now_utc();
+        let mut revoked_certs = Vec::new();
+        for issued_cert in index {
+            let Some(revoked_at) = issued_cert.revoked_at else {
+                continue;
+            };
+            if issued_cert.not_after <= now.unix_timestamp() {
+                continue;
+            }
+            revoked_certs.push(RevokedCertParams {
+                serial_number: SerialNumber::from_slice(&unhex(&issued_cert.serial)?),
+                revocation_time: OffsetDateTime::from_unix_timestamp(revoked_at)?,
+                reason_code: None,
+                invalidity_date: None,
+            });
+        }
+        let crl = CertificateRevocationListParams {
+            this_update: now,
+            next_update: now + Duration::days(30),
+            // 以秒级时间戳作为递增的 CRL 编号
+            crl_number: SerialNumber::from(now.unix_timestamp() as u64),
+            issuing_distribution_point: None,
+            revoked_certs,
+            key_identifier_method: KeyIdMethod::Sha256,
+        }
+        .signed_by(&self.sub_issuer()?)?;
+        fs::write(self.dir.join(CRL), crl.pem()?)?;
+        Ok(())
+    }
+}
+
+fn ca_params(common_name: &str, constraints: BasicConstraints) -> CertificateParams {
+    let mut params = CertificateParams::default();
+    params.distinguished_name = DistinguishedName::new();
+    params
+        .distinguished_name
+        .push(DnType::CommonName, common_name);
+    params.is_ca = IsCa::Ca(constraints);
+    params.key_usages = vec![
+        KeyUsagePurpose::KeyCertSign,
+        KeyUsagePurpose::CrlSign,
+        KeyUsagePurpose::DigitalSignature,
+    ];
+    params.serial_number = Some(SerialNumber::from_slice(&new_serial()));
+    params
+}
+
+/// 私钥只允许属主读写
+fn write_private(path: &Path, pem: String) -> anyhow::Result<()> {
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)?;
+    file.write_all(pem.as_bytes())?;
+    Ok(())
+}
+
+/// 最高位置 0、次高位置 1，保证为正数且 DER 编码后长度不变
+fn new_serial() -> [u8; 16] {
+    let mut serial = *uuid::Uuid::new_v4().as_bytes();
+    serial[0] = serial[0] & 0x7f | 0x40;
+    serial
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn unhex(s: &str) -> anyhow::Result<Vec<u8>> {
+    ensure!(s.len().is_multiple_of(2), "invalid serial {s}");
+    (0..s.len())
+        .step_by(2)
+        .map(|i| Ok(u8::from_str_radix(&s[i..i + 2], 16)?))
+        .collect()
+}
+
+fn format_ts(timestamp: i64) -> String {
+    chrono::DateTime::from_timestamp(timestamp, 0)
+        .unwrap_or_default()
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+    use ui_service::cert::read_crl;
+
+    #[derive(Parser, Debug)]
+    struct TestArgs {
+        #[command(flatten)]
+        ca_args: CaArgs,
+    }
+
+    fn parse(args: &[&str]) -> Result<CaArgs, clap::Error> {
+        TestArgs::try_parse_from([&["ca"], args].concat()).map(|v| v.ca_args)
+    }
+
+    #[test]
+    fn ca_args_test() -> anyhow::Result<()> {
+        let ca_args = parse(&["issue", "server"])?;
+        assert_eq!(ca_args.dir, PathBuf::from("./ca"));
+        match ca_args.command {
+            CaCommand::Issue { name, sans, days } => {
+                assert_eq!(name, "server");
+                assert_eq!(sans, vec!["z11n.com"]);
+                assert_eq!(days, 825);
+            }
+            command => bail!("unexpected {command:?}"),
+        }
+        let ca_args = parse(&[
+            "--dir", "/tmp/ca", "issue", "server", "--san", "10.0.0.1", "--san", "z11n.com",
+            "--days", "30",
+        ])?;
+        assert_eq!(ca_args.dir, PathBuf::from("/tmp/ca"));
+        match ca_args.command {
+            CaCommand::Issue { sans, days, .. } => {
+                assert_eq!(sans, vec!["10.0.0.1", "z11n.com"]);
+                assert_eq!(days, 30);
+            }
+            command => bail!("unexpected {command:?}"),
+        }
+        // 导出服务端证书时必须指定名称
+        assert!(parse(&["export"]).is_err());
+        match parse(&["export", "--agent"])?.command {
+            CaCommand::Export { name, agent, .. } => {
+                assert_eq!(name, None);
+                assert!(agent);
+            }
+            command => bail!("unexpected {command:?}"),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn revoke_test() -> anyhow::Result<()> {
+        let ca = Ca {
+            dir: std::env::temp_dir().join(format!("ca_{}", uuid::Uuid::new_v4())),
+        };
+        ca.init(30, 10, false)?;
+        ca.issue("server", vec!["z11n.com".to_string()], 5)?;
+        let serial = ca.load_index()?[0].serial.clone();
+        let crl = ca.dir.join(CRL).to_string_lossy().to_string();
+        assert!(read_crl(&crl)?.is_empty());
+        ca.revoke(&serial)?;
+        assert!(read_crl(&crl)?.contains(&serial));
+        // 吊销的证书不能再导出
+        assert!(
+            ca.export(Some("server"), &ca.dir.join("out"), false)
+                .is_err()
+        );
+        fs::remove_dir_all(&ca.dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn serial_test() -> anyhow::Result<()> {
+        let serial = new_serial();
+        assert_eq!(serial[0] & 0xc0, 0x40);
+        assert_eq!(unhex(&hex(&serial))?, serial);
+        assert!(unhex("abc").is_err());
+        assert!(unhex("zz").is_err());
+        Ok(())
+    }
+}
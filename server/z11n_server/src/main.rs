@@ -3,7 +3,7 @@ use std::{
     path::Path,
 };
 
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use migration::{Migrator, MigratorTrait};
 use prost::Message;
 use rustls::crypto::{CryptoProvider, ring};
@@ -11,12 +11,27 @@ use sea_orm::Database;
 use tokio::sync::broadcast;
 use ui_service::z11n::HeartbeatRsp;
 
+mod ca;
+
 #[derive(Parser, Debug)]
 #[command(version)]
-struct Args {}
+struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// 证书管理，不带子命令时启动服务
+    Ca(ca::CaArgs),
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    let _args = Args::parse();
+    let args = Args::parse();
+    if let Some(Command::Ca(ca_args)) = args.command {
+        return ca::run(ca_args);
+    }
     log4rs::init_file("./config/log4rs.yml", Default::default())?;
     log::info!("server starting");
 